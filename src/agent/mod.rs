@@ -7,6 +7,8 @@ pub use operations::{MockAgentOperations, MockAgentRegistry};
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::CustomAgentConfig;
+
 /// Known coding agents that agtx can work with
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
@@ -15,6 +17,9 @@ pub struct Agent {
     pub args: Vec<String>,
     pub description: String,
     pub co_author: String,
+    /// Command templates for agents declared via `[[custom_agents]]` (None for built-ins)
+    #[serde(default)]
+    pub custom: Option<CustomAgentConfig>,
 }
 
 impl Agent {
@@ -25,6 +30,25 @@ impl Agent {
             args: vec![],
             description: description.to_string(),
             co_author: co_author.to_string(),
+            custom: None,
+        }
+    }
+
+    /// Build an agent from a `[[custom_agents]]` entry.
+    pub fn from_custom(config: &CustomAgentConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            command: config.command.clone(),
+            args: vec![],
+            description: config
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Custom agent ({})", config.command)),
+            co_author: config
+                .co_author
+                .clone()
+                .unwrap_or_else(|| format!("{} <noreply@agtx>", config.name)),
+            custom: Some(config.clone()),
         }
     }
 
@@ -36,6 +60,9 @@ impl Agent {
     /// Build the shell command to resume the agent's most recent session
     /// in the current working directory. Used to recover from tmux/server restarts.
     pub fn build_resume_command(&self) -> String {
+        if let Some(resume) = self.custom.as_ref().and_then(|c| c.resume.as_ref()) {
+            return resume.clone();
+        }
         match self.name.as_str() {
             "claude" => "claude --dangerously-skip-permissions --continue".to_string(),
             "codex" => "codex resume --last".to_string(),
@@ -51,6 +78,21 @@ impl Agent {
     /// When prompt is empty, the agent starts with no initial message
     /// (task content and skill commands are sent later via tmux send_keys).
     pub fn build_interactive_command(&self, prompt: &str) -> String {
+        if let Some(custom) = &self.custom {
+            let base = custom
+                .interactive
+                .clone()
+                .unwrap_or_else(|| self.command.clone());
+            if prompt.is_empty() {
+                return base;
+            }
+            let quoted = format!("'{}'", prompt.replace('\'', "'\"'\"'"));
+            return match &custom.interactive_prompt {
+                Some(template) => template.replace("{prompt}", &quoted),
+                None => format!("{} {}", base, quoted),
+            };
+        }
+
         if prompt.is_empty() {
             return match self.name.as_str() {
                 "claude" => "claude --dangerously-skip-permissions".to_string(),
@@ -74,9 +116,26 @@ impl Agent {
             _ => format!("{} '{}'", self.command, escaped_prompt),
         }
     }
+
+    /// Program and arguments for a custom agent's print-mode invocation.
+    /// The template is split into words with shell quoting rules and `{prompt}`
+    /// is substituted per argument, so the prompt never goes through a shell.
+    /// Returns None for built-in agents.
+    pub fn build_print_command(&self, prompt: &str) -> Option<(String, Vec<String>)> {
+        let custom = self.custom.as_ref()?;
+        let Some(template) = &custom.print else {
+            return Some((self.command.clone(), vec![prompt.to_string()]));
+        };
+        // Split before substituting so the prompt stays a single argument;
+        // `CustomAgentConfig::validate` rejects templates that don't parse.
+        let words = shell_words::split(template).unwrap_or_default();
+        let mut parts = words.iter().map(|part| part.replace("{prompt}", prompt));
+        let program = parts.next().unwrap_or_else(|| self.command.clone());
+        Some((program, parts.collect()))
+    }
 }
 
-/// Get the list of known agents
+/// Get the list of built-in agents
 pub fn known_agents() -> Vec<Agent> {
     vec![
         Agent::new(
@@ -167,4 +226,3 @@ pub fn parse_agent_selection(input: &str, agent_count: usize) -> Option<usize> {
     }
     None
 }
-
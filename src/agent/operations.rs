@@ -54,6 +54,18 @@ impl CodingAgent {
 
 impl AgentOperations for CodingAgent {
     fn generate_text(&self, working_dir: &Path, prompt: &str) -> Result<String> {
+        if let Some((program, args)) = self.agent.build_print_command(prompt) {
+            let output = std::process::Command::new(&program)
+                .current_dir(working_dir)
+                .args(&args)
+                .output()?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                anyhow::bail!("{} command failed: {}", self.agent.name, stderr);
+            }
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+
         // Build the command based on agent type
         let (cmd, args) = match self.agent.name.as_str() {
             "claude" => ("claude", vec!["--print", prompt]),
@@ -89,7 +101,18 @@ impl AgentOperations for CodingAgent {
         self.agent.build_resume_command()
     }
 
-    fn build_orchestrator_command(&self, mcp_json: &str, _agtx_bin: &str) -> String {
+    fn build_orchestrator_command(&self, mcp_json: &str, agtx_bin: &str) -> String {
+        if let Some(template) = self
+            .agent
+            .custom
+            .as_ref()
+            .and_then(|c| c.orchestrator.as_ref())
+        {
+            return template
+                .replace("{interactive}", &self.build_interactive_command(""))
+                .replace("{mcp_json}", mcp_json)
+                .replace("{agtx_bin}", agtx_bin);
+        }
         match self.agent.name.as_str() {
             // Pre-remove any stale `agtx` registration (last run crashed before
             // its own `mcp remove`) so `add-json` doesn't fail with "already
@@ -120,34 +143,62 @@ pub trait AgentRegistry: Send + Sync {
 /// Holds all available agents, keyed by name.
 pub struct RealAgentRegistry {
     agents: HashMap<String, Arc<dyn AgentOperations>>,
+    /// Agents from `[[custom_agents]]`, in config order
+    custom_agents: Vec<Agent>,
     default_name: String,
 }
 
 impl RealAgentRegistry {
+    /// Create a registry with the built-ins plus user-defined `[[custom_agents]]`.
+    /// Custom entries are validated first; an invalid entry fails the whole load
+    /// so misconfigurations surface at startup instead of mid-transition.
+    pub fn with_custom_agents(
+        default_name: &str,
+        custom_agents: &[crate::config::CustomAgentConfig],
+    ) -> Result<Self> {
+        crate::config::CustomAgentConfig::validate_all(custom_agents)?;
+        let custom_agents = custom_agents.iter().map(Agent::from_custom).collect();
+        Ok(Self::build(default_name, custom_agents))
+    }
+
     /// Create a new registry populated with all available agents.
     /// `default_name` is used as the fallback when a requested name isn't found.
     pub fn new(default_name: &str) -> Self {
+        Self::build(default_name, Vec::new())
+    }
+
+    fn build(default_name: &str, custom_agents: Vec<Agent>) -> Self {
         let mut agents: HashMap<String, Arc<dyn AgentOperations>> = HashMap::new();
+        let all_agents: Vec<Agent> = super::known_agents()
+            .into_iter()
+            .chain(custom_agents.iter().cloned())
+            .collect();
 
-        for agent in super::known_agents() {
+        for agent in &all_agents {
             if agent.is_available() {
                 let name = agent.name.clone();
-                agents.insert(name, Arc::new(CodingAgent::new(agent)));
+                agents.insert(name, Arc::new(CodingAgent::new(agent.clone())));
             }
         }
 
         // Ensure we have the default agent even if not detected as available
         if !agents.contains_key(default_name) {
-            if let Some(agent) = super::get_agent(default_name) {
+            if let Some(agent) = all_agents.into_iter().find(|a| a.name == default_name) {
                 agents.insert(default_name.to_string(), Arc::new(CodingAgent::new(agent)));
             }
         }
 
         Self {
             agents,
+            custom_agents,
             default_name: default_name.to_string(),
         }
     }
+
+    /// The user-defined agents this registry was built with
+    pub fn custom_agents(&self) -> &[Agent] {
+        &self.custom_agents
+    }
 }
 
 impl AgentRegistry for RealAgentRegistry {
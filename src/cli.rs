@@ -0,0 +1,943 @@
+//! Headless `agtx task ...`, `agtx stats` and `agtx db ...` subcommands.
+//!
+//! Task commands work on the same project database as the TUI and the MCP
+//! server. Moves that need worktrees, tmux windows or PRs are queued as
+//! `TransitionRequest`s and executed by whichever agtx instance claims them;
+//! moves without side effects are applied directly.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::{GlobalConfig, MergedConfig, ProjectConfig};
+use crate::db::{
+    Database, EventActor, MigrationStatus, Priority, SchemaKind, SubtaskProgress, Task, TaskStatus,
+    TransitionRequest,
+};
+
+pub const TASK_USAGE: &str = "\
+Usage: agtx task <command> [options]
+
+Commands:
+  add <title>            Create a Backlog task
+  list                   List tasks (--status <status> to filter)
+  show <id>              Show task details
+  move <id> <target>     Move a task (planning, running, review, done, forward,
+                         research, resume, escalate, or an MCP action name)
+  delete <id>            Delete a task that has no worktree or session
+  edit <id>              Update task fields
+
+Options:
+  --title <text>         New title (edit)
+  --description <text>   Task description (add, edit)
+  --plugin <name>        Workflow plugin (add, edit)
+  --depends-on <ids>     Comma-separated task IDs this task depends on (add, edit)
+  --base-branch <name>   Base branch for the worktree (add, edit)
+  --parent <id>          Create the task as a subtask of <id>, inheriting its
+                         plugin and base branch (add)
+  --labels <names>       Comma-separated labels, replacing existing ones (add, edit)
+  --priority <level>     low, normal, high or urgent (add, edit)
+  --due <YYYY-MM-DD>     Due date, empty to clear (add, edit)
+  --reason <text>        Reason for escalate (move)
+  --status <status>      Filter by status (list)
+  --project <path>       Project directory (default: current directory)
+  --json                 Print JSON instead of a table
+
+Task IDs may be abbreviated to any unique prefix.";
+
+/// Optional task fields shared by `add` and `edit`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskFields {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub plugin: Option<String>,
+    pub depends_on: Option<String>,
+    pub base_branch: Option<String>,
+    pub labels: Option<String>,
+    pub priority: Option<String>,
+    pub due_date: Option<String>,
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskCommand {
+    Add {
+        title: String,
+        fields: TaskFields,
+    },
+    List {
+        status: Option<TaskStatus>,
+    },
+    Show {
+        id: String,
+    },
+    Move {
+        id: String,
+        target: String,
+        reason: Option<String>,
+    },
+    Delete {
+        id: String,
+    },
+    Edit {
+        id: String,
+        fields: TaskFields,
+    },
+}
+
+/// Parsed `agtx task` invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskArgs {
+    pub command: TaskCommand,
+    pub json: bool,
+    pub project: Option<PathBuf>,
+}
+
+/// Project-level defaults applied to tasks created from the CLI.
+#[derive(Debug, Clone)]
+pub struct TaskContext {
+    /// Project name stored in `Task::project_id` (directory name, as in the TUI)
+    pub project_name: String,
+    pub default_agent: String,
+    pub default_plugin: Option<String>,
+}
+
+/// Parse the arguments following `agtx task`.
+/// Global flags (`--experimental`, `--no-init-scripts`) are accepted and ignored.
+pub fn parse_task_args(args: &[String]) -> Result<TaskArgs> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut fields = TaskFields::default();
+    let mut reason = None;
+    let mut status = None;
+    let mut project = None;
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| -> Result<String> {
+            iter.next()
+                .cloned()
+                .with_context(|| format!("{} requires a value", name))
+        };
+        match arg.as_str() {
+            "--json" => json = true,
+            "--experimental" | "--no-init-scripts" => {}
+            "--title" => fields.title = Some(value(arg)?),
+            "--description" => fields.description = Some(value(arg)?),
+            "--plugin" => fields.plugin = Some(value(arg)?),
+            "--depends-on" => fields.depends_on = Some(value(arg)?),
+            "--base-branch" => fields.base_branch = Some(value(arg)?),
+            "--labels" => fields.labels = Some(value(arg)?),
+            "--priority" => fields.priority = Some(value(arg)?),
+            "--due" => fields.due_date = Some(value(arg)?),
+            "--parent" => fields.parent = Some(value(arg)?),
+            "--reason" => reason = Some(value(arg)?),
+            "--project" => project = Some(PathBuf::from(value(arg)?)),
+            "--status" => {
+                let s = value(arg)?;
+                status = Some(TaskStatus::from_str(&s).with_context(|| {
+                    format!(
+                        "Invalid status '{}'. Valid values: backlog, planning, running, review, done",
+                        s
+                    )
+                })?);
+            }
+            other if other.starts_with("--") => anyhow::bail!("Unknown option: {}", other),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let sub = positional.next().context(TASK_USAGE)?;
+    let mut required = |what: &str| -> Result<String> {
+        positional
+            .next()
+            .with_context(|| format!("agtx task {} requires <{}>", sub, what))
+    };
+
+    let command = match sub.as_str() {
+        "add" => {
+            let title = required("title")?;
+            TaskCommand::Add { title, fields }
+        }
+        "list" | "ls" => TaskCommand::List { status },
+        "show" => TaskCommand::Show {
+            id: required("id")?,
+        },
+        "move" | "mv" => {
+            let id = required("id")?;
+            let target = required("target")?;
+            TaskCommand::Move { id, target, reason }
+        }
+        "delete" | "rm" => TaskCommand::Delete {
+            id: required("id")?,
+        },
+        "edit" => TaskCommand::Edit {
+            id: required("id")?,
+            fields,
+        },
+        other => anyhow::bail!("Unknown task command: {}\n\n{}", other, TASK_USAGE),
+    };
+
+    Ok(TaskArgs {
+        command,
+        json,
+        project,
+    })
+}
+
+/// Entry point for `agtx task ...`: resolve the project, run the command, print the result.
+pub fn run(args: &[String]) -> Result<()> {
+    let args = parse_task_args(args)?;
+    let project_path = project_dir(args.project.as_deref(), "agtx task")?;
+
+    let db = Database::open_project(&project_path)?;
+    db.set_actor(EventActor::Cli);
+    let ctx = task_context_for(&project_path);
+    let output = run_task_command(&db, &ctx, &args)?;
+    println!("{}", output);
+    Ok(())
+}
+
+/// The `--project` directory, or the current one, which must be a git repository.
+fn project_dir(project: Option<&Path>, command: &str) -> Result<PathBuf> {
+    let project_path = match project {
+        Some(p) => p.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let project_path = project_path
+        .canonicalize()
+        .with_context(|| format!("Project not found: {}", project_path.display()))?;
+    if !crate::git::is_git_repo(&project_path) {
+        anyhow::bail!("{} requires a git project directory", command);
+    }
+    Ok(project_path)
+}
+
+/// Build CLI defaults from the merged global + project config.
+pub fn task_context_for(project_path: &Path) -> TaskContext {
+    let global = GlobalConfig::load().unwrap_or_default();
+    let project = ProjectConfig::load(project_path).unwrap_or_default();
+    let merged = MergedConfig::merge(&global, &project);
+    TaskContext {
+        project_name: project_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string(),
+        default_agent: merged.default_agent,
+        default_plugin: merged.workflow_plugin,
+    }
+}
+
+/// Execute a parsed task command and return what should be printed.
+pub fn run_task_command(db: &Database, ctx: &TaskContext, args: &TaskArgs) -> Result<String> {
+    match &args.command {
+        TaskCommand::Add { title, fields } => {
+            let mut task = Task::new(title, &ctx.default_agent, &ctx.project_name);
+            task.description = fields.description.clone();
+            task.plugin = fields.plugin.clone();
+            task.base_branch = fields.base_branch.clone();
+            if let Some(parent) = &fields.parent {
+                task.set_parent(&find_task(db, parent)?);
+            }
+            task.plugin = task.plugin.or_else(|| ctx.default_plugin.clone());
+            if let Some(refs) = &fields.depends_on {
+                task.referenced_tasks = resolve_dependencies(db, refs)?;
+            }
+            apply_planning_fields(&mut task, fields)?;
+            db.create_task(&task)?;
+            tracing::info!(task_id = %task.id, source = "cli", "Task created");
+            if args.json {
+                to_json(&TaskRecord::new(db, &task))
+            } else {
+                Ok(format!(
+                    "Created task {} ({})",
+                    short_id(&task.id),
+                    task.title
+                ))
+            }
+        }
+        TaskCommand::List { status } => {
+            let tasks = match status {
+                Some(s) => db.get_tasks_by_status(*s)?,
+                None => db.get_all_tasks()?,
+            };
+            if args.json {
+                let records: Vec<TaskRecord> =
+                    tasks.iter().map(|t| TaskRecord::new(db, t)).collect();
+                to_json(&records)
+            } else {
+                Ok(format_task_table(&tasks))
+            }
+        }
+        TaskCommand::Show { id } => {
+            let task = find_task(db, id)?;
+            if args.json {
+                to_json(&TaskRecord::new(db, &task))
+            } else {
+                Ok(format_task_detail(db, &task))
+            }
+        }
+        TaskCommand::Move { id, target, reason } => {
+            let task = find_task(db, id)?;
+            let outcome = move_task(db, &task, target, reason.clone())?;
+            if args.json {
+                to_json(&outcome)
+            } else {
+                Ok(outcome.message)
+            }
+        }
+        TaskCommand::Delete { id } => {
+            let task = find_task(db, id)?;
+            if task.worktree_path.is_some() || task.session_name.is_some() {
+                anyhow::bail!(
+                    "Task {} has a worktree or agent session; delete it from the TUI so they are cleaned up",
+                    short_id(&task.id)
+                );
+            }
+            db.delete_task(&task.id)?;
+            tracing::info!(task_id = %task.id, source = "cli", "Task deleted");
+            if args.json {
+                to_json(&serde_json::json!({
+                    "id": task.id,
+                    "title": task.title,
+                    "message": "Task deleted",
+                }))
+            } else {
+                Ok(format!(
+                    "Deleted task {} ({})",
+                    short_id(&task.id),
+                    task.title
+                ))
+            }
+        }
+        TaskCommand::Edit { id, fields } => {
+            let mut task = find_task(db, id)?;
+            let updated_fields = apply_edit(db, &mut task, fields)?;
+            if updated_fields.is_empty() {
+                anyhow::bail!(
+                    "Nothing to update. Pass --title, --description, --plugin, --depends-on, --base-branch, --labels, --priority or --due"
+                );
+            }
+            task.updated_at = chrono::Utc::now();
+            db.update_task(&task)?;
+            tracing::info!(task_id = %task.id, fields = ?updated_fields, source = "cli", "Task edited");
+            if args.json {
+                to_json(&serde_json::json!({
+                    "id": task.id,
+                    "title": task.title,
+                    "updated_fields": updated_fields,
+                }))
+            } else {
+                Ok(format!(
+                    "Updated task {}: {}",
+                    short_id(&task.id),
+                    updated_fields.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+/// JSON shape for a task. Mirrors the MCP `get_task` field names so scripts
+/// can switch between the two without remapping.
+#[derive(Debug, Serialize)]
+struct TaskRecord {
+    id: String,
+    title: String,
+    description: Option<String>,
+    status: String,
+    agent: String,
+    plugin: Option<String>,
+    session_name: Option<String>,
+    worktree_path: Option<String>,
+    branch_name: Option<String>,
+    pr_number: Option<i32>,
+    pr_url: Option<String>,
+    cycle: i32,
+    referenced_tasks: Option<String>,
+    base_branch: Option<String>,
+    escalation_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queued_phase: Option<String>,
+    labels: Vec<String>,
+    priority: Priority,
+    due_date: Option<chrono::NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subtasks: Option<SubtaskProgress>,
+    deps_satisfied: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+impl TaskRecord {
+    fn new(db: &Database, t: &Task) -> Self {
+        Self {
+            id: t.id.clone(),
+            title: t.title.clone(),
+            description: t.description.clone(),
+            status: t.status.as_str().to_string(),
+            agent: t.agent.clone(),
+            plugin: t.plugin.clone(),
+            session_name: t.session_name.clone(),
+            worktree_path: t.worktree_path.clone(),
+            branch_name: t.branch_name.clone(),
+            pr_number: t.pr_number,
+            pr_url: t.pr_url.clone(),
+            cycle: t.cycle,
+            referenced_tasks: t.referenced_tasks.clone(),
+            base_branch: t.base_branch.clone(),
+            escalation_note: t.escalation_note.clone(),
+            queued_phase: t.queued_phase.clone(),
+            labels: t.labels.clone(),
+            priority: t.priority,
+            due_date: t.due_date,
+            parent_id: t.parent_id.clone(),
+            subtasks: db.subtask_progress(t).ok().flatten(),
+            deps_satisfied: db.deps_satisfied(t),
+            created_at: t.created_at.to_rfc3339(),
+            updated_at: t.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Result of `agtx task move`.
+#[derive(Debug, Serialize)]
+pub struct MoveOutcome {
+    pub task_id: String,
+    pub action: String,
+    /// True when the move was queued for a running agtx instance to execute
+    pub queued: bool,
+    pub request_id: Option<String>,
+    pub message: String,
+}
+
+/// Move a task. Escalations and marking an untouched task done are applied
+/// directly; everything else is queued as a `TransitionRequest`.
+pub fn move_task(
+    db: &Database,
+    task: &Task,
+    target: &str,
+    reason: Option<String>,
+) -> Result<MoveOutcome> {
+    let action = resolve_move_action(task, target)?;
+    validate_move(db, task, action)?;
+
+    let has_resources = task.worktree_path.is_some() || task.session_name.is_some();
+    match action {
+        "escalate_to_user" => {
+            let mut task = task.clone();
+            task.escalation_note = reason.or_else(|| Some("Needs attention".to_string()));
+            task.updated_at = chrono::Utc::now();
+            db.update_task(&task)?;
+            return Ok(MoveOutcome {
+                task_id: task.id.clone(),
+                action: action.to_string(),
+                queued: false,
+                request_id: None,
+                message: format!("Escalated task {} to user", short_id(&task.id)),
+            });
+        }
+        "move_to_done" if !has_resources => {
+            let mut task = task.clone();
+            task.status = TaskStatus::Done;
+            task.updated_at = chrono::Utc::now();
+            db.update_task(&task)?;
+            return Ok(MoveOutcome {
+                task_id: task.id.clone(),
+                action: action.to_string(),
+                queued: false,
+                request_id: None,
+                message: format!("Moved task {} to done", short_id(&task.id)),
+            });
+        }
+        _ => {}
+    }
+
+    let mut req = TransitionRequest::new(&task.id, action);
+    req.reason = reason;
+    req.requested_by = Some(db.actor());
+    db.create_transition_request(&req)?;
+    tracing::info!(task_id = %task.id, action = action, request_id = %req.id, source = "cli", "Transition request queued");
+    Ok(MoveOutcome {
+        task_id: task.id.clone(),
+        action: action.to_string(),
+        queued: true,
+        request_id: Some(req.id),
+        message: format!(
+            "Queued '{}' for task {}. A running agtx TUI or `agtx daemon` will execute it.",
+            action,
+            short_id(&task.id)
+        ),
+    })
+}
+
+/// Map a user-facing move target (column name or MCP action) to a transition action.
+fn resolve_move_action(task: &Task, target: &str) -> Result<&'static str> {
+    Ok(match target {
+        "research" => "research",
+        "forward" | "next" | "move_forward" => "move_forward",
+        "planning" | "move_to_planning" => "move_to_planning",
+        "running" if task.status == TaskStatus::Review => "resume",
+        "running" | "move_to_running" => "move_to_running",
+        "review" | "move_to_review" => "move_to_review",
+        "done" | "move_to_done" => "move_to_done",
+        "resume" => "resume",
+        "escalate" | "escalate_to_user" => "escalate_to_user",
+        other => anyhow::bail!(
+            "Unknown move target '{}'. Use planning, running, review, done, forward, research, resume or escalate",
+            other
+        ),
+    })
+}
+
+/// Reject moves the transition engine would refuse, so the caller gets the
+/// error now instead of a failed request later.
+fn validate_move(db: &Database, task: &Task, action: &str) -> Result<()> {
+    let status = task.status;
+    let expect = |ok: bool, need: &str| -> Result<()> {
+        if ok {
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "Cannot {} task {}: it must be in {} (current: {})",
+                action,
+                short_id(&task.id),
+                need,
+                status.as_str()
+            )
+        }
+    };
+    match action {
+        "research" => expect(status == TaskStatus::Backlog, "backlog")?,
+        "move_forward" => expect(status != TaskStatus::Done, "a column before done")?,
+        "move_to_planning" => expect(status == TaskStatus::Backlog, "backlog")?,
+        "move_to_running" => expect(
+            matches!(status, TaskStatus::Backlog | TaskStatus::Planning),
+            "backlog or planning",
+        )?,
+        "move_to_review" => expect(status == TaskStatus::Running, "running")?,
+        "move_to_done" => {
+            let untouched = task.worktree_path.is_none() && task.session_name.is_none();
+            expect(
+                status == TaskStatus::Review || (untouched && status != TaskStatus::Done),
+                "review",
+            )?
+        }
+        "resume" => expect(status == TaskStatus::Review, "review")?,
+        "escalate_to_user" => expect(
+            matches!(status, TaskStatus::Planning | TaskStatus::Running),
+            "planning or running",
+        )?,
+        _ => {}
+    }
+
+    let is_forward = matches!(
+        action,
+        "move_forward" | "move_to_planning" | "move_to_running" | "research"
+    );
+    if is_forward && status == TaskStatus::Backlog && !db.deps_satisfied(task) {
+        anyhow::bail!("Cannot advance task: dependencies not in Review/Done");
+    }
+    let to_done =
+        action == "move_to_done" || (action == "move_forward" && status == TaskStatus::Review);
+    if to_done && !db.subtasks_done(task)? {
+        anyhow::bail!("Cannot move task to done: not all subtasks are done");
+    }
+    Ok(())
+}
+
+/// Apply `edit` fields to a task, returning the names of the fields changed.
+/// Plugin, dependencies and base branch only matter before the worktree
+/// exists, so they can only be changed in Backlog (same rule as MCP `update_task`).
+fn apply_edit(db: &Database, task: &mut Task, fields: &TaskFields) -> Result<Vec<String>> {
+    if fields.parent.is_some() {
+        anyhow::bail!("--parent can only be given when adding a task");
+    }
+    let backlog_only =
+        fields.plugin.is_some() || fields.depends_on.is_some() || fields.base_branch.is_some();
+    if backlog_only && task.status != TaskStatus::Backlog {
+        anyhow::bail!(
+            "--plugin, --depends-on and --base-branch can only be changed in Backlog (task is in {})",
+            task.status.as_str()
+        );
+    }
+
+    let mut updated = Vec::new();
+    if let Some(title) = &fields.title {
+        task.title = title.clone();
+        updated.push("title".to_string());
+    }
+    if let Some(description) = &fields.description {
+        task.description = if description.is_empty() {
+            None
+        } else {
+            Some(description.clone())
+        };
+        updated.push("description".to_string());
+    }
+    if let Some(plugin) = &fields.plugin {
+        task.plugin = if plugin.is_empty() {
+            None
+        } else {
+            Some(plugin.clone())
+        };
+        updated.push("plugin".to_string());
+    }
+    if let Some(refs) = &fields.depends_on {
+        let refs = resolve_dependencies(db, refs)?;
+        if refs
+            .as_deref()
+            .is_some_and(|r| r.split(',').any(|id| id == task.id))
+        {
+            anyhow::bail!("A task cannot depend on itself");
+        }
+        task.referenced_tasks = refs;
+        updated.push("referenced_tasks".to_string());
+    }
+    if let Some(base_branch) = &fields.base_branch {
+        task.base_branch = if base_branch.is_empty() {
+            None
+        } else {
+            Some(base_branch.clone())
+        };
+        updated.push("base_branch".to_string());
+    }
+    updated.extend(apply_planning_fields(task, fields)?);
+    Ok(updated)
+}
+
+/// Apply `--labels`, `--priority` and `--due`, returning the names of the
+/// fields changed. These can be changed in any column.
+fn apply_planning_fields(task: &mut Task, fields: &TaskFields) -> Result<Vec<String>> {
+    let mut updated = Vec::new();
+    if let Some(labels) = &fields.labels {
+        task.set_labels(labels.split(','));
+        updated.push("labels".to_string());
+    }
+    if let Some(priority) = &fields.priority {
+        task.priority = Priority::from_str(priority).with_context(|| {
+            format!(
+                "Invalid priority '{}'. Valid values: low, normal, high, urgent",
+                priority
+            )
+        })?;
+        updated.push("priority".to_string());
+    }
+    if let Some(due) = &fields.due_date {
+        task.due_date = if due.is_empty() {
+            None
+        } else {
+            Some(
+                chrono::NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid due date '{}'. Use YYYY-MM-DD", due))?,
+            )
+        };
+        updated.push("due_date".to_string());
+    }
+    Ok(updated)
+}
+
+/// Resolve a comma-separated list of (possibly abbreviated) task IDs into the
+/// full-ID CSV stored in `referenced_tasks`. An empty list clears dependencies.
+fn resolve_dependencies(db: &Database, refs: &str) -> Result<Option<String>> {
+    let ids = refs
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|id| find_task(db, id).map(|t| t.id))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(if ids.is_empty() {
+        None
+    } else {
+        Some(ids.join(","))
+    })
+}
+
+/// Look up a task by full ID or unique ID prefix.
+pub fn find_task(db: &Database, id: &str) -> Result<Task> {
+    if let Some(task) = db.get_task(id)? {
+        return Ok(task);
+    }
+    let mut matches: Vec<Task> = db
+        .get_all_tasks()?
+        .into_iter()
+        .filter(|t| t.id.starts_with(id))
+        .collect();
+    match matches.len() {
+        0 => anyhow::bail!("Task not found: {}", id),
+        1 => Ok(matches.remove(0)),
+        n => anyhow::bail!("Task ID prefix '{}' is ambiguous ({} matches)", id, n),
+    }
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+/// Render tasks as an aligned table, one row per task.
+pub fn format_task_table(tasks: &[Task]) -> String {
+    if tasks.is_empty() {
+        return "No tasks".to_string();
+    }
+    let mut out = format!("{:<8}  {:<8}  {:<10}  {}", "ID", "STATUS", "AGENT", "TITLE");
+    for t in tasks {
+        let flag = if t.escalation_note.is_some() {
+            " ⚠"
+        } else if t.queued_phase.is_some() {
+            " ⧗"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "\n{:<8}  {:<8}  {:<10}  {}{}",
+            short_id(&t.id),
+            t.status.as_str(),
+            t.agent,
+            t.title,
+            flag
+        ));
+    }
+    out
+}
+
+fn format_task_detail(db: &Database, t: &Task) -> String {
+    let mut lines = vec![
+        format!("ID:          {}", t.id),
+        format!("Title:       {}", t.title),
+        format!("Status:      {}", t.status.as_str()),
+        format!("Agent:       {}", t.agent),
+        format!("Priority:    {}", t.priority.as_str()),
+    ];
+    if !t.labels.is_empty() {
+        lines.push(format!("Labels:      {}", t.labels.join(", ")));
+    }
+    if let Some(due) = t.due_date {
+        lines.push(format!("Due:         {}", due));
+    }
+    let optional = [
+        ("Plugin:     ", &t.plugin),
+        ("Branch:     ", &t.branch_name),
+        ("Base branch:", &t.base_branch),
+        ("Worktree:   ", &t.worktree_path),
+        ("Session:    ", &t.session_name),
+        ("PR:         ", &t.pr_url),
+        ("Depends on: ", &t.referenced_tasks),
+        ("Parent:     ", &t.parent_id),
+        ("Escalation: ", &t.escalation_note),
+        ("Queued for: ", &t.queued_phase),
+    ];
+    for (label, value) in optional {
+        if let Some(v) = value {
+            lines.push(format!("{} {}", label, v));
+        }
+    }
+    if !db.deps_satisfied(t) {
+        lines.push("Blocked:     dependencies not in Review/Done".to_string());
+    }
+    if let Some(progress) = db.subtask_progress(t).ok().flatten() {
+        lines.push(format!(
+            "Subtasks:    {}/{} done",
+            progress.done, progress.total
+        ));
+    }
+    lines.push(format!("Created:     {}", t.created_at.to_rfc3339()));
+    lines.push(format!("Updated:     {}", t.updated_at.to_rfc3339()));
+    if let Some(desc) = &t.description {
+        lines.push(String::new());
+        lines.push(desc.clone());
+    }
+    lines.join("\n")
+}
+
+// === Stats ===
+
+pub const STATS_USAGE: &str = "\
+Usage: agtx stats [options]
+
+Show lead time, time per phase (including idle time), tasks finished per week
+and review loops per plugin and agent, averaged over a project's finished tasks.
+
+Options:
+  --weeks <n>            Weeks of throughput to show (default: 8)
+  --project <path>       Project directory (default: current directory)
+  --json                 Print JSON instead of a report";
+
+/// Parsed `agtx stats` invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsArgs {
+    pub weeks: usize,
+    pub json: bool,
+    pub project: Option<PathBuf>,
+}
+
+/// Parse the arguments following `agtx stats`.
+pub fn parse_stats_args(args: &[String]) -> Result<StatsArgs> {
+    let mut parsed = StatsArgs {
+        weeks: crate::stats::DEFAULT_WEEKS,
+        json: false,
+        project: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| -> Result<String> {
+            iter.next()
+                .cloned()
+                .with_context(|| format!("{} requires a value", name))
+        };
+        match arg.as_str() {
+            "--json" => parsed.json = true,
+            "--experimental" | "--no-init-scripts" => {}
+            "--project" => parsed.project = Some(PathBuf::from(value(arg)?)),
+            "--weeks" => {
+                let weeks = value(arg)?;
+                parsed.weeks = weeks
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .with_context(|| format!("Invalid --weeks '{}'", weeks))?;
+            }
+            other => anyhow::bail!("Unknown argument: {}\n\n{}", other, STATS_USAGE),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Entry point for `agtx stats`.
+pub fn run_stats(args: &[String]) -> Result<()> {
+    let args = parse_stats_args(args)?;
+    let project_path = project_dir(args.project.as_deref(), "agtx stats")?;
+    let db = Database::open_project(&project_path)?;
+    println!("{}", stats_report(&db, &args)?);
+    Ok(())
+}
+
+/// Compute the project's stats and render them as text or JSON.
+pub fn stats_report(db: &Database, args: &StatsArgs) -> Result<String> {
+    let stats = crate::stats::compute(
+        &db.get_all_tasks()?,
+        &db.get_all_task_events()?,
+        chrono::Utc::now(),
+        args.weeks,
+    );
+    if args.json {
+        to_json(&stats)
+    } else {
+        Ok(crate::stats::format_stats(&stats).join("\n"))
+    }
+}
+
+// === Database Maintenance ===
+
+pub const DB_USAGE: &str = "\
+Usage: agtx db migrate [options]
+
+Apply pending schema migrations to the global database and, when run inside a
+git repository (or with --project), to that project's database.
+
+Options:
+  --dry-run              List pending migrations without applying them
+  --project <path>       Project directory (default: current directory)";
+
+/// Parsed `agtx db migrate` invocation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DbArgs {
+    pub dry_run: bool,
+    pub project: Option<PathBuf>,
+}
+
+/// Parse the arguments following `agtx db`.
+pub fn parse_db_args(args: &[String]) -> Result<DbArgs> {
+    let mut parsed = DbArgs::default();
+    let mut sub = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dry-run" => parsed.dry_run = true,
+            "--experimental" | "--no-init-scripts" => {}
+            "--project" => {
+                let value = iter.next().context("--project requires a value")?;
+                parsed.project = Some(PathBuf::from(value));
+            }
+            other if other.starts_with("--") => anyhow::bail!("Unknown option: {}", other),
+            other if sub.is_none() => sub = Some(other.to_string()),
+            other => anyhow::bail!("Unexpected argument: {}", other),
+        }
+    }
+    match sub.as_deref() {
+        Some("migrate") => Ok(parsed),
+        Some(other) => anyhow::bail!("Unknown db command: {}\n\n{}", other, DB_USAGE),
+        None => anyhow::bail!("{}", DB_USAGE),
+    }
+}
+
+/// Entry point for `agtx db ...`.
+pub fn run_db(args: &[String]) -> Result<()> {
+    let args = parse_db_args(args)?;
+
+    let project_path = match &args.project {
+        Some(p) => {
+            let path = p
+                .canonicalize()
+                .with_context(|| format!("Project not found: {}", p.display()))?;
+            if !crate::git::is_git_repo(&path) {
+                anyhow::bail!("{} is not a git project directory", path.display());
+            }
+            Some(path)
+        }
+        None => std::env::current_dir()?
+            .canonicalize()
+            .ok()
+            .filter(|p| crate::git::is_git_repo(p)),
+    };
+
+    let global_path = Database::global_db_path()?;
+    let status = Database::migration_status(&global_path, SchemaKind::Global)?;
+    if !args.dry_run {
+        Database::open_global()?;
+    }
+    println!(
+        "{}",
+        format_migration_status(&status, &global_path, args.dry_run)
+    );
+
+    if let Some(project_path) = project_path {
+        let db_path = Database::project_db_path(&project_path)?;
+        let status = Database::migration_status(&db_path, SchemaKind::Project)?;
+        if !args.dry_run {
+            Database::open_project(&project_path)?;
+        }
+        println!(
+            "{}",
+            format_migration_status(&status, &db_path, args.dry_run)
+        );
+    }
+    Ok(())
+}
+
+/// Describe the migrations in `status` as pending (dry run) or applied.
+pub fn format_migration_status(status: &MigrationStatus, db_path: &Path, dry_run: bool) -> String {
+    let header = format!("{} database ({})", status.kind.as_str(), db_path.display());
+    if status.pending.is_empty() {
+        return format!(
+            "{}: up to date at schema version {}",
+            header, status.current_version
+        );
+    }
+    let verb = if dry_run { "would migrate" } else { "migrated" };
+    let mut out = format!(
+        "{}: {} from schema version {} to {}",
+        header, verb, status.current_version, status.latest_version
+    );
+    for m in &status.pending {
+        out.push_str(&format!("\n  {:>3}  {}", m.version, m.description));
+    }
+    out
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Global configuration (stored in ~/.config/agtx/)
@@ -24,6 +25,273 @@ pub struct GlobalConfig {
     /// Whether to automatically fullscreen-attach to the tmux session when opening a task popup
     #[serde(default)]
     pub fullscreen_on_enter: bool,
+
+    /// User-defined agents (`[[custom_agents]]`), available alongside the built-ins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_agents: Vec<CustomAgentConfig>,
+
+    /// How Review → Done finishes a task (default: pull requests)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_strategy: Option<FinishStrategy>,
+
+    /// Key chords for named actions (`[keybindings]`), e.g. `move_forward = "n"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keybindings: BTreeMap<String, KeyBinding>,
+
+    /// Caps on agents working at once in each project (`[max_agents_per_project]`)
+    #[serde(default, skip_serializing_if = "AgentLimits::is_empty")]
+    pub max_agents_per_project: AgentLimits,
+
+    /// Terminal multiplexer hosting agent sessions (default: tmux)
+    #[serde(default)]
+    pub session_backend: SessionBackend,
+
+    /// Sandbox that task agents run in (`[sandbox]`)
+    #[serde(default, skip_serializing_if = "SandboxConfig::is_empty")]
+    pub sandbox: SandboxConfig,
+
+    /// MCP permission profiles (`[mcp_roles.<name>]`) for `agtx mcp-serve --role`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mcp_roles: BTreeMap<String, McpRoleConfig>,
+}
+
+/// A user-defined MCP role. Unset fields don't restrict anything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpRoleConfig {
+    /// Tools the role may call. Names of built-in roles stand for their tools,
+    /// e.g. `["readonly", "create_task"]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+
+    /// Projects the role may use, by ID, name or path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<String>>,
+}
+
+/// Terminal multiplexer that agent sessions run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionBackend {
+    /// Windows on the dedicated `agtx` tmux server
+    #[default]
+    Tmux,
+    /// Tabs in one zellij session per project
+    Zellij,
+    /// Built-in pseudo-terminals owned by `agtx session-host`
+    Pty,
+}
+
+/// Sandbox for task agents. Everything outside the task worktree (and the
+/// repository's git directory, so agents can commit) is read-only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// Run task agents inside the sandbox (default: off)
+    pub enabled: Option<bool>,
+    /// Sandboxing tool (default: bubblewrap)
+    pub tool: Option<SandboxTool>,
+    /// Allow network access (default: on, agents need their model APIs)
+    pub network: Option<bool>,
+    /// Extra paths the agent may write; `~` is the home directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable: Vec<String>,
+    /// Paths hidden from the agent entirely, e.g. `~/.ssh`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden: Vec<String>,
+}
+
+impl SandboxConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn tool(&self) -> SandboxTool {
+        self.tool.unwrap_or_default()
+    }
+
+    pub fn allows_network(&self) -> bool {
+        self.network.unwrap_or(true)
+    }
+
+    /// Field-wise override: values set here win over `fallback`; path lists
+    /// are combined
+    pub fn or(self, fallback: &SandboxConfig) -> SandboxConfig {
+        let mut writable = fallback.writable.clone();
+        writable.extend(self.writable);
+        let mut hidden = fallback.hidden.clone();
+        hidden.extend(self.hidden);
+        SandboxConfig {
+            enabled: self.enabled.or(fallback.enabled),
+            tool: self.tool.or(fallback.tool),
+            network: self.network.or(fallback.network),
+            writable,
+            hidden,
+        }
+    }
+}
+
+/// Program used to sandbox agents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxTool {
+    /// `bwrap`, an unprivileged mount namespace
+    #[default]
+    Bubblewrap,
+    /// `firejail`
+    Firejail,
+}
+
+impl SandboxTool {
+    /// Executable to look for on PATH
+    pub fn binary(&self) -> &'static str {
+        match self {
+            SandboxTool::Bubblewrap => "bwrap",
+            SandboxTool::Firejail => "firejail",
+        }
+    }
+}
+
+/// How many agents may work at once in a project, in total and per phase.
+/// Each project counts only its own agents. Unset or 0 means no limit. Tasks over a limit are queued and started when
+/// a slot frees up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentLimits {
+    pub total: Option<usize>,
+    pub research: Option<usize>,
+    pub planning: Option<usize>,
+    pub running: Option<usize>,
+}
+
+impl AgentLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Limit across all phases, if any
+    pub fn total(&self) -> Option<usize> {
+        self.total.filter(|n| *n > 0)
+    }
+
+    /// Limit for "research", "planning" or "running", if any
+    pub fn for_phase(&self, phase: &str) -> Option<usize> {
+        match phase {
+            "research" => self.research,
+            "planning" => self.planning,
+            "running" => self.running,
+            _ => None,
+        }
+        .filter(|n| *n > 0)
+    }
+
+    /// Field-wise override: values set here win over `fallback`
+    pub fn or(self, fallback: AgentLimits) -> AgentLimits {
+        AgentLimits {
+            total: self.total.or(fallback.total),
+            research: self.research.or(fallback.research),
+            planning: self.planning.or(fallback.planning),
+            running: self.running.or(fallback.running),
+        }
+    }
+}
+
+/// Spending limits for one task or a whole project. Unset or 0 means no limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetLimits {
+    /// Input, output and cache tokens combined
+    pub tokens: Option<u64>,
+    /// Estimated cost in US dollars
+    pub usd: Option<f64>,
+    /// Minutes agents have spent in Planning and Running
+    pub minutes: Option<u64>,
+}
+
+impl BudgetLimits {
+    pub fn is_empty(&self) -> bool {
+        [self.tokens, self.minutes]
+            .iter()
+            .all(|l| l.unwrap_or(0) == 0)
+            && self.usd.unwrap_or(0.0) <= 0.0
+    }
+
+    /// Describe the first limit that has been reached, if any
+    pub fn exceeded(&self, tokens: u64, usd: f64, minutes: u64) -> Option<String> {
+        if let Some(limit) = self.usd.filter(|l| *l > 0.0 && usd >= *l) {
+            return Some(format!("${:.2} of ${:.2}", usd, limit));
+        }
+        if let Some(limit) = self.tokens.filter(|l| *l > 0 && tokens >= *l) {
+            return Some(format!("{} of {} tokens", tokens, limit));
+        }
+        if let Some(limit) = self.minutes.filter(|l| *l > 0 && minutes >= *l) {
+            return Some(format!("{} of {} minutes", minutes, limit));
+        }
+        None
+    }
+}
+
+/// Budget guardrails. A task over its limit, or every active task once the
+/// project is over its limit, is escalated to the user and the orchestrator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    #[serde(default)]
+    pub task: BudgetLimits,
+    #[serde(default)]
+    pub project: BudgetLimits,
+    /// Also interrupt the agent (send Escape) when it goes over budget
+    #[serde(default)]
+    pub interrupt: bool,
+}
+
+impl BudgetConfig {
+    pub fn is_empty(&self) -> bool {
+        self.task.is_empty() && self.project.is_empty()
+    }
+}
+
+/// One chord (`"C-f"`) or several (`["j", "Down"]`) bound to an action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    pub fn chords(&self) -> &[String] {
+        match self {
+            KeyBinding::One(chord) => std::slice::from_ref(chord),
+            KeyBinding::Many(chords) => chords,
+        }
+    }
+}
+
+/// How a reviewed task lands in its base branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FinishStrategy {
+    /// Open a pull/merge request on the hosting provider
+    #[default]
+    Pr,
+    /// Merge the task branch locally with a merge commit
+    Merge,
+    /// Squash the task branch into one local commit
+    Squash,
+    /// Rebase the task branch onto base and fast-forward
+    Rebase,
+}
+
+impl FinishStrategy {
+    /// The local merge to perform, or None when finishing through a PR
+    pub fn local_merge(&self) -> Option<crate::git::MergeStrategy> {
+        match self {
+            FinishStrategy::Pr => None,
+            FinishStrategy::Merge => Some(crate::git::MergeStrategy::Merge),
+            FinishStrategy::Squash => Some(crate::git::MergeStrategy::Squash),
+            FinishStrategy::Rebase => Some(crate::git::MergeStrategy::Rebase),
+        }
+    }
 }
 
 impl Default for GlobalConfig {
@@ -34,6 +302,13 @@ impl Default for GlobalConfig {
             worktree: WorktreeConfig::default(),
             theme: ThemeConfig::default(),
             fullscreen_on_enter: false,
+            custom_agents: vec![],
+            finish_strategy: None,
+            keybindings: BTreeMap::new(),
+            max_agents_per_project: AgentLimits::default(),
+            session_backend: SessionBackend::default(),
+            sandbox: SandboxConfig::default(),
+            mcp_roles: BTreeMap::new(),
         }
     }
 }
@@ -157,6 +432,151 @@ pub struct PhaseAgentsConfig {
     pub review: Option<String>,
 }
 
+/// A coding agent declared in config instead of being built into agtx.
+///
+/// Command templates are plain shell strings. `{prompt}` is the only placeholder
+/// for `interactive_prompt` and `print`; `orchestrator` additionally understands
+/// `{mcp_json}`, `{agtx_bin}` and `{interactive}`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct CustomAgentConfig {
+    /// Agent name used by `default_agent` and `[agents]` (e.g. "aider")
+    pub name: String,
+
+    /// Binary looked up on PATH to decide whether the agent is installed
+    pub command: String,
+
+    /// One-line description shown in agent selection
+    pub description: Option<String>,
+
+    /// Command to start the agent with no initial message (defaults to `command`)
+    pub interactive: Option<String>,
+
+    /// Command to start the agent with an initial message; `{prompt}` is replaced
+    /// with the single-quoted prompt (defaults to `<interactive> '<prompt>'`)
+    pub interactive_prompt: Option<String>,
+
+    /// Non-interactive command used to generate text such as PR descriptions;
+    /// `{prompt}` is passed as a single argument (defaults to `<command> <prompt>`)
+    pub print: Option<String>,
+
+    /// Command to resume the most recent session in the worktree (defaults to `interactive`)
+    pub resume: Option<String>,
+
+    /// Command to run the agent as orchestrator (defaults to `interactive`, no MCP)
+    pub orchestrator: Option<String>,
+
+    /// Co-author trailer for commits (defaults to "<name> <noreply@agtx>")
+    pub co_author: Option<String>,
+
+    /// Where and how agtx skills are deployed for this agent
+    pub skills: Option<CustomAgentSkills>,
+}
+
+/// Skill directory layout for a custom agent.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct CustomAgentSkills {
+    /// Directory relative to the worktree (e.g. ".aider/commands")
+    pub dir: String,
+
+    /// Namespace subdirectory inside `dir` (e.g. "agtx"); empty for a flat layout
+    #[serde(default)]
+    pub namespace: String,
+
+    /// Built-in agent whose file format and command syntax this agent follows
+    /// ("claude", "codex", "copilot", "gemini", "opencode" or "cursor")
+    #[serde(default = "default_agent")]
+    pub style: String,
+}
+
+/// Built-in agent names a custom agent may not shadow and may use as skill style.
+pub const BUILTIN_AGENT_NAMES: &[&str] =
+    &["claude", "codex", "copilot", "gemini", "opencode", "cursor"];
+
+impl CustomAgentConfig {
+    /// Check a single entry for problems that would only surface later as
+    /// confusing tmux or git failures.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            anyhow::bail!("Custom agent name must not be empty");
+        }
+        if !self
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!(
+                "Custom agent name '{}' contains invalid characters (only a-z, A-Z, 0-9, -, _ allowed)",
+                self.name
+            );
+        }
+        if BUILTIN_AGENT_NAMES.contains(&self.name.as_str()) {
+            anyhow::bail!(
+                "Custom agent '{}' conflicts with a built-in agent",
+                self.name
+            );
+        }
+        if self.command.trim().is_empty() {
+            anyhow::bail!("Custom agent '{}' has an empty command", self.name);
+        }
+        for (field, template) in [
+            ("interactive_prompt", &self.interactive_prompt),
+            ("print", &self.print),
+        ] {
+            if let Some(t) = template {
+                if !t.contains("{prompt}") {
+                    anyhow::bail!(
+                        "Custom agent '{}': {} must contain {{prompt}}",
+                        self.name,
+                        field
+                    );
+                }
+            }
+        }
+        if let Some(print) = &self.print {
+            if shell_words::split(print).is_err() {
+                anyhow::bail!("Custom agent '{}': print has unbalanced quotes", self.name);
+            }
+        }
+        if let Some(skills) = &self.skills {
+            let dir = Path::new(&skills.dir);
+            if skills.dir.is_empty()
+                || dir.is_absolute()
+                || dir
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+                || skills.namespace.contains('/')
+                || skills.namespace.contains("..")
+            {
+                anyhow::bail!(
+                    "Custom agent '{}': skills.dir must be a relative path inside the worktree",
+                    self.name
+                );
+            }
+            if !BUILTIN_AGENT_NAMES.contains(&skills.style.as_str()) {
+                anyhow::bail!(
+                    "Custom agent '{}': unknown skills.style '{}' (expected one of {})",
+                    self.name,
+                    skills.style,
+                    BUILTIN_AGENT_NAMES.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate a full `[[custom_agents]]` list, including duplicate names.
+    pub fn validate_all(agents: &[CustomAgentConfig]) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for agent in agents {
+            agent.validate()?;
+            if !seen.insert(agent.name.as_str()) {
+                anyhow::bail!("Custom agent '{}' is declared more than once", agent.name);
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Worktree configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorktreeConfig {
@@ -196,6 +616,14 @@ fn default_true() -> bool {
     true
 }
 
+/// A saved board filter (`[[views]]` in .agtx/config.toml)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct BoardView {
+    pub name: String,
+    /// Filter query, e.g. "label:bug is:idle"
+    pub filter: String,
+}
+
 /// Project-specific configuration (stored in .agtx/config.toml)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectConfig {
@@ -211,6 +639,15 @@ pub struct ProjectConfig {
     /// GitHub URL for this project
     pub github_url: Option<String>,
 
+    /// Hosting provider for PRs/MRs ("github" or "gitlab"); detected from the origin remote if unset
+    pub git_provider: Option<crate::git::GitProviderKind>,
+
+    /// GitLab instance URL, when the API isn't served from the origin remote's host
+    pub gitlab_url: Option<String>,
+
+    /// Send GITLAB_TOKEN to an `http://` instance; refused by default
+    pub gitlab_allow_http: Option<bool>,
+
     /// Directory (relative to project root) where worktrees are created
     pub worktree_dir: Option<String>,
 
@@ -225,6 +662,26 @@ pub struct ProjectConfig {
 
     /// Workflow plugin name (e.g. "gsd", "spec-kit")
     pub workflow_plugin: Option<String>,
+
+    /// Project-specific custom agents; entries replace global ones with the same name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_agents: Vec<CustomAgentConfig>,
+
+    /// Override how Review → Done finishes tasks in this project
+    pub finish_strategy: Option<FinishStrategy>,
+
+    /// Override agent limits for this project, field by field
+    pub max_agents_per_project: Option<AgentLimits>,
+
+    /// Sandbox profile for this project; set fields override the global `[sandbox]`
+    pub sandbox: Option<SandboxConfig>,
+
+    /// Token, cost and time limits for tasks in this project
+    pub budget: Option<BudgetConfig>,
+
+    /// Named board filters, cycled with `v` on the board
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub views: Vec<BoardView>,
 }
 
 impl GlobalConfig {
@@ -350,6 +807,12 @@ pub struct MergedConfig {
     pub cleanup_script: Option<String>,
     pub workflow_plugin: Option<String>,
     pub fullscreen_on_enter: bool,
+    pub custom_agents: Vec<CustomAgentConfig>,
+    pub finish_strategy: FinishStrategy,
+    pub agent_limits: AgentLimits,
+    pub sandbox: SandboxConfig,
+    pub budget: BudgetConfig,
+    pub views: Vec<BoardView>,
 }
 
 impl MergedConfig {
@@ -384,6 +847,22 @@ impl MergedConfig {
             cleanup_script: project.cleanup_script.clone(),
             workflow_plugin: project.workflow_plugin.clone(),
             fullscreen_on_enter: global.fullscreen_on_enter,
+            custom_agents: merge_custom_agents(&global.custom_agents, &project.custom_agents),
+            finish_strategy: project
+                .finish_strategy
+                .or(global.finish_strategy)
+                .unwrap_or_default(),
+            agent_limits: project
+                .max_agents_per_project
+                .unwrap_or_default()
+                .or(global.max_agents_per_project),
+            sandbox: project
+                .sandbox
+                .clone()
+                .unwrap_or_default()
+                .or(&global.sandbox),
+            budget: project.budget.unwrap_or_default(),
+            views: project.views.clone(),
         }
     }
 
@@ -407,6 +886,21 @@ impl MergedConfig {
     }
 }
 
+/// Merge custom agent lists: project entries replace global entries of the same
+/// name, new project entries are appended.
+fn merge_custom_agents(
+    global: &[CustomAgentConfig],
+    project: &[CustomAgentConfig],
+) -> Vec<CustomAgentConfig> {
+    let mut merged: Vec<CustomAgentConfig> = global
+        .iter()
+        .filter(|g| !project.iter().any(|p| p.name == g.name))
+        .cloned()
+        .collect();
+    merged.extend(project.iter().cloned());
+    merged
+}
+
 /// Workflow plugin configuration loaded from plugin.toml
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowPlugin {
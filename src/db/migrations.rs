@@ -0,0 +1,576 @@
+//! Numbered schema migrations for the project and global databases.
+//!
+//! Each database records applied migrations in `schema_version`. Pending
+//! migrations run in order, each inside its own transaction together with its
+//! `schema_version` row, so a failure leaves the database at the last fully
+//! applied version. The transactions take the write lock up front and re-check
+//! the version, so processes opening the same database at once (TUI, daemon,
+//! MCP server) apply each migration exactly once. Databases created before versioning are adopted: the
+//! original tables are created `IF NOT EXISTS` and column additions are skipped
+//! when the column is already present.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+
+/// Which database a migration set belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    Project,
+    Global,
+}
+
+impl SchemaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaKind::Project => "project",
+            SchemaKind::Global => "global",
+        }
+    }
+
+    fn migrations(&self) -> &'static [Migration] {
+        match self {
+            SchemaKind::Project => PROJECT_MIGRATIONS,
+            SchemaKind::Global => GLOBAL_MIGRATIONS,
+        }
+    }
+
+    /// Highest schema version this build knows about.
+    pub fn latest_version(&self) -> u32 {
+        self.migrations().last().map_or(0, |m| m.version)
+    }
+}
+
+pub(crate) enum Step {
+    Sql(&'static str),
+    /// `ALTER TABLE .. ADD COLUMN`, skipped if the column already exists
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+}
+
+pub(crate) struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub steps: &'static [Step],
+}
+
+const PROJECT_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create tasks table",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS tasks (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT,
+                status TEXT NOT NULL DEFAULT 'backlog',
+                agent TEXT NOT NULL,
+                project_id TEXT NOT NULL,
+                session_name TEXT,
+                worktree_path TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+            CREATE INDEX IF NOT EXISTS idx_tasks_project ON tasks(project_id);
+            "#,
+        )],
+    },
+    Migration {
+        version: 2,
+        description: "Add branch, PR, plugin and cycle columns to tasks",
+        steps: &[
+            Step::AddColumn {
+                table: "tasks",
+                column: "branch_name",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "pr_number",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "pr_url",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "plugin",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "cycle",
+                definition: "INTEGER NOT NULL DEFAULT 1",
+            },
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "Add dependency, escalation and base branch columns to tasks",
+        steps: &[
+            Step::AddColumn {
+                table: "tasks",
+                column: "referenced_tasks",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "escalation_note",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "base_branch",
+                definition: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 4,
+        description: "Create MCP transition request queue and notifications",
+        steps: &[
+            Step::Sql(
+                r#"
+                CREATE TABLE IF NOT EXISTS transition_requests (
+                    id TEXT PRIMARY KEY,
+                    task_id TEXT NOT NULL,
+                    action TEXT NOT NULL,
+                    requested_at TEXT NOT NULL,
+                    processed_at TEXT,
+                    error TEXT
+                );
+
+                CREATE TABLE IF NOT EXISTS notifications (
+                    id TEXT PRIMARY KEY,
+                    message TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+                "#,
+            ),
+            Step::AddColumn {
+                table: "transition_requests",
+                column: "reason",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "transition_requests",
+                column: "claimed_by",
+                definition: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "Create task_events history table",
+        steps: &[
+            Step::AddColumn {
+                table: "transition_requests",
+                column: "requested_by",
+                definition: "TEXT",
+            },
+            Step::Sql(
+                r#"
+                CREATE TABLE IF NOT EXISTS task_events (
+                    id TEXT PRIMARY KEY,
+                    task_id TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    from_value TEXT,
+                    to_value TEXT,
+                    actor TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_task_events_task ON task_events(task_id, created_at);
+                "#,
+            ),
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "Add concurrency queue columns to tasks",
+        steps: &[
+            Step::AddColumn {
+                table: "tasks",
+                column: "queued_phase",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "queued_at",
+                definition: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 7,
+        description: "Create task_usage table",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS task_usage (
+                task_id TEXT NOT NULL,
+                phase TEXT NOT NULL,
+                agent TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (task_id, phase, agent)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 8,
+        description: "Add priority, due date and labels to tasks",
+        steps: &[
+            Step::AddColumn {
+                table: "tasks",
+                column: "priority",
+                definition: "TEXT NOT NULL DEFAULT 'normal'",
+            },
+            Step::AddColumn {
+                table: "tasks",
+                column: "due_date",
+                definition: "TEXT",
+            },
+            Step::Sql(
+                r#"
+                CREATE TABLE IF NOT EXISTS labels (
+                    id INTEGER PRIMARY KEY,
+                    name TEXT NOT NULL UNIQUE
+                );
+
+                CREATE TABLE IF NOT EXISTS task_labels (
+                    task_id TEXT NOT NULL,
+                    label_id INTEGER NOT NULL REFERENCES labels(id),
+                    PRIMARY KEY (task_id, label_id)
+                );
+                "#,
+            ),
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "Add parent task to tasks",
+        steps: &[
+            Step::AddColumn {
+                table: "tasks",
+                column: "parent_id",
+                definition: "TEXT",
+            },
+            Step::Sql("CREATE INDEX IF NOT EXISTS idx_tasks_parent_id ON tasks(parent_id);"),
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "Move task dependencies into task_dependencies",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS task_dependencies (
+                task_id TEXT NOT NULL,
+                depends_on TEXT NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (task_id, depends_on)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_task_dependencies_depends_on ON task_dependencies(depends_on);
+
+            INSERT OR IGNORE INTO task_dependencies (task_id, depends_on, position)
+            WITH RECURSIVE split(task_id, depends_on, rest, position) AS (
+                SELECT id, '', referenced_tasks || ',', -1 FROM tasks
+                WHERE referenced_tasks IS NOT NULL AND referenced_tasks != ''
+                UNION ALL
+                SELECT task_id,
+                       trim(substr(rest, 1, instr(rest, ',') - 1)),
+                       substr(rest, instr(rest, ',') + 1),
+                       position + 1
+                FROM split WHERE rest != ''
+            )
+            SELECT task_id, depends_on, position FROM split WHERE depends_on != '';
+
+            UPDATE tasks SET referenced_tasks = NULL;
+            "#,
+        )],
+    },
+    Migration {
+        version: 11,
+        description: "Add task_phase_status",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS task_phase_status (
+                task_id TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            "#,
+        )],
+    },
+];
+
+const GLOBAL_MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "Create projects and running_agents tables",
+    steps: &[Step::Sql(
+        r#"
+        CREATE TABLE IF NOT EXISTS projects (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            path TEXT NOT NULL UNIQUE,
+            github_url TEXT,
+            default_agent TEXT,
+            last_opened TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS running_agents (
+            session_name TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            task_id TEXT NOT NULL,
+            agent_name TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            status TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_running_project ON running_agents(project_id);
+        "#,
+    )],
+}];
+
+/// A migration that has not been applied yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingMigration {
+    pub version: u32,
+    pub description: &'static str,
+}
+
+/// Schema state of a database relative to this build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub kind: SchemaKind,
+    pub current_version: u32,
+    pub latest_version: u32,
+    pub pending: Vec<PendingMigration>,
+}
+
+/// Highest applied version, or 0 for a database that predates versioning.
+pub(crate) fn current_version(conn: &Connection) -> Result<u32> {
+    let has_table: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_table {
+        return Ok(0);
+    }
+    let version: Option<u32> =
+        conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+            row.get(0)
+        })?;
+    Ok(version.unwrap_or(0))
+}
+
+fn check_not_newer(current: u32, kind: SchemaKind) -> Result<()> {
+    let latest = kind.latest_version();
+    if current > latest {
+        anyhow::bail!(
+            "The {} database was written by a newer version of agtx (schema version {}, this build supports up to {}). Upgrade agtx to open it.",
+            kind.as_str(),
+            current,
+            latest
+        );
+    }
+    Ok(())
+}
+
+/// Report which migrations would run, without changing the database.
+pub(crate) fn status(conn: &Connection, kind: SchemaKind) -> Result<MigrationStatus> {
+    status_for_version(kind, current_version(conn)?)
+}
+
+pub(crate) fn status_for_version(kind: SchemaKind, current: u32) -> Result<MigrationStatus> {
+    check_not_newer(current, kind)?;
+    Ok(MigrationStatus {
+        kind,
+        current_version: current,
+        latest_version: kind.latest_version(),
+        pending: kind
+            .migrations()
+            .iter()
+            .filter(|m| m.version > current)
+            .map(|m| PendingMigration {
+                version: m.version,
+                description: m.description,
+            })
+            .collect(),
+    })
+}
+
+/// Bring the database up to the latest schema. Returns the migrations applied.
+pub(crate) fn migrate(conn: &Connection, kind: SchemaKind) -> Result<Vec<PendingMigration>> {
+    run_migrations(conn, kind, kind.migrations())
+}
+
+fn run_migrations(
+    conn: &Connection,
+    kind: SchemaKind,
+    migrations: &[Migration],
+) -> Result<Vec<PendingMigration>> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        );",
+    )?;
+    let current = current_version(conn)?;
+    check_not_newer(current, kind)?;
+
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        if !apply_migration(conn, kind, migration)? {
+            continue;
+        }
+        tracing::info!(
+            database = kind.as_str(),
+            version = migration.version,
+            description = migration.description,
+            "Applied schema migration"
+        );
+        applied.push(PendingMigration {
+            version: migration.version,
+            description: migration.description,
+        });
+    }
+    Ok(applied)
+}
+
+/// Apply one migration in an immediate transaction. Returns false without
+/// changing anything if another process applied it since `current` was read.
+fn apply_migration(conn: &Connection, kind: SchemaKind, migration: &Migration) -> Result<bool> {
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    if current_version(&tx)? >= migration.version {
+        return Ok(false);
+    }
+    for step in migration.steps {
+        apply_step(&tx, step).with_context(|| {
+            format!(
+                "{} database migration {} ({}) failed",
+                kind.as_str(),
+                migration.version,
+                migration.description
+            )
+        })?;
+    }
+    tx.execute(
+        "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+        params![
+            migration.version,
+            migration.description,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )?;
+    tx.commit()?;
+    Ok(true)
+}
+
+fn apply_step(conn: &Connection, step: &Step) -> Result<()> {
+    match step {
+        Step::Sql(sql) => conn.execute_batch(sql)?,
+        Step::AddColumn {
+            table,
+            column,
+            definition,
+        } => {
+            if !column_exists(conn, table, column)? {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table, column, definition
+                ))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>("name"))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(names.iter().any(|n| n == column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for kind in [SchemaKind::Project, SchemaKind::Global] {
+            for (i, m) in kind.migrations().iter().enumerate() {
+                assert_eq!(m.version as usize, i + 1, "{} migrations", kind.as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        const BROKEN: &[Migration] = &[
+            Migration {
+                version: 1,
+                description: "ok",
+                steps: &[Step::Sql("CREATE TABLE a (id TEXT);")],
+            },
+            Migration {
+                version: 2,
+                description: "broken",
+                steps: &[
+                    Step::Sql("CREATE TABLE b (id TEXT);"),
+                    Step::Sql("THIS IS NOT SQL;"),
+                ],
+            },
+        ];
+        let conn = Connection::open_in_memory().unwrap();
+
+        let err = run_migrations(&conn, SchemaKind::Project, BROKEN).unwrap_err();
+        assert!(format!("{:#}", err).contains("migration 2 (broken) failed"));
+
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        let b_exists: bool = conn
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'b')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!b_exists, "partial migration must be rolled back");
+    }
+
+    #[test]
+    fn test_migration_applied_elsewhere_is_skipped() {
+        const CREATE: &[Migration] = &[Migration {
+            version: 1,
+            description: "create",
+            steps: &[Step::Sql("CREATE TABLE a (id TEXT);")],
+        }];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agtx.db");
+        let first = Connection::open(&path).unwrap();
+        let second = Connection::open(&path).unwrap();
+
+        run_migrations(&first, SchemaKind::Project, CREATE).unwrap();
+        // `second` read version 0 before `first` migrated; applying again
+        // must notice the new version instead of failing on CREATE TABLE
+        assert!(!apply_migration(&second, SchemaKind::Project, &CREATE[0]).unwrap());
+        assert_eq!(current_version(&second).unwrap(), 1);
+    }
+}
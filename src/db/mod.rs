@@ -1,5 +1,7 @@
+mod migrations;
 mod models;
 mod schema;
 
+pub use migrations::{MigrationStatus, PendingMigration, SchemaKind};
 pub use models::*;
 pub use schema::Database;
@@ -1,5 +1,5 @@
 use crate::tmux::safe_session_name;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Task status in the kanban board
@@ -55,6 +55,40 @@ impl TaskStatus {
     }
 }
 
+/// Task priority; higher priorities sort to the top of their column
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "low" => Some(Priority::Low),
+            "normal" => Some(Priority::Normal),
+            "high" => Some(Priority::High),
+            "urgent" => Some(Priority::Urgent),
+            _ => None,
+        }
+    }
+}
+
 /// A task on the kanban board
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -72,8 +106,22 @@ pub struct Task {
     pub plugin: Option<String>,
     pub cycle: i32,
     pub referenced_tasks: Option<String>,
+    /// Parent task when this is a subtask
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub escalation_note: Option<String>,
     pub base_branch: Option<String>,
+    /// Phase the task is waiting to enter because an agent limit was reached
+    /// ("research", "planning" or "running")
+    pub queued_phase: Option<String>,
+    pub queued_at: Option<DateTime<Utc>>,
+    /// Sorted, without duplicates
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -101,13 +149,58 @@ impl Task {
             plugin: None,
             cycle: 1,
             referenced_tasks: None,
+            parent_id: None,
             escalation_note: None,
             base_branch: None,
+            queued_phase: None,
+            queued_at: None,
+            labels: Vec::new(),
+            priority: Priority::Normal,
+            due_date: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Replace the labels. Names are trimmed and lose a leading `#`; empty
+    /// names and duplicates are dropped.
+    pub fn set_labels<I, S>(&mut self, labels: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut labels: Vec<String> = labels
+            .into_iter()
+            .map(|l| l.as_ref().trim().trim_start_matches('#').trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+        labels.sort();
+        labels.dedup();
+        self.labels = labels;
+    }
+
+    /// Make this task a subtask of `parent`. It runs with the parent's plugin
+    /// and base branch unless it has its own.
+    pub fn set_parent(&mut self, parent: &Task) {
+        self.parent_id = Some(parent.id.clone());
+        if self.plugin.is_none() {
+            self.plugin = parent.plugin.clone();
+        }
+        if self.base_branch.is_none() {
+            self.base_branch = parent.base_branch.clone();
+        }
+    }
+
+    /// IDs of the tasks this one depends on, in the order they were given
+    pub fn dependency_ids(&self) -> impl Iterator<Item = &str> {
+        self.referenced_tasks
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+    }
+
     /// Returns the task description if present, otherwise the title.
     pub fn content_text(&self) -> String {
         self.description
@@ -132,6 +225,34 @@ impl Task {
     }
 }
 
+/// How many of a parent task's subtasks are Done
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SubtaskProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl SubtaskProgress {
+    /// Progress of `parent_id`'s subtasks among `tasks`, or `None` when it has
+    /// no subtasks
+    pub fn of<'a>(parent_id: &str, tasks: impl IntoIterator<Item = &'a Task>) -> Option<Self> {
+        let mut progress = Self { done: 0, total: 0 };
+        for task in tasks {
+            if task.parent_id.as_deref() == Some(parent_id) {
+                progress.total += 1;
+                if task.status == TaskStatus::Done {
+                    progress.done += 1;
+                }
+            }
+        }
+        (progress.total > 0).then_some(progress)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.done == self.total
+    }
+}
+
 /// A project tracked by agtx
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -164,6 +285,8 @@ pub struct TransitionRequest {
     pub task_id: String,
     pub action: String,
     pub reason: Option<String>,
+    /// Who queued the request; events caused by executing it are attributed to them.
+    pub requested_by: Option<EventActor>,
     pub requested_at: DateTime<Utc>,
     pub processed_at: Option<DateTime<Utc>>,
     pub error: Option<String>,
@@ -176,6 +299,7 @@ impl TransitionRequest {
             task_id: task_id.into(),
             action: action.into(),
             reason: None,
+            requested_by: None,
             requested_at: Utc::now(),
             processed_at: None,
             error: None,
@@ -183,6 +307,325 @@ impl TransitionRequest {
     }
 }
 
+/// Who caused a task event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventActor {
+    /// Interactive TUI user
+    User,
+    /// `agtx task` command line
+    Cli,
+    /// MCP client in global mode (brainstorm/sweep skills, external agents)
+    Mcp,
+    /// Orchestrator agent (project-scoped MCP server)
+    Orchestrator,
+}
+
+impl EventActor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventActor::User => "user",
+            EventActor::Cli => "cli",
+            EventActor::Mcp => "mcp",
+            EventActor::Orchestrator => "orchestrator",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "user" => Some(EventActor::User),
+            "cli" => Some(EventActor::Cli),
+            "mcp" => Some(EventActor::Mcp),
+            "orchestrator" => Some(EventActor::Orchestrator),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of change recorded in a task's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventKind {
+    Created,
+    StatusChanged,
+    AgentSwitched,
+    PrCreated,
+    Escalated,
+    EscalationCleared,
+    CycleAdvanced,
+    Queued,
+    IdleStarted,
+    IdleEnded,
+}
+
+impl TaskEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskEventKind::Created => "created",
+            TaskEventKind::StatusChanged => "status_changed",
+            TaskEventKind::AgentSwitched => "agent_switched",
+            TaskEventKind::PrCreated => "pr_created",
+            TaskEventKind::Escalated => "escalated",
+            TaskEventKind::EscalationCleared => "escalation_cleared",
+            TaskEventKind::CycleAdvanced => "cycle_advanced",
+            TaskEventKind::Queued => "queued",
+            TaskEventKind::IdleStarted => "idle_started",
+            TaskEventKind::IdleEnded => "idle_ended",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "created" => Some(TaskEventKind::Created),
+            "status_changed" => Some(TaskEventKind::StatusChanged),
+            "agent_switched" => Some(TaskEventKind::AgentSwitched),
+            "pr_created" => Some(TaskEventKind::PrCreated),
+            "escalated" => Some(TaskEventKind::Escalated),
+            "escalation_cleared" => Some(TaskEventKind::EscalationCleared),
+            "cycle_advanced" => Some(TaskEventKind::CycleAdvanced),
+            "queued" => Some(TaskEventKind::Queued),
+            "idle_started" => Some(TaskEventKind::IdleStarted),
+            "idle_ended" => Some(TaskEventKind::IdleEnded),
+            _ => None,
+        }
+    }
+}
+
+/// One entry in a task's history (`task_events` table).
+/// Written by `Database::create_task` / `update_task`, so every path that
+/// persists a task change is covered. Idle periods of the agent, which aren't
+/// task changes, are added with `Database::add_task_event`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub id: String,
+    pub task_id: String,
+    pub kind: TaskEventKind,
+    /// Previous value (status, agent, cycle), if the event is a change
+    pub from_value: Option<String>,
+    /// New value (status, agent, cycle, PR URL, escalation note)
+    pub to_value: Option<String>,
+    pub actor: EventActor,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskEvent {
+    pub fn new(task_id: impl Into<String>, kind: TaskEventKind, actor: EventActor) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id: task_id.into(),
+            kind,
+            from_value: None,
+            to_value: None,
+            actor,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Build the events describing the difference between two versions of a task.
+    pub fn diff(old: &Task, new: &Task, actor: EventActor) -> Vec<TaskEvent> {
+        let mut events = Vec::new();
+        let mut push = |kind, from: Option<String>, to: Option<String>| {
+            let mut event = TaskEvent::new(&new.id, kind, actor);
+            event.from_value = from;
+            event.to_value = to;
+            events.push(event);
+        };
+
+        if old.status != new.status {
+            push(
+                TaskEventKind::StatusChanged,
+                Some(old.status.as_str().to_string()),
+                Some(new.status.as_str().to_string()),
+            );
+        }
+        if old.agent != new.agent {
+            push(
+                TaskEventKind::AgentSwitched,
+                Some(old.agent.clone()),
+                Some(new.agent.clone()),
+            );
+        }
+        if old.cycle != new.cycle {
+            push(
+                TaskEventKind::CycleAdvanced,
+                Some(old.cycle.to_string()),
+                Some(new.cycle.to_string()),
+            );
+        }
+        if old.pr_url.is_none() && new.pr_url.is_some() {
+            push(TaskEventKind::PrCreated, None, new.pr_url.clone());
+        }
+        match (&old.escalation_note, &new.escalation_note) {
+            (None, Some(note)) => push(TaskEventKind::Escalated, None, Some(note.clone())),
+            (Some(old_note), Some(note)) if old_note != note => push(
+                TaskEventKind::Escalated,
+                Some(old_note.clone()),
+                Some(note.clone()),
+            ),
+            (Some(old_note), None) => push(
+                TaskEventKind::EscalationCleared,
+                Some(old_note.clone()),
+                None,
+            ),
+            _ => {}
+        }
+        if new.queued_phase.is_some() && old.queued_phase != new.queued_phase {
+            push(TaskEventKind::Queued, None, new.queued_phase.clone());
+        }
+        events
+    }
+
+    /// Human-readable one-line summary, e.g. "planning → running".
+    pub fn summary(&self) -> String {
+        let from = self.from_value.as_deref().unwrap_or("");
+        let to = self.to_value.as_deref().unwrap_or("");
+        match self.kind {
+            TaskEventKind::Created => format!("created in {}", to),
+            TaskEventKind::StatusChanged => format!("{} → {}", from, to),
+            TaskEventKind::AgentSwitched => format!("agent {} → {}", from, to),
+            TaskEventKind::CycleAdvanced => format!("cycle {} → {}", from, to),
+            TaskEventKind::PrCreated => format!("PR opened {}", to),
+            TaskEventKind::Escalated => format!("escalated: {}", to),
+            TaskEventKind::EscalationCleared => "escalation cleared".to_string(),
+            TaskEventKind::Queued => format!("queued for {} (agent limit reached)", to),
+            TaskEventKind::IdleStarted => "agent idle".to_string(),
+            TaskEventKind::IdleEnded => "agent active again".to_string(),
+        }
+    }
+}
+
+/// Time spent in each status, computed from a task's status events.
+/// The current status accrues time up to `now`. Statuses never entered are omitted.
+pub fn status_durations(
+    events: &[TaskEvent],
+    now: DateTime<Utc>,
+) -> Vec<(TaskStatus, chrono::Duration)> {
+    let mut totals: Vec<(TaskStatus, chrono::Duration)> = Vec::new();
+    let mut current: Option<(TaskStatus, DateTime<Utc>)> = None;
+
+    let entered = events.iter().filter_map(|e| match e.kind {
+        TaskEventKind::Created | TaskEventKind::StatusChanged => e
+            .to_value
+            .as_deref()
+            .and_then(TaskStatus::from_str)
+            .map(|s| (s, e.created_at)),
+        _ => None,
+    });
+    let mut add = |status: TaskStatus, d: chrono::Duration| match totals
+        .iter_mut()
+        .find(|(s, _)| *s == status)
+    {
+        Some((_, total)) => *total += d,
+        None => totals.push((status, d)),
+    };
+    for (status, at) in entered {
+        if let Some((prev, since)) = current {
+            add(prev, at - since);
+        }
+        current = Some((status, at));
+    }
+    if let Some((status, since)) = current {
+        // Done is terminal — don't keep counting time after completion
+        if status != TaskStatus::Done {
+            add(status, now - since);
+        } else {
+            add(status, chrono::Duration::zero());
+        }
+    }
+    totals
+}
+
+/// Time the agent sat idle in each status, from `IdleStarted` / `IdleEnded`
+/// events. An idle period that spans a status change is split between the two
+/// statuses; one still open accrues up to `now`. Statuses without idle time are omitted.
+pub fn idle_durations(
+    events: &[TaskEvent],
+    now: DateTime<Utc>,
+) -> Vec<(TaskStatus, chrono::Duration)> {
+    let mut totals: Vec<(TaskStatus, chrono::Duration)> = Vec::new();
+    let mut add = |status: TaskStatus, d: chrono::Duration| match totals
+        .iter_mut()
+        .find(|(s, _)| *s == status)
+    {
+        Some((_, total)) => *total += d,
+        None => totals.push((status, d)),
+    };
+    let mut status: Option<TaskStatus> = None;
+    let mut idle_since: Option<DateTime<Utc>> = None;
+
+    for event in events {
+        match event.kind {
+            TaskEventKind::Created | TaskEventKind::StatusChanged => {
+                let Some(next) = event.to_value.as_deref().and_then(TaskStatus::from_str) else {
+                    continue;
+                };
+                if let (Some(prev), Some(since)) = (status, idle_since) {
+                    add(prev, event.created_at - since);
+                    // Done is terminal, like in status_durations
+                    idle_since = (next != TaskStatus::Done).then_some(event.created_at);
+                }
+                status = Some(next);
+            }
+            TaskEventKind::IdleStarted => {
+                idle_since.get_or_insert(event.created_at);
+            }
+            TaskEventKind::IdleEnded => {
+                if let (Some(current), Some(since)) = (status, idle_since.take()) {
+                    add(current, event.created_at - since);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let (Some(current), Some(since)) = (status, idle_since) {
+        if current != TaskStatus::Done {
+            add(current, now - since);
+        }
+    }
+    totals
+}
+
+/// Tokens an agent used and what they cost
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    /// Estimated from list prices; 0 for models without a known price
+    pub cost_usd: f64,
+}
+
+impl Usage {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_write_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_tokens() == 0 && self.cost_usd == 0.0
+    }
+
+    pub fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+}
+
+/// Usage of one agent during one phase of a task (`task_usage` table).
+/// Phases are named as in transcripts: research, planning, running, review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskUsage {
+    pub task_id: String,
+    pub phase: String,
+    pub agent: String,
+    pub usage: Usage,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Represents a running agent session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunningAgent {
@@ -230,7 +673,8 @@ impl Notification {
     }
 }
 
-/// Phase completion status (runtime-only, not persisted to DB)
+/// Phase completion status, worked out by the TUI from the agent's session.
+/// The latest value is kept in the `task_phase_status` table for MCP clients.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PhaseStatus {
     /// Agent is still working, no artifact yet
@@ -242,3 +686,24 @@ pub enum PhaseStatus {
     /// Tmux window gone (process exited)
     Exited,
 }
+
+impl PhaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhaseStatus::Working => "working",
+            PhaseStatus::Idle => "idle",
+            PhaseStatus::Ready => "ready",
+            PhaseStatus::Exited => "exited",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "working" => Some(PhaseStatus::Working),
+            "idle" => Some(PhaseStatus::Idle),
+            "ready" => Some(PhaseStatus::Ready),
+            "exited" => Some(PhaseStatus::Exited),
+            _ => None,
+        }
+    }
+}
@@ -1,28 +1,37 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection};
-use std::path::Path;
-
-use super::models::{Notification, Project, Task, TaskStatus, TransitionRequest};
+use rusqlite::{params, Connection, OpenFlags, Transaction, TransactionBehavior};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::migrations::{self, MigrationStatus, SchemaKind};
+use super::models::{
+    EventActor, Notification, PhaseStatus, Priority, Project, SubtaskProgress, Task, TaskEvent,
+    TaskEventKind, TaskStatus, TaskUsage, TransitionRequest, Usage,
+};
+use crate::graph;
+
+/// Task rows together with their label names, joined by the ASCII unit separator,
+/// and their dependencies as a comma-separated list of IDs
+const SELECT_TASKS: &str = r#"
+    SELECT tasks.*,
+        (SELECT group_concat(l.name, char(31)) FROM task_labels tl
+            JOIN labels l ON l.id = tl.label_id WHERE tl.task_id = tasks.id) AS label_names,
+        (SELECT group_concat(depends_on, ',' ORDER BY position) FROM task_dependencies
+            WHERE task_id = tasks.id) AS dependency_ids
+    FROM tasks"#;
 
 /// Database wrapper for SQLite operations
 pub struct Database {
     conn: Connection,
+    /// Attributed to task events written through this handle
+    actor: Cell<EventActor>,
 }
 
 impl Database {
     /// Open or create a project database (stored centrally in config dir)
     pub fn open_project(project_path: &Path) -> Result<Self> {
-        let config_dir = directories::ProjectDirs::from("", "", "agtx")
-            .context("Could not determine config directory")?;
-
-        // Create a stable ID from the project path using a hash
-        let path_str = project_path.to_string_lossy();
-        let path_hash = Self::hash_path(&path_str);
-
-        let db_path = config_dir
-            .config_dir()
-            .join("projects")
-            .join(format!("{}.db", path_hash));
+        let db_path = Self::project_db_path(project_path)?;
 
         // Ensure projects directory exists
         if let Some(parent) = db_path.parent() {
@@ -31,11 +40,8 @@ impl Database {
 
         // Migration: if the new-hash DB doesn't exist, check for an old-hash DB and rename it
         if !db_path.exists() {
-            let old_hash = Self::hash_path_legacy(&path_str);
-            let old_db_path = config_dir
-                .config_dir()
-                .join("projects")
-                .join(format!("{}.db", old_hash));
+            let old_hash = Self::hash_path_legacy(&project_path.to_string_lossy());
+            let old_db_path = db_path.with_file_name(format!("{}.db", old_hash));
             if old_db_path.exists() {
                 let _ = std::fs::rename(&old_db_path, &db_path);
             }
@@ -51,11 +57,48 @@ impl Database {
             let _ = std::fs::set_permissions(&db_path, std::fs::Permissions::from_mode(0o600));
         }
 
-        let db = Self { conn };
+        let db = Self::from_connection(conn);
         db.init_project_schema()?;
         Ok(db)
     }
 
+    /// Location of a project's database file (stored centrally in config dir)
+    pub fn project_db_path(project_path: &Path) -> Result<PathBuf> {
+        let config_dir = directories::ProjectDirs::from("", "", "agtx")
+            .context("Could not determine config directory")?;
+
+        // Create a stable ID from the project path using a hash
+        let path_hash = Self::hash_path(&project_path.to_string_lossy());
+
+        Ok(config_dir
+            .config_dir()
+            .join("projects")
+            .join(format!("{}.db", path_hash)))
+    }
+
+    /// Location of the global index database file
+    pub fn global_db_path() -> Result<PathBuf> {
+        let config_dir = directories::ProjectDirs::from("", "", "agtx")
+            .context("Could not determine config directory")?;
+        Ok(config_dir.config_dir().join("index.db"))
+    }
+
+    fn from_connection(conn: Connection) -> Self {
+        Self {
+            conn,
+            actor: Cell::new(EventActor::User),
+        }
+    }
+
+    /// Set who subsequent task changes are attributed to. Returns the previous actor.
+    pub fn set_actor(&self, actor: EventActor) -> EventActor {
+        self.actor.replace(actor)
+    }
+
+    pub fn actor(&self) -> EventActor {
+        self.actor.get()
+    }
+
     /// Create a stable hash from a path string for database filename.
     /// Uses SHA-256 (truncated to 16 hex chars) for cross-version stability.
     fn hash_path(path: &str) -> String {
@@ -79,9 +122,7 @@ impl Database {
 
     /// Open or create the global index database
     pub fn open_global() -> Result<Self> {
-        let config_dir = directories::ProjectDirs::from("", "", "agtx")
-            .context("Could not determine config directory")?;
-        let db_path = config_dir.config_dir().join("index.db");
+        let db_path = Self::global_db_path()?;
 
         // Ensure config directory exists
         if let Some(parent) = db_path.parent() {
@@ -98,7 +139,7 @@ impl Database {
             let _ = std::fs::set_permissions(&db_path, std::fs::Permissions::from_mode(0o600));
         }
 
-        let db = Self { conn };
+        let db = Self::from_connection(conn);
         db.init_global_schema()?;
         Ok(db)
     }
@@ -107,7 +148,7 @@ impl Database {
     #[cfg(feature = "test-mocks")]
     pub fn open_in_memory_project() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
-        let db = Self { conn };
+        let db = Self::from_connection(conn);
         db.init_project_schema()?;
         Ok(db)
     }
@@ -117,7 +158,7 @@ impl Database {
     pub fn open_project_at_path(path: &Path) -> Result<Self> {
         let conn = Connection::open(path)
             .with_context(|| format!("Failed to open database at {:?}", path))?;
-        let db = Self { conn };
+        let db = Self::from_connection(conn);
         db.init_project_schema()?;
         Ok(db)
     }
@@ -126,141 +167,52 @@ impl Database {
     #[cfg(feature = "test-mocks")]
     pub fn open_in_memory_global() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
-        let db = Self { conn };
+        let db = Self::from_connection(conn);
         db.init_global_schema()?;
         Ok(db)
     }
 
     fn init_project_schema(&self) -> Result<()> {
-        self.conn.execute_batch(
-            "PRAGMA journal_mode=WAL;
-             PRAGMA busy_timeout=5000;",
-        )?;
-
-        self.conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS tasks (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                description TEXT,
-                status TEXT NOT NULL DEFAULT 'backlog',
-                agent TEXT NOT NULL,
-                project_id TEXT NOT NULL,
-                session_name TEXT,
-                worktree_path TEXT,
-                branch_name TEXT,
-                pr_number INTEGER,
-                pr_url TEXT,
-                plugin TEXT,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
-            CREATE INDEX IF NOT EXISTS idx_tasks_project ON tasks(project_id);
-            "#,
-        )?;
-
-        // Migration: add new columns if they don't exist
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN branch_name TEXT", []);
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN pr_number INTEGER", []);
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN pr_url TEXT", []);
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN plugin TEXT", []);
-        let _ = self.conn.execute(
-            "ALTER TABLE tasks ADD COLUMN cycle INTEGER NOT NULL DEFAULT 1",
-            [],
-        );
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN referenced_tasks TEXT", []);
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN escalation_note TEXT", []);
-        let _ = self
-            .conn
-            .execute("ALTER TABLE tasks ADD COLUMN base_branch TEXT", []);
-
-        // MCP transition request queue
-        self.conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS transition_requests (
-                id TEXT PRIMARY KEY,
-                task_id TEXT NOT NULL,
-                action TEXT NOT NULL,
-                requested_at TEXT NOT NULL,
-                processed_at TEXT,
-                error TEXT
-            );
-
-            CREATE TABLE IF NOT EXISTS notifications (
-                id TEXT PRIMARY KEY,
-                message TEXT NOT NULL,
-                created_at TEXT NOT NULL
-            );
-            "#,
-        )?;
-
-        // Migration: add reason column to transition_requests if it doesn't exist
-        let _ = self
-            .conn
-            .execute("ALTER TABLE transition_requests ADD COLUMN reason TEXT", []);
-
-        let _ = self.conn.execute(
-            "ALTER TABLE transition_requests ADD COLUMN claimed_by TEXT",
-            [],
-        );
-
-        Ok(())
+        self.init_schema(SchemaKind::Project)
     }
 
     fn init_global_schema(&self) -> Result<()> {
+        self.init_schema(SchemaKind::Global)
+    }
+
+    fn init_schema(&self, kind: SchemaKind) -> Result<()> {
         self.conn.execute_batch(
             "PRAGMA journal_mode=WAL;
              PRAGMA busy_timeout=5000;",
         )?;
-
-        self.conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS projects (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                path TEXT NOT NULL UNIQUE,
-                github_url TEXT,
-                default_agent TEXT,
-                last_opened TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS running_agents (
-                session_name TEXT PRIMARY KEY,
-                project_id TEXT NOT NULL,
-                task_id TEXT NOT NULL,
-                agent_name TEXT NOT NULL,
-                started_at TEXT NOT NULL,
-                status TEXT NOT NULL,
-                FOREIGN KEY (project_id) REFERENCES projects(id)
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_running_project ON running_agents(project_id);
-            "#,
-        )?;
+        migrations::migrate(&self.conn, kind)?;
         Ok(())
     }
 
+    /// Report pending migrations for the database file at `db_path` without modifying it.
+    /// A missing file is reported as version 0 with every migration pending.
+    pub fn migration_status(db_path: &Path, kind: SchemaKind) -> Result<MigrationStatus> {
+        if !db_path.exists() {
+            return migrations::status_for_version(kind, 0);
+        }
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Failed to open database at {:?}", db_path))?;
+        migrations::status(&conn, kind)
+    }
+
+    /// Schema version currently recorded in this database
+    pub fn schema_version(&self) -> Result<u32> {
+        migrations::current_version(&self.conn)
+    }
+
     // === Task Operations ===
 
     pub fn create_task(&self, task: &Task) -> Result<()> {
-        self.conn.execute(
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        tx.execute(
             r#"
-            INSERT INTO tasks (id, title, description, status, agent, project_id, session_name, worktree_path, branch_name, pr_number, pr_url, plugin, cycle, referenced_tasks, escalation_note, base_branch, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+            INSERT INTO tasks (id, title, description, status, agent, project_id, session_name, worktree_path, branch_name, pr_number, pr_url, plugin, cycle, escalation_note, base_branch, queued_phase, queued_at, priority, due_date, parent_id, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)
             "#,
             params![
                 task.id,
@@ -276,23 +228,33 @@ impl Database {
                 task.pr_url,
                 task.plugin,
                 task.cycle,
-                task.referenced_tasks,
                 task.escalation_note,
                 task.base_branch,
+                task.queued_phase,
+                task.queued_at.map(|t| t.to_rfc3339()),
+                task.priority.as_str(),
+                task.due_date.map(|d| d.to_string()),
+                task.parent_id,
                 task.created_at.to_rfc3339(),
                 task.updated_at.to_rfc3339(),
             ],
         )?;
+        Self::set_task_labels(&tx, &task.id, &task.labels)?;
+        Self::set_task_dependencies(&tx, &task.id, task.dependency_ids())?;
+        Self::check_dependency_cycle(&tx, &task.id)?;
+        Self::insert_task_event(&tx, &Self::created_event(task, self.actor()))?;
+        tx.commit()?;
         Ok(())
     }
 
     pub fn create_tasks_batch(&mut self, tasks: &[Task]) -> Result<()> {
+        let actor = self.actor();
         let tx = self.conn.transaction()?;
         for task in tasks {
             tx.execute(
                 r#"
-                INSERT INTO tasks (id, title, description, status, agent, project_id, session_name, worktree_path, branch_name, pr_number, pr_url, plugin, cycle, referenced_tasks, escalation_note, base_branch, created_at, updated_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+                INSERT INTO tasks (id, title, description, status, agent, project_id, session_name, worktree_path, branch_name, pr_number, pr_url, plugin, cycle, escalation_note, base_branch, queued_phase, queued_at, priority, due_date, parent_id, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)
                 "#,
                 params![
                     task.id,
@@ -308,20 +270,40 @@ impl Database {
                     task.pr_url,
                     task.plugin,
                     task.cycle,
-                    task.referenced_tasks,
                     task.escalation_note,
                     task.base_branch,
+                    task.queued_phase,
+                    task.queued_at.map(|t| t.to_rfc3339()),
+                    task.priority.as_str(),
+                    task.due_date.map(|d| d.to_string()),
+                    task.parent_id,
                     task.created_at.to_rfc3339(),
                     task.updated_at.to_rfc3339(),
                 ],
             )?;
+            Self::set_task_labels(&tx, &task.id, &task.labels)?;
+            Self::set_task_dependencies(&tx, &task.id, task.dependency_ids())?;
+            Self::insert_task_event(&tx, &Self::created_event(task, actor))?;
+        }
+        for task in tasks {
+            Self::check_dependency_cycle(&tx, &task.id)?;
         }
         tx.commit()?;
         Ok(())
     }
 
     pub fn update_task(&self, task: &Task) -> Result<()> {
-        self.conn.execute(
+        // IMMEDIATE so the read of `previous` and the writes it drives happen
+        // under the same write lock; a concurrent writer can't slip in between
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let previous = tx
+            .query_row(
+                &format!("{} WHERE id = ?1", SELECT_TASKS),
+                params![task.id],
+                Self::task_from_row,
+            )
+            .ok();
+        tx.execute(
             r#"
             UPDATE tasks SET
                 title = ?2,
@@ -335,10 +317,14 @@ impl Database {
                 pr_url = ?10,
                 plugin = ?11,
                 cycle = ?12,
-                referenced_tasks = ?13,
-                escalation_note = ?14,
-                base_branch = ?15,
-                updated_at = ?16
+                escalation_note = ?13,
+                base_branch = ?14,
+                queued_phase = ?15,
+                queued_at = ?16,
+                priority = ?17,
+                due_date = ?18,
+                parent_id = ?19,
+                updated_at = ?20
             WHERE id = ?1
             "#,
             params![
@@ -354,21 +340,349 @@ impl Database {
                 task.pr_url,
                 task.plugin,
                 task.cycle,
-                task.referenced_tasks,
                 task.escalation_note,
                 task.base_branch,
+                task.queued_phase,
+                task.queued_at.map(|t| t.to_rfc3339()),
+                task.priority.as_str(),
+                task.due_date.map(|d| d.to_string()),
+                task.parent_id,
                 task.updated_at.to_rfc3339(),
             ],
         )?;
+        Self::set_task_labels(&tx, &task.id, &task.labels)?;
+        Self::set_task_dependencies(&tx, &task.id, task.dependency_ids())?;
+        Self::check_dependency_cycle(&tx, &task.id)?;
+        if let Some(previous) = previous {
+            for event in TaskEvent::diff(&previous, task, self.actor()) {
+                Self::insert_task_event(&tx, &event)?;
+            }
+        }
+        tx.commit()?;
         Ok(())
     }
 
+    /// Delete a task and everything hanging off it in one transaction.
+    /// The task's `task_events` are kept, so its history survives deletion.
     pub fn delete_task(&self, task_id: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM tasks WHERE id = ?1", params![task_id])?;
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        tx.execute("DELETE FROM tasks WHERE id = ?1", params![task_id])?;
+        tx.execute(
+            "DELETE FROM task_usage WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        tx.execute(
+            "DELETE FROM task_labels WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        tx.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1 OR depends_on = ?1",
+            params![task_id],
+        )?;
+        tx.execute(
+            "DELETE FROM task_phase_status WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        // Subtasks outlive their parent as top-level tasks
+        tx.execute(
+            "UPDATE tasks SET parent_id = NULL WHERE parent_id = ?1",
+            params![task_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn set_task_labels(conn: &Connection, task_id: &str, labels: &[String]) -> Result<()> {
+        conn.execute(
+            "DELETE FROM task_labels WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        for label in labels {
+            conn.execute(
+                "INSERT OR IGNORE INTO labels (name) VALUES (?1)",
+                params![label],
+            )?;
+            conn.execute(
+                r#"
+                INSERT OR IGNORE INTO task_labels (task_id, label_id)
+                SELECT ?1, id FROM labels WHERE name = ?2
+                "#,
+                params![task_id, label],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_task_dependencies<'a>(
+        conn: &Connection,
+        task_id: &str,
+        depends_on: impl IntoIterator<Item = &'a str>,
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        for (position, dep) in depends_on.into_iter().enumerate() {
+            conn.execute(
+                "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on, position) VALUES (?1, ?2, ?3)",
+                params![task_id, dep, position as i64],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Fail if `task_id`'s dependencies lead back to it. Only cycles through
+    /// this task are reported, so one left over elsewhere doesn't block edits.
+    fn check_dependency_cycle(conn: &Connection, task_id: &str) -> Result<()> {
+        let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+        let mut stmt = conn.prepare("SELECT task_id, depends_on FROM task_dependencies")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (from, to): (String, String) = row?;
+            edges.entry(from).or_default().push(to);
+        }
+        let Some(path) = graph::find_cycle(&edges, task_id) else {
+            return Ok(());
+        };
+        let names: Vec<String> = path
+            .iter()
+            .map(|id| {
+                conn.query_row(
+                    "SELECT title FROM tasks WHERE id = ?1",
+                    params![id],
+                    |row| row.get::<_, String>(0),
+                )
+                .unwrap_or_else(|_| id.clone())
+            })
+            .collect();
+        anyhow::bail!("Dependency cycle: {}", names.join(" \u{2192} "))
+    }
+
+    /// Every label used by at least one task, sorted by name.
+    pub fn get_labels(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT DISTINCT l.name FROM labels l
+            JOIN task_labels tl ON tl.label_id = l.id
+            ORDER BY l.name
+            "#,
+        )?;
+
+        let labels = stmt
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(labels)
+    }
+
+    // === Phase Status ===
+
+    /// Record the phase status the TUI last saw for a task.
+    pub fn set_phase_status(&self, task_id: &str, status: PhaseStatus) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO task_phase_status (task_id, status, updated_at) VALUES (?1, ?2, ?3)
+            ON CONFLICT(task_id) DO UPDATE SET status = ?2, updated_at = ?3
+            "#,
+            params![task_id, status.as_str(), chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_phase_status(&self, task_id: &str) -> Result<Option<PhaseStatus>> {
+        let status: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT status FROM task_phase_status WHERE task_id = ?1",
+                params![task_id],
+                |row| row.get(0),
+            )
+            .ok();
+        Ok(status.as_deref().and_then(PhaseStatus::from_str))
+    }
+
+    // === Token Usage ===
+
+    /// Replace everything recorded for a task with `rows`.
+    pub fn replace_task_usage(&self, task_id: &str, rows: &[TaskUsage]) -> Result<()> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        tx.execute(
+            "DELETE FROM task_usage WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        for row in rows {
+            tx.execute(
+                r#"
+                INSERT INTO task_usage (task_id, phase, agent, input_tokens, output_tokens,
+                    cache_read_tokens, cache_write_tokens, cost_usd, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                "#,
+                params![
+                    task_id,
+                    row.phase,
+                    row.agent,
+                    row.usage.input_tokens as i64,
+                    row.usage.output_tokens as i64,
+                    row.usage.cache_read_tokens as i64,
+                    row.usage.cache_write_tokens as i64,
+                    row.usage.cost_usd,
+                    row.updated_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
+    /// Usage of a task per phase and agent, in workflow order.
+    pub fn get_task_usage(&self, task_id: &str) -> Result<Vec<TaskUsage>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT * FROM task_usage WHERE task_id = ?1
+            ORDER BY CASE phase WHEN 'research' THEN 0 WHEN 'planning' THEN 1
+                WHEN 'running' THEN 2 WHEN 'review' THEN 3 ELSE 4 END, agent
+            "#,
+        )?;
+
+        let rows = stmt
+            .query_map(params![task_id], |row| {
+                Ok(TaskUsage {
+                    task_id: row.get("task_id")?,
+                    phase: row.get("phase")?,
+                    agent: row.get("agent")?,
+                    usage: Self::usage_from_row(row)?,
+                    updated_at: chrono::DateTime::parse_from_rfc3339(
+                        &row.get::<_, String>("updated_at")?,
+                    )
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(rows)
+    }
+
+    /// Total usage of every task that has any, keyed by task ID.
+    pub fn get_usage_totals(&self) -> Result<std::collections::HashMap<String, Usage>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT task_id, SUM(input_tokens) AS input_tokens, SUM(output_tokens) AS output_tokens,
+                SUM(cache_read_tokens) AS cache_read_tokens,
+                SUM(cache_write_tokens) AS cache_write_tokens, SUM(cost_usd) AS cost_usd
+            FROM task_usage GROUP BY task_id
+            "#,
+        )?;
+
+        let totals = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>("task_id")?, Self::usage_from_row(row)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(totals)
+    }
+
+    /// Usage summed over all tasks in the project.
+    pub fn get_project_usage(&self) -> Result<Usage> {
+        let mut total = Usage::default();
+        for usage in self.get_usage_totals()?.values() {
+            total.add(usage);
+        }
+        Ok(total)
+    }
+
+    fn usage_from_row(row: &rusqlite::Row) -> rusqlite::Result<Usage> {
+        Ok(Usage {
+            input_tokens: row.get::<_, i64>("input_tokens")? as u64,
+            output_tokens: row.get::<_, i64>("output_tokens")? as u64,
+            cache_read_tokens: row.get::<_, i64>("cache_read_tokens")? as u64,
+            cache_write_tokens: row.get::<_, i64>("cache_write_tokens")? as u64,
+            cost_usd: row.get("cost_usd")?,
+        })
+    }
+
+    // === Task History ===
+
+    fn created_event(task: &Task, actor: EventActor) -> TaskEvent {
+        let mut event = TaskEvent::new(&task.id, TaskEventKind::Created, actor);
+        event.to_value = Some(task.status.as_str().to_string());
+        event.created_at = task.created_at;
+        event
+    }
+
+    fn insert_task_event(conn: &Connection, event: &TaskEvent) -> Result<()> {
+        conn.execute(
+            r#"
+            INSERT INTO task_events (id, task_id, kind, from_value, to_value, actor, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![
+                event.id,
+                event.task_id,
+                event.kind.as_str(),
+                event.from_value,
+                event.to_value,
+                event.actor.as_str(),
+                event.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record an event that isn't a change to the task row, such as the agent
+    /// going idle.
+    pub fn add_task_event(&self, event: &TaskEvent) -> Result<()> {
+        Self::insert_task_event(&self.conn, event)
+    }
+
+    /// History of a task, oldest first.
+    pub fn get_task_events(&self, task_id: &str) -> Result<Vec<TaskEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM task_events WHERE task_id = ?1 ORDER BY created_at ASC, rowid ASC",
+        )?;
+
+        let events = stmt
+            .query_map(params![task_id], Self::task_event_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(events)
+    }
+
+    /// History of every task in the project, oldest first.
+    pub fn get_all_task_events(&self) -> Result<Vec<TaskEvent>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM task_events ORDER BY created_at ASC, rowid ASC")?;
+
+        let events = stmt
+            .query_map([], Self::task_event_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(events)
+    }
+
+    fn task_event_from_row(row: &rusqlite::Row) -> rusqlite::Result<TaskEvent> {
+        let kind: String = row.get("kind")?;
+        let actor: String = row.get("actor")?;
+        Ok(TaskEvent {
+            id: row.get("id")?,
+            task_id: row.get("task_id")?,
+            kind: TaskEventKind::parse(&kind).unwrap_or(TaskEventKind::StatusChanged),
+            from_value: row.get("from_value")?,
+            to_value: row.get("to_value")?,
+            actor: EventActor::parse(&actor).unwrap_or(EventActor::User),
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        })
+    }
+
     fn task_from_row(row: &rusqlite::Row) -> rusqlite::Result<Task> {
         Ok(Task {
             id: row.get("id")?,
@@ -385,9 +699,37 @@ impl Database {
             pr_url: row.get("pr_url").ok().flatten(),
             plugin: row.get("plugin").ok().flatten(),
             cycle: row.get("cycle").unwrap_or(1),
-            referenced_tasks: row.get("referenced_tasks").ok().flatten(),
+            referenced_tasks: row.get("dependency_ids").ok().flatten(),
+            parent_id: row.get("parent_id").ok().flatten(),
             escalation_note: row.get("escalation_note").ok().flatten(),
             base_branch: row.get("base_branch").ok().flatten(),
+            queued_phase: row.get("queued_phase").ok().flatten(),
+            queued_at: row
+                .get::<_, Option<String>>("queued_at")
+                .ok()
+                .flatten()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&chrono::Utc)),
+            labels: row
+                .get::<_, Option<String>>("label_names")
+                .ok()
+                .flatten()
+                .map(|names| {
+                    let mut labels: Vec<String> = names.split('\u{1f}').map(String::from).collect();
+                    labels.sort();
+                    labels
+                })
+                .unwrap_or_default(),
+            priority: row
+                .get::<_, String>("priority")
+                .ok()
+                .and_then(|p| Priority::from_str(&p))
+                .unwrap_or_default(),
+            due_date: row
+                .get::<_, Option<String>>("due_date")
+                .ok()
+                .flatten()
+                .and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
             created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
                 .map(|dt| dt.with_timezone(&chrono::Utc))
                 .unwrap_or_else(|_| chrono::Utc::now()),
@@ -398,7 +740,9 @@ impl Database {
     }
 
     pub fn get_task(&self, task_id: &str) -> Result<Option<Task>> {
-        let mut stmt = self.conn.prepare("SELECT * FROM tasks WHERE id = ?1")?;
+        let mut stmt = self
+            .conn
+            .prepare(&format!("{} WHERE id = ?1", SELECT_TASKS))?;
 
         let task = stmt.query_row(params![task_id], Self::task_from_row).ok();
 
@@ -408,7 +752,7 @@ impl Database {
     pub fn get_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<Task>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT * FROM tasks WHERE status = ?1 ORDER BY created_at")?;
+            .prepare(&format!("{} WHERE status = ?1 ORDER BY created_at", SELECT_TASKS))?;
 
         let tasks = stmt
             .query_map(params![status.as_str()], Self::task_from_row)?
@@ -421,7 +765,7 @@ impl Database {
     pub fn get_all_tasks(&self) -> Result<Vec<Task>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT * FROM tasks ORDER BY created_at")?;
+            .prepare(&format!("{} ORDER BY created_at", SELECT_TASKS))?;
 
         let tasks = stmt
             .query_map([], Self::task_from_row)?
@@ -446,6 +790,35 @@ impl Database {
         })
     }
 
+    /// Subtasks of `parent_id`, oldest first.
+    pub fn get_subtasks(&self, parent_id: &str) -> Result<Vec<Task>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} WHERE parent_id = ?1 ORDER BY created_at",
+            SELECT_TASKS
+        ))?;
+
+        let tasks = stmt
+            .query_map(params![parent_id], Self::task_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(tasks)
+    }
+
+    /// Check whether all of the task's subtasks are Done, which it needs
+    /// before it can be moved to Done itself. Errors if they can't be read,
+    /// so callers refuse the move rather than let it through.
+    pub fn subtasks_done(&self, task: &Task) -> Result<bool> {
+        Ok(self
+            .subtask_progress(task)?
+            .is_none_or(|progress| progress.is_complete()))
+    }
+
+    /// Done and total subtasks, or `None` when the task has none.
+    pub fn subtask_progress(&self, task: &Task) -> Result<Option<SubtaskProgress>> {
+        let subtasks = self.get_subtasks(&task.id)?;
+        Ok(SubtaskProgress::of(&task.id, &subtasks))
+    }
+
     // === Project Operations (for global db) ===
 
     pub fn upsert_project(&self, project: &Project) -> Result<()> {
@@ -527,14 +900,15 @@ impl Database {
     pub fn create_transition_request(&self, req: &TransitionRequest) -> Result<()> {
         self.conn.execute(
             r#"
-            INSERT INTO transition_requests (id, task_id, action, reason, requested_at, processed_at, error)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            INSERT INTO transition_requests (id, task_id, action, reason, requested_by, requested_at, processed_at, error)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
             "#,
             params![
                 req.id,
                 req.task_id,
                 req.action,
                 req.reason,
+                req.requested_by.map(|a| a.as_str()),
                 req.requested_at.to_rfc3339(),
                 req.processed_at.map(|dt| dt.to_rfc3339()),
                 req.error,
@@ -626,6 +1000,11 @@ impl Database {
             task_id: row.get("task_id")?,
             action: row.get("action")?,
             reason: row.get("reason").ok().flatten(),
+            requested_by: row
+                .get::<_, Option<String>>("requested_by")
+                .ok()
+                .flatten()
+                .and_then(|s| EventActor::parse(&s)),
             requested_at: chrono::DateTime::parse_from_rfc3339(
                 &row.get::<_, String>("requested_at")?,
             )
@@ -0,0 +1,201 @@
+//! Reading a task's changes out of its worktree, for the TUI diff popup and
+//! the MCP diff tools.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use super::GitOperations;
+
+/// Collect git diff content from a worktree
+/// Returns formatted diff sections (unstaged, staged, untracked)
+pub fn collect_task_diff(
+    worktree_path: &str,
+    git_ops: &dyn GitOperations,
+    exclude_prefixes: &[&str],
+) -> String {
+    let worktree = Path::new(worktree_path);
+    let mut sections = Vec::new();
+
+    // Unstaged changes (modified tracked files)
+    let unstaged = git_ops.diff(worktree);
+    if !unstaged.trim().is_empty() {
+        sections.push(format!("=== Unstaged Changes ===\n\n{}", unstaged));
+    }
+
+    // Staged changes
+    let staged = git_ops.diff_cached(worktree);
+    if !staged.trim().is_empty() {
+        sections.push(format!("=== Staged Changes ===\n\n{}", staged));
+    }
+
+    // Untracked files - show as diff (new file content)
+    let untracked = untracked_files(worktree, git_ops, exclude_prefixes);
+    if !untracked.is_empty() {
+        let mut untracked_section = String::from("=== Untracked Files ===\n");
+        for file in &untracked {
+            // Show diff for untracked file (as if adding new file)
+            let file_diff = git_ops.diff_untracked_file(worktree, file);
+            if !file_diff.trim().is_empty() {
+                untracked_section.push_str(&format!("\n{}", file_diff));
+            } else {
+                // Fallback: just show file name
+                untracked_section.push_str(&format!("\n+++ new file: {}\n", file));
+            }
+        }
+        sections.push(untracked_section);
+    }
+
+    if sections.is_empty() {
+        format!("(no changes)\n\nWorktree: {}", worktree_path)
+    } else {
+        sections.join("\n\n")
+    }
+}
+
+/// Untracked files in a worktree, leaving out the directories agtx copies
+/// into it (agent configs, plugin dirs)
+pub fn untracked_files(
+    worktree: &Path,
+    git_ops: &dyn GitOperations,
+    exclude_prefixes: &[&str],
+) -> Vec<String> {
+    git_ops
+        .list_untracked_files(worktree)
+        .lines()
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .filter(|file| {
+            !exclude_prefixes
+                .iter()
+                .any(|prefix| file.starts_with(&format!("{}/", prefix.trim_end_matches('/'))))
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// One file's part of a task's diff
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// The file is new and not yet known to git
+    pub untracked: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub diff: String,
+    /// `diff` was cut short to fit the size limit
+    pub truncated: bool,
+}
+
+impl FileDiff {
+    fn new(path: impl Into<String>, diff: String, untracked: bool) -> Self {
+        let mut additions = 0;
+        let mut deletions = 0;
+        for line in diff.lines() {
+            if line.starts_with('+') && !line.starts_with("+++") {
+                additions += 1;
+            } else if line.starts_with('-') && !line.starts_with("---") {
+                deletions += 1;
+            }
+        }
+        Self {
+            path: path.into(),
+            untracked,
+            additions,
+            deletions,
+            diff,
+            truncated: false,
+        }
+    }
+}
+
+/// Everything a task changed since its branch left `base`, committed or not,
+/// one entry per file. Untracked files come last.
+pub fn collect_file_diffs(
+    worktree_path: &str,
+    base: &str,
+    git_ops: &dyn GitOperations,
+    exclude_prefixes: &[&str],
+) -> Result<Vec<FileDiff>> {
+    let worktree = Path::new(worktree_path);
+    let mut files = split_diff(&git_ops.diff_from_base(worktree, base)?);
+    for file in untracked_files(worktree, git_ops, exclude_prefixes) {
+        let diff = git_ops.diff_untracked_file(worktree, &file);
+        files.push(FileDiff::new(file, diff, true));
+    }
+    Ok(files)
+}
+
+/// The branch a task's changes are measured against: its own base branch,
+/// else the main branch of the repository at `repo`
+pub fn task_base_branch(base_branch: Option<&str>, repo: &Path) -> Result<String> {
+    match base_branch.filter(|b| !b.is_empty()) {
+        Some(base) => Ok(base.to_string()),
+        None => super::detect_main_branch(repo),
+    }
+}
+
+/// Split `git diff` output into one entry per file
+pub fn split_diff(diff: &str) -> Vec<FileDiff> {
+    let mut chunks: Vec<String> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        match chunks.last_mut() {
+            Some(chunk) if !line.starts_with("diff --git ") => chunk.push_str(line),
+            _ => chunks.push(line.to_string()),
+        }
+    }
+    chunks
+        .into_iter()
+        .filter(|chunk| chunk.starts_with("diff --git "))
+        .map(|chunk| {
+            let header = chunk.lines().next().unwrap_or_default();
+            let path = header
+                .rsplit_once(" b/")
+                .map_or(header, |(_, path)| path)
+                .to_string();
+            FileDiff::new(path, chunk, false)
+        })
+        .collect()
+}
+
+/// A page of file diffs
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffPage {
+    pub files: Vec<FileDiff>,
+    /// Where the next page starts, if there are more files
+    pub next_offset: Option<usize>,
+}
+
+/// Take up to `max_files` files starting at `offset`, stopping early once
+/// their diffs add up to `max_bytes`. A single file larger than that is cut
+/// short, so every page makes progress.
+pub fn page_file_diffs(
+    files: &[FileDiff],
+    offset: usize,
+    max_files: usize,
+    max_bytes: usize,
+) -> DiffPage {
+    let mut page = Vec::new();
+    let mut bytes = 0;
+    for file in files.iter().skip(offset).take(max_files.max(1)) {
+        if !page.is_empty() && bytes + file.diff.len() > max_bytes {
+            break;
+        }
+        let mut file = file.clone();
+        if file.diff.len() > max_bytes {
+            let mut end = max_bytes;
+            while !file.diff.is_char_boundary(end) {
+                end -= 1;
+            }
+            file.diff.truncate(end);
+            file.truncated = true;
+        }
+        bytes += file.diff.len();
+        page.push(file);
+    }
+    let next = offset + page.len();
+    DiffPage {
+        files: page,
+        next_offset: (next < files.len()).then_some(next),
+    }
+}
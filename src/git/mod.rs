@@ -1,9 +1,14 @@
+mod diff;
 mod operations;
 mod provider;
 mod worktree;
 
+pub use diff::*;
 pub use operations::*;
-pub use provider::{GitProviderOperations, PullRequestState, RealGitHubOps};
+pub use provider::{
+    GitProviderKind, GitProviderOperations, PullRequestState, RealGitHubOps, RealGitLabOps,
+    RealGitProviderOps, RemoteRepo,
+};
 pub use worktree::*;
 
 #[cfg(feature = "test-mocks")]
@@ -12,6 +17,7 @@ pub use operations::MockGitOperations;
 pub use provider::MockGitProviderOperations;
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::process::Command;
 
@@ -86,6 +92,123 @@ pub fn merge_branch(path: &Path, branch: &str, message: &str) -> Result<()> {
     Ok(())
 }
 
+/// How a finished task branch is folded into its base branch without a hosting provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// `git merge --no-ff`, keeping the task's commits
+    Merge,
+    /// `git merge --squash` into a single commit
+    Squash,
+    /// Rebase the task branch onto base, then fast-forward
+    Rebase,
+}
+
+impl MergeStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStrategy::Merge => "merge",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Rebase => "rebase",
+        }
+    }
+}
+
+/// Ensure `base` is checked out in `path` with no uncommitted changes to tracked files,
+/// so a local merge can run there.
+pub fn check_base_checkout(path: &Path, base: &str) -> Result<()> {
+    let current = current_branch(path)?;
+    if current != base {
+        anyhow::bail!(
+            "Local merge needs '{}' checked out in {} (currently on '{}')",
+            base,
+            path.display(),
+            current
+        );
+    }
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .context("Failed to check working tree status")?;
+    if !output.stdout.is_empty() {
+        anyhow::bail!(
+            "Local merge needs a clean checkout of '{}' — commit or stash changes in {} first",
+            base,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Fold `branch` into `base`, which must be checked out in `path`.
+/// Rebase runs in `worktree` (where `branch` is checked out) and then fast-forwards;
+/// merge and squash commit with `message`. Failed operations are aborted.
+pub fn merge_into_base(
+    path: &Path,
+    worktree: Option<&Path>,
+    branch: &str,
+    base: &str,
+    strategy: MergeStrategy,
+    message: &str,
+) -> Result<()> {
+    check_base_checkout(path, base)?;
+    let git = |dir: &Path, args: &[&str]| -> Result<std::process::Output> {
+        Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run git {}", args.join(" ")))
+    };
+
+    match strategy {
+        MergeStrategy::Merge => {
+            if let Err(e) = merge_branch(path, branch, message) {
+                let _ = git(path, &["merge", "--abort"]);
+                return Err(e);
+            }
+        }
+        MergeStrategy::Squash => {
+            let output = git(path, &["merge", "--squash", branch])?;
+            if !output.status.success() {
+                let _ = git(path, &["reset", "--merge"]);
+                anyhow::bail!(
+                    "Squash merge failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            let staged = git(path, &["diff", "--cached", "--quiet"])?;
+            if !staged.status.success() {
+                let output = git(path, &["commit", "-m", message])?;
+                if !output.status.success() {
+                    let _ = git(path, &["reset", "--merge"]);
+                    anyhow::bail!("Commit failed: {}", String::from_utf8_lossy(&output.stderr));
+                }
+            }
+        }
+        MergeStrategy::Rebase => {
+            let worktree = worktree.context("Rebase needs the task's worktree")?;
+            let output = git(worktree, &["rebase", base])?;
+            if !output.status.success() {
+                let _ = git(worktree, &["rebase", "--abort"]);
+                anyhow::bail!(
+                    "Rebase onto '{}' failed: {}",
+                    base,
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            let output = git(path, &["merge", "--ff-only", branch])?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "Fast-forward failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Check if merging a branch into the base branch would produce conflicts.
 /// Uses `git merge-tree --write-tree` (Git 2.38+) for a non-destructive check.
 /// Returns Ok((has_conflicts, conflicting_files)).
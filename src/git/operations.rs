@@ -42,6 +42,13 @@ pub trait GitOperations: Send + Sync {
     /// Get diff stats from main branch
     fn diff_stat_from_main(&self, worktree_path: &Path) -> String;
 
+    /// Get the full diff, committed and uncommitted, since the branch left `base`
+    /// (its merge-base with HEAD)
+    fn diff_from_base(&self, worktree_path: &Path, base: &str) -> Result<String>;
+
+    /// Get diff stats since the branch left `base`
+    fn diff_stat_from_base(&self, worktree_path: &Path, base: &str) -> Result<String>;
+
     /// Stage all changes
     fn add_all(&self, worktree_path: &Path) -> Result<()>;
 
@@ -59,6 +66,25 @@ pub trait GitOperations: Send + Sync {
     /// Returns Ok(true) if conflicts exist, Ok(false) if clean merge.
     fn fetch_and_check_conflicts(&self, worktree_path: &Path) -> Result<bool>;
 
+    /// Check whether merging `branch` into `base` would conflict, without touching any
+    /// working tree. Returns the conflicting files (empty for a clean merge).
+    fn merge_conflicts(&self, project_path: &Path, base: &str, branch: &str)
+        -> Result<Vec<String>>;
+
+    /// Ensure `base` is checked out and clean in the project root, ready for a local merge
+    fn check_base_checkout(&self, project_path: &Path, base: &str) -> Result<()>;
+
+    /// Merge, squash or rebase a task branch into `base` in the project root
+    fn merge_into_base(
+        &self,
+        project_path: &Path,
+        worktree_path: Option<String>,
+        branch: &str,
+        base: &str,
+        strategy: super::MergeStrategy,
+        message: &str,
+    ) -> Result<()>;
+
     /// List all files (tracked + untracked, respects .gitignore)
     fn list_files(&self, project_path: &Path) -> Vec<String>;
 
@@ -155,6 +181,16 @@ impl GitOperations for RealGitOps {
             .unwrap_or_default()
     }
 
+    fn diff_from_base(&self, worktree_path: &Path, base: &str) -> Result<String> {
+        let merge_base = merge_base(worktree_path, base)?;
+        git_output(worktree_path, &["diff", &merge_base])
+    }
+
+    fn diff_stat_from_base(&self, worktree_path: &Path, base: &str) -> Result<String> {
+        let merge_base = merge_base(worktree_path, base)?;
+        git_output(worktree_path, &["diff", &merge_base, "--stat"])
+    }
+
     fn add_all(&self, worktree_path: &Path) -> Result<()> {
         std::process::Command::new("git")
             .current_dir(worktree_path)
@@ -242,6 +278,47 @@ impl GitOperations for RealGitOps {
         Ok(!merge_tree.status.success())
     }
 
+    fn merge_conflicts(
+        &self,
+        project_path: &Path,
+        base: &str,
+        branch: &str,
+    ) -> Result<Vec<String>> {
+        let (has_conflicts, files) = super::check_merge_conflicts(project_path, base, branch)?;
+        if has_conflicts && files.is_empty() {
+            // merge-tree failed without reporting conflicts (unknown ref or Git < 2.38)
+            anyhow::bail!(
+                "Could not check '{}' against '{}' for conflicts (requires Git 2.38+)",
+                branch,
+                base
+            );
+        }
+        Ok(files)
+    }
+
+    fn check_base_checkout(&self, project_path: &Path, base: &str) -> Result<()> {
+        super::check_base_checkout(project_path, base)
+    }
+
+    fn merge_into_base(
+        &self,
+        project_path: &Path,
+        worktree_path: Option<String>,
+        branch: &str,
+        base: &str,
+        strategy: super::MergeStrategy,
+        message: &str,
+    ) -> Result<()> {
+        super::merge_into_base(
+            project_path,
+            worktree_path.as_deref().map(Path::new),
+            branch,
+            base,
+            strategy,
+            message,
+        )
+    }
+
     fn list_files(&self, project_path: &Path) -> Vec<String> {
         std::process::Command::new("git")
             .current_dir(project_path)
@@ -273,3 +350,26 @@ impl GitOperations for RealGitOps {
         )
     }
 }
+
+/// The commit where HEAD's branch left `base`
+fn merge_base(worktree_path: &Path, base: &str) -> Result<String> {
+    Ok(git_output(worktree_path, &["merge-base", base, "HEAD"])?
+        .trim()
+        .to_string())
+}
+
+/// Run git and return its stdout, failing with its stderr
+fn git_output(worktree_path: &Path, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("git")
+        .current_dir(worktree_path)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
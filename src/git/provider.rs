@@ -3,8 +3,11 @@
 //! This module provides a generic interface for interacting with git hosting
 //! providers like GitHub, GitLab, Bitbucket, etc.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
 
 #[cfg(feature = "test-mocks")]
 use mockall::automock;
@@ -18,6 +21,91 @@ pub enum PullRequestState {
     Unknown,
 }
 
+/// Hosting provider behind a project's `origin` remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitProviderKind {
+    GitHub,
+    GitLab,
+}
+
+impl GitProviderKind {
+    /// Guess the provider from a remote URL's host. Self-hosted GitLab instances
+    /// without "gitlab" in their hostname need `git_provider = "gitlab"` in the project config.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let remote = RemoteRepo::parse(url)?;
+        let host = remote.host().to_lowercase();
+        if host.contains("gitlab") {
+            Some(GitProviderKind::GitLab)
+        } else if host.contains("github") {
+            Some(GitProviderKind::GitHub)
+        } else {
+            None
+        }
+    }
+}
+
+/// Host and repository path parsed from a git remote URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteRepo {
+    /// Web root of the host, e.g. `https://gitlab.com`
+    pub base_url: String,
+    /// Repository path without `.git`, e.g. `group/subgroup/repo`
+    pub path: String,
+}
+
+impl RemoteRepo {
+    /// Parse `https://host/group/repo.git`, `ssh://git@host:22/group/repo.git`
+    /// or scp-style `git@host:group/repo.git`.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (base_url, path) = if let Some((scheme, rest)) = url.split_once("://") {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            let base_url = match scheme {
+                "http" | "https" => format!("{}://{}", scheme, host),
+                // SSH ports say nothing about where the web UI lives
+                _ => format!("https://{}", host.split(':').next()?),
+            };
+            (base_url, path)
+        } else {
+            let (authority, path) = url.split_once(':')?;
+            let host = authority.rsplit('@').next()?;
+            (format!("https://{}", host), path)
+        };
+
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        if path.is_empty() || base_url.ends_with("://") {
+            return None;
+        }
+        Some(Self {
+            base_url,
+            path: path.to_string(),
+        })
+    }
+
+    fn host(&self) -> &str {
+        self.base_url
+            .split_once("://")
+            .map_or(self.base_url.as_str(), |(_, host)| host)
+    }
+}
+
+/// URL of the `origin` remote, if any
+fn origin_url(project_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .current_dir(project_path)
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!url.is_empty()).then_some(url)
+}
+
 /// Operations for git hosting providers (GitHub, GitLab, etc.)
 #[cfg_attr(feature = "test-mocks", automock)]
 pub trait GitProviderOperations: Send + Sync {
@@ -107,3 +195,227 @@ impl GitProviderOperations for RealGitHubOps {
         Ok((pr_number, pr_url))
     }
 }
+
+/// GitLab implementation using the REST API (v4).
+///
+/// Authenticates with the `GITLAB_TOKEN` environment variable. The instance URL
+/// defaults to the host of the `origin` remote. The token is only sent over
+/// plain `http://` when [`RealGitLabOps::allow_insecure_http`] opts in.
+pub struct RealGitLabOps {
+    instance_url: Option<String>,
+    token: Option<String>,
+    allow_http: bool,
+}
+
+impl RealGitLabOps {
+    pub fn new(instance_url: Option<String>) -> Self {
+        let token = std::env::var("GITLAB_TOKEN").ok().filter(|t| !t.is_empty());
+        Self::with_token(instance_url, token)
+    }
+
+    pub fn with_token(instance_url: Option<String>, token: Option<String>) -> Self {
+        Self {
+            instance_url,
+            token,
+            allow_http: false,
+        }
+    }
+
+    /// Allow sending the token to an `http://` instance (`gitlab_allow_http`)
+    pub fn allow_insecure_http(mut self, allow: bool) -> Self {
+        self.allow_http = allow;
+        self
+    }
+
+    /// API root and URL-encoded project ID for the repository at `project_path`
+    fn project_endpoint(&self, project_path: &Path) -> Result<String> {
+        let url = origin_url(project_path).context("Project has no 'origin' remote")?;
+        let remote =
+            RemoteRepo::parse(&url).with_context(|| format!("Unrecognized remote URL: {}", url))?;
+        let base = self.instance_url.as_deref().unwrap_or(&remote.base_url);
+        Ok(format!(
+            "{}/api/v4/projects/{}",
+            base.trim_end_matches('/'),
+            encode_path_segment(&remote.path)
+        ))
+    }
+
+    fn agent() -> ureq::Agent {
+        ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(30)))
+            .http_status_as_error(false)
+            .build()
+            .into()
+    }
+
+    /// Attach the token, refusing to send it in the clear unless allowed
+    fn authorize<B>(
+        &self,
+        url: &str,
+        request: ureq::RequestBuilder<B>,
+    ) -> Result<ureq::RequestBuilder<B>> {
+        let Some(token) = &self.token else {
+            return Ok(request);
+        };
+        if url.starts_with("http://") && !self.allow_http {
+            anyhow::bail!(
+                "Refusing to send GITLAB_TOKEN over plain HTTP to {}; use https or set gitlab_allow_http = true",
+                url
+            );
+        }
+        Ok(request.header("PRIVATE-TOKEN", token))
+    }
+
+    /// Turn a response into JSON, or an error that includes GitLab's message
+    fn read_json(
+        mut response: ureq::http::Response<ureq::Body>,
+        action: &str,
+    ) -> Result<serde_json::Value> {
+        let status = response.status();
+        let body = response.body_mut().read_to_string().unwrap_or_default();
+        if !status.is_success() {
+            let hint = if status.as_u16() == 401 {
+                " (set GITLAB_TOKEN to a personal access token with the api scope)"
+            } else {
+                ""
+            };
+            anyhow::bail!(
+                "Failed to {}: HTTP {} {}{}",
+                action,
+                status.as_u16(),
+                body.trim(),
+                hint
+            );
+        }
+        serde_json::from_str(&body).with_context(|| format!("Failed to {}: invalid JSON", action))
+    }
+}
+
+impl GitProviderOperations for RealGitLabOps {
+    fn get_pr_state(&self, project_path: &Path, pr_number: i32) -> Result<PullRequestState> {
+        let url = format!(
+            "{}/merge_requests/{}",
+            self.project_endpoint(project_path)?,
+            pr_number
+        );
+        let response = match self.authorize(&url, Self::agent().get(&url))?.call() {
+            Ok(response) if response.status().is_success() => response,
+            _ => return Ok(PullRequestState::Unknown),
+        };
+        let json = Self::read_json(response, "read merge request")?;
+        Ok(match json["state"].as_str() {
+            Some("merged") => PullRequestState::Merged,
+            Some("closed") => PullRequestState::Closed,
+            Some("opened") | Some("locked") => PullRequestState::Open,
+            _ => PullRequestState::Unknown,
+        })
+    }
+
+    fn create_pr(
+        &self,
+        project_path: &Path,
+        title: &str,
+        body: &str,
+        head_branch: &str,
+        base_branch: Option<String>,
+    ) -> Result<(i32, String)> {
+        let endpoint = self.project_endpoint(project_path)?;
+        let agent = Self::agent();
+
+        let target_branch = match base_branch {
+            Some(base) => base,
+            None => {
+                let response = self
+                    .authorize(&endpoint, agent.get(&endpoint))?
+                    .call()
+                    .context("Failed to reach GitLab")?;
+                let project = Self::read_json(response, "look up GitLab project")?;
+                project["default_branch"]
+                    .as_str()
+                    .context("GitLab project has no default branch")?
+                    .to_string()
+            }
+        };
+
+        let mr_url = format!("{}/merge_requests", endpoint);
+        let response = self
+            .authorize(&mr_url, agent.post(&mr_url))?
+            .send_json(serde_json::json!({
+                "source_branch": head_branch,
+                "target_branch": target_branch,
+                "title": title,
+                "description": body,
+            }))
+            .context("Failed to reach GitLab")?;
+        let mr = Self::read_json(response, "create merge request")?;
+
+        let iid = mr["iid"]
+            .as_i64()
+            .and_then(|iid| i32::try_from(iid).ok())
+            .context("GitLab response has no merge request iid")?;
+        let web_url = mr["web_url"]
+            .as_str()
+            .filter(|url| !url.is_empty())
+            .context("GitLab response has no merge request web_url")?
+            .to_string();
+        Ok((iid, web_url))
+    }
+}
+
+/// Percent-encode a value for use as a single URL path segment
+fn encode_path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Provider used by the app: resolves GitHub or GitLab per project on each call.
+///
+/// `git_provider` in `.agtx/config.toml` wins, then the `origin` remote host,
+/// then GitHub. `gitlab_url` and `gitlab_allow_http` are only honoured for
+/// trusted project configs, since they decide where and how the token is sent.
+pub struct RealGitProviderOps;
+
+impl RealGitProviderOps {
+    fn resolve(project_path: &Path) -> Box<dyn GitProviderOperations> {
+        let config = crate::config::ProjectConfig::load(project_path).unwrap_or_default();
+        let kind = config
+            .git_provider
+            .or_else(|| origin_url(project_path).and_then(|u| GitProviderKind::from_remote_url(&u)))
+            .unwrap_or(GitProviderKind::GitHub);
+        match kind {
+            GitProviderKind::GitHub => Box::new(RealGitHubOps),
+            GitProviderKind::GitLab => {
+                let trusted = crate::config::TrustStore::load()
+                    .unwrap_or_default()
+                    .is_trusted(project_path);
+                let instance_url = config.gitlab_url.filter(|_| trusted);
+                let allow_http = trusted && config.gitlab_allow_http.unwrap_or(false);
+                Box::new(RealGitLabOps::new(instance_url).allow_insecure_http(allow_http))
+            }
+        }
+    }
+}
+
+impl GitProviderOperations for RealGitProviderOps {
+    fn get_pr_state(&self, project_path: &Path, pr_number: i32) -> Result<PullRequestState> {
+        Self::resolve(project_path).get_pr_state(project_path, pr_number)
+    }
+
+    fn create_pr(
+        &self,
+        project_path: &Path,
+        title: &str,
+        body: &str,
+        head_branch: &str,
+        base_branch: Option<String>,
+    ) -> Result<(i32, String)> {
+        Self::resolve(project_path).create_pr(project_path, title, body, head_branch, base_branch)
+    }
+}
@@ -0,0 +1,172 @@
+//! The task dependency graph: cycle detection, the tasks that are ready to
+//! start, and a text rendering for the TUI.
+//!
+//! Edges point from a task to the tasks it depends on (`referenced_tasks`).
+//! A dependency is satisfied once it reaches Review or Done, the same rule
+//! `Database::deps_satisfied` applies.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use crate::db::{Task, TaskStatus};
+
+/// The dependency path from `task_id` back to itself, if following the edges
+/// from `task_id` leads back to it. The path starts and ends with `task_id`.
+pub fn find_cycle(edges: &HashMap<String, Vec<String>>, task_id: &str) -> Option<Vec<String>> {
+    fn visit(
+        edges: &HashMap<String, Vec<String>>,
+        target: &str,
+        current: &str,
+        path: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        for next in edges.get(current).into_iter().flatten() {
+            path.push(next.clone());
+            if next == target {
+                return true;
+            }
+            if seen.insert(next.clone()) && visit(edges, target, next, path, seen) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let mut path = vec![task_id.to_string()];
+    let mut seen = HashSet::new();
+    visit(edges, task_id, task_id, &mut path, &mut seen).then_some(path)
+}
+
+fn satisfied(dep: Option<&&Task>) -> bool {
+    // Missing dependencies were deleted and don't block
+    dep.is_none_or(|t| matches!(t.status, TaskStatus::Review | TaskStatus::Done))
+}
+
+/// Backlog tasks whose dependencies are all in Review or Done, highest
+/// priority first, then oldest first.
+pub fn ready_tasks(tasks: &[Task]) -> Vec<&Task> {
+    let by_id: HashMap<&str, &Task> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut ready: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Backlog)
+        .filter(|t| t.dependency_ids().all(|id| satisfied(by_id.get(id))))
+        .collect();
+    ready.sort_by_key(|t| (Reverse(t.priority), t.created_at));
+    ready
+}
+
+/// One line of the rendered graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphLine {
+    pub text: String,
+    /// A Backlog task waiting on dependencies that are not in Review or Done yet
+    pub blocked: bool,
+}
+
+impl GraphLine {
+    fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            blocked: false,
+        }
+    }
+}
+
+/// Render the tasks that take part in dependencies, grouped by level: level 0
+/// has no dependencies, every other task sits one level below its deepest
+/// dependency. Level headings are the lines that don't start with a space.
+pub fn format_graph(tasks: &[Task]) -> Vec<GraphLine> {
+    let by_id: HashMap<&str, &Task> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let depended_on: HashSet<&str> = tasks.iter().flat_map(|t| t.dependency_ids()).collect();
+    let nodes: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.dependency_ids().next().is_some() || depended_on.contains(t.id.as_str()))
+        .collect();
+    if nodes.is_empty() {
+        return vec![GraphLine::plain("No dependencies between tasks")];
+    }
+
+    let mut levels: HashMap<&str, usize> = HashMap::new();
+    for task in &nodes {
+        level(task, &by_id, &mut levels, &mut HashSet::new());
+    }
+    let max_level = levels.values().copied().max().unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for depth in 0..=max_level {
+        if depth > 0 {
+            lines.push(GraphLine::plain(""));
+        }
+        lines.push(GraphLine::plain(format!("Level {}", depth)));
+        for task in nodes.iter().filter(|t| levels[t.id.as_str()] == depth) {
+            let deps: Vec<&str> = task.dependency_ids().collect();
+            let blocked = task.status == TaskStatus::Backlog
+                && !deps.iter().all(|id| satisfied(by_id.get(id)));
+            let mut text = format!(
+                "  {} {:<8}  {:<8}  {}",
+                if blocked {
+                    "\u{2298}"
+                } else {
+                    status_marker(task.status)
+                },
+                &task.id[..task.id.len().min(8)],
+                task.status.as_str(),
+                task.title
+            );
+            if !deps.is_empty() {
+                let names: Vec<&str> = deps
+                    .iter()
+                    .map(|id| by_id.get(id).map_or(*id, |t| t.title.as_str()))
+                    .collect();
+                text.push_str(&format!("  \u{2190} {}", names.join(", ")));
+            }
+            lines.push(GraphLine { text, blocked });
+        }
+    }
+
+    let unconnected = tasks.len() - nodes.len();
+    if unconnected > 0 {
+        lines.push(GraphLine::plain(""));
+        lines.push(GraphLine::plain(format!(
+            "  ({} other tasks have no dependencies)",
+            unconnected
+        )));
+    }
+    lines
+}
+
+/// Longest dependency chain below `task`. Cycles (from before cycles were
+/// rejected) are cut where they close.
+fn level<'a>(
+    task: &'a Task,
+    by_id: &HashMap<&str, &'a Task>,
+    levels: &mut HashMap<&'a str, usize>,
+    visiting: &mut HashSet<&'a str>,
+) -> usize {
+    if let Some(level) = levels.get(task.id.as_str()) {
+        return *level;
+    }
+    visiting.insert(&task.id);
+    let mut depth = 0;
+    for id in task.dependency_ids() {
+        let Some(dep) = by_id.get(id) else {
+            continue;
+        };
+        if !visiting.contains(dep.id.as_str()) {
+            depth = depth.max(level(dep, by_id, levels, visiting) + 1);
+        }
+    }
+    visiting.remove(task.id.as_str());
+    levels.insert(&task.id, depth);
+    depth
+}
+
+fn status_marker(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Done => "\u{2713}",
+        TaskStatus::Review => "\u{25c9}",
+        TaskStatus::Planning | TaskStatus::Running => "\u{25b6}",
+        TaskStatus::Backlog => "\u{25cb}",
+    }
+}
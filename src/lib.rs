@@ -1,11 +1,16 @@
 pub mod agent;
+pub mod cli;
 pub mod config;
 pub mod db;
 pub mod git;
+pub mod graph;
 pub mod mcp;
 pub mod skills;
+pub mod stats;
 pub mod tmux;
+pub mod transcript;
 pub mod tui;
+pub mod usage;
 
 use std::path::PathBuf;
 
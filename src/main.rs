@@ -48,10 +48,14 @@ async fn main() -> Result<()> {
 
     let mode = match positional_args.first().copied() {
         Some("mcp-serve") => {
-            let project_path = positional_args
-                .get(1)
-                .map(PathBuf::from);
-            let project_path = match project_path {
+            let serve_args: Vec<String> = args
+                .iter()
+                .skip_while(|a| a.as_str() != "mcp-serve")
+                .skip(1)
+                .cloned()
+                .collect();
+            let mut options = agtx::mcp::ServeOptions::parse(&serve_args)?;
+            options.project_path = match options.project_path {
                 Some(p) => {
                     let p = p.canonicalize()?;
                     if !git::is_git_repo(&p) {
@@ -61,7 +65,58 @@ async fn main() -> Result<()> {
                 }
                 None => None, // global mode
             };
-            return agtx::mcp::serve(project_path).await;
+            return agtx::mcp::serve(options).await;
+        }
+        Some("daemon") => {
+            let project_path = match positional_args.get(1) {
+                Some(p) => PathBuf::from(p),
+                None => std::env::current_dir()?,
+            }
+            .canonicalize()?;
+            if !git::is_git_repo(&project_path) {
+                anyhow::bail!("daemon requires a git project directory");
+            }
+            let flags = FeatureFlags {
+                experimental,
+                no_init_scripts,
+            };
+            let mut app = tui::App::new_headless(project_path.clone(), flags)?;
+            println!(
+                "agtx daemon running for {} (Ctrl+C to stop)",
+                project_path.display()
+            );
+            return app.run_headless().await;
+        }
+        Some("session-host") => {
+            // Started on demand by the built-in PTY session backend
+            return agtx::tmux::pty::run_host();
+        }
+        Some("task") => {
+            let task_args: Vec<String> = args
+                .iter()
+                .skip_while(|a| a.as_str() != "task")
+                .skip(1)
+                .cloned()
+                .collect();
+            return agtx::cli::run(&task_args);
+        }
+        Some("stats") => {
+            let stats_args: Vec<String> = args
+                .iter()
+                .skip_while(|a| a.as_str() != "stats")
+                .skip(1)
+                .cloned()
+                .collect();
+            return agtx::cli::run_stats(&stats_args);
+        }
+        Some("db") => {
+            let db_args: Vec<String> = args
+                .iter()
+                .skip_while(|a| a.as_str() != "db")
+                .skip(1)
+                .cloned()
+                .collect();
+            return agtx::cli::run_db(&db_args);
         }
         Some("trust") => {
             let project_path = std::env::current_dir()?.canonicalize()?;
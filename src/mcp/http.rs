@@ -0,0 +1,138 @@
+//! Streamable HTTP transport for `agtx mcp-serve --http`.
+//!
+//! One long-lived server can serve several agents and editors at once. Every
+//! request must carry `Authorization: Bearer <token>`, where the token is read
+//! from `mcp-token` in the agtx config dir (created on first use).
+
+use anyhow::{Context, Result};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::roles::McpRole;
+use super::server::{AgtxMcpServer, ServerMode};
+use crate::config::GlobalConfig;
+
+/// Location of the bearer token file (`~/.config/agtx/mcp-token`)
+pub fn token_path() -> Result<PathBuf> {
+    let config_path = GlobalConfig::config_path()?;
+    let dir = config_path
+        .parent()
+        .context("Could not determine config directory")?;
+    Ok(dir.join("mcp-token"))
+}
+
+/// Read the token stored at `path`, generating a random one if there is none.
+pub fn load_or_create_token(path: &Path) -> Result<String> {
+    if let Ok(token) = std::fs::read_to_string(path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    std::fs::write(path, format!("{}\n", token))
+        .with_context(|| format!("Failed to write MCP token to {:?}", path))?;
+
+    // Owner-only, like the databases
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(token)
+}
+
+/// Whether an `Authorization` header value carries `token`.
+pub fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some(given) = authorization.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    // Constant-time comparison so the token can't be probed byte by byte
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn require_token(State(token): State<Arc<String>>, request: Request, next: Next) -> Response {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if is_authorized(authorization, &token) {
+        return next.run(request).await;
+    }
+    tracing::warn!(path = %request.uri().path(), "MCP HTTP request rejected: missing or wrong bearer token");
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        "Unauthorized\n",
+    )
+        .into_response()
+}
+
+/// The MCP endpoint at `/mcp`, behind the bearer token check.
+pub fn router(
+    mode: ServerMode,
+    role: McpRole,
+    token: String,
+    config: StreamableHttpServerConfig,
+) -> Router {
+    let service = StreamableHttpService::new(
+        move || Ok(AgtxMcpServer::new(mode.clone(), role.clone()).over_http()),
+        Arc::new(LocalSessionManager::default()),
+        config,
+    );
+    Router::new()
+        .nest_service("/mcp", service)
+        .layer(middleware::from_fn_with_state(
+            Arc::new(token),
+            require_token,
+        ))
+}
+
+/// Serve MCP over streamable HTTP on `addr` until Ctrl+C.
+pub async fn serve_http(mode: ServerMode, role: McpRole, addr: SocketAddr) -> Result<()> {
+    let token_path = token_path()?;
+    let token = load_or_create_token(&token_path)?;
+    let config = StreamableHttpServerConfig::default();
+    let shutdown = config.cancellation_token.clone();
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to listen on {}", addr))?;
+    let addr = listener.local_addr()?;
+    tracing::info!(%addr, "MCP HTTP server started");
+    eprintln!(
+        "agtx MCP server listening on http://{}/mcp (bearer token in {})",
+        addr,
+        token_path.display()
+    );
+
+    axum::serve(listener, router(mode, role, token, config))
+        .with_graceful_shutdown(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            // Ends open SSE streams so the server can stop
+            shutdown.cancel();
+        })
+        .await?;
+    Ok(())
+}
@@ -1,4 +1,9 @@
+pub mod http;
+pub mod policy;
+pub mod resources;
+pub mod roles;
 mod server;
 
 pub use server::serve;
+pub use server::ServeOptions;
 pub use server::ServerMode;
@@ -0,0 +1,348 @@
+//! The orchestrator policy in `.agtx/orchestrator.toml`: limits on the moves
+//! agents may make through `move_task`.
+//!
+//! ```toml
+//! columns = ["planning", "running", "review"]  # columns it may move tasks out of and into
+//! allow_done = false                           # may it move tasks to Done?
+//!
+//! [max_retries]
+//! running = 2    # times a task may come back to Running after its first run
+//!
+//! [sign_off]     # tasks the orchestrator may not move to Done
+//! labels = ["security"]
+//! paths = ["migrations/**", "src/auth/**"]
+//!
+//! [quiet_hours]  # local time; only escalate_to_user is allowed
+//! start = "22:00"
+//! end = "07:00"
+//! ```
+//!
+//! `escalate_to_user` is always allowed, so an agent can always hand a task back.
+//! The TUI checks the policy again when it runs a queued move, since the task
+//! may have moved on in the meantime.
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveTime;
+use serde::Deserialize;
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::db::{Database, Task, TaskEvent, TaskEventKind, TaskStatus};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OrchestratorPolicy {
+    /// Columns moves may start or end in; unset allows all of them
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+
+    #[serde(default = "default_allow_done")]
+    pub allow_done: bool,
+
+    /// Per phase, how many times a task may re-enter it
+    #[serde(default)]
+    pub max_retries: BTreeMap<String, usize>,
+
+    #[serde(default)]
+    pub sign_off: SignOff,
+
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+fn default_allow_done() -> bool {
+    true
+}
+
+impl Default for OrchestratorPolicy {
+    fn default() -> Self {
+        Self {
+            columns: None,
+            allow_done: default_allow_done(),
+            max_retries: BTreeMap::new(),
+            sign_off: SignOff::default(),
+            quiet_hours: None,
+        }
+    }
+}
+
+/// Tasks that need a human to move them to Done
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct SignOff {
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Globs relative to the repository root: `*` within a name, `**` across directories
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// A daily window, e.g. 22:00 to 07:00, in local time
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn times(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let parse = |s: &str| {
+            NaiveTime::parse_from_str(s, "%H:%M")
+                .with_context(|| format!("Invalid quiet_hours time '{}', expected HH:MM", s))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    pub fn contains(&self, now: NaiveTime) -> bool {
+        let Ok((start, end)) = self.times() else {
+            return false;
+        };
+        if start <= end {
+            start <= now && now < end
+        } else {
+            // Past midnight
+            now >= start || now < end
+        }
+    }
+}
+
+impl OrchestratorPolicy {
+    /// Read `.agtx/orchestrator.toml`; `None` if the project has none.
+    pub fn load(project_path: &Path) -> Result<Option<Self>> {
+        let path = project_path.join(".agtx").join("orchestrator.toml");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        let policy: Self =
+            toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))?;
+        policy.validate()?;
+        Ok(Some(policy))
+    }
+
+    fn validate(&self) -> Result<()> {
+        for column in self.columns.iter().flatten() {
+            if TaskStatus::from_str(column).is_none() {
+                bail!("Unknown column '{}' in orchestrator policy", column);
+            }
+        }
+        for phase in self.max_retries.keys() {
+            if !matches!(phase.as_str(), "planning" | "running" | "review") {
+                bail!(
+                    "Unknown phase '{}' in [max_retries]; use planning, running or review",
+                    phase
+                );
+            }
+        }
+        if let Some(quiet_hours) = &self.quiet_hours {
+            quiet_hours.times()?;
+        }
+        Ok(())
+    }
+
+    /// Why the policy forbids `action` on `task`, if it does.
+    ///
+    /// `times_entered` is how often the task entered the column the action
+    /// leads to before; `touched_paths` lists the files it changed, and is
+    /// only called when the sign-off rules need it. If the changed files can't
+    /// be listed, the move needs sign-off.
+    pub fn check(
+        &self,
+        task: &Task,
+        action: &str,
+        times_entered: usize,
+        touched_paths: impl FnOnce() -> Result<Vec<String>, String>,
+        now: NaiveTime,
+    ) -> Result<(), String> {
+        if action == "escalate_to_user" {
+            return Ok(());
+        }
+        if let Some(quiet_hours) = self.quiet_hours.as_ref().filter(|q| q.contains(now)) {
+            return Err(format!(
+                "Orchestrator policy: quiet hours ({}-{}), only escalate_to_user is allowed",
+                quiet_hours.start, quiet_hours.end
+            ));
+        }
+
+        let target = target_status(action, task.status);
+        if let Some(columns) = &self.columns {
+            for column in [Some(task.status), target].into_iter().flatten() {
+                if !columns.iter().any(|c| c == column.as_str()) {
+                    return Err(format!(
+                        "Orchestrator policy: may not move tasks in or out of {}",
+                        column.as_str()
+                    ));
+                }
+            }
+        }
+        if target == Some(TaskStatus::Done) && !self.allow_done {
+            return Err(
+                "Orchestrator policy: may not move tasks to Done; leave them in Review".to_string(),
+            );
+        }
+
+        if let Some(target) = target.filter(|t| *t != task.status) {
+            if let Some(max) = self.max_retries.get(target.as_str()) {
+                if times_entered > *max {
+                    return Err(format!(
+                        "Orchestrator policy: task was in {} {} times already (max_retries.{} = {}); escalate_to_user instead",
+                        target.as_str(),
+                        times_entered,
+                        target.as_str(),
+                        max
+                    ));
+                }
+            }
+        }
+
+        if target == Some(TaskStatus::Done) {
+            if let Some(label) = task
+                .labels
+                .iter()
+                .find(|l| self.sign_off.labels.contains(l))
+            {
+                return Err(format!(
+                    "Orchestrator policy: tasks labeled '{}' need human sign-off",
+                    label
+                ));
+            }
+            if !self.sign_off.paths.is_empty() {
+                let touched = touched_paths().map_err(|e| {
+                    format!(
+                        "Orchestrator policy: can't tell which files the task changed ({}), so it needs human sign-off",
+                        e
+                    )
+                })?;
+                for pattern in &self.sign_off.paths {
+                    if let Some(path) = touched.iter().find(|p| glob_matches(pattern, p)) {
+                        return Err(format!(
+                            "Orchestrator policy: task changes {} ({}) and needs human sign-off",
+                            path, pattern
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The policy as it applies to one task. The policy file and the task's
+/// history are read once, and the files it changed at most once, however
+/// many actions are checked.
+pub struct TaskPolicy<'a> {
+    task: &'a Task,
+    policy: Result<Option<OrchestratorPolicy>, String>,
+    events: Vec<TaskEvent>,
+    list_touched_paths: Box<dyn Fn() -> Result<Vec<String>, String> + 'a>,
+    touched_paths: OnceCell<Result<Vec<String>, String>>,
+}
+
+impl<'a> TaskPolicy<'a> {
+    /// Load the policy of the project at `project_path` for `task`.
+    /// `touched_paths` lists the files the task changed since it left its
+    /// base branch.
+    pub fn load(
+        db: &Database,
+        task: &'a Task,
+        project_path: Option<&Path>,
+        touched_paths: impl Fn() -> Result<Vec<String>, String> + 'a,
+    ) -> Self {
+        let policy = match project_path.map(OrchestratorPolicy::load) {
+            Some(Ok(policy)) => Ok(policy),
+            Some(Err(e)) => Err(format!("Orchestrator policy: {:#}", e)),
+            None => Ok(None),
+        };
+        let events = match &policy {
+            Ok(Some(_)) => db.get_task_events(&task.id).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        Self {
+            task,
+            policy,
+            events,
+            list_touched_paths: Box::new(touched_paths),
+            touched_paths: OnceCell::new(),
+        }
+    }
+
+    /// Why the policy forbids `action`, if it does. A policy that fails to
+    /// load only allows escalating.
+    pub fn denial(&self, action: &str) -> Option<String> {
+        let policy = match &self.policy {
+            Ok(Some(policy)) => policy,
+            Ok(None) => return None,
+            Err(_) if action == "escalate_to_user" => return None,
+            Err(e) => return Some(e.clone()),
+        };
+        let times_entered = target_status(action, self.task.status).map_or(0, |target| {
+            self.events
+                .iter()
+                .filter(|e| {
+                    e.kind == TaskEventKind::StatusChanged
+                        && e.to_value.as_deref() == Some(target.as_str())
+                })
+                .count()
+        });
+        policy
+            .check(
+                self.task,
+                action,
+                times_entered,
+                || {
+                    self.touched_paths
+                        .get_or_init(|| (self.list_touched_paths)())
+                        .clone()
+                },
+                chrono::Local::now().time(),
+            )
+            .err()
+    }
+}
+
+/// The column a `move_task` action leads to from `status`, if it moves the task
+pub fn target_status(action: &str, status: TaskStatus) -> Option<TaskStatus> {
+    match action {
+        "research" => Some(TaskStatus::Backlog),
+        "move_forward" => match status {
+            TaskStatus::Backlog => Some(TaskStatus::Planning),
+            TaskStatus::Planning => Some(TaskStatus::Running),
+            TaskStatus::Running => Some(TaskStatus::Review),
+            TaskStatus::Review | TaskStatus::Done => Some(TaskStatus::Done),
+        },
+        "move_to_planning" => Some(TaskStatus::Planning),
+        "move_to_running" | "resume" => Some(TaskStatus::Running),
+        "move_to_review" => Some(TaskStatus::Review),
+        "move_to_done" => Some(TaskStatus::Done),
+        _ => None,
+    }
+}
+
+/// Whether `path` matches `pattern`, where `*` matches within one path
+/// component and `**` any number of them.
+pub fn glob_matches(pattern: &str, path: &str) -> bool {
+    fn components(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|skip| components(rest, &path[skip..])),
+            Some((first, rest)) => path
+                .split_first()
+                .is_some_and(|(name, tail)| name_matches(first, name) && components(rest, tail)),
+        }
+    }
+    let pattern: Vec<&str> = pattern.trim_start_matches("./").split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    components(&pattern, &path)
+}
+
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => name.strip_prefix(prefix).is_some_and(|name| {
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain([name.len()])
+                .any(|i| name_matches(rest, &name[i..]))
+        }),
+    }
+}
@@ -0,0 +1,198 @@
+//! MCP resources: projects, tasks and the artifacts their agents write,
+//! addressed as `agtx://project/<project_id>[/task/<task_id>[/artifact/<phase>]]`,
+//! plus the bookkeeping behind `resources/subscribe`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::WorkflowPlugin;
+use crate::db::Task;
+
+/// Phases that can have an artifact, in workflow order
+pub const ARTIFACT_PHASES: [&str; 4] = ["research", "planning", "running", "review"];
+
+/// A parsed `agtx://` resource URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    Project {
+        project_id: String,
+    },
+    Task {
+        project_id: String,
+        task_id: String,
+    },
+    Artifact {
+        project_id: String,
+        task_id: String,
+        phase: String,
+    },
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let parts: Vec<&str> = uri.strip_prefix("agtx://project/")?.split('/').collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return None;
+        }
+        match parts.as_slice() {
+            [project] => Some(ResourceUri::Project {
+                project_id: project.to_string(),
+            }),
+            [project, "task", task] => Some(ResourceUri::Task {
+                project_id: project.to_string(),
+                task_id: task.to_string(),
+            }),
+            [project, "task", task, "artifact", phase] if ARTIFACT_PHASES.contains(phase) => {
+                Some(ResourceUri::Artifact {
+                    project_id: project.to_string(),
+                    task_id: task.to_string(),
+                    phase: phase.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn project_id(&self) -> &str {
+        match self {
+            ResourceUri::Project { project_id }
+            | ResourceUri::Task { project_id, .. }
+            | ResourceUri::Artifact { project_id, .. } => project_id,
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceUri::Project { project_id } => write!(f, "agtx://project/{}", project_id),
+            ResourceUri::Task {
+                project_id,
+                task_id,
+            } => write!(f, "agtx://project/{}/task/{}", project_id, task_id),
+            ResourceUri::Artifact {
+                project_id,
+                task_id,
+                phase,
+            } => write!(
+                f,
+                "agtx://project/{}/task/{}/artifact/{}",
+                project_id, task_id, phase
+            ),
+        }
+    }
+}
+
+/// The artifact files of a task's phases that exist in its worktree.
+/// Research is always cycle 1; the other phases use the task's cycle.
+pub fn task_artifacts(task: &Task, plugin: &WorkflowPlugin) -> Vec<(&'static str, PathBuf)> {
+    let Some(worktree) = task.worktree_path.as_deref() else {
+        return Vec::new();
+    };
+    ARTIFACT_PHASES
+        .iter()
+        .filter_map(|&phase| {
+            let template = match phase {
+                "research" => plugin.artifacts.research.as_deref(),
+                "planning" => plugin.artifacts.planning.as_deref(),
+                "running" => plugin.artifacts.running.as_deref(),
+                _ => plugin.artifacts.review.as_deref(),
+            }?;
+            let cycle = if phase == "research" { 1 } else { task.cycle };
+            find_artifact(Path::new(worktree), template, &task.id, cycle).map(|path| (phase, path))
+        })
+        .collect()
+}
+
+/// The file an artifact template points to under `dir`, if there is one.
+/// `{task_id}` and `{phase}` (zero-padded first, e.g. "01", then "1") are
+/// filled in, and a `*` matches within a single path component.
+pub fn find_artifact(dir: &Path, template: &str, task_id: &str, cycle: i32) -> Option<PathBuf> {
+    let template = template.replace("{task_id}", task_id);
+    [format!("{:02}", cycle), cycle.to_string()]
+        .iter()
+        .find_map(|phase| glob_first(dir, &template.replace("{phase}", phase)))
+}
+
+/// First file matching `pattern` under `dir`, in name order
+fn glob_first(dir: &Path, pattern: &str) -> Option<PathBuf> {
+    let (component, rest) = match pattern.split_once('/') {
+        Some((component, rest)) => (component, Some(rest)),
+        None => (pattern, None),
+    };
+    let candidates = match component.split_once('*') {
+        Some((prefix, suffix)) => {
+            let mut matches: Vec<PathBuf> = std::fs::read_dir(dir)
+                .ok()?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| {
+                            name.len() >= prefix.len() + suffix.len()
+                                && name.starts_with(prefix)
+                                && name.ends_with(suffix)
+                        })
+                })
+                .collect();
+            matches.sort();
+            matches
+        }
+        None => vec![dir.join(component)],
+    };
+    candidates.into_iter().find_map(|path| match rest {
+        Some(rest) => glob_first(&path, rest),
+        None => path.is_file().then_some(path),
+    })
+}
+
+/// The resources one client subscribed to, each with a fingerprint of its
+/// state when last checked (`None` while it doesn't exist)
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    fingerprints: HashMap<String, Option<String>>,
+    watching: bool,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&mut self, uri: impl Into<String>, fingerprint: Option<String>) {
+        self.fingerprints.insert(uri.into(), fingerprint);
+    }
+
+    pub fn unsubscribe(&mut self, uri: &str) {
+        self.fingerprints.remove(uri);
+    }
+
+    /// Mark the watcher as running. False if it already was.
+    pub fn start_watching(&mut self) -> bool {
+        !std::mem::replace(&mut self.watching, true)
+    }
+
+    /// Whether the watcher should keep running; it stops once nothing is
+    /// subscribed and is started again by the next subscription.
+    pub fn keep_watching(&mut self) -> bool {
+        self.watching = !self.fingerprints.is_empty();
+        self.watching
+    }
+
+    /// Drop every subscription, e.g. when the client has gone away.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Re-fingerprint every subscribed resource and return the URIs that changed.
+    pub fn update(&mut self, current: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut changed = Vec::new();
+        for (uri, fingerprint) in self.fingerprints.iter_mut() {
+            let now = current(uri);
+            if *fingerprint != now {
+                *fingerprint = now;
+                changed.push(uri.clone());
+            }
+        }
+        changed.sort();
+        changed
+    }
+}
@@ -0,0 +1,172 @@
+//! Permission profiles for `agtx mcp-serve --role <name>`: which tools a
+//! client may call and which projects it may use.
+//!
+//! Built-in roles:
+//! - `orchestrator`: every tool
+//! - `sweep`: reading the board and planning work (create, update, delete and
+//!   move tasks), but not typing into agent panes or taking notifications
+//! - `readonly`: reading only
+//!
+//! More roles can be defined under `[mcp_roles.<name>]` in the global config.
+
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::config::McpRoleConfig;
+use crate::db::{EventActor, Project};
+
+/// Tools that only read the board, the worktrees and agent output
+pub const READ_ONLY_TOOLS: &[&str] = &[
+    "list_projects",
+    "list_tasks",
+    "get_ready_tasks",
+    "get_task",
+    "get_transition_status",
+    "get_task_history",
+    "read_task_transcript",
+    "check_conflicts",
+    "get_diff_stat",
+    "get_task_diff",
+    "read_pane_content",
+];
+
+/// Tools the sweep role gets on top of the read-only ones
+const SWEEP_TOOLS: &[&str] = &[
+    "create_task",
+    "create_tasks_batch",
+    "update_task",
+    "delete_task",
+    "move_task",
+];
+
+pub const BUILT_IN_ROLES: &[&str] = &["orchestrator", "sweep", "readonly"];
+
+/// Tools of a built-in role; `Some(None)` means all of them
+fn built_in_tools(name: &str) -> Option<Option<BTreeSet<String>>> {
+    let tools = |lists: &[&[&str]]| {
+        lists
+            .iter()
+            .flat_map(|list| list.iter().map(|t| t.to_string()))
+            .collect()
+    };
+    match name {
+        "orchestrator" => Some(None),
+        "sweep" => Some(Some(tools(&[READ_ONLY_TOOLS, SWEEP_TOOLS]))),
+        "readonly" => Some(Some(tools(&[READ_ONLY_TOOLS]))),
+        _ => None,
+    }
+}
+
+/// What a connected MCP client may do
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpRole {
+    pub name: String,
+    /// `None` allows every tool
+    tools: Option<BTreeSet<String>>,
+    /// Project IDs, names or paths; `None` allows every project
+    projects: Option<Vec<String>>,
+}
+
+impl McpRole {
+    /// No restrictions, for servers started without `--role`
+    pub fn unrestricted() -> Self {
+        Self {
+            name: "unrestricted".to_string(),
+            tools: None,
+            projects: None,
+        }
+    }
+
+    /// Look up a built-in role or one from `[mcp_roles]`.
+    pub fn resolve(name: &str, configured: &BTreeMap<String, McpRoleConfig>) -> Result<Self> {
+        if let Some(shadowing) = configured
+            .keys()
+            .find(|k| BUILT_IN_ROLES.contains(&k.as_str()))
+        {
+            bail!(
+                "[mcp_roles.{}] shadows a built-in role; pick another name",
+                shadowing
+            );
+        }
+        if let Some(tools) = built_in_tools(name) {
+            return Ok(Self {
+                name: name.to_string(),
+                tools,
+                projects: None,
+            });
+        }
+        let Some(config) = configured.get(name) else {
+            let known: Vec<&str> = BUILT_IN_ROLES
+                .iter()
+                .copied()
+                .chain(configured.keys().map(String::as_str))
+                .collect();
+            bail!(
+                "Unknown MCP role '{}'. Available roles: {}",
+                name,
+                known.join(", ")
+            );
+        };
+
+        // "orchestrator" in the list allows everything
+        let tools = match &config.tools {
+            Some(entries) if entries.iter().all(|e| built_in_tools(e) != Some(None)) => Some(
+                entries
+                    .iter()
+                    .flat_map(|entry| match built_in_tools(entry) {
+                        Some(Some(role_tools)) => role_tools.into_iter().collect(),
+                        _ => vec![entry.clone()],
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+        Ok(Self {
+            name: name.to_string(),
+            tools,
+            projects: config.projects.clone(),
+        })
+    }
+
+    /// Who task changes made under this role are recorded as. Only the
+    /// orchestrator agent agtx spawns, a project server on stdio with the
+    /// orchestrator role, counts as the orchestrator; every other client,
+    /// including anything connecting over HTTP, is an MCP client.
+    pub fn actor(&self, project_scoped: bool, over_http: bool) -> EventActor {
+        if self.name == "orchestrator" && project_scoped && !over_http {
+            EventActor::Orchestrator
+        } else {
+            EventActor::Mcp
+        }
+    }
+
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        self.tools.as_ref().is_none_or(|tools| tools.contains(tool))
+    }
+
+    /// Tools the role lists that aren't in `known`, most likely typos
+    pub fn unknown_tools<'a>(&'a self, known: &[String]) -> Vec<&'a str> {
+        self.tools
+            .iter()
+            .flatten()
+            .filter(|tool| !known.contains(tool))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether the role is limited to some projects
+    pub fn restricts_projects(&self) -> bool {
+        self.projects.is_some()
+    }
+
+    pub fn allows_project(&self, project: &Project) -> bool {
+        self.projects.as_ref().is_none_or(|allowed| {
+            allowed.iter().any(|entry| {
+                *entry == project.id
+                    || *entry == project.name
+                    || Path::new(entry) == Path::new(&project.path)
+            })
+        })
+    }
+}
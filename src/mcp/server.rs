@@ -1,17 +1,39 @@
-use std::path::PathBuf;
-use std::process::Command;
+use chrono::NaiveDate;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
-    model::{ServerCapabilities, ServerInfo},
-    schemars, tool, tool_handler, tool_router,
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
+    model::{
+        AnnotateAble, CallToolRequestParams, CallToolResult, Content, ListResourceTemplatesResult,
+        ListResourcesResult, ListToolsResult, PaginatedRequestParams, RawResource,
+        RawResourceTemplate, ReadResourceRequestParams, ReadResourceResult, ResourceContents,
+        ResourceUpdatedNotificationParam, ServerCapabilities, ServerInfo, SubscribeRequestParams,
+        Tool, UnsubscribeRequestParams,
+    },
+    schemars,
+    service::{Peer, RequestContext},
+    tool, tool_router,
     transport::io::stdio,
-    ServerHandler, ServiceExt,
+    ErrorData as McpError, RoleServer, ServerHandler, ServiceExt,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::config::{GlobalConfig, ProjectConfig};
-use crate::db::{Database, Task, TaskStatus, TransitionRequest};
+use super::policy::TaskPolicy;
+use super::resources::{self, ResourceUri, Subscriptions};
+use super::roles::McpRole;
+use crate::config::{GlobalConfig, ProjectConfig, WorkflowPlugin};
+use crate::db::{
+    Database, EventActor, PhaseStatus, Priority, Project, SubtaskProgress, Task, TaskStatus,
+    TransitionRequest, Usage,
+};
+use crate::git::{FileDiff, GitOperations, RealGitOps};
+use crate::graph;
+
+/// How often subscribed resources are checked for changes
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Whether the MCP server is bound to a specific project or serves all projects globally.
 #[derive(Debug, Clone)]
@@ -40,6 +62,15 @@ pub struct ListTasksParams {
     pub project_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetReadyTasksParams {
+    /// Project ID (required in global mode — call list_projects first to get IDs).
+    #[schemars(
+        description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
+    )]
+    pub project_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct GetTaskParams {
     /// The task ID (UUID)
@@ -84,6 +115,43 @@ pub struct GetTransitionStatusParams {
     pub project_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetTaskHistoryParams {
+    /// The task ID (UUID)
+    #[schemars(description = "The task ID (UUID)")]
+    pub task_id: String,
+    /// Project ID (required in global mode — call list_projects first to get IDs).
+    #[schemars(
+        description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
+    )]
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ReadTaskTranscriptParams {
+    /// The task ID (UUID)
+    #[schemars(description = "The task ID (UUID)")]
+    pub task_id: String,
+    /// Phase to read: research, planning, running or review. Defaults to the latest recorded phase.
+    #[schemars(
+        description = "Phase to read: research, planning, running or review. Defaults to the latest recorded phase."
+    )]
+    pub phase: Option<String>,
+    /// Number of lines to read from the end of the transcript (default 200)
+    #[schemars(
+        description = "Number of lines to read from the end of the transcript (default 200, max 10000)"
+    )]
+    pub tail_lines: Option<usize>,
+    /// Keep ANSI escape sequences (default false: plain text)
+    #[schemars(description = "Keep ANSI escape sequences in the content (default false)")]
+    pub raw: Option<bool>,
+    /// Project ID (required in global mode — call list_projects first to get IDs).
+    #[schemars(
+        description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
+    )]
+    pub project_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CheckConflictsParams {
     /// Optional task ID. If omitted, checks all tasks in Review status.
@@ -96,6 +164,48 @@ pub struct CheckConflictsParams {
     pub project_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetDiffStatParams {
+    /// The task ID (UUID)
+    #[schemars(description = "The task ID (UUID)")]
+    pub task_id: String,
+    /// Project ID (required in global mode — call list_projects first to get IDs).
+    #[schemars(
+        description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
+    )]
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetTaskDiffParams {
+    /// The task ID (UUID)
+    #[schemars(description = "The task ID (UUID)")]
+    pub task_id: String,
+    /// Only return the diff of this file (path relative to the worktree)
+    #[schemars(
+        description = "Only return the diff of this file (path relative to the worktree, as listed by get_diff_stat)"
+    )]
+    pub file: Option<String>,
+    /// Index of the first file to return, from next_offset of the previous page (default 0)
+    #[schemars(
+        description = "Index of the first file to return; pass next_offset from the previous page (default 0)"
+    )]
+    pub offset: Option<usize>,
+    /// Maximum number of files per page (default 20)
+    #[schemars(description = "Maximum number of files per page (default 20)")]
+    pub max_files: Option<usize>,
+    /// Maximum size of the returned diffs in bytes (default 50000)
+    #[schemars(
+        description = "Maximum total size of the returned diffs in bytes (default 50000, max 200000). A single larger file is truncated."
+    )]
+    pub max_bytes: Option<usize>,
+    /// Project ID (required in global mode — call list_projects first to get IDs).
+    #[schemars(
+        description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
+    )]
+    pub project_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct GetNotificationsParams {
     /// Project ID (required in global mode — call list_projects first to get IDs).
@@ -156,6 +266,22 @@ pub struct CreateTaskParams {
         description = "Base branch to create the worktree from (e.g. another task's branch for stacked PRs). Defaults to project's main branch."
     )]
     pub base_branch: Option<String>,
+    /// Parent task ID, making this task a subtask
+    #[schemars(
+        description = "ID of the parent task. The new task becomes its subtask and inherits the parent's plugin and base branch unless given its own. A parent can only move to Done once all its subtasks are Done."
+    )]
+    pub parent_id: Option<String>,
+    /// Labels (e.g. "bug", "frontend")
+    #[schemars(description = "Labels for the task, e.g. [\"bug\", \"frontend\"]")]
+    pub labels: Option<Vec<String>>,
+    /// Priority: low, normal, high or urgent
+    #[schemars(
+        description = "Priority: low, normal (default), high or urgent. Higher priorities sort to the top of their column."
+    )]
+    pub priority: Option<String>,
+    /// Due date (YYYY-MM-DD)
+    #[schemars(description = "Due date in YYYY-MM-DD format")]
+    pub due_date: Option<String>,
     /// Project ID (required in global mode — call list_projects first to get IDs).
     #[schemars(
         description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
@@ -179,6 +305,11 @@ pub struct BatchTask {
         description = "Indices (0-based) into the tasks array that this task depends on. Referenced tasks must have a lower index (no forward references)."
     )]
     pub depends_on: Option<Vec<usize>>,
+    /// Index (0-based) into the tasks array of this task's parent
+    #[schemars(
+        description = "Index (0-based) into the tasks array of this task's parent, making it a subtask. The parent must have a lower index. Subtasks inherit the parent's plugin and base branch unless given their own."
+    )]
+    pub parent: Option<usize>,
     /// Base branch to create worktree from (defaults to project's main branch)
     #[schemars(
         description = "Base branch to create the worktree from (e.g. another task's branch for stacked PRs). Defaults to project's main branch."
@@ -189,7 +320,9 @@ pub struct BatchTask {
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTasksBatchParams {
     /// Array of tasks to create, with index-based dependency wiring
-    #[schemars(description = "Array of tasks to create. Use depends_on with 0-based indices to wire dependencies between them.")]
+    #[schemars(
+        description = "Array of tasks to create. Use depends_on with 0-based indices to wire dependencies between them, and parent to nest subtasks under a task."
+    )]
     pub tasks: Vec<BatchTask>,
     /// Project ID (required in global mode — call list_projects first to get IDs).
     #[schemars(
@@ -222,6 +355,15 @@ pub struct UpdateTaskParams {
         description = "Base branch to create the worktree from (e.g. another task's branch for stacked PRs)"
     )]
     pub base_branch: Option<String>,
+    /// New labels (if provided, replaces existing)
+    #[schemars(description = "Labels for the task (replaces existing labels; [] removes all)")]
+    pub labels: Option<Vec<String>>,
+    /// New priority (if provided)
+    #[schemars(description = "Priority: low, normal, high or urgent")]
+    pub priority: Option<String>,
+    /// New due date (if provided)
+    #[schemars(description = "Due date in YYYY-MM-DD format (empty string removes it)")]
+    pub due_date: Option<String>,
     /// Project ID (required in global mode — call list_projects first to get IDs).
     #[schemars(
         description = "Project ID. Required in global mode. Call list_projects first to get project IDs."
@@ -250,6 +392,15 @@ struct ProjectSummary {
     path: String,
 }
 
+/// Content of a project resource
+#[derive(Serialize)]
+struct ProjectResource {
+    id: String,
+    name: String,
+    path: String,
+    tasks: Vec<TaskSummary>,
+}
+
 #[derive(Serialize)]
 struct TaskSummary {
     id: String,
@@ -262,9 +413,32 @@ struct TaskSummary {
     plugin: Option<String>,
     referenced_tasks: Option<String>,
     base_branch: Option<String>,
+    /// Phase the task is waiting to enter because an agent limit was reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queued_phase: Option<String>,
+    labels: Vec<String>,
+    priority: Priority,
+    due_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<String>,
+    /// Done and total subtasks, for parent tasks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subtasks: Option<SubtaskProgress>,
     deps_satisfied: bool,
 }
 
+/// A Backlog task whose dependencies are all in Review or Done
+#[derive(Serialize)]
+struct ReadyTask {
+    id: String,
+    title: String,
+    plugin: Option<String>,
+    labels: Vec<String>,
+    priority: Priority,
+    due_date: Option<NaiveDate>,
+    referenced_tasks: Option<String>,
+}
+
 #[derive(Serialize)]
 struct TaskDetail {
     id: String,
@@ -283,6 +457,17 @@ struct TaskDetail {
     referenced_tasks: Option<String>,
     base_branch: Option<String>,
     escalation_note: Option<String>,
+    /// What the agent in the task's session is doing: working, idle, ready or exited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phase_status: Option<String>,
+    /// Phase the task is waiting to enter because an agent limit was reached
+    queued_phase: Option<String>,
+    labels: Vec<String>,
+    priority: Priority,
+    due_date: Option<NaiveDate>,
+    parent_id: Option<String>,
+    /// Subtasks with their status; the task can't move to Done until all are Done.
+    subtasks: Vec<BlockingTask>,
     created_at: String,
     updated_at: String,
     /// Whether all referenced_tasks (dependencies) are in Review or Done.
@@ -291,6 +476,23 @@ struct TaskDetail {
     blocking_tasks: Vec<BlockingTask>,
     /// Actions the orchestrator can take on this task given its current status and plugin rules.
     allowed_actions: Vec<String>,
+    /// Tokens and estimated cost of the task's agent sessions so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<TaskUsageDetail>,
+}
+
+#[derive(Serialize)]
+struct TaskUsageDetail {
+    total: Usage,
+    by_phase: Vec<PhaseUsage>,
+}
+
+#[derive(Serialize)]
+struct PhaseUsage {
+    phase: String,
+    agent: String,
+    #[serde(flatten)]
+    usage: Usage,
 }
 
 #[derive(Serialize)]
@@ -313,6 +515,30 @@ struct TransitionStatusResult {
     error: Option<String>,
 }
 
+#[derive(Serialize)]
+struct TaskEventItem {
+    kind: String,
+    from: Option<String>,
+    to: Option<String>,
+    actor: String,
+    created_at: String,
+}
+
+#[derive(Serialize)]
+struct StatusDuration {
+    status: String,
+    seconds: i64,
+}
+
+#[derive(Serialize)]
+struct TaskHistoryResponse {
+    task_id: String,
+    status: String,
+    events: Vec<TaskEventItem>,
+    /// Total time spent in each status entered so far (current status counts up to now).
+    time_in_status: Vec<StatusDuration>,
+}
+
 #[derive(Serialize)]
 struct ConflictCheckResult {
     task_id: String,
@@ -329,6 +555,34 @@ struct CheckConflictsResponse {
     results: Vec<ConflictCheckResult>,
 }
 
+#[derive(Serialize)]
+struct DiffStatResponse {
+    task_id: String,
+    branch_name: Option<String>,
+    /// `git diff --stat` against main
+    stat: String,
+    files: Vec<FileStat>,
+    untracked_files: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct FileStat {
+    path: String,
+    untracked: bool,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Serialize)]
+struct TaskDiffResponse {
+    task_id: String,
+    total_files: usize,
+    offset: usize,
+    files: Vec<FileDiff>,
+    /// Offset of the next page, if there are more files
+    next_offset: Option<usize>,
+}
+
 #[derive(Serialize)]
 struct NotificationItem {
     message: String,
@@ -348,6 +602,20 @@ struct ReadPaneResponse {
     lines_requested: i32,
 }
 
+#[derive(Serialize)]
+struct TranscriptPhaseItem {
+    phase: String,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+struct ReadTaskTranscriptResponse {
+    task_id: String,
+    phase: String,
+    available: Vec<TranscriptPhaseItem>,
+    content: String,
+}
+
 #[derive(Serialize)]
 struct SendToTaskResponse {
     task_id: String,
@@ -390,20 +658,188 @@ struct DeleteTaskResponse {
     message: String,
 }
 
+fn parse_priority(value: &str) -> Result<Priority, String> {
+    Priority::from_str(&value.trim().to_lowercase()).ok_or_else(|| {
+        format!(
+            "Error: invalid priority '{}'. Use low, normal, high or urgent.",
+            value
+        )
+    })
+}
+
+/// An empty string clears the due date
+fn parse_due_date(value: &str) -> Result<Option<NaiveDate>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| format!("Error: invalid due_date '{}'. Use YYYY-MM-DD.", value))
+}
+
+fn task_summary(db: &Database, t: Task) -> TaskSummary {
+    let deps_satisfied = db.deps_satisfied(&t);
+    let subtasks = db.subtask_progress(&t).ok().flatten();
+    TaskSummary {
+        id: t.id,
+        title: t.title,
+        description: t.description,
+        status: t.status.as_str().to_string(),
+        agent: t.agent,
+        branch_name: t.branch_name,
+        pr_url: t.pr_url,
+        plugin: t.plugin,
+        referenced_tasks: t.referenced_tasks,
+        base_branch: t.base_branch,
+        queued_phase: t.queued_phase,
+        labels: t.labels,
+        priority: t.priority,
+        due_date: t.due_date,
+        parent_id: t.parent_id,
+        subtasks,
+        deps_satisfied,
+    }
+}
+
+/// The workflow plugin a task runs with
+fn task_plugin(task: &Task, project_path: Option<&Path>) -> Option<WorkflowPlugin> {
+    match &task.plugin {
+        Some(name) => WorkflowPlugin::load(name, project_path)
+            .ok()
+            .or_else(|| crate::skills::load_bundled_plugin(name)),
+        None => crate::skills::load_bundled_plugin("agtx"),
+    }
+}
+
+/// The phase status recorded by the TUI, while the task has a live session
+fn live_phase_status(db: &Database, task: &Task) -> Option<PhaseStatus> {
+    if task.session_name.is_none() || task.status == TaskStatus::Done {
+        return None;
+    }
+    db.get_phase_status(&task.id).ok().flatten()
+}
+
+/// The parts of a task that subscribers are told about when they change
+fn task_fingerprint(db: &Database, task: &Task) -> String {
+    format!(
+        "{}|{}|{}",
+        task.status.as_str(),
+        task.escalation_note.as_deref().unwrap_or_default(),
+        live_phase_status(db, task).map_or("", |p| p.as_str())
+    )
+}
+
+fn listed_resource(
+    uri: &ResourceUri,
+    name: String,
+    description: String,
+    mime_type: &str,
+) -> rmcp::model::Resource {
+    let mut resource = RawResource::new(uri.to_string(), name);
+    resource.description = Some(description);
+    resource.mime_type = Some(mime_type.to_string());
+    resource.no_annotation()
+}
+
+fn resource_template(
+    uri_template: &str,
+    name: &str,
+    description: &str,
+    mime_type: Option<&str>,
+) -> rmcp::model::ResourceTemplate {
+    RawResourceTemplate {
+        uri_template: uri_template.to_string(),
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        mime_type: mime_type.map(String::from),
+        icons: None,
+    }
+    .no_annotation()
+}
+
+fn json_contents(uri: &ResourceUri, value: &impl Serialize) -> Result<ResourceContents, McpError> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| McpError::internal_error(format!("Error serializing: {}", e), None))?;
+    Ok(ResourceContents::TextResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some("application/json".to_string()),
+        text,
+        meta: None,
+    })
+}
+
+fn artifact_mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("md") => "text/markdown",
+        Some("json") => "application/json",
+        _ => "text/plain",
+    }
+}
+
 // === MCP Server ===
 
 #[derive(Debug, Clone)]
 pub struct AgtxMcpServer {
     mode: ServerMode,
+    /// The tools and projects this client may use
+    role: McpRole,
     tool_router: ToolRouter<Self>,
+    /// Resources this client subscribed to (one server per client session)
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    /// Serving an HTTP session rather than stdio
+    over_http: bool,
 }
 
 impl AgtxMcpServer {
-    fn new(mode: ServerMode) -> Self {
+    pub(super) fn new(mode: ServerMode, role: McpRole) -> Self {
         Self {
             mode,
+            role,
             tool_router: Self::tool_router(),
+            subscriptions: Arc::default(),
+            over_http: false,
+        }
+    }
+
+    /// A server for one session of the HTTP transport
+    pub(super) fn over_http(mut self) -> Self {
+        self.over_http = true;
+        self
+    }
+
+    /// Why this client may not make a tool call, if it may not.
+    fn check_tool_call(&self, request: &CallToolRequestParams) -> Result<(), String> {
+        if !self.role.allows_tool(&request.name) {
+            return Err(format!(
+                "Permission denied: role '{}' may not call {}",
+                self.role.name, request.name
+            ));
+        }
+        // A project-scoped server is checked once at startup
+        let project_id = request
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("project_id"))
+            .and_then(|id| id.as_str());
+        if let (ServerMode::Global, Some(project_id)) = (&self.mode, project_id) {
+            if self.role.restricts_projects() {
+                let project = self
+                    .open_global_db()?
+                    .get_project_by_id(project_id)
+                    .ok()
+                    .flatten();
+                // Unknown IDs are left to the tool to report
+                if project.is_some_and(|p| !self.role.allows_project(&p)) {
+                    return Err(format!(
+                        "Permission denied: role '{}' may not use project {}",
+                        self.role.name, project_id
+                    ));
+                }
+            }
         }
+        Ok(())
     }
 
     /// Resolve a project path from an optional `project_id`.
@@ -429,10 +865,19 @@ impl AgtxMcpServer {
     }
 
     /// Open a project DB, resolving the path via `resolve_project_path`.
+    /// Task changes made through it are attributed to this server's caller.
     fn open_project_db_for(&self, project_id: Option<&str>) -> Result<Database, String> {
         let path = self.resolve_project_path(project_id)?;
-        Database::open_project(&path)
-            .map_err(|e| format!("Failed to open project database: {}", e))
+        let db = Database::open_project(&path)
+            .map_err(|e| format!("Failed to open project database: {}", e))?;
+        db.set_actor(self.actor());
+        Ok(db)
+    }
+
+    /// Who this client's task changes are recorded as, by its role and transport
+    fn actor(&self) -> EventActor {
+        self.role
+            .actor(matches!(self.mode, ServerMode::Project(_)), self.over_http)
     }
 
     fn open_project_db(&self) -> Result<Database, String> {
@@ -454,6 +899,11 @@ impl AgtxMcpServer {
         }
     }
 
+    /// Session operations for the multiplexer agents run in.
+    fn session_ops(&self) -> std::sync::Arc<dyn crate::tmux::TmuxOperations> {
+        crate::tmux::backend_ops(GlobalConfig::load().unwrap_or_default().session_backend)
+    }
+
     /// Get the default agent and plugin from merged config.
     fn config_defaults_for(&self, project_id: Option<&str>) -> (String, Option<String>) {
         let global = GlobalConfig::load().unwrap_or_default();
@@ -470,18 +920,346 @@ impl AgtxMcpServer {
         }
     }
 
-    /// Compute which move_task actions are valid for a task given its status and plugin rules.
-    fn allowed_actions(&self, task: &Task, deps_satisfied: bool) -> Vec<String> {
-        let project_path = self.resolve_project_path(None).ok();
+    /// Open a task and its worktree for the diff tools.
+    fn task_worktree(
+        &self,
+        project_id: Option<&str>,
+        task_id: &str,
+    ) -> Result<(Task, String), String> {
+        let db = self.open_project_db_for(project_id)?;
+        let task = match db.get_task(task_id) {
+            Ok(Some(t)) => t,
+            Ok(None) => return Err(format!("Task not found: {}", task_id)),
+            Err(e) => return Err(format!("Error getting task: {}", e)),
+        };
+        match task.worktree_path.clone() {
+            Some(worktree) if std::path::Path::new(&worktree).exists() => Ok((task, worktree)),
+            Some(worktree) => Err(format!("Worktree no longer exists: {}", worktree)),
+            None => Err(format!("Task {} has no worktree yet", task.id)),
+        }
+    }
+
+    /// Directories agtx copies into a task's worktree, which are not part of its changes.
+    fn copied_dirs(&self, task: &Task, project_path: Option<&Path>) -> Vec<String> {
+        let plugin = task_plugin(task, project_path);
+        let mut dirs: Vec<String> = crate::git::AGENT_CONFIG_DIRS
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        dirs.extend(plugin.map_or_else(Vec::new, |p| p.copy_dirs));
+        dirs
+    }
+
+    /// The branch a task's changes are measured against: its own base branch,
+    /// else the project's main branch.
+    fn task_base_branch(
+        &self,
+        task: &Task,
+        worktree: &str,
+        project_path: Option<&Path>,
+    ) -> Result<String, String> {
+        crate::git::task_base_branch(
+            task.base_branch.as_deref(),
+            project_path.unwrap_or(Path::new(worktree)),
+        )
+        .map_err(|e| format!("Error detecting main branch: {}", e))
+    }
+
+    /// Everything a task changed since it left its base branch, leaving out
+    /// the directories agtx copied into its worktree.
+    fn task_file_diffs(
+        &self,
+        task: &Task,
+        worktree: &str,
+        base: &str,
+        project_path: Option<&Path>,
+    ) -> Result<Vec<FileDiff>, String> {
+        let copied = self.copied_dirs(task, project_path);
+        let exclude: Vec<&str> = copied.iter().map(String::as_str).collect();
+        crate::git::collect_file_diffs(worktree, base, &RealGitOps, &exclude)
+            .map_err(|e| format!("Error reading diff: {:#}", e))
+    }
+
+    /// Projects this server exposes as resources: every indexed project in
+    /// global mode, otherwise the bound one. A bound project that was never
+    /// opened in the TUI goes by its directory name, like its tasks do.
+    fn projects(&self) -> Result<Vec<Project>, String> {
+        let indexed = self.open_global_db().and_then(|db| {
+            db.get_all_projects()
+                .map_err(|e| format!("Error listing projects: {}", e))
+        });
+        match &self.mode {
+            ServerMode::Global => indexed.map(|projects| {
+                projects
+                    .into_iter()
+                    .filter(|p| self.role.allows_project(p))
+                    .collect()
+            }),
+            ServerMode::Project(path) => {
+                let project = indexed
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|p| Path::new(&p.path) == path)
+                    .unwrap_or_else(|| {
+                        let name = self.project_name_for(None);
+                        let mut project = Project::new(name.clone(), path.to_string_lossy());
+                        project.id = name;
+                        project
+                    });
+                Ok(vec![project])
+            }
+        }
+    }
+
+    fn resource_project(&self, project_id: &str) -> Result<(Project, Database), McpError> {
+        let project = self
+            .projects()
+            .map_err(|e| McpError::internal_error(e, None))?
+            .into_iter()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| {
+                McpError::resource_not_found(format!("Project not found: {}", project_id), None)
+            })?;
+        let db = Database::open_project(Path::new(&project.path)).map_err(|e| {
+            McpError::internal_error(format!("Failed to open project database: {}", e), None)
+        })?;
+        Ok((project, db))
+    }
+
+    fn resource_task(&self, db: &Database, task_id: &str) -> Result<Task, McpError> {
+        match db.get_task(task_id) {
+            Ok(Some(task)) => Ok(task),
+            Ok(None) => Err(McpError::resource_not_found(
+                format!("Task not found: {}", task_id),
+                None,
+            )),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error getting task: {}", e),
+                None,
+            )),
+        }
+    }
+
+    fn read_resource_uri(&self, uri: &ResourceUri) -> Result<ResourceContents, McpError> {
+        let (project, db) = self.resource_project(uri.project_id())?;
+        match uri {
+            ResourceUri::Project { .. } => {
+                let tasks = db.get_all_tasks().map_err(|e| {
+                    McpError::internal_error(format!("Error listing tasks: {}", e), None)
+                })?;
+                let content = ProjectResource {
+                    id: project.id,
+                    name: project.name,
+                    path: project.path,
+                    tasks: tasks.into_iter().map(|t| task_summary(&db, t)).collect(),
+                };
+                json_contents(uri, &content)
+            }
+            ResourceUri::Task { task_id, .. } => {
+                let task = self.resource_task(&db, task_id)?;
+                json_contents(
+                    uri,
+                    &self.task_detail(&db, task, Some(Path::new(&project.path))),
+                )
+            }
+            ResourceUri::Artifact { task_id, phase, .. } => {
+                let task = self.resource_task(&db, task_id)?;
+                let path = task_plugin(&task, Some(Path::new(&project.path)))
+                    .map(|plugin| resources::task_artifacts(&task, &plugin))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|(p, _)| p == phase)
+                    .map(|(_, path)| path)
+                    .ok_or_else(|| {
+                        McpError::resource_not_found(
+                            format!("Task {} has no {} artifact", task_id, phase),
+                            None,
+                        )
+                    })?;
+                let text = std::fs::read_to_string(&path).map_err(|e| {
+                    McpError::internal_error(format!("Failed to read {:?}: {}", path, e), None)
+                })?;
+                Ok(ResourceContents::TextResourceContents {
+                    uri: uri.to_string(),
+                    mime_type: Some(artifact_mime_type(&path).to_string()),
+                    text,
+                    meta: None,
+                })
+            }
+        }
+    }
+
+    /// What a resource looks like now, for change detection. `None` if it
+    /// doesn't exist (or can't be read).
+    fn resource_fingerprint(&self, uri: &ResourceUri) -> Option<String> {
+        let (project, db) = self.resource_project(uri.project_id()).ok()?;
+        match uri {
+            ResourceUri::Project { .. } => {
+                let tasks = db.get_all_tasks().ok()?;
+                Some(
+                    tasks
+                        .iter()
+                        .map(|t| format!("{}={}", t.id, task_fingerprint(&db, t)))
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                )
+            }
+            ResourceUri::Task { task_id, .. } => {
+                let task = db.get_task(task_id).ok()??;
+                Some(task_fingerprint(&db, &task))
+            }
+            ResourceUri::Artifact { task_id, phase, .. } => {
+                let task = db.get_task(task_id).ok()??;
+                let plugin = task_plugin(&task, Some(Path::new(&project.path)))?;
+                let (_, path) = resources::task_artifacts(&task, &plugin)
+                    .into_iter()
+                    .find(|(p, _)| p == phase)?;
+                let meta = std::fs::metadata(&path).ok()?;
+                Some(format!(
+                    "{}|{}|{:?}",
+                    path.display(),
+                    meta.len(),
+                    meta.modified().ok()
+                ))
+            }
+        }
+    }
+
+    /// Tell the client about changes to the resources it subscribed to,
+    /// until it unsubscribes from all of them or goes away.
+    async fn watch_subscriptions(self, peer: Peer<RoleServer>) {
+        let mut interval = tokio::time::interval(SUBSCRIPTION_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let changed = {
+                let mut subscriptions = self.subscriptions.lock().unwrap();
+                if !subscriptions.keep_watching() {
+                    return;
+                }
+                subscriptions.update(|uri| {
+                    ResourceUri::parse(uri).and_then(|uri| self.resource_fingerprint(&uri))
+                })
+            };
+            for uri in changed {
+                tracing::info!(%uri, "MCP resource updated");
+                if let Err(e) = peer
+                    .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                    .await
+                {
+                    tracing::info!(error = %e, "MCP client gone, dropping its subscriptions");
+                    self.subscriptions.lock().unwrap().clear();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Everything get_task reports about a task
+    fn task_detail(&self, db: &Database, t: Task, project_path: Option<&Path>) -> TaskDetail {
+        let deps_ok = db.deps_satisfied(&t);
+        // Unreadable subtasks keep move_to_done off the list, as the move itself would
+        let (subtasks, subtasks_done) = match db.get_subtasks(&t.id) {
+            Ok(subs) => {
+                let subtasks: Vec<BlockingTask> = subs
+                    .into_iter()
+                    .map(|sub| BlockingTask {
+                        id: sub.id,
+                        title: sub.title,
+                        status: sub.status.as_str().to_string(),
+                    })
+                    .collect();
+                let done = subtasks.iter().all(|s| s.status == "done");
+                (subtasks, done)
+            }
+            Err(_) => (Vec::new(), false),
+        };
+        let allowed = self.allowed_actions(db, &t, deps_ok, subtasks_done, project_path);
+        let blocking = match &t.referenced_tasks {
+            Some(refs) if !refs.is_empty() => refs
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .filter_map(|ref_id| {
+                    db.get_task(ref_id)
+                        .ok()
+                        .flatten()
+                        .filter(|dep| !matches!(dep.status, TaskStatus::Review | TaskStatus::Done))
+                        .map(|dep| BlockingTask {
+                            id: dep.id,
+                            title: dep.title,
+                            status: dep.status.as_str().to_string(),
+                        })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let phase_status = live_phase_status(db, &t);
+        let usage_rows = db.get_task_usage(&t.id).unwrap_or_default();
+        let usage = (!usage_rows.is_empty()).then(|| {
+            let mut total = Usage::default();
+            for row in &usage_rows {
+                total.add(&row.usage);
+            }
+            TaskUsageDetail {
+                total,
+                by_phase: usage_rows
+                    .into_iter()
+                    .map(|row| PhaseUsage {
+                        phase: row.phase,
+                        agent: row.agent,
+                        usage: row.usage,
+                    })
+                    .collect(),
+            }
+        });
+        TaskDetail {
+            id: t.id,
+            title: t.title,
+            description: t.description,
+            status: t.status.as_str().to_string(),
+            agent: t.agent,
+            project_id: t.project_id,
+            session_name: t.session_name,
+            worktree_path: t.worktree_path,
+            branch_name: t.branch_name,
+            pr_number: t.pr_number,
+            pr_url: t.pr_url,
+            plugin: t.plugin,
+            cycle: t.cycle,
+            referenced_tasks: t.referenced_tasks,
+            base_branch: t.base_branch,
+            escalation_note: t.escalation_note,
+            phase_status: phase_status.map(|p| p.as_str().to_string()),
+            queued_phase: t.queued_phase,
+            labels: t.labels,
+            priority: t.priority,
+            due_date: t.due_date,
+            parent_id: t.parent_id,
+            subtasks,
+            created_at: t.created_at.to_rfc3339(),
+            updated_at: t.updated_at.to_rfc3339(),
+            deps_satisfied: deps_ok,
+            blocking_tasks: blocking,
+            allowed_actions: allowed,
+            usage,
+        }
+    }
+
+    /// Compute which move_task actions are valid for a task given its status,
+    /// plugin rules and the project's orchestrator policy.
+    fn allowed_actions(
+        &self,
+        db: &Database,
+        task: &Task,
+        deps_satisfied: bool,
+        subtasks_done: bool,
+        project_path: Option<&Path>,
+    ) -> Vec<String> {
         let mut actions = Vec::new();
 
         let _plugin = match &task.plugin {
-            Some(name) => crate::config::WorkflowPlugin::load(
-                name,
-                project_path.as_deref(),
-            )
-            .ok()
-            .or_else(|| crate::skills::load_bundled_plugin(name)),
+            Some(name) => crate::config::WorkflowPlugin::load(name, project_path)
+                .ok()
+                .or_else(|| crate::skills::load_bundled_plugin(name)),
             None => crate::skills::load_bundled_plugin("agtx"),
         };
 
@@ -514,8 +1292,32 @@ impl AgtxMcpServer {
             });
         }
 
+        // A parent task finishes after its subtasks
+        if !subtasks_done && task.status == TaskStatus::Review {
+            actions.retain(|a| !matches!(a.as_str(), "move_forward" | "move_to_done"));
+        }
+
+        let policy = self.task_policy(db, task, project_path);
+        actions.retain(|a| policy.denial(a).is_none());
         actions
     }
+
+    /// The project's `.agtx/orchestrator.toml` as it applies to `task`
+    fn task_policy<'a>(
+        &'a self,
+        db: &Database,
+        task: &'a Task,
+        project_path: Option<&'a Path>,
+    ) -> TaskPolicy<'a> {
+        TaskPolicy::load(db, task, project_path, move || {
+            let Some(worktree) = task.worktree_path.as_deref() else {
+                return Ok(Vec::new());
+            };
+            let base = self.task_base_branch(task, worktree, project_path)?;
+            let files = self.task_file_diffs(task, worktree, &base, project_path)?;
+            Ok(files.into_iter().map(|f| f.path).collect())
+        })
+    }
 }
 
 #[tool_router]
@@ -528,6 +1330,7 @@ impl AgtxMcpServer {
                 Ok(projects) => {
                     let summaries: Vec<ProjectSummary> = projects
                         .into_iter()
+                        .filter(|p| self.role.allows_project(p))
                         .map(|p| ProjectSummary {
                             id: p.id,
                             name: p.name,
@@ -562,22 +1365,7 @@ impl AgtxMcpServer {
                     Ok(tasks) => {
                         let summaries: Vec<TaskSummary> = tasks
                             .into_iter()
-                            .map(|t| {
-                                let deps_satisfied = db.deps_satisfied(&t);
-                                TaskSummary {
-                                    id: t.id,
-                                    title: t.title,
-                                    description: t.description,
-                                    status: t.status.as_str().to_string(),
-                                    agent: t.agent,
-                                    branch_name: t.branch_name,
-                                    pr_url: t.pr_url,
-                                    plugin: t.plugin,
-                                    referenced_tasks: t.referenced_tasks,
-                                    base_branch: t.base_branch,
-                                    deps_satisfied,
-                                }
-                            })
+                            .map(|t| task_summary(&db, t))
                             .collect();
                         serde_json::to_string_pretty(&summaries)
                             .unwrap_or_else(|e| format!("Error serializing: {}", e))
@@ -590,61 +1378,45 @@ impl AgtxMcpServer {
     }
 
     #[tool(
-        description = "Get full details of a specific task by its ID. Includes allowed_actions based on the task's current status and plugin rules. In global mode, project_id is required — call list_projects first."
+        description = "List the Backlog tasks that are ready to start: every task they depend on is in Review or Done. Sorted by priority (highest first), then by age. Use this to pick the next tasks to move forward. In global mode, project_id is required — call list_projects first."
+    )]
+    fn get_ready_tasks(&self, Parameters(params): Parameters<GetReadyTasksParams>) -> String {
+        tracing::info!(tool = "get_ready_tasks", project_id = ?params.project_id, "MCP tool called");
+        let db = match self.open_project_db_for(params.project_id.as_deref()) {
+            Ok(db) => db,
+            Err(e) => return e,
+        };
+        match db.get_all_tasks() {
+            Ok(tasks) => {
+                let ready: Vec<ReadyTask> = graph::ready_tasks(&tasks)
+                    .into_iter()
+                    .map(|t| ReadyTask {
+                        id: t.id.clone(),
+                        title: t.title.clone(),
+                        plugin: t.plugin.clone(),
+                        labels: t.labels.clone(),
+                        priority: t.priority,
+                        due_date: t.due_date,
+                        referenced_tasks: t.referenced_tasks.clone(),
+                    })
+                    .collect();
+                serde_json::to_string_pretty(&ready)
+                    .unwrap_or_else(|e| format!("Error serializing: {}", e))
+            }
+            Err(e) => format!("Error listing tasks: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Get full details of a specific task by its ID. Includes allowed_actions based on the task's current status and plugin rules, and token usage with estimated cost per phase once the task's agents have used any. In global mode, project_id is required — call list_projects first."
     )]
     fn get_task(&self, Parameters(params): Parameters<GetTaskParams>) -> String {
         tracing::info!(tool = "get_task", task_id = %params.task_id, "MCP tool called");
         match self.open_project_db_for(params.project_id.as_deref()) {
             Ok(db) => match db.get_task(&params.task_id) {
                 Ok(Some(t)) => {
-                    let deps_ok = db.deps_satisfied(&t);
-                    let allowed = self.allowed_actions(&t, deps_ok);
-                    let blocking = match &t.referenced_tasks {
-                        Some(refs) if !refs.is_empty() => refs
-                            .split(',')
-                            .filter(|s| !s.is_empty())
-                            .filter_map(|ref_id| {
-                                db.get_task(ref_id)
-                                    .ok()
-                                    .flatten()
-                                    .filter(|dep| {
-                                        !matches!(
-                                            dep.status,
-                                            TaskStatus::Review | TaskStatus::Done
-                                        )
-                                    })
-                                    .map(|dep| BlockingTask {
-                                        id: dep.id,
-                                        title: dep.title,
-                                        status: dep.status.as_str().to_string(),
-                                    })
-                            })
-                            .collect(),
-                        _ => Vec::new(),
-                    };
-                    let detail = TaskDetail {
-                        id: t.id,
-                        title: t.title,
-                        description: t.description,
-                        status: t.status.as_str().to_string(),
-                        agent: t.agent,
-                        project_id: t.project_id,
-                        session_name: t.session_name,
-                        worktree_path: t.worktree_path,
-                        branch_name: t.branch_name,
-                        pr_number: t.pr_number,
-                        pr_url: t.pr_url,
-                        plugin: t.plugin,
-                        cycle: t.cycle,
-                        referenced_tasks: t.referenced_tasks,
-                        base_branch: t.base_branch,
-                        escalation_note: t.escalation_note,
-                        created_at: t.created_at.to_rfc3339(),
-                        updated_at: t.updated_at.to_rfc3339(),
-                        deps_satisfied: deps_ok,
-                        blocking_tasks: blocking,
-                        allowed_actions: allowed,
-                    };
+                    let project_path = self.resolve_project_path(params.project_id.as_deref());
+                    let detail = self.task_detail(&db, t, project_path.ok().as_deref());
                     serde_json::to_string_pretty(&detail)
                         .unwrap_or_else(|e| format!("Error serializing: {}", e))
                 }
@@ -656,7 +1428,7 @@ impl AgtxMcpServer {
     }
 
     #[tool(
-        description = "Queue a task state transition. The agtx TUI will process it and execute all side effects (worktree creation, agent spawning, etc). Use get_transition_status to check completion. Actions: research (start research phase for backlog task), move_forward, move_to_planning, move_to_running, move_to_review, move_to_done, resume, escalate_to_user (flag task for user attention with an optional reason)"
+        description = "Queue a task state transition. The agtx TUI will process it and execute all side effects (worktree creation, agent spawning, etc). Use get_transition_status to check completion. Actions: research (start research phase for backlog task), move_forward, move_to_planning, move_to_running, move_to_review, move_to_done, resume, escalate_to_user (flag task for user attention with an optional reason). Moves the project's .agtx/orchestrator.toml policy forbids are refused with the reason."
     )]
     fn move_task(&self, Parameters(params): Parameters<MoveTaskParams>) -> String {
         tracing::info!(tool = "move_task", task_id = %params.task_id, action = %params.action, "MCP tool called");
@@ -701,8 +1473,28 @@ impl AgtxMcpServer {
                     return "Cannot advance task: dependencies not in Review/Done. Use get_task to see blocking_tasks.".to_string();
                 }
 
+                let to_done = params.action == "move_to_done"
+                    || (params.action == "move_forward" && task.status == TaskStatus::Review);
+                if to_done {
+                    match db.subtasks_done(&task) {
+                        Ok(true) => {}
+                        Ok(false) => return "Cannot move task to Done: not all subtasks are Done. Use get_task to see subtasks.".to_string(),
+                        Err(e) => return format!("Error checking subtasks: {}", e),
+                    }
+                }
+
+                let project_path = self.resolve_project_path(params.project_id.as_deref()).ok();
+                if let Some(reason) = self
+                    .task_policy(&db, &task, project_path.as_deref())
+                    .denial(&params.action)
+                {
+                    tracing::warn!(task_id = %task.id, action = %params.action, reason = %reason, "Orchestrator policy denied move");
+                    return reason;
+                }
+
                 let mut req = TransitionRequest::new(&params.task_id, &params.action);
                 req.reason = params.reason.clone();
+                req.requested_by = Some(db.actor());
                 let request_id = req.id.clone();
 
                 match db.create_transition_request(&req) {
@@ -759,6 +1551,120 @@ impl AgtxMcpServer {
         }
     }
 
+    #[tool(
+        description = "Get the history of a task: status changes, agent switches, PR creation, escalations and cycle bumps, with who caused each (user, cli, mcp, orchestrator) and time spent in each status. In global mode, project_id is required — call list_projects first."
+    )]
+    fn get_task_history(&self, Parameters(params): Parameters<GetTaskHistoryParams>) -> String {
+        tracing::info!(tool = "get_task_history", task_id = %params.task_id, "MCP tool called");
+        match self.open_project_db_for(params.project_id.as_deref()) {
+            Ok(db) => {
+                let task = match db.get_task(&params.task_id) {
+                    Ok(Some(t)) => t,
+                    Ok(None) => return format!("Task not found: {}", params.task_id),
+                    Err(e) => return format!("Error getting task: {}", e),
+                };
+                let events = match db.get_task_events(&task.id) {
+                    Ok(events) => events,
+                    Err(e) => return format!("Error getting task history: {}", e),
+                };
+                let time_in_status = crate::db::status_durations(&events, chrono::Utc::now())
+                    .into_iter()
+                    .map(|(status, d)| StatusDuration {
+                        status: status.as_str().to_string(),
+                        seconds: d.num_seconds(),
+                    })
+                    .collect();
+                let response = TaskHistoryResponse {
+                    task_id: task.id,
+                    status: task.status.as_str().to_string(),
+                    events: events
+                        .into_iter()
+                        .map(|e| TaskEventItem {
+                            kind: e.kind.as_str().to_string(),
+                            from: e.from_value,
+                            to: e.to_value,
+                            actor: e.actor.as_str().to_string(),
+                            created_at: e.created_at.to_rfc3339(),
+                        })
+                        .collect(),
+                    time_in_status,
+                };
+                serde_json::to_string_pretty(&response)
+                    .unwrap_or_else(|e| format!("Error serializing: {}", e))
+            }
+            Err(e) => e,
+        }
+    }
+
+    #[tool(
+        description = "Read a task's recorded agent transcript (the full terminal output of a phase session, kept on disk after the session ends). Returns the phases that have transcripts and the last lines of the requested one. In global mode, project_id is required — call list_projects first."
+    )]
+    fn read_task_transcript(
+        &self,
+        Parameters(params): Parameters<ReadTaskTranscriptParams>,
+    ) -> String {
+        tracing::info!(tool = "read_task_transcript", task_id = %params.task_id, phase = ?params.phase, "MCP tool called");
+        let project_path = match self.resolve_project_path(params.project_id.as_deref()) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let db = match self.open_project_db_for(params.project_id.as_deref()) {
+            Ok(db) => db,
+            Err(e) => return e,
+        };
+        let task = match db.get_task(&params.task_id) {
+            Ok(Some(t)) => t,
+            Ok(None) => return format!("Task not found: {}", params.task_id),
+            Err(e) => return format!("Error getting task: {}", e),
+        };
+
+        let files = crate::transcript::list(&project_path, &task.id);
+        let file = match params.phase.as_deref() {
+            Some(phase) => match files.iter().find(|f| f.phase == phase) {
+                Some(f) => f,
+                None if crate::transcript::PHASES.contains(&phase) => {
+                    return format!("No {} transcript recorded for task {}", phase, task.id)
+                }
+                None => {
+                    return format!(
+                        "Unknown phase '{}'. Valid phases: {}",
+                        phase,
+                        crate::transcript::PHASES.join(", ")
+                    )
+                }
+            },
+            None => match files.last() {
+                Some(f) => f,
+                None => return format!("No transcript recorded for task {}", task.id),
+            },
+        };
+
+        let lines = params.tail_lines.unwrap_or(200).clamp(1, 10000);
+        let bytes = match crate::transcript::read_tail(&file.path, lines) {
+            Ok(b) => b,
+            Err(e) => return format!("Error reading transcript: {}", e),
+        };
+        let content = if params.raw.unwrap_or(false) {
+            String::from_utf8_lossy(&bytes).into_owned()
+        } else {
+            crate::transcript::strip_ansi(&bytes)
+        };
+        let response = ReadTaskTranscriptResponse {
+            task_id: task.id,
+            phase: file.phase.to_string(),
+            available: files
+                .iter()
+                .map(|f| TranscriptPhaseItem {
+                    phase: f.phase.to_string(),
+                    bytes: f.size,
+                })
+                .collect(),
+            content,
+        };
+        serde_json::to_string_pretty(&response)
+            .unwrap_or_else(|e| format!("Error serializing: {}", e))
+    }
+
     #[tool(
         description = "Check if task branches have merge conflicts with the main branch. Pass a task_id to check one task, or omit it to check all Review tasks. Uses a read-only git check — no files are modified."
     )]
@@ -837,6 +1743,97 @@ impl AgtxMcpServer {
             .unwrap_or_else(|e| format!("Error serializing: {}", e))
     }
 
+    #[tool(
+        description = "Summarize what a task changed since it left its base branch (the project's main branch unless the task sets one): the git diff --stat, added and deleted lines per file, and new untracked files. Use get_task_diff to read the changes themselves. In global mode, project_id is required — call list_projects first."
+    )]
+    fn get_diff_stat(&self, Parameters(params): Parameters<GetDiffStatParams>) -> String {
+        tracing::info!(tool = "get_diff_stat", task_id = %params.task_id, "MCP tool called");
+        let (task, worktree) =
+            match self.task_worktree(params.project_id.as_deref(), &params.task_id) {
+                Ok(found) => found,
+                Err(e) => return e,
+            };
+        let project_path = self.resolve_project_path(params.project_id.as_deref()).ok();
+        let project_path = project_path.as_deref();
+        let base = match self.task_base_branch(&task, &worktree, project_path) {
+            Ok(base) => base,
+            Err(e) => return e,
+        };
+        let files = match self.task_file_diffs(&task, &worktree, &base, project_path) {
+            Ok(files) => files,
+            Err(e) => return e,
+        };
+        let stat = match RealGitOps.diff_stat_from_base(Path::new(&worktree), &base) {
+            Ok(stat) => stat,
+            Err(e) => return format!("Error reading diff: {:#}", e),
+        };
+        let response = DiffStatResponse {
+            task_id: task.id,
+            branch_name: task.branch_name,
+            stat: stat.trim_end().to_string(),
+            untracked_files: files
+                .iter()
+                .filter(|f| f.untracked)
+                .map(|f| f.path.clone())
+                .collect(),
+            files: files
+                .into_iter()
+                .map(|f| FileStat {
+                    path: f.path,
+                    untracked: f.untracked,
+                    additions: f.additions,
+                    deletions: f.deletions,
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&response)
+            .unwrap_or_else(|e| format!("Error serializing: {}", e))
+    }
+
+    #[tool(
+        description = "Read a task's changes since it left its base branch (committed and uncommitted, plus new untracked files) as unified diffs, one entry per file. Large diffs are paged: pass next_offset as offset to get the next page, or file to read a single file. In global mode, project_id is required — call list_projects first."
+    )]
+    fn get_task_diff(&self, Parameters(params): Parameters<GetTaskDiffParams>) -> String {
+        tracing::info!(tool = "get_task_diff", task_id = %params.task_id, file = ?params.file, offset = ?params.offset, "MCP tool called");
+        let (task, worktree) =
+            match self.task_worktree(params.project_id.as_deref(), &params.task_id) {
+                Ok(found) => found,
+                Err(e) => return e,
+            };
+        let project_path = self.resolve_project_path(params.project_id.as_deref()).ok();
+        let project_path = project_path.as_deref();
+        let base = match self.task_base_branch(&task, &worktree, project_path) {
+            Ok(base) => base,
+            Err(e) => return e,
+        };
+        let mut files = match self.task_file_diffs(&task, &worktree, &base, project_path) {
+            Ok(files) => files,
+            Err(e) => return e,
+        };
+        if let Some(path) = &params.file {
+            files.retain(|f| f.path == *path);
+            if files.is_empty() {
+                return format!("No changes to {} in task {}", path, task.id);
+            }
+        }
+        let offset = params.offset.unwrap_or(0);
+        let page = crate::git::page_file_diffs(
+            &files,
+            offset,
+            params.max_files.unwrap_or(20),
+            params.max_bytes.unwrap_or(50_000).min(200_000),
+        );
+        let response = TaskDiffResponse {
+            task_id: task.id,
+            total_files: files.len(),
+            offset,
+            files: page.files,
+            next_offset: page.next_offset,
+        };
+        serde_json::to_string_pretty(&response)
+            .unwrap_or_else(|e| format!("Error serializing: {}", e))
+    }
+
     #[tool(
         description = "Fetch and consume pending notifications. Returns new events (task created, phase completed, etc.) and removes them from the queue. Note: notifications are also pushed to your input automatically when you are idle, so you usually don't need to call this manually."
     )]
@@ -886,35 +1883,17 @@ impl AgtxMcpServer {
         };
 
         let lines = params.lines.unwrap_or(50).max(1).min(10000);
-        let lines_arg = format!("-{}", lines);
-
-        let output = Command::new("tmux")
-            .args([
-                "-L",
-                "agtx",
-                "capture-pane",
-                "-t",
-                &session_name,
-                "-p",
-                "-S",
-                &lines_arg,
-            ])
-            .output();
-
-        match output {
-            Ok(out) => {
-                let content = String::from_utf8_lossy(&out.stdout).to_string();
-                let response = ReadPaneResponse {
-                    task_id: params.task_id,
-                    session_name,
-                    content,
-                    lines_requested: lines,
-                };
-                serde_json::to_string_pretty(&response)
-                    .unwrap_or_else(|e| format!("Error serializing: {}", e))
-            }
-            Err(e) => format!("Error reading pane content: {}", e),
-        }
+
+        let tmux_ops = self.session_ops();
+        let raw = tmux_ops.capture_pane_with_history(&session_name, lines);
+        let response = ReadPaneResponse {
+            task_id: params.task_id,
+            session_name,
+            content: crate::transcript::strip_ansi(&raw),
+            lines_requested: lines,
+        };
+        serde_json::to_string_pretty(&response)
+            .unwrap_or_else(|e| format!("Error serializing: {}", e))
     }
 
     #[tool(
@@ -960,29 +1939,9 @@ impl AgtxMcpServer {
             None => return format!("Task {} has no active session", params.task_id),
         };
 
-        // Send the message text
-        let send_text = Command::new("tmux")
-            .args([
-                "-L",
-                "agtx",
-                "send-keys",
-                "-t",
-                &session_name,
-                &params.message,
-            ])
-            .output();
-
-        if let Err(e) = send_text {
-            return format!("Error sending message: {}", e);
-        }
-
-        // Send Enter
-        let send_enter = Command::new("tmux")
-            .args(["-L", "agtx", "send-keys", "-t", &session_name, "Enter"])
-            .output();
-
-        match send_enter {
-            Ok(_) => {
+        // Send the message text followed by Enter
+        match self.session_ops().send_keys(&session_name, &params.message) {
+            Ok(()) => {
                 let response = SendToTaskResponse {
                     task_id: params.task_id,
                     session_name,
@@ -992,7 +1951,7 @@ impl AgtxMcpServer {
                 serde_json::to_string_pretty(&response)
                     .unwrap_or_else(|e| format!("Error serializing: {}", e))
             }
-            Err(e) => format!("Error sending Enter: {}", e),
+            Err(e) => format!("Error sending message: {}", e),
         }
     }
 
@@ -1021,11 +1980,42 @@ impl AgtxMcpServer {
             }
         }
 
+        let parent = match params.parent_id.as_deref().map(|id| db.get_task(id)) {
+            Some(Ok(Some(parent))) => Some(parent),
+            Some(Ok(None)) => {
+                return format!(
+                    "Error: parent task not found: {}",
+                    params.parent_id.unwrap_or_default()
+                )
+            }
+            Some(Err(e)) => return format!("Error checking parent task: {}", e),
+            None => None,
+        };
+
         let mut task = Task::new(&params.title, &default_agent, &project_name);
         task.description = params.description;
-        task.plugin = params.plugin.or(default_plugin);
+        task.plugin = params.plugin;
         task.referenced_tasks = params.referenced_tasks;
         task.base_branch = params.base_branch;
+        if let Some(parent) = &parent {
+            task.set_parent(parent);
+        }
+        task.plugin = task.plugin.or(default_plugin);
+        if let Some(labels) = params.labels {
+            task.set_labels(labels);
+        }
+        if let Some(priority) = params.priority {
+            match parse_priority(&priority) {
+                Ok(priority) => task.priority = priority,
+                Err(e) => return e,
+            }
+        }
+        if let Some(due_date) = params.due_date {
+            match parse_due_date(&due_date) {
+                Ok(due_date) => task.due_date = due_date,
+                Err(e) => return e,
+            }
+        }
 
         match db.create_task(&task) {
             Ok(()) => {
@@ -1042,7 +2032,7 @@ impl AgtxMcpServer {
     }
 
     #[tool(
-        description = "Create multiple tasks at once with index-based dependency wiring. Each task's depends_on field uses 0-based indices into the tasks array (no forward references). Returns all created task IDs. In global mode, project_id is required — call list_projects first."
+        description = "Create multiple tasks at once with index-based dependency wiring. Each task's depends_on field uses 0-based indices into the tasks array (no forward references); parent nests a task under an earlier one as its subtask. Returns all created task IDs. In global mode, project_id is required — call list_projects first."
     )]
     fn create_tasks_batch(
         &self,
@@ -1075,6 +2065,14 @@ impl AgtxMcpServer {
                     }
                 }
             }
+            if let Some(parent_idx) = batch_task.parent {
+                if parent_idx >= i {
+                    return format!(
+                        "Error: task[{}] '{}' has parent index {} which is >= its own index {}. Only backward references allowed.",
+                        i, batch_task.title, parent_idx, i
+                    );
+                }
+            }
         }
 
         let mut db = match self.open_project_db_for(params.project_id.as_deref()) {
@@ -1091,10 +2089,20 @@ impl AgtxMcpServer {
         for batch_task in &params.tasks {
             let mut task = Task::new(&batch_task.title, &default_agent, &project_name);
             task.description = batch_task.description.clone();
-            task.plugin = batch_task.plugin.clone().or_else(|| default_plugin.clone());
+            task.plugin = batch_task.plugin.clone();
             task.base_branch = batch_task.base_branch.clone();
+            // Parents come first, so they already carry what they inherited
+            if let Some(parent_idx) = batch_task.parent {
+                let parent = created_tasks[parent_idx].clone();
+                task.set_parent(&parent);
+            }
             created_tasks.push(task);
         }
+        for task in &mut created_tasks {
+            if task.plugin.is_none() {
+                task.plugin = default_plugin.clone();
+            }
+        }
 
         // Pass 3: Resolve index-based deps to real task IDs
         for (i, batch_task) in params.tasks.iter().enumerate() {
@@ -1184,6 +2192,24 @@ impl AgtxMcpServer {
             task.base_branch = Some(base_branch);
             updated_fields.push("base_branch".to_string());
         }
+        if let Some(labels) = params.labels {
+            task.set_labels(labels);
+            updated_fields.push("labels".to_string());
+        }
+        if let Some(priority) = params.priority {
+            match parse_priority(&priority) {
+                Ok(priority) => task.priority = priority,
+                Err(e) => return e,
+            }
+            updated_fields.push("priority".to_string());
+        }
+        if let Some(due_date) = params.due_date {
+            match parse_due_date(&due_date) {
+                Ok(due_date) => task.due_date = due_date,
+                Err(e) => return e,
+            }
+            updated_fields.push("due_date".to_string());
+        }
 
         if updated_fields.is_empty() {
             return "No fields to update".to_string();
@@ -1242,8 +2268,43 @@ impl AgtxMcpServer {
     }
 }
 
-#[tool_handler]
 impl ServerHandler for AgtxMcpServer {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Err(reason) = self.check_tool_call(&request) {
+            tracing::warn!(tool = %request.name, role = %self.role.name, reason = %reason, "MCP tool call denied");
+            return Ok(CallToolResult::error(vec![Content::text(reason)]));
+        }
+        self.tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .await
+    }
+
+    /// Only the tools the role may call
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult::with_all_items(
+            self.tool_router
+                .list_all()
+                .into_iter()
+                .filter(|t| self.role.allows_tool(&t.name))
+                .collect(),
+        ))
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router
+            .get(name)
+            .filter(|_| self.role.allows_tool(name))
+            .cloned()
+    }
+
     fn get_info(&self) -> ServerInfo {
         let instructions = match &self.mode {
             ServerMode::Global =>
@@ -1251,26 +2312,231 @@ impl ServerHandler for AgtxMcpServer {
                  IMPORTANT: always call list_projects first to get the project_id for your target project, \
                  then pass it to every other tool call. \
                  Use list_tasks to see tasks, create_task or create_tasks_batch to add new tasks \
-                 (with optional dependency wiring via referenced_tasks), update_task to modify backlog \
+                 (with optional dependency wiring via referenced_tasks; cycles are rejected), \
+                 get_ready_tasks to find backlog tasks whose dependencies are done, update_task to modify backlog \
                  task fields, move_task to transition tasks between phases, get_transition_status to \
-                 check if a transition completed, and delete_task to remove backlog tasks.",
+                 check if a transition completed, get_diff_stat and get_task_diff to see what a task \
+                 changed, and delete_task to remove backlog tasks. Projects, tasks and their phase \
+                 artifacts are also resources (agtx://project/<id>/task/<id>); subscribe to a task to be \
+                 told when its status, escalation note or phase status changes instead of polling.",
             ServerMode::Project(_) =>
                 "agtx MCP server — control the terminal kanban board for coding agents. \
                  Use list_tasks to see current tasks, create_task or create_tasks_batch to add new tasks \
-                 (with optional dependency wiring via referenced_tasks), update_task to modify backlog \
+                 (with optional dependency wiring via referenced_tasks; cycles are rejected), \
+                 get_ready_tasks to find backlog tasks whose dependencies are done, update_task to modify backlog \
                  task fields, move_task to transition tasks between phases, get_transition_status to \
-                 check if a transition completed, and delete_task to remove backlog tasks.",
+                 check if a transition completed, get_diff_stat and get_task_diff to see what a task \
+                 changed, and delete_task to remove backlog tasks. Projects, tasks and their phase \
+                 artifacts are also resources (agtx://project/<id>/task/<id>); subscribe to a task to be \
+                 told when its status, escalation note or phase status changes instead of polling.",
         };
         ServerInfo {
             instructions: Some(instructions.into()),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_tools()
+                .build(),
             ..Default::default()
         }
     }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        tracing::info!(method = "resources/list", "MCP request");
+        let projects = self
+            .projects()
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let mut listed = Vec::new();
+        for project in projects {
+            let Ok(db) = Database::open_project(Path::new(&project.path)) else {
+                continue;
+            };
+            let project_id = project.id.clone();
+            listed.push(listed_resource(
+                &ResourceUri::Project {
+                    project_id: project_id.clone(),
+                },
+                project.name.clone(),
+                format!("Project at {} with a summary of its tasks", project.path),
+                "application/json",
+            ));
+            for task in db.get_all_tasks().unwrap_or_default() {
+                listed.push(listed_resource(
+                    &ResourceUri::Task {
+                        project_id: project_id.clone(),
+                        task_id: task.id.clone(),
+                    },
+                    task.title.clone(),
+                    format!("{} task in {}", task.status.as_str(), project.name),
+                    "application/json",
+                ));
+                let artifacts = task_plugin(&task, Some(Path::new(&project.path)))
+                    .map(|plugin| resources::task_artifacts(&task, &plugin))
+                    .unwrap_or_default();
+                for (phase, path) in artifacts {
+                    listed.push(listed_resource(
+                        &ResourceUri::Artifact {
+                            project_id: project_id.clone(),
+                            task_id: task.id.clone(),
+                            phase: phase.to_string(),
+                        },
+                        format!("{} ({})", task.title, phase),
+                        format!("{} artifact of {}", phase, path.display()),
+                        artifact_mime_type(&path),
+                    ));
+                }
+            }
+        }
+        Ok(ListResourcesResult::with_all_items(listed))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(vec![
+            resource_template(
+                "agtx://project/{project_id}",
+                "project",
+                "A project and a summary of its tasks",
+                Some("application/json"),
+            ),
+            resource_template(
+                "agtx://project/{project_id}/task/{task_id}",
+                "task",
+                "A task with its status, escalation note and phase status, as get_task returns it",
+                Some("application/json"),
+            ),
+            resource_template(
+                "agtx://project/{project_id}/task/{task_id}/artifact/{phase}",
+                "task artifact",
+                "The file a task's agent wrote for a phase: research, planning, running or review",
+                None,
+            ),
+        ]))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        tracing::info!(method = "resources/read", uri = %request.uri, "MCP request");
+        let uri = parse_resource_uri(&request.uri)?;
+        Ok(ReadResourceResult {
+            contents: vec![self.read_resource_uri(&uri)?],
+        })
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        tracing::info!(method = "resources/subscribe", uri = %request.uri, "MCP request");
+        let uri = parse_resource_uri(&request.uri)?;
+        // Artifacts may not have been written yet, but their task must exist
+        let (_, db) = self.resource_project(uri.project_id())?;
+        if let ResourceUri::Task { task_id, .. } | ResourceUri::Artifact { task_id, .. } = &uri {
+            self.resource_task(&db, task_id)?;
+        }
+        let fingerprint = self.resource_fingerprint(&uri);
+        let start = {
+            let mut subscriptions = self.subscriptions.lock().unwrap();
+            subscriptions.subscribe(request.uri, fingerprint);
+            subscriptions.start_watching()
+        };
+        if start {
+            tokio::spawn(self.clone().watch_subscriptions(context.peer));
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        tracing::info!(method = "resources/unsubscribe", uri = %request.uri, "MCP request");
+        self.subscriptions.lock().unwrap().unsubscribe(&request.uri);
+        Ok(())
+    }
+}
+
+fn parse_resource_uri(uri: &str) -> Result<ResourceUri, McpError> {
+    ResourceUri::parse(uri).ok_or_else(|| {
+        McpError::invalid_params(
+            format!(
+                "Invalid resource URI: {}. Expected agtx://project/<id>[/task/<id>[/artifact/<phase>]]",
+                uri
+            ),
+            None,
+        )
+    })
+}
+
+/// Command line options of `agtx mcp-serve`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServeOptions {
+    /// Project to bind to; `None` serves all projects (global mode)
+    pub project_path: Option<PathBuf>,
+    /// Serve streamable HTTP on this address instead of stdio
+    pub http: Option<SocketAddr>,
+    /// Permission profile (`--role`); unrestricted if unset
+    pub role: Option<String>,
+}
+
+impl ServeOptions {
+    /// Parse the arguments following `mcp-serve`: an optional project path,
+    /// `--http <addr>` and `--role <name>` (or `--http=<addr>`, `--role=<name>`).
+    /// Global flags (`--experimental`, `--no-init-scripts`) are accepted and
+    /// ignored; any other flag is an error, so a mistyped `--role` can't start
+    /// an unrestricted server.
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = |example: &str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| anyhow::anyhow!("{} needs {}", flag, example))
+            };
+            match flag {
+                "--http" => {
+                    let addr = value("an address, e.g. 127.0.0.1:8765")?;
+                    options.http = Some(addr.parse().map_err(|_| {
+                        anyhow::anyhow!("Invalid --http address '{}', expected IP:PORT", addr)
+                    })?);
+                }
+                "--role" => {
+                    options.role = Some(value("a name, e.g. sweep, orchestrator or readonly")?);
+                }
+                "--experimental" | "--no-init-scripts" if inline.is_none() => {}
+                flag if flag.starts_with("--") => {
+                    anyhow::bail!("Unknown mcp-serve option: {}", arg)
+                }
+                path if options.project_path.is_none() => {
+                    options.project_path = Some(PathBuf::from(path));
+                }
+                extra => anyhow::bail!("Unexpected argument: {}", extra),
+            }
+        }
+        Ok(options)
+    }
 }
 
-pub async fn serve(project_path: Option<PathBuf>) -> anyhow::Result<()> {
-    let mode = match project_path {
+pub async fn serve(options: ServeOptions) -> anyhow::Result<()> {
+    let mode = match options.project_path {
         Some(path) => {
             // Validate project DB can be opened
             Database::open_project(&path)?;
@@ -1283,7 +2549,31 @@ pub async fn serve(project_path: Option<PathBuf>) -> anyhow::Result<()> {
         }
     };
 
-    let server = AgtxMcpServer::new(mode);
+    let role = match &options.role {
+        Some(name) => McpRole::resolve(name, &GlobalConfig::load()?.mcp_roles)?,
+        None => McpRole::unrestricted(),
+    };
+    let known: Vec<String> = AgtxMcpServer::tool_router()
+        .list_all()
+        .into_iter()
+        .map(|t| t.name.to_string())
+        .collect();
+    if let Some(tool) = role.unknown_tools(&known).first() {
+        anyhow::bail!("MCP role '{}' lists unknown tool '{}'", role.name, tool);
+    }
+    if let ServerMode::Project(path) = &mode {
+        let bound = AgtxMcpServer::new(mode.clone(), McpRole::unrestricted()).projects();
+        if !bound.is_ok_and(|projects| projects.iter().all(|p| role.allows_project(p))) {
+            anyhow::bail!("MCP role '{}' may not use project {:?}", role.name, path);
+        }
+    }
+    tracing::info!(role = %role.name, "MCP server starting");
+
+    if let Some(addr) = options.http {
+        return super::http::serve_http(mode, role, addr).await;
+    }
+
+    let server = AgtxMcpServer::new(mode, role);
     let service = server.serve(stdio()).await?;
     service.waiting().await?;
     Ok(())
@@ -44,6 +44,50 @@ pub fn agent_native_skill_dir(agent_name: &str) -> Option<(&'static str, &'stati
     }
 }
 
+/// Relative path (from the worktree root) of an agent's native skill directory,
+/// including the namespace subdirectory. Custom agents use their declared
+/// `skills.dir`/`skills.namespace`; built-ins use `agent_native_skill_dir`.
+pub fn native_skill_path(
+    agent_name: &str,
+    custom_agents: &[crate::agent::Agent],
+) -> Option<std::path::PathBuf> {
+    if let Some(custom) = custom_agent_skills(agent_name, custom_agents) {
+        let base = std::path::PathBuf::from(&custom.dir);
+        return Some(if custom.namespace.is_empty() {
+            base
+        } else {
+            base.join(&custom.namespace)
+        });
+    }
+    let (base, namespace) = agent_native_skill_dir(agent_name)?;
+    let base = std::path::PathBuf::from(base);
+    Some(if namespace.is_empty() {
+        base
+    } else {
+        base.join(namespace)
+    })
+}
+
+/// Built-in agent whose skill file format and command syntax `agent_name` follows.
+/// Built-ins map to themselves; custom agents without a `[custom_agents.skills]`
+/// table keep their own name and therefore get no skill transforms.
+pub fn skill_style(agent_name: &str, custom_agents: &[crate::agent::Agent]) -> String {
+    custom_agent_skills(agent_name, custom_agents)
+        .map(|s| s.style)
+        .unwrap_or_else(|| agent_name.to_string())
+}
+
+fn custom_agent_skills(
+    agent_name: &str,
+    custom_agents: &[crate::agent::Agent],
+) -> Option<crate::config::CustomAgentSkills> {
+    custom_agents
+        .iter()
+        .find(|a| a.name == agent_name)
+        .and_then(|a| a.custom.as_ref())
+        .and_then(|c| c.skills.clone())
+}
+
 /// Transform SKILL.md frontmatter `name: agtx-plan` to command name `agtx:plan`.
 /// Replaces the first hyphen with `:`.
 pub fn skill_name_to_command(skill_name: &str) -> String {
@@ -55,12 +99,12 @@ pub fn skill_name_to_command(skill_name: &str) -> String {
 }
 
 /// Map internal skill directory name to agent-native command file name.
-/// Format depends on the agent:
+/// `style` is the agent's `skill_style`. Format depends on the agent:
 /// - Claude/Gemini: "agtx-plan" → "plan.md" / "plan.toml" (namespace subdir handles prefix)
 /// - OpenCode: "agtx-plan" → "agtx-plan.md" (flat directory, full name)
 /// - Codex: uses SKILL.md in skill directories (handled separately)
-pub fn skill_dir_to_filename(skill_dir_name: &str, agent_name: &str) -> String {
-    match agent_name {
+pub fn skill_dir_to_filename(skill_dir_name: &str, style: &str) -> String {
+    match style {
         "gemini" => {
             let short = skill_dir_name
                 .strip_prefix("agtx-")
@@ -90,8 +134,10 @@ pub fn skill_dir_to_filename(skill_dir_name: &str, agent_name: &str) -> String {
 /// - OpenCode: colon → hyphen (`/gsd-plan-phase 1`)
 /// - Codex: slash → dollar + colon → hyphen (`$gsd-plan-phase 1`)
 /// - Unsupported agents: None (will fall back to file-path reference)
-pub fn transform_plugin_command(canonical_cmd: &str, agent_name: &str) -> Option<String> {
-    match agent_name {
+///
+/// `style` is the agent's `skill_style`.
+pub fn transform_plugin_command(canonical_cmd: &str, style: &str) -> Option<String> {
+    match style {
         "claude" | "gemini" => Some(canonical_cmd.to_string()),
         "opencode" => {
             // /gsd:plan-phase 1 → /gsd-plan-phase 1
@@ -210,11 +256,12 @@ pub fn extract_description(content: &str) -> Option<String> {
 
 /// Enumerate built-in skills as agent-native `(command, description)` pairs.
 /// Uses compile-time embedded `BUILTIN_SKILLS` — no filesystem access needed.
-pub fn enumerate_available_skills(agent_name: &str) -> Vec<(String, String)> {
+/// `style` is the agent's `skill_style`.
+pub fn enumerate_available_skills(style: &str) -> Vec<(String, String)> {
     let mut results = Vec::new();
     for (skill_name, skill_content) in BUILTIN_SKILLS {
         let canonical = format!("/{}", skill_name_to_command(skill_name));
-        let command = match transform_plugin_command(&canonical, agent_name) {
+        let command = match transform_plugin_command(&canonical, style) {
             Some(cmd) => cmd,
             None => canonical,
         };
@@ -0,0 +1,273 @@
+//! Flow metrics for a project: lead time, time per phase, throughput and
+//! review loops.
+//!
+//! Everything is computed from the task history (`task_events`), so the
+//! numbers cover every task that was finished since the history was added,
+//! not only those finished while agtx was watching. Durations are averaged
+//! over finished tasks; a task counts as finished when it last entered Done.
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::db::{idle_durations, status_durations, Task, TaskEvent, TaskEventKind, TaskStatus};
+
+/// Number of weeks shown in the throughput histogram
+pub const DEFAULT_WEEKS: usize = 8;
+
+/// Average time finished tasks spent in one status
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseTime {
+    pub status: String,
+    /// Finished tasks that passed through this status
+    pub tasks: usize,
+    pub avg_secs: i64,
+    /// Part of `avg_secs` the agent sat idle
+    pub avg_idle_secs: i64,
+}
+
+/// Tasks finished in one ISO week
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeekCount {
+    /// e.g. "2026-W07"
+    pub week: String,
+    pub finished: usize,
+}
+
+/// Average number of Review → Running round trips for one plugin or agent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewLoops {
+    pub name: String,
+    pub tasks: usize,
+    pub avg_loops: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub total_tasks: usize,
+    pub finished_tasks: usize,
+    /// Created → Done
+    pub avg_lead_time_secs: Option<i64>,
+    /// First left Backlog → Done
+    pub avg_cycle_time_secs: Option<i64>,
+    pub phases: Vec<PhaseTime>,
+    /// Oldest week first, ending with the current week
+    pub finished_per_week: Vec<WeekCount>,
+    pub review_loops_by_plugin: Vec<ReviewLoops>,
+    pub review_loops_by_agent: Vec<ReviewLoops>,
+}
+
+/// Compute the metrics for `tasks` from the project's `events` (oldest first).
+pub fn compute(tasks: &[Task], events: &[TaskEvent], now: DateTime<Utc>, weeks: usize) -> Stats {
+    let mut by_task: HashMap<&str, Vec<TaskEvent>> = HashMap::new();
+    for event in events {
+        by_task
+            .entry(event.task_id.as_str())
+            .or_default()
+            .push(event.clone());
+    }
+    let no_events = Vec::new();
+
+    let mut lead_times = Vec::new();
+    let mut cycle_times = Vec::new();
+    let mut phase_totals: BTreeMap<usize, (TaskStatus, Vec<i64>, i64)> = BTreeMap::new();
+    let mut finished_at = Vec::new();
+    let mut plugin_loops: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut agent_loops: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+    for task in tasks.iter().filter(|t| t.status == TaskStatus::Done) {
+        let history = by_task.get(task.id.as_str()).unwrap_or(&no_events);
+        let Some(done_at) = entered_at(history, TaskStatus::Done).last().copied() else {
+            // Finished before task history was recorded
+            continue;
+        };
+        finished_at.push(done_at);
+        lead_times.push((done_at - task.created_at).num_seconds());
+        if let Some(started) = history.iter().find(|e| {
+            e.kind == TaskEventKind::StatusChanged && e.from_value.as_deref() == Some("backlog")
+        }) {
+            cycle_times.push((done_at - started.created_at).num_seconds());
+        }
+
+        let idle = idle_durations(history, now);
+        for (status, time) in status_durations(history, now) {
+            if status == TaskStatus::Done {
+                continue;
+            }
+            let idle_secs = idle
+                .iter()
+                .find(|(s, _)| *s == status)
+                .map_or(0, |(_, d)| d.num_seconds());
+            let entry = phase_totals
+                .entry(column(status))
+                .or_insert((status, Vec::new(), 0));
+            entry.1.push(time.num_seconds());
+            entry.2 += idle_secs;
+        }
+
+        let loops = history
+            .iter()
+            .filter(|e| {
+                e.kind == TaskEventKind::StatusChanged
+                    && e.from_value.as_deref() == Some("review")
+                    && e.to_value.as_deref() == Some("running")
+            })
+            .count();
+        plugin_loops
+            .entry(task.plugin.clone().unwrap_or_else(|| "agtx".to_string()))
+            .or_default()
+            .push(loops);
+        agent_loops
+            .entry(task.agent.clone())
+            .or_default()
+            .push(loops);
+    }
+
+    Stats {
+        total_tasks: tasks.len(),
+        finished_tasks: finished_at.len(),
+        avg_lead_time_secs: average(&lead_times),
+        avg_cycle_time_secs: average(&cycle_times),
+        phases: phase_totals
+            .into_values()
+            .map(|(status, times, idle)| PhaseTime {
+                status: status.as_str().to_string(),
+                tasks: times.len(),
+                avg_secs: average(&times).unwrap_or(0),
+                avg_idle_secs: idle / times.len() as i64,
+            })
+            .collect(),
+        finished_per_week: per_week(&finished_at, now, weeks),
+        review_loops_by_plugin: review_loops(plugin_loops),
+        review_loops_by_agent: review_loops(agent_loops),
+    }
+}
+
+/// Times the task entered `status`, oldest first
+fn entered_at(events: &[TaskEvent], status: TaskStatus) -> Vec<DateTime<Utc>> {
+    events
+        .iter()
+        .filter(|e| {
+            matches!(
+                e.kind,
+                TaskEventKind::Created | TaskEventKind::StatusChanged
+            )
+        })
+        .filter(|e| e.to_value.as_deref() == Some(status.as_str()))
+        .map(|e| e.created_at)
+        .collect()
+}
+
+/// Board order, so phases are listed left to right
+fn column(status: TaskStatus) -> usize {
+    TaskStatus::columns()
+        .iter()
+        .position(|s| *s == status)
+        .unwrap_or(usize::MAX)
+}
+
+fn average(values: &[i64]) -> Option<i64> {
+    (!values.is_empty()).then(|| values.iter().sum::<i64>() / values.len() as i64)
+}
+
+fn per_week(finished_at: &[DateTime<Utc>], now: DateTime<Utc>, weeks: usize) -> Vec<WeekCount> {
+    let label = |t: DateTime<Utc>| {
+        let week = t.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+    };
+    (0..weeks)
+        .rev()
+        .map(|ago| {
+            let week = label(now - Duration::weeks(ago as i64));
+            let finished = finished_at.iter().filter(|t| label(**t) == week).count();
+            WeekCount { week, finished }
+        })
+        .collect()
+}
+
+fn review_loops(groups: BTreeMap<String, Vec<usize>>) -> Vec<ReviewLoops> {
+    groups
+        .into_iter()
+        .map(|(name, loops)| ReviewLoops {
+            tasks: loops.len(),
+            avg_loops: loops.iter().sum::<usize>() as f64 / loops.len() as f64,
+            name,
+        })
+        .collect()
+}
+
+/// Plain-text report shared by `agtx stats` and the TUI popup. Section
+/// headings are the lines that don't start with a space.
+pub fn format_stats(stats: &Stats) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Tasks: {} finished of {}",
+        stats.finished_tasks, stats.total_tasks
+    )];
+    if stats.finished_tasks == 0 {
+        lines.push("  (no finished tasks yet)".to_string());
+        return lines;
+    }
+    let avg =
+        |secs: Option<i64>| secs.map_or("-".to_string(), |s| format_elapsed(Duration::seconds(s)));
+    lines.push(format!(
+        "  lead time   {}  (created → done)",
+        avg(stats.avg_lead_time_secs)
+    ));
+    lines.push(format!(
+        "  cycle time  {}  (started → done)",
+        avg(stats.avg_cycle_time_secs)
+    ));
+
+    lines.push(String::new());
+    lines.push("Time per phase (average)".to_string());
+    for phase in &stats.phases {
+        let mut line = format!(
+            "  {:<10}  {:>8}",
+            phase.status,
+            format_elapsed(Duration::seconds(phase.avg_secs))
+        );
+        if phase.avg_idle_secs > 0 {
+            line.push_str(&format!(
+                "  idle {}",
+                format_elapsed(Duration::seconds(phase.avg_idle_secs))
+            ));
+        }
+        lines.push(line);
+    }
+
+    lines.push(String::new());
+    lines.push("Finished per week".to_string());
+    for week in &stats.finished_per_week {
+        lines.push(format!(
+            "  {}  {:>3} {}",
+            week.week,
+            week.finished,
+            "█".repeat(week.finished.min(40))
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("Review → running loops (average per task)".to_string());
+    for (label, groups) in [
+        ("plugin", &stats.review_loops_by_plugin),
+        ("agent", &stats.review_loops_by_agent),
+    ] {
+        let summary: Vec<String> = groups
+            .iter()
+            .map(|g| format!("{} {:.1} ({} tasks)", g.name, g.avg_loops, g.tasks))
+            .collect();
+        lines.push(format!("  by {:<7} {}", label, summary.join(" · ")));
+    }
+    lines
+}
+
+/// Compact elapsed-time string: "45s", "12m", "3h 05m", "2d 4h".
+pub fn format_elapsed(d: Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h {:02}m", s / 3600, (s % 3600) / 60),
+        s => format!("{}d {}h", s / 86_400, (s % 86_400) / 3600),
+    }
+}
@@ -1,16 +1,35 @@
 mod operations;
+pub mod pty;
+mod sandbox;
+mod term;
+mod zellij;
 
 pub use operations::*;
+pub use pty::PtyOps;
+pub use sandbox::{sandbox_argv, sandboxed, SandboxedOps};
+pub use zellij::ZellijOps;
 
 #[cfg(feature = "test-mocks")]
 pub use operations::MockTmuxOperations;
 
 use anyhow::{Context, Result};
 use std::process::Command;
+use std::sync::Arc;
+
+use crate::config::SessionBackend;
 
 /// The tmux server name for agent sessions
 pub const AGENT_SERVER: &str = "agtx";
 
+/// Session operations for the configured multiplexer
+pub fn backend_ops(backend: SessionBackend) -> Arc<dyn TmuxOperations> {
+    match backend {
+        SessionBackend::Tmux => Arc::new(RealTmuxOps),
+        SessionBackend::Zellij => Arc::new(ZellijOps::new()),
+        SessionBackend::Pty => Arc::new(PtyOps::new()),
+    }
+}
+
 /// Spawn a new agent session in the agents tmux server
 pub fn spawn_session(
     session_name: &str,
@@ -1,11 +1,13 @@
 //! Traits for tmux operations to enable testing with mocks.
 
 use anyhow::Result;
+use std::path::Path;
 
 #[cfg(feature = "test-mocks")]
 use mockall::automock;
 
-/// Operations for tmux window management
+/// Operations for agent session windows, implemented for tmux ([`RealTmuxOps`])
+/// and zellij ([`super::ZellijOps`])
 #[cfg_attr(feature = "test-mocks", automock)]
 pub trait TmuxOperations: Send + Sync {
     /// Create a new tmux window. `keep_shell_on_exit=true` drops to a shell
@@ -56,6 +58,21 @@ pub trait TmuxOperations: Send + Sync {
 
     /// Create a new detached session
     fn create_session(&self, session: &str, working_dir: &str) -> Result<()>;
+
+    /// Append everything the pane outputs to `log_path` (`tmux pipe-pane`),
+    /// replacing any pipe already attached to the pane.
+    fn pipe_pane(&self, target: &str, log_path: &Path) -> Result<()>;
+
+    /// Whether agtx itself runs inside a client of `session`'s server, so
+    /// showing a window means selecting it rather than nesting an attach.
+    fn is_inside_server(&self, session: &str) -> bool;
+
+    /// Make `target` the active window for clients already attached.
+    fn select_window(&self, target: &str) -> Result<()>;
+
+    /// Attach the terminal to `session` with `target` selected.
+    /// Blocks until the user detaches or the session ends.
+    fn attach(&self, session: &str, target: &str) -> Result<()>;
 }
 
 /// Real implementation using actual tmux commands
@@ -245,4 +262,47 @@ impl TmuxOperations for RealTmuxOps {
             .output()?;
         Ok(())
     }
+
+    fn pipe_pane(&self, target: &str, log_path: &Path) -> Result<()> {
+        let quoted = log_path.to_string_lossy().replace('\'', "'\"'\"'");
+        let output = std::process::Command::new("tmux")
+            .args(["-L", super::AGENT_SERVER])
+            .args(["pipe-pane", "-t", target])
+            .arg(format!("cat >> '{}'", quoted))
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "tmux pipe-pane failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    fn is_inside_server(&self, _session: &str) -> bool {
+        std::env::var("TMUX")
+            .map(|v| v.contains(super::AGENT_SERVER))
+            .unwrap_or(false)
+    }
+
+    fn select_window(&self, target: &str) -> Result<()> {
+        std::process::Command::new("tmux")
+            .args(["-L", super::AGENT_SERVER])
+            .args(["select-window", "-t", target])
+            .args([";", "resize-window", "-A"])
+            .output()?;
+        Ok(())
+    }
+
+    fn attach(&self, session: &str, target: &str) -> Result<()> {
+        // Unset $TMUX so tmux allows attaching when inside a different tmux.
+        std::process::Command::new("tmux")
+            .args(["-L", super::AGENT_SERVER])
+            .args(["attach", "-t", session])
+            .args([";", "select-window", "-t", target])
+            .args([";", "resize-window", "-A"])
+            .env_remove("TMUX")
+            .status()?;
+        Ok(())
+    }
 }
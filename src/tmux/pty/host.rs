@@ -0,0 +1,598 @@
+//! `agtx session-host`: owns the pseudo-terminals of the built-in backend.
+//!
+//! Every window is a PTY running `sh -c <command>` with a reader thread that
+//! feeds its output to a vt100 parser (screen plus scrollback), the window's
+//! transcript log and any attached clients. A window closes when its process
+//! exits, and a session when its last window does, as in tmux. The host exits
+//! on its own after it has had no windows for a while.
+
+use anyhow::{Context, Result};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::protocol::{read_message, write_message, Request, Response};
+use super::socket_path;
+use crate::tmux::term::{process_name, split_target, PASTE_END, PASTE_START};
+
+/// Lines of scrollback kept per window
+const SCROLLBACK_LINES: usize = 10_000;
+
+/// How long the host lingers with no windows before exiting
+const IDLE_EXIT: Duration = Duration::from_secs(10 * 60);
+
+/// Size of a new window until someone resizes it (tmux's default)
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Run the session host until it has been idle for [`IDLE_EXIT`].
+pub fn run() -> Result<()> {
+    let path = socket_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        // Another host already serves this socket
+        Err(_) if UnixStream::connect(&path).is_ok() => return Ok(()),
+        Err(_) => {
+            std::fs::remove_file(&path)?;
+            UnixListener::bind(&path)
+                .with_context(|| format!("Failed to listen on {}", path.display()))?
+        }
+    };
+    tracing::info!(socket = %path.display(), "Session host started");
+
+    let host = Arc::new(Host::default());
+    {
+        let host = host.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(Duration::from_secs(30));
+            if host.idle_for().is_some_and(|idle| idle >= IDLE_EXIT) {
+                tracing::info!("Session host idle, exiting");
+                let _ = std::fs::remove_file(&path);
+                std::process::exit(0);
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let host = host.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = serve(&host, stream) {
+                tracing::debug!(error = %e, "Session host connection failed");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn serve(host: &Arc<Host>, stream: UnixStream) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let request: Request = read_message(&mut reader)?;
+    let mut writer = stream;
+    match request {
+        Request::Attach { target, cols, rows } => {
+            host.attach(&target, (cols, rows), reader, writer)
+        }
+        request => write_message(&mut writer, &host.handle(request)),
+    }
+}
+
+struct Session {
+    windows: Vec<Arc<Pane>>,
+}
+
+#[derive(Default)]
+pub(super) struct Host {
+    sessions: Mutex<BTreeMap<String, Session>>,
+    /// Set while there are no windows
+    idle_since: Mutex<Option<Instant>>,
+}
+
+impl Host {
+    fn idle_for(&self) -> Option<Duration> {
+        let mut idle_since = lock(&self.idle_since);
+        if lock(&self.sessions).values().all(|s| s.windows.is_empty()) {
+            Some(idle_since.get_or_insert_with(Instant::now).elapsed())
+        } else {
+            *idle_since = None;
+            None
+        }
+    }
+
+    fn find_pane(&self, target: &str) -> Option<Arc<Pane>> {
+        let (session, window) = split_target(target);
+        let sessions = lock(&self.sessions);
+        let windows = &sessions.get(session)?.windows;
+        match window {
+            Some(window) => windows.iter().find(|p| p.name == window).cloned(),
+            None => windows.first().cloned(),
+        }
+    }
+
+    fn remove_pane(&self, pane: &Arc<Pane>) {
+        let mut sessions = lock(&self.sessions);
+        for session in sessions.values_mut() {
+            session.windows.retain(|p| !Arc::ptr_eq(p, pane));
+        }
+        sessions.retain(|_, s| !s.windows.is_empty());
+        drop(sessions);
+        pane.disconnect_clients();
+    }
+
+    pub(super) fn handle(self: &Arc<Self>, request: Request) -> Response {
+        match self.try_handle(request) {
+            Ok(response) => response,
+            Err(e) => Response::Error(format!("{:#}", e)),
+        }
+    }
+
+    fn pane(&self, target: &str) -> Result<Arc<Pane>> {
+        self.find_pane(target)
+            .with_context(|| format!("can't find window: {}", target))
+    }
+
+    fn try_handle(self: &Arc<Self>, request: Request) -> Result<Response> {
+        Ok(match request {
+            Request::CreateSession { session, .. } => {
+                lock(&self.sessions)
+                    .entry(session)
+                    .or_insert_with(|| Session { windows: vec![] });
+                Response::Ok
+            }
+            Request::HasSession { session } => {
+                Response::Bool(lock(&self.sessions).contains_key(&session))
+            }
+            Request::CreateWindow {
+                session,
+                window,
+                working_dir,
+                command,
+                keep_shell_on_exit,
+            } => {
+                if !lock(&self.sessions).contains_key(&session) {
+                    anyhow::bail!("can't find session: {}", session);
+                }
+                let command = command.map(|cmd| {
+                    if keep_shell_on_exit {
+                        format!("{}; exec $SHELL", cmd)
+                    } else {
+                        cmd
+                    }
+                });
+                let pane = self.spawn_pane(window, &working_dir, command)?;
+                match lock(&self.sessions).get_mut(&session) {
+                    Some(s) => s.windows.push(pane),
+                    None => pane.kill(),
+                }
+                Response::Ok
+            }
+            Request::KillWindow { target } => {
+                if let Some(pane) = self.find_pane(&target) {
+                    pane.kill();
+                    self.remove_pane(&pane);
+                }
+                Response::Ok
+            }
+            Request::WindowExists { target } => Response::Bool(self.find_pane(&target).is_some()),
+            Request::Write { target, bytes } => {
+                self.pane(&target)?.write(&bytes)?;
+                Response::Ok
+            }
+            Request::Paste { target, text } => {
+                let pane = self.pane(&target)?;
+                let bracketed = lock(&pane.parser).screen().bracketed_paste();
+                let mut bytes = Vec::with_capacity(text.len() + 12);
+                if bracketed {
+                    bytes.extend_from_slice(PASTE_START);
+                }
+                bytes.extend_from_slice(text.as_bytes());
+                if bracketed {
+                    bytes.extend_from_slice(PASTE_END);
+                }
+                pane.write(&bytes)?;
+                Response::Ok
+            }
+            Request::Capture { target, history } => {
+                let pane = self.pane(&target)?;
+                let mut parser = lock(&pane.parser);
+                Response::Text(match history {
+                    Some(lines) => capture_with_history(parser.screen_mut(), lines),
+                    None => capture_plain(parser.screen()),
+                })
+            }
+            Request::CursorInfo { target } => {
+                let pane = self.pane(&target)?;
+                let parser = lock(&pane.parser);
+                let screen = parser.screen();
+                Response::Cursor(Some((
+                    screen.cursor_position().0 as usize,
+                    screen.size().0 as usize,
+                )))
+            }
+            Request::Resize { target, cols, rows } => {
+                self.pane(&target)?.resize(cols, rows)?;
+                Response::Ok
+            }
+            Request::CurrentCommand { target } => {
+                let pane = self.pane(&target)?;
+                let leader = lock(&pane.master).process_group_leader();
+                Response::Command(leader.and_then(|pid| process_name(pid as u32)))
+            }
+            Request::PipePane { target, log_path } => {
+                let pane = self.pane(&target)?;
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&log_path)
+                    .with_context(|| format!("Failed to open {}", log_path.display()))?;
+                *lock(&pane.log) = Some(file);
+                Response::Ok
+            }
+            Request::Attach { .. } => anyhow::bail!("attach needs its own connection"),
+        })
+    }
+
+    fn spawn_pane(
+        self: &Arc<Self>,
+        name: String,
+        working_dir: &str,
+        command: Option<String>,
+    ) -> Result<Arc<Pane>> {
+        let (cols, rows) = DEFAULT_SIZE;
+        let pair = native_pty_system().openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+        let mut cmd = match command {
+            Some(command) => {
+                let mut cmd = CommandBuilder::new("sh");
+                cmd.args(["-c", &command]);
+                cmd
+            }
+            None => CommandBuilder::new_default_prog(),
+        };
+        cmd.cwd(working_dir);
+        cmd.env("TERM", "xterm-256color");
+        let mut child = pair
+            .slave
+            .spawn_command(cmd)
+            .context("Failed to spawn window process")?;
+        // Only the child may hold the slave, or the reader never sees EOF
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader()?;
+        let pane = Arc::new(Pane {
+            name,
+            parser: Mutex::new(vt100::Parser::new(rows, cols, SCROLLBACK_LINES)),
+            writer: Mutex::new(pair.master.take_writer()?),
+            master: Mutex::new(pair.master),
+            killer: Mutex::new(child.clone_killer()),
+            clients: Mutex::new(Vec::new()),
+            log: Mutex::new(None),
+        });
+
+        let host = self.clone();
+        let reader_pane = pane.clone();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => reader_pane.output(&buf[..n]),
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+            let _ = child.wait();
+            host.remove_pane(&reader_pane);
+        });
+        Ok(pane)
+    }
+
+    /// Stream a window to an attached client until it disconnects or the
+    /// window closes. The client first gets the current screen.
+    fn attach(
+        &self,
+        target: &str,
+        (cols, rows): (u16, u16),
+        mut reader: BufReader<UnixStream>,
+        mut writer: UnixStream,
+    ) -> Result<()> {
+        let Some(pane) = self.find_pane(target) else {
+            return write_message(
+                &mut writer,
+                &Response::Error(format!("can't find window: {}", target)),
+            );
+        };
+        pane.resize(cols, rows)?;
+        write_message(&mut writer, &Response::Ok)?;
+
+        static NEXT_CLIENT: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_CLIENT.fetch_add(1, Ordering::Relaxed);
+        writer.set_write_timeout(Some(Duration::from_secs(1)))?;
+        {
+            // Holding the parser keeps output from slipping in between the
+            // snapshot and registering the client
+            let parser = lock(&pane.parser);
+            writer.write_all(&parser.screen().state_formatted())?;
+            lock(&pane.clients).push((id, writer.try_clone()?));
+        }
+
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if pane.write(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        lock(&pane.clients).retain(|(client, _)| *client != id);
+        Ok(())
+    }
+}
+
+struct Pane {
+    name: String,
+    parser: Mutex<vt100::Parser>,
+    writer: Mutex<Box<dyn Write + Send>>,
+    master: Mutex<Box<dyn MasterPty + Send>>,
+    killer: Mutex<Box<dyn ChildKiller + Send + Sync>>,
+    clients: Mutex<Vec<(u64, UnixStream)>>,
+    log: Mutex<Option<File>>,
+}
+
+impl Pane {
+    fn output(&self, bytes: &[u8]) {
+        let mut parser = lock(&self.parser);
+        parser.process(bytes);
+        let mut log = lock(&self.log);
+        if log.as_mut().is_some_and(|f| f.write_all(bytes).is_err()) {
+            *log = None;
+        }
+        lock(&self.clients).retain_mut(|(_, client)| client.write_all(bytes).is_ok());
+    }
+
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        let mut writer = lock(&self.writer);
+        writer.write_all(bytes)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn resize(&self, cols: u16, rows: u16) -> Result<()> {
+        if cols == 0 || rows == 0 {
+            return Ok(());
+        }
+        lock(&self.master).resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+        lock(&self.parser).screen_mut().set_size(rows, cols);
+        Ok(())
+    }
+
+    fn kill(&self) {
+        let _ = lock(&self.killer).kill();
+    }
+
+    fn disconnect_clients(&self) {
+        for (_, client) in lock(&self.clients).drain(..) {
+            let _ = client.shutdown(std::net::Shutdown::Both);
+        }
+    }
+}
+
+/// Visible screen as plain text, one line per row (`tmux capture-pane -p`)
+fn capture_plain(screen: &vt100::Screen) -> String {
+    let (_, cols) = screen.size();
+    let mut out = String::new();
+    for row in screen.rows(0, cols) {
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+/// Up to `history` scrollback lines followed by the visible screen, with SGR
+/// escapes for colors and styles (`tmux capture-pane -p -e -S -<history>`)
+fn capture_with_history(screen: &mut vt100::Screen, history: usize) -> String {
+    let (rows, _) = screen.size();
+    screen.set_scrollback(usize::MAX);
+    let mut offset = history.min(screen.scrollback());
+    let mut out = String::new();
+    // At scrollback offset k the first min(k, rows) visible rows are history
+    while offset > 0 {
+        screen.set_scrollback(offset);
+        let take = offset.min(rows as usize);
+        for row in 0..take as u16 {
+            push_formatted_row(&mut out, screen, row);
+        }
+        offset -= take;
+    }
+    screen.set_scrollback(0);
+    for row in 0..rows {
+        push_formatted_row(&mut out, screen, row);
+    }
+    out
+}
+
+fn push_formatted_row(out: &mut String, screen: &vt100::Screen, row: u16) {
+    let (_, cols) = screen.size();
+    let blank = |col: u16| {
+        screen.cell(row, col).is_none_or(|cell| {
+            !cell.has_contents() && cell.bgcolor() == vt100::Color::Default && !cell.inverse()
+        })
+    };
+    let end = (0..cols)
+        .rev()
+        .find(|&col| !blank(col))
+        .map_or(0, |col| col + 1);
+
+    const RESET: &str = "\x1b[0m";
+    let mut current = RESET.to_string();
+    for col in 0..end {
+        let Some(cell) = screen.cell(row, col) else {
+            continue;
+        };
+        if cell.is_wide_continuation() {
+            continue;
+        }
+        let sgr = cell_sgr(cell);
+        if sgr != current {
+            out.push_str(&sgr);
+            current = sgr;
+        }
+        if cell.has_contents() {
+            out.push_str(cell.contents());
+        } else {
+            out.push(' ');
+        }
+    }
+    if current != RESET {
+        out.push_str(RESET);
+    }
+    out.push('\n');
+}
+
+fn cell_sgr(cell: &vt100::Cell) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (on, code) in [
+        (cell.bold(), "1"),
+        (cell.dim(), "2"),
+        (cell.italic(), "3"),
+        (cell.underline(), "4"),
+        (cell.inverse(), "7"),
+    ] {
+        if on {
+            codes.push(code.to_string());
+        }
+    }
+    for (color, base, bright, extended) in
+        [(cell.fgcolor(), 30, 90, 38), (cell.bgcolor(), 40, 100, 48)]
+    {
+        match color {
+            vt100::Color::Default => {}
+            vt100::Color::Idx(i) if i < 8 => codes.push((base + i as u16).to_string()),
+            vt100::Color::Idx(i) if i < 16 => codes.push((bright + i as u16 - 8).to_string()),
+            vt100::Color::Idx(i) => codes.push(format!("{};5;{}", extended, i)),
+            vt100::Color::Rgb(r, g, b) => codes.push(format!("{};2;{};{};{}", extended, r, g, b)),
+        }
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen_with(rows: u16, cols: u16, bytes: &[u8]) -> vt100::Parser {
+        let mut parser = vt100::Parser::new(rows, cols, 100);
+        parser.process(bytes);
+        parser
+    }
+
+    #[test]
+    fn test_capture_plain_has_one_line_per_row() {
+        let parser = screen_with(3, 10, b"one\r\ntwo");
+        assert_eq!(capture_plain(parser.screen()), "one\ntwo\n\n");
+    }
+
+    #[test]
+    fn test_capture_with_history_prepends_scrollback_with_colors() {
+        let mut parser = screen_with(2, 10, b"a\r\nb\r\n\x1b[31mc\x1b[0m\r\nd");
+        let out = capture_with_history(parser.screen_mut(), 10);
+        assert_eq!(out, "a\nb\n\x1b[0;31mc\x1b[0m\nd\n");
+        assert_eq!(parser.screen().scrollback(), 0);
+
+        let out = capture_with_history(parser.screen_mut(), 1);
+        assert_eq!(out, "b\n\x1b[0;31mc\x1b[0m\nd\n");
+    }
+
+    #[test]
+    fn test_cell_sgr_colors() {
+        let parser = screen_with(1, 10, b"\x1b[1;92;48;5;200mx\x1b[38;2;1;2;3my");
+        let screen = parser.screen();
+        assert_eq!(
+            cell_sgr(screen.cell(0, 0).unwrap()),
+            "\x1b[0;1;92;48;5;200m"
+        );
+        assert_eq!(
+            cell_sgr(screen.cell(0, 1).unwrap()),
+            "\x1b[0;1;38;2;1;2;3;48;5;200m"
+        );
+    }
+
+    fn wait_for(mut check: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if check() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_window_lifecycle() {
+        let host = Arc::new(Host::default());
+        let create = |command: &str| Request::CreateWindow {
+            session: "proj".to_string(),
+            window: "task-1".to_string(),
+            working_dir: "/".to_string(),
+            command: Some(command.to_string()),
+            keep_shell_on_exit: false,
+        };
+        assert!(matches!(host.handle(create("true")), Response::Error(_)));
+
+        host.handle(Request::CreateSession {
+            session: "proj".to_string(),
+            working_dir: "/".to_string(),
+        });
+        assert_eq!(host.handle(create("cat")), Response::Ok);
+        let target = "proj:task-1".to_string();
+        assert_eq!(
+            host.handle(Request::WindowExists {
+                target: target.clone()
+            }),
+            Response::Bool(true)
+        );
+
+        host.handle(Request::Write {
+            target: target.clone(),
+            bytes: b"hello\r".to_vec(),
+        });
+        assert!(wait_for(|| matches!(
+            host.handle(Request::Capture { target: target.clone(), history: None }),
+            Response::Text(t) if t.lines().filter(|l| *l == "hello").count() == 2
+        )));
+
+        // EOF ends `cat`, which closes the window and then the session
+        host.handle(Request::Write {
+            target: target.clone(),
+            bytes: vec![0x04],
+        });
+        assert!(wait_for(|| host.handle(Request::HasSession {
+            session: "proj".to_string()
+        }) == Response::Bool(false)));
+        assert!(host.idle_for().is_some());
+    }
+}
@@ -0,0 +1,378 @@
+//! Built-in PTY implementation of [`TmuxOperations`], for machines without
+//! tmux or zellij.
+//!
+//! Agents run in pseudo-terminals owned by `agtx session-host`, a background
+//! process started on first use that keeps a vt100 screen and scrollback per
+//! window. The TUI, daemon and MCP server talk to it over a Unix socket (see
+//! [`protocol`]), so sessions outlive any of them. Targets use the same
+//! `session:window` form as tmux.
+
+mod host;
+mod protocol;
+
+pub use host::run as run_host;
+
+use anyhow::{Context, Result};
+use std::io::{BufReader, Read, Write};
+use std::os::fd::AsFd;
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use protocol::{read_message, write_message, Request, Response};
+
+use super::term::key_bytes;
+use super::TmuxOperations;
+
+/// Detach from an attached window with Ctrl-b d, as in tmux
+const DETACH_PREFIX: u8 = 0x02;
+
+/// Socket the session host listens on: `$XDG_RUNTIME_DIR/agtx/pty.sock`,
+/// falling back to the agtx data directory.
+pub fn socket_path() -> Result<PathBuf> {
+    socket_path_in(std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from))
+}
+
+/// [`socket_path`] for a given runtime directory
+pub(crate) fn socket_path_in(runtime_dir: Option<PathBuf>) -> Result<PathBuf> {
+    let dir = match runtime_dir {
+        Some(dir) => dir.join("agtx"),
+        None => crate::config::GlobalConfig::data_dir()?,
+    };
+    Ok(dir.join("pty.sock"))
+}
+
+/// Session operations served by the agtx session host
+#[derive(Default)]
+pub struct PtyOps;
+
+impl PtyOps {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Connect to the host. With `start`, launch it first if it isn't running.
+    fn connect(&self, start: bool) -> Result<UnixStream> {
+        let path = socket_path()?;
+        if let Ok(stream) = UnixStream::connect(&path) {
+            return Ok(stream);
+        }
+        if !start {
+            anyhow::bail!("agtx session host is not running");
+        }
+        spawn_host()?;
+        for _ in 0..100 {
+            std::thread::sleep(Duration::from_millis(20));
+            if let Ok(stream) = UnixStream::connect(&path) {
+                return Ok(stream);
+            }
+        }
+        anyhow::bail!("agtx session host did not start ({})", path.display())
+    }
+
+    fn request(&self, request: Request, start: bool) -> Result<Response> {
+        let stream = self.connect(start)?;
+        stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+        write_message(&mut &stream, &request)?;
+        match read_message(&mut BufReader::new(&stream))? {
+            Response::Error(e) => anyhow::bail!(e),
+            response => Ok(response),
+        }
+    }
+
+    /// Send a request to a running host; no host means nothing to act on.
+    fn query(&self, request: Request) -> Option<Response> {
+        self.request(request, false).ok()
+    }
+
+    fn write_bytes(&self, target: &str, bytes: Vec<u8>) -> Result<()> {
+        self.request(
+            Request::Write {
+                target: target.to_string(),
+                bytes,
+            },
+            false,
+        )?;
+        Ok(())
+    }
+}
+
+/// Start `agtx session-host` in its own process group so it keeps running
+/// after the terminal that started it closes.
+fn spawn_host() -> Result<()> {
+    let path = socket_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let exe = std::env::current_exe().context("Failed to locate the agtx binary")?;
+    std::process::Command::new(exe)
+        .arg("session-host")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()
+        .context("Failed to start agtx session host")?;
+    Ok(())
+}
+
+impl TmuxOperations for PtyOps {
+    fn create_window(
+        &self,
+        session: &str,
+        window_name: &str,
+        working_dir: &str,
+        command: Option<String>,
+        keep_shell_on_exit: bool,
+    ) -> Result<()> {
+        self.request(
+            Request::CreateWindow {
+                session: session.to_string(),
+                window: window_name.to_string(),
+                working_dir: working_dir.to_string(),
+                command,
+                keep_shell_on_exit,
+            },
+            true,
+        )
+        .context("Failed to create window")?;
+        Ok(())
+    }
+
+    fn kill_window(&self, target: &str) -> Result<()> {
+        self.query(Request::KillWindow {
+            target: target.to_string(),
+        });
+        Ok(())
+    }
+
+    fn window_exists(&self, target: &str) -> Result<bool> {
+        Ok(matches!(
+            self.query(Request::WindowExists {
+                target: target.to_string(),
+            }),
+            Some(Response::Bool(true))
+        ))
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        let mut bytes = key_bytes(keys).unwrap_or_else(|| keys.as_bytes().to_vec());
+        bytes.push(b'\r');
+        self.write_bytes(target, bytes)
+    }
+
+    fn send_keys_literal(&self, target: &str, keys: &str) -> Result<()> {
+        let bytes = key_bytes(keys).unwrap_or_else(|| keys.as_bytes().to_vec());
+        self.write_bytes(target, bytes)
+    }
+
+    fn paste_text(&self, target: &str, text: &str) -> Result<()> {
+        self.request(
+            Request::Paste {
+                target: target.to_string(),
+                text: text.to_string(),
+            },
+            false,
+        )?;
+        Ok(())
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        match self.request(
+            Request::Capture {
+                target: target.to_string(),
+                history: None,
+            },
+            false,
+        )? {
+            Response::Text(text) => Ok(text),
+            other => anyhow::bail!("Unexpected session host response: {:?}", other),
+        }
+    }
+
+    fn capture_pane_with_history(&self, target: &str, history_lines: i32) -> Vec<u8> {
+        match self.query(Request::Capture {
+            target: target.to_string(),
+            history: Some(history_lines.max(0) as usize),
+        }) {
+            Some(Response::Text(text)) => text.into_bytes(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn get_cursor_info(&self, target: &str) -> Option<(usize, usize)> {
+        match self.query(Request::CursorInfo {
+            target: target.to_string(),
+        }) {
+            Some(Response::Cursor(info)) => info,
+            _ => None,
+        }
+    }
+
+    fn resize_window(&self, target: &str, width: u16, height: u16) -> Result<()> {
+        self.request(
+            Request::Resize {
+                target: target.to_string(),
+                cols: width,
+                rows: height,
+            },
+            false,
+        )?;
+        Ok(())
+    }
+
+    fn pane_current_command(&self, target: &str) -> Option<String> {
+        match self.query(Request::CurrentCommand {
+            target: target.to_string(),
+        }) {
+            Some(Response::Command(command)) => command,
+            _ => None,
+        }
+    }
+
+    fn has_session(&self, session: &str) -> bool {
+        matches!(
+            self.query(Request::HasSession {
+                session: session.to_string(),
+            }),
+            Some(Response::Bool(true))
+        )
+    }
+
+    fn create_session(&self, session: &str, working_dir: &str) -> Result<()> {
+        self.request(
+            Request::CreateSession {
+                session: session.to_string(),
+                working_dir: working_dir.to_string(),
+            },
+            true,
+        )?;
+        Ok(())
+    }
+
+    fn pipe_pane(&self, target: &str, log_path: &Path) -> Result<()> {
+        self.request(
+            Request::PipePane {
+                target: target.to_string(),
+                log_path: log_path.to_path_buf(),
+            },
+            false,
+        )?;
+        Ok(())
+    }
+
+    fn is_inside_server(&self, _session: &str) -> bool {
+        // Host windows have no client of their own to switch
+        false
+    }
+
+    fn select_window(&self, _target: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn attach(&self, _session: &str, target: &str) -> Result<()> {
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        let stream = self.connect(false)?;
+        write_message(
+            &mut &stream,
+            &Request::Attach {
+                target: target.to_string(),
+                cols,
+                rows,
+            },
+        )?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        if let Response::Error(e) = read_message(&mut reader)? {
+            anyhow::bail!(e);
+        }
+
+        crossterm::terminal::enable_raw_mode()?;
+        let result = self.forward_attached(target, stream, reader, (cols, rows));
+        // Undo modes the pane may have left on
+        let mut stdout = std::io::stdout();
+        let _ =
+            stdout.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l\x1b[?2004l\x1b[?1000l\x1b[?1006l\r\n");
+        let _ = stdout.flush();
+        let _ = crossterm::terminal::disable_raw_mode();
+        result
+    }
+}
+
+impl PtyOps {
+    /// Pump pane output to stdout and stdin to the pane until the user
+    /// detaches (Ctrl-b d) or the window closes.
+    fn forward_attached(
+        &self,
+        target: &str,
+        stream: UnixStream,
+        mut reader: BufReader<UnixStream>,
+        mut size: (u16, u16),
+    ) -> Result<()> {
+        let closed = Arc::new(AtomicBool::new(false));
+        let output = {
+            let closed = closed.clone();
+            std::thread::spawn(move || {
+                let mut stdout = std::io::stdout();
+                let mut buf = [0u8; 8192];
+                while let Ok(n) = reader.read(&mut buf) {
+                    if n == 0 || stdout.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                    let _ = stdout.flush();
+                }
+                closed.store(true, Ordering::SeqCst);
+            })
+        };
+
+        let stdin = std::io::stdin();
+        let mut input = &stream;
+        let mut prefix = false;
+        let mut buf = [0u8; 1024];
+        'attached: while !closed.load(Ordering::SeqCst) {
+            // Poll so no read is left pending on stdin after detaching
+            let mut fds = [nix::poll::PollFd::new(
+                stdin.as_fd(),
+                nix::poll::PollFlags::POLLIN,
+            )];
+            if nix::poll::poll(&mut fds, 100u16)? > 0 {
+                let n = stdin.lock().read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                let mut keys = Vec::with_capacity(n + 1);
+                for &b in &buf[..n] {
+                    if prefix {
+                        prefix = false;
+                        if b == b'd' {
+                            break 'attached;
+                        }
+                        if b != DETACH_PREFIX {
+                            keys.push(DETACH_PREFIX);
+                        }
+                        keys.push(b);
+                    } else if b == DETACH_PREFIX {
+                        prefix = true;
+                    } else {
+                        keys.push(b);
+                    }
+                }
+                if !keys.is_empty() && input.write_all(&keys).is_err() {
+                    break;
+                }
+            }
+
+            let current = crossterm::terminal::size().unwrap_or(size);
+            if current != size {
+                size = current;
+                let _ = self.resize_window(target, size.0, size.1);
+            }
+        }
+
+        let _ = stream.shutdown(std::net::Shutdown::Both);
+        let _ = output.join();
+        Ok(())
+    }
+}
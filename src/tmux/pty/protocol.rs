@@ -0,0 +1,124 @@
+//! Wire format between [`super::PtyOps`] and the session host.
+//!
+//! Each connection carries one JSON request line and one JSON response line.
+//! An `attach` request is the exception: after its response the connection
+//! becomes a raw byte stream in both directions (pane output one way, key
+//! input the other) until either side closes it.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    CreateSession {
+        session: String,
+        working_dir: String,
+    },
+    HasSession {
+        session: String,
+    },
+    CreateWindow {
+        session: String,
+        window: String,
+        working_dir: String,
+        command: Option<String>,
+        keep_shell_on_exit: bool,
+    },
+    KillWindow {
+        target: String,
+    },
+    WindowExists {
+        target: String,
+    },
+    /// Raw bytes for the pane, as if typed
+    Write {
+        target: String,
+        bytes: Vec<u8>,
+    },
+    /// Text wrapped in bracketed paste markers when the pane asked for them
+    Paste {
+        target: String,
+        text: String,
+    },
+    /// Screen text; with `history` the last that many scrollback lines come
+    /// first and SGR escapes are kept, like `tmux capture-pane -e -S -N`
+    Capture {
+        target: String,
+        history: Option<usize>,
+    },
+    CursorInfo {
+        target: String,
+    },
+    Resize {
+        target: String,
+        cols: u16,
+        rows: u16,
+    },
+    CurrentCommand {
+        target: String,
+    },
+    PipePane {
+        target: String,
+        log_path: PathBuf,
+    },
+    Attach {
+        target: String,
+        cols: u16,
+        rows: u16,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "value", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Bool(bool),
+    Text(String),
+    Command(Option<String>),
+    Cursor(Option<(usize, usize)>),
+    Error(String),
+}
+
+pub fn write_message<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read_message<T: for<'de> Deserialize<'de>>(reader: &mut impl BufRead) -> Result<T> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        anyhow::bail!("session host closed the connection");
+    }
+    serde_json::from_str(&line).context("Malformed session host message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_roundtrip_as_lines() {
+        let request = Request::Write {
+            target: "proj:task-1".to_string(),
+            bytes: b"y\r".to_vec(),
+        };
+        let mut buf = Vec::new();
+        write_message(&mut buf, &request).unwrap();
+        write_message(&mut buf, &Response::Cursor(Some((3, 24)))).unwrap();
+        assert_eq!(buf.iter().filter(|&&b| b == b'\n').count(), 2);
+
+        let mut reader = std::io::BufReader::new(buf.as_slice());
+        assert_eq!(read_message::<Request>(&mut reader).unwrap(), request);
+        assert_eq!(
+            read_message::<Response>(&mut reader).unwrap(),
+            Response::Cursor(Some((3, 24)))
+        );
+        assert!(read_message::<Response>(&mut reader).is_err());
+    }
+}
@@ -0,0 +1,881 @@
+//! Sandboxed agent windows.
+//!
+//! [`SandboxedOps`] wraps another backend and starts every task window (any
+//! window outside the project root, so not the orchestrator) under bubblewrap
+//! or firejail. The whole filesystem is read-only except the task worktree,
+//! the parts of the repository's git directory a commit to the task branch
+//! writes to (the worktree's own admin directory, `objects` and the
+//! directories holding the task branch's ref and reflog), the state
+//! directories of the agent CLIs and any extra `writable` paths. Nothing an
+//! unsandboxed process later runs stays writable: git `hooks` and `config`,
+//! the worktree's links to its repository, other branches and the agent
+//! settings that name hooks, MCP servers and plugins are re-mounted read-only
+//! even if a `writable` entry covers them. The sockets and state of the
+//! session backends (tmux, zellij, the PTY host) are hidden, so an agent
+//! can't open or type into a window outside the sandbox. The shell a task
+//! falls back to when its agent exits runs inside the sandbox too, so agents
+//! started from it later stay confined.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::{SandboxConfig, SandboxTool};
+
+use super::term::shell_quote;
+use super::TmuxOperations;
+
+/// Where agent CLIs keep logins, history and caches. Those that exist stay
+/// writable inside the sandbox.
+const AGENT_STATE_PATHS: &[&str] = &[
+    "~/.claude",
+    "~/.codex",
+    "~/.gemini",
+    "~/.copilot",
+    "~/.local/share/opencode",
+    "~/.local/state/opencode",
+    "~/.cursor",
+    "~/.cache",
+];
+
+/// Agent settings inside [`AGENT_STATE_PATHS`] that make the CLIs run
+/// commands (hooks, MCP servers, plugins, extensions), with the content to
+/// create them with if missing, or `None` for directories. They stay
+/// read-only so an agent can't plant commands for the unsandboxed
+/// orchestrator to run. `~/.claude.json` is handled by [`claude_config_copy`].
+const AGENT_CONFIG_PATHS: &[(&str, Option<&str>)] = &[
+    ("~/.claude/settings.json", Some("{}\n")),
+    ("~/.claude/settings.local.json", Some("{}\n")),
+    ("~/.claude/hooks", None),
+    ("~/.claude/plugins", None),
+    ("~/.codex/config.toml", Some("")),
+    ("~/.gemini/settings.json", Some("{}\n")),
+    ("~/.gemini/extensions", None),
+    ("~/.copilot/mcp-config.json", Some("{}\n")),
+    ("~/.cursor/mcp.json", Some("{}\n")),
+];
+
+/// Parts of the git directory that are never writable inside the sandbox
+const PROTECTED_GIT_PATHS: &[&str] = &["hooks", "config"];
+
+/// Files in a worktree's admin directory that point git at its repository
+/// and configuration
+const PROTECTED_ADMIN_PATHS: &[&str] = &["commondir", "gitdir", "config.worktree"];
+
+/// Session operations that run task windows inside a sandbox
+pub struct SandboxedOps {
+    inner: Arc<dyn TmuxOperations>,
+    config: SandboxConfig,
+    project_path: PathBuf,
+}
+
+impl SandboxedOps {
+    pub fn new(
+        inner: Arc<dyn TmuxOperations>,
+        config: SandboxConfig,
+        project_path: PathBuf,
+    ) -> Self {
+        Self {
+            inner,
+            config,
+            project_path,
+        }
+    }
+}
+
+/// Wrap `ops` in [`SandboxedOps`] when `config` enables the sandbox
+pub fn sandboxed(
+    ops: Arc<dyn TmuxOperations>,
+    config: &SandboxConfig,
+    project_path: &Path,
+) -> Arc<dyn TmuxOperations> {
+    if config.is_enabled() {
+        Arc::new(SandboxedOps::new(
+            ops,
+            config.clone(),
+            project_path.to_path_buf(),
+        ))
+    } else {
+        ops
+    }
+}
+
+/// What the sandbox needs to know about the machine outside it
+#[derive(Debug, Default)]
+struct Host {
+    home: Option<PathBuf>,
+    /// Sockets and state directories of the session backends
+    session_paths: Vec<PathBuf>,
+    /// The task's own copy of `~/.claude.json`, mounted over the real one
+    claude_config: Option<PathBuf>,
+}
+
+/// Command line running `script` with `sh -c` inside the sandbox, with
+/// `worktree` writable
+pub fn sandbox_argv(
+    config: &SandboxConfig,
+    project_path: &Path,
+    worktree: &Path,
+    script: &str,
+) -> Vec<String> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if let Some(home) = &home {
+        create_agent_configs(home);
+    }
+    let claude_config = home.as_deref().and_then(|home| {
+        if config.tool() != SandboxTool::Bubblewrap {
+            return None;
+        }
+        let admin = worktree_admin_dir(&project_path.join(".git"), worktree)?;
+        claude_config_copy(home, &admin)
+            .map_err(
+                |e| tracing::warn!(error = %e, "Failed to copy ~/.claude.json for the sandbox"),
+            )
+            .ok()
+            .flatten()
+    });
+    let host = Host {
+        home,
+        session_paths: session_paths(|name| std::env::var_os(name).map(PathBuf::from)),
+        claude_config,
+    };
+    build_argv(config, project_path, worktree, &host, script)
+}
+
+fn build_argv(
+    config: &SandboxConfig,
+    project_path: &Path,
+    worktree: &Path,
+    host: &Host,
+    script: &str,
+) -> Vec<String> {
+    let home = host.home.as_deref();
+    let git_dir = project_path.join(".git");
+    let mut writable: Vec<PathBuf> = AGENT_STATE_PATHS
+        .iter()
+        .copied()
+        .chain(config.writable.iter().map(String::as_str))
+        .map(|p| expand_home(p, home))
+        .collect();
+    writable.extend(git_writable_paths(&git_dir, worktree));
+    // Mount targets must exist; a path nobody created has nothing to protect
+    writable.retain(|p| p.exists());
+    writable.push(worktree.to_path_buf());
+    let mut protected: Vec<PathBuf> = PROTECTED_GIT_PATHS
+        .iter()
+        .map(|p| git_dir.join(p))
+        .chain(other_refs(&git_dir, worktree))
+        .chain(
+            worktree_admin_dir(&git_dir, worktree)
+                .into_iter()
+                .flat_map(|admin| PROTECTED_ADMIN_PATHS.iter().map(move |p| admin.join(p))),
+        )
+        .chain([worktree.join(".git")])
+        .chain(AGENT_CONFIG_PATHS.iter().map(|(p, _)| expand_home(p, home)))
+        .collect();
+    let claude_json = expand_home("~/.claude.json", home);
+    // firejail can't mount a copy in its place, so there it is read-only
+    let claude_config = host
+        .claude_config
+        .as_ref()
+        .filter(|_| claude_json.exists() && config.tool() == SandboxTool::Bubblewrap);
+    if claude_config.is_none() {
+        protected.push(claude_json.clone());
+    }
+    protected.retain(|p| p.exists());
+    let hidden: Vec<PathBuf> = config
+        .hidden
+        .iter()
+        .map(|p| expand_home(p, home))
+        .filter(|p| p.exists())
+        .collect();
+    let session: Vec<&PathBuf> = host
+        .session_paths
+        .iter()
+        .filter(|p| p.symlink_metadata().is_ok())
+        .collect();
+
+    let mut argv: Vec<String> = Vec::new();
+    match config.tool() {
+        SandboxTool::Bubblewrap => {
+            argv.extend(
+                [
+                    "bwrap",
+                    "--die-with-parent",
+                    "--ro-bind",
+                    "/",
+                    "/",
+                    "--dev",
+                    "/dev",
+                    "--proc",
+                    "/proc",
+                    "--tmpfs",
+                    "/tmp",
+                ]
+                .map(String::from),
+            );
+            // Before the writable binds, so a worktree inside one stays usable
+            for path in &session {
+                argv.extend(hide_bwrap(path));
+            }
+            for path in &writable {
+                let path = path.to_string_lossy().to_string();
+                argv.extend(["--bind".to_string(), path.clone(), path]);
+            }
+            if let Some(copy) = claude_config {
+                argv.extend([
+                    "--bind".to_string(),
+                    copy.to_string_lossy().to_string(),
+                    claude_json.to_string_lossy().to_string(),
+                ]);
+            }
+            // Later mounts win, so these override any writable parent
+            for path in &protected {
+                let path = path.to_string_lossy().to_string();
+                argv.extend(["--ro-bind".to_string(), path.clone(), path]);
+            }
+            for path in &hidden {
+                argv.extend(hide_bwrap(path));
+            }
+            if !config.allows_network() {
+                argv.push("--unshare-net".to_string());
+            }
+            argv.extend([
+                "--chdir".to_string(),
+                worktree.to_string_lossy().to_string(),
+                "--".to_string(),
+            ]);
+        }
+        SandboxTool::Firejail => {
+            argv.extend(["firejail", "--quiet", "--noprofile", "--private-tmp"].map(String::from));
+            if let Some(home) = home {
+                argv.push(format!("--read-only={}", home.display()));
+            }
+            argv.push(format!("--read-only={}", project_path.display()));
+            for path in &writable {
+                argv.push(format!("--read-write={}", path.display()));
+            }
+            for path in &protected {
+                argv.push(format!("--read-only={}", path.display()));
+            }
+            for path in session.iter().copied().chain(&hidden) {
+                argv.push(format!("--blacklist={}", path.display()));
+            }
+            if !config.allows_network() {
+                argv.push("--net=none".to_string());
+            }
+        }
+    }
+    argv.extend(["sh".to_string(), "-c".to_string(), script.to_string()]);
+    argv
+}
+
+/// bwrap arguments covering `path` with an empty directory or file
+fn hide_bwrap(path: &Path) -> Vec<String> {
+    let target = path.to_string_lossy().to_string();
+    if path.is_dir() {
+        vec!["--tmpfs".to_string(), target]
+    } else {
+        vec!["--ro-bind".to_string(), "/dev/null".to_string(), target]
+    }
+}
+
+/// Where the session backends listen and keep their state, besides `/tmp`,
+/// which the sandbox always replaces: the runtime directory (the PTY host's
+/// `agtx/pty.sock`, zellij's sockets), tmux's and zellij's socket
+/// directories when moved elsewhere, the temp directory zellij tabs record
+/// their pids in, and the PTY host's socket when there is no runtime
+/// directory.
+fn session_paths(var: impl Fn(&str) -> Option<PathBuf>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = [
+        "XDG_RUNTIME_DIR",
+        "TMUX_TMPDIR",
+        "ZELLIJ_SOCKET_DIR",
+        "TMPDIR",
+    ]
+    .into_iter()
+    .filter_map(&var)
+    .collect();
+    if let Ok(socket) = super::pty::socket_path_in(var("XDG_RUNTIME_DIR")) {
+        paths.push(socket);
+    }
+    paths.retain(|p| p.is_absolute() && !p.starts_with("/tmp"));
+    let all = paths.clone();
+    paths.retain(|p| !all.iter().any(|other| other != p && p.starts_with(other)));
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Create the [`AGENT_CONFIG_PATHS`] of installed agents that don't exist
+/// yet, so an agent can't create them from inside the sandbox.
+fn create_agent_configs(home: &Path) {
+    for (path, content) in AGENT_CONFIG_PATHS {
+        let path = expand_home(path, Some(home));
+        if path.exists() || !path.parent().is_some_and(Path::exists) {
+            continue;
+        }
+        let created = match content {
+            Some(content) => std::fs::write(&path, content),
+            None => std::fs::create_dir(&path),
+        };
+        if let Err(e) = created {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to create agent config");
+        }
+    }
+}
+
+/// Copy `~/.claude.json`, which lists the MCP servers Claude starts, into the
+/// worktree's admin directory for the sandbox to mount in its place. Claude
+/// keeps rewriting it, so it can't simply be read-only; changes made inside
+/// the sandbox stay with the task.
+fn claude_config_copy(home: &Path, admin_dir: &Path) -> Result<Option<PathBuf>> {
+    let original = home.join(".claude.json");
+    if !original.exists() || !admin_dir.is_dir() {
+        return Ok(None);
+    }
+    let copy = admin_dir.join("agtx-claude.json");
+    // The admin directory is writable by the agent: never follow what it left there
+    match std::fs::remove_file(&copy) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&copy)?
+        .write_all(&std::fs::read(&original)?)?;
+    Ok(Some(copy))
+}
+
+/// Git directory paths a task needs to commit to its branch: its worktree's
+/// admin directory under `.git/worktrees`, `objects`, and the directories
+/// holding the branch's ref and reflog (git writes a lock file next to the
+/// ref, so the file alone isn't enough).
+fn git_writable_paths(git_dir: &Path, worktree: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(admin) = worktree_admin_dir(git_dir, worktree) {
+        paths.push(admin);
+    }
+    paths.push(git_dir.join("objects"));
+    if let Some(dir) = task_ref(worktree).and_then(|r| Some(Path::new(&r).parent()?.to_path_buf()))
+    {
+        paths.push(git_dir.join(&dir));
+        paths.push(git_dir.join("logs").join(dir));
+    }
+    paths
+}
+
+/// The ref of the task branch agtx creates for `worktree`, `refs/heads/task/<name>`
+fn task_ref(worktree: &Path) -> Option<String> {
+    let name = worktree.file_name()?.to_str()?;
+    Some(format!("refs/heads/task/{}", name))
+}
+
+/// Refs and reflogs next to the task branch's, which the sandbox makes
+/// writable as a directory: the other tasks' branches
+fn other_refs(git_dir: &Path, worktree: &Path) -> Vec<PathBuf> {
+    let Some(task_ref) = task_ref(worktree) else {
+        return Vec::new();
+    };
+    let own = [
+        git_dir.join(&task_ref),
+        git_dir.join("logs").join(&task_ref),
+    ];
+    own.iter()
+        .filter_map(|path| std::fs::read_dir(path.parent()?).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| !own.contains(path))
+        .collect()
+}
+
+/// `.git/worktrees/<name>` for `worktree`, from the `gitdir:` line of its
+/// `.git` file. The file is writable by the agent, so anything that doesn't
+/// resolve to a directory under `.git/worktrees` is ignored in favor of the
+/// name git gives by default.
+fn worktree_admin_dir(git_dir: &Path, worktree: &Path) -> Option<PathBuf> {
+    let worktrees = git_dir.join("worktrees");
+    let from_file = std::fs::read_to_string(worktree.join(".git"))
+        .ok()
+        .and_then(|content| {
+            let gitdir = content.lines().next()?.strip_prefix("gitdir:")?.trim();
+            let path = worktree.join(gitdir).canonicalize().ok()?;
+            let worktrees = worktrees.canonicalize().ok()?;
+            (path.parent() == Some(worktrees.as_path())).then_some(path)
+        });
+    from_file.or_else(|| Some(worktrees.join(worktree.file_name()?)))
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path: &str, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix('~'), home) {
+        (Some(""), Some(home)) => home.to_path_buf(),
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+impl TmuxOperations for SandboxedOps {
+    fn create_window(
+        &self,
+        session: &str,
+        window_name: &str,
+        working_dir: &str,
+        command: Option<String>,
+        keep_shell_on_exit: bool,
+    ) -> Result<()> {
+        // The orchestrator works from the project root and drives agtx itself
+        if same_path(Path::new(working_dir), &self.project_path) {
+            return self.inner.create_window(
+                session,
+                window_name,
+                working_dir,
+                command,
+                keep_shell_on_exit,
+            );
+        }
+
+        let tool = self.config.tool().binary();
+        which::which(tool)
+            .with_context(|| format!("Sandbox is enabled but {} is not installed", tool))?;
+        let script = match command {
+            Some(cmd) if keep_shell_on_exit => format!("{}; exec $SHELL", cmd),
+            Some(cmd) => cmd,
+            None => "exec ${SHELL:-sh}".to_string(),
+        };
+        let argv = sandbox_argv(
+            &self.config,
+            &self.project_path,
+            Path::new(working_dir),
+            &script,
+        );
+        let line = argv
+            .iter()
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        tracing::debug!(window = window_name, tool, "Starting sandboxed window");
+        self.inner
+            .create_window(session, window_name, working_dir, Some(line), false)
+    }
+
+    fn kill_window(&self, target: &str) -> Result<()> {
+        self.inner.kill_window(target)
+    }
+
+    fn window_exists(&self, target: &str) -> Result<bool> {
+        self.inner.window_exists(target)
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        self.inner.send_keys(target, keys)
+    }
+
+    fn send_keys_literal(&self, target: &str, keys: &str) -> Result<()> {
+        self.inner.send_keys_literal(target, keys)
+    }
+
+    fn paste_text(&self, target: &str, text: &str) -> Result<()> {
+        self.inner.paste_text(target, text)
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        self.inner.capture_pane(target)
+    }
+
+    fn capture_pane_with_history(&self, target: &str, history_lines: i32) -> Vec<u8> {
+        self.inner.capture_pane_with_history(target, history_lines)
+    }
+
+    fn get_cursor_info(&self, target: &str) -> Option<(usize, usize)> {
+        self.inner.get_cursor_info(target)
+    }
+
+    fn resize_window(&self, target: &str, width: u16, height: u16) -> Result<()> {
+        self.inner.resize_window(target, width, height)
+    }
+
+    fn pane_current_command(&self, target: &str) -> Option<String> {
+        self.inner.pane_current_command(target)
+    }
+
+    fn has_session(&self, session: &str) -> bool {
+        self.inner.has_session(session)
+    }
+
+    fn create_session(&self, session: &str, working_dir: &str) -> Result<()> {
+        self.inner.create_session(session, working_dir)
+    }
+
+    fn pipe_pane(&self, target: &str, log_path: &Path) -> Result<()> {
+        self.inner.pipe_pane(target, log_path)
+    }
+
+    fn is_inside_server(&self, session: &str) -> bool {
+        self.inner.is_inside_server(session)
+    }
+
+    fn select_window(&self, target: &str) -> Result<()> {
+        self.inner.select_window(target)
+    }
+
+    fn attach(&self, session: &str, target: &str) -> Result<()> {
+        self.inner.attach(session, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("repo");
+        let worktree = project.join(".agtx/worktrees/task");
+        for git_path in [
+            "hooks",
+            "objects",
+            "refs/heads/task",
+            "logs/refs/heads/task",
+            "worktrees/task",
+        ] {
+            std::fs::create_dir_all(project.join(".git").join(git_path)).unwrap();
+        }
+        for file in [
+            "config",
+            "refs/heads/main",
+            "refs/heads/task/task",
+            "refs/heads/task/other",
+            "logs/refs/heads/task/task",
+            "logs/refs/heads/task/other",
+            "worktrees/task/commondir",
+        ] {
+            std::fs::write(project.join(".git").join(file), "").unwrap();
+        }
+        std::fs::create_dir_all(&worktree).unwrap();
+        (dir, project, worktree)
+    }
+
+    fn host(home: &Path) -> Host {
+        Host {
+            home: Some(home.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    /// Paths a command line hides: covered by an empty tmpfs or file,
+    /// blacklisted, or under a private /tmp
+    fn hidden_paths(argv: &[String]) -> Vec<PathBuf> {
+        let mut hidden: Vec<PathBuf> = argv
+            .windows(3)
+            .filter_map(|w| match (w[0].as_str(), w[1].as_str()) {
+                ("--tmpfs", path) => Some(PathBuf::from(path)),
+                ("--ro-bind", "/dev/null") => Some(PathBuf::from(&w[2])),
+                _ => None,
+            })
+            .collect();
+        hidden.extend(
+            argv.iter()
+                .filter_map(|a| a.strip_prefix("--blacklist="))
+                .map(PathBuf::from),
+        );
+        if argv.iter().any(|a| a == "--private-tmp") {
+            hidden.push(PathBuf::from("/tmp"));
+        }
+        hidden
+    }
+
+    /// Paths bound writable by a bwrap command line
+    fn bwrap_writable(argv: &[String]) -> Vec<&str> {
+        argv.windows(2)
+            .filter(|w| w[0] == "--bind")
+            .map(|w| w[1].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_git_hooks_and_config_stay_read_only() {
+        let (dir, project, worktree) = project();
+        let config = SandboxConfig {
+            // Even a writable entry covering the repository can't expose them
+            writable: vec![project.display().to_string()],
+            ..Default::default()
+        };
+        let argv = build_argv(&config, &project, &worktree, &host(dir.path()), "claude");
+
+        let git = project.join(".git");
+        let writable = bwrap_writable(&argv);
+        assert!(!writable.contains(&git.display().to_string().as_str()));
+        assert!(!writable.contains(&git.join("hooks").display().to_string().as_str()));
+        assert!(!writable.contains(&git.join("config").display().to_string().as_str()));
+        let joined = argv.join(" ");
+        for protected in [
+            "hooks",
+            "config",
+            "worktrees/task/commondir",
+            "refs/heads/task/other",
+            "logs/refs/heads/task/other",
+        ] {
+            let path = git.join(protected).display().to_string();
+            assert!(joined.contains(&format!("--ro-bind {} {}", path, path)));
+        }
+
+        let firejail = SandboxConfig {
+            tool: Some(SandboxTool::Firejail),
+            ..config
+        };
+        let argv = build_argv(&firejail, &project, &worktree, &host(dir.path()), "claude");
+        assert!(argv.contains(&format!("--read-only={}", git.join("hooks").display())));
+        assert!(!argv.contains(&format!("--read-write={}", git.display())));
+    }
+
+    #[test]
+    fn test_worktree_admin_dir_must_be_under_git_worktrees() {
+        let (dir, project, worktree) = project();
+        let git = project.join(".git");
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", dir.path().display()),
+        )
+        .unwrap();
+        assert_eq!(
+            worktree_admin_dir(&git, &worktree),
+            Some(git.join("worktrees/task"))
+        );
+
+        std::fs::create_dir_all(git.join("worktrees/task1")).unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            "gitdir: ../../../.git/worktrees/task1\n",
+        )
+        .unwrap();
+        assert_eq!(
+            worktree_admin_dir(&git, &worktree),
+            Some(git.join("worktrees/task1").canonicalize().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_bwrap_binds_worktree_and_git_dir_writable() {
+        let (dir, project, worktree) = project();
+        std::fs::create_dir_all(dir.path().join("home/.ssh")).unwrap();
+        let config = SandboxConfig {
+            network: Some(false),
+            writable: vec!["~/missing".to_string()],
+            hidden: vec!["~/.ssh".to_string()],
+            ..Default::default()
+        };
+        let home = dir.path().join("home");
+        let argv = build_argv(&config, &project, &worktree, &host(&home), "claude");
+        let joined = argv.join(" ");
+
+        assert!(joined.starts_with("bwrap --die-with-parent --ro-bind / /"));
+        let git = project.join(".git");
+        let writable = bwrap_writable(&argv);
+        for git_path in [
+            "worktrees/task",
+            "objects",
+            "refs/heads/task",
+            "logs/refs/heads/task",
+        ] {
+            let path = git.join(git_path).display().to_string();
+            assert!(writable.contains(&path.as_str()), "{} not writable", path);
+        }
+        // Only the task branch's own directory, never the base branch's
+        for git_path in ["refs", "refs/heads", "logs"] {
+            let path = git.join(git_path).display().to_string();
+            assert!(!writable.contains(&path.as_str()), "{} writable", path);
+        }
+        assert!(!joined.contains(&format!(
+            "--ro-bind {0} {0}",
+            git.join("refs/heads/task/task").display()
+        )));
+        let wt = worktree.display().to_string();
+        assert!(joined.contains(&format!("--bind {} {}", wt, wt)));
+        assert!(joined.contains(&format!("--tmpfs {}", home.join(".ssh").display())));
+        assert!(!joined.contains("missing"));
+        assert!(joined.contains("--unshare-net"));
+        assert_eq!(argv[argv.len() - 3..], ["sh", "-c", "claude"]);
+    }
+
+    #[test]
+    fn test_firejail_profile() {
+        let (dir, project, worktree) = project();
+        let config = SandboxConfig {
+            tool: Some(SandboxTool::Firejail),
+            ..Default::default()
+        };
+        let home = dir.path().join("home");
+        let argv = build_argv(&config, &project, &worktree, &host(&home), "codex");
+
+        assert_eq!(argv[..3], ["firejail", "--quiet", "--noprofile"]);
+        assert!(argv.contains(&format!("--read-only={}", project.display())));
+        assert!(argv.contains(&format!("--read-write={}", worktree.display())));
+        assert!(!argv.iter().any(|a| a == "--net=none"));
+    }
+
+    #[test]
+    fn test_session_sockets_are_hidden() {
+        let (dir, project, worktree) = project();
+        let runtime = dir.path().join("run");
+        let tmux_tmp = dir.path().join("tmux");
+        let zellij_sockets = dir.path().join("zellij");
+        let temp = dir.path().join("tmp");
+        for path in [
+            runtime.join("agtx"),
+            tmux_tmp.clone(),
+            zellij_sockets.clone(),
+            temp.clone(),
+        ] {
+            std::fs::create_dir_all(path).unwrap();
+        }
+        let default_sockets = [
+            // tmux -L agtx, zellij and agtx's zellij pid files without overrides
+            PathBuf::from("/tmp/tmux-1000/agtx"),
+            PathBuf::from("/tmp/zellij-1000/0.41.2/proj"),
+            PathBuf::from("/tmp/agtx-zellij/proj/focus.lock"),
+            super::super::pty::socket_path_in(Some(runtime.clone())).unwrap(),
+            runtime.join("zellij/0.41.2/proj"),
+        ];
+        let moved_sockets = [
+            tmux_tmp.join("tmux-1000/agtx"),
+            zellij_sockets.join("proj"),
+            temp.join("agtx-zellij/proj/focus.lock"),
+        ];
+
+        let env = |name: &str| match name {
+            "XDG_RUNTIME_DIR" => Some(runtime.clone()),
+            "TMUX_TMPDIR" => Some(tmux_tmp.clone()),
+            "ZELLIJ_SOCKET_DIR" => Some(zellij_sockets.clone()),
+            "TMPDIR" => Some(temp.clone()),
+            _ => None,
+        };
+        let host = Host {
+            home: Some(dir.path().join("home")),
+            session_paths: session_paths(env),
+            claude_config: None,
+        };
+        for tool in [SandboxTool::Bubblewrap, SandboxTool::Firejail] {
+            let config = SandboxConfig {
+                tool: Some(tool),
+                ..Default::default()
+            };
+            let argv = build_argv(&config, &project, &worktree, &host, "claude");
+            let hidden = hidden_paths(&argv);
+            for socket in default_sockets.iter().chain(&moved_sockets) {
+                assert!(
+                    hidden.iter().any(|h| socket.starts_with(h)),
+                    "{} visible under {:?}",
+                    socket.display(),
+                    tool
+                );
+            }
+        }
+
+        // Without a runtime directory the PTY host listens in the data directory
+        let socket = super::super::pty::socket_path_in(None).unwrap();
+        assert!(session_paths(|_| None).contains(&socket));
+    }
+
+    #[test]
+    fn test_agent_configs_stay_read_only() {
+        let (dir, project, worktree) = project();
+        let home = dir.path().join("home");
+        std::fs::create_dir_all(home.join(".claude")).unwrap();
+        std::fs::write(home.join(".claude.json"), r#"{"mcpServers":{}}"#).unwrap();
+        create_agent_configs(&home);
+        assert_eq!(
+            std::fs::read_to_string(home.join(".claude/settings.json")).unwrap(),
+            "{}\n"
+        );
+        assert!(home.join(".claude/hooks").is_dir());
+        // Agents that aren't installed are left alone
+        assert!(!home.join(".gemini").exists());
+
+        let admin = project.join(".git/worktrees/task");
+        std::fs::write(admin.join("agtx-claude.json"), "stale").unwrap();
+        let copy = claude_config_copy(&home, &admin).unwrap().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&copy).unwrap(),
+            r#"{"mcpServers":{}}"#
+        );
+
+        let host = Host {
+            home: Some(home.clone()),
+            session_paths: Vec::new(),
+            claude_config: Some(copy.clone()),
+        };
+        let argv = build_argv(
+            &SandboxConfig::default(),
+            &project,
+            &worktree,
+            &host,
+            "claude",
+        );
+        let joined = argv.join(" ");
+        assert!(bwrap_writable(&argv).contains(&home.join(".claude").to_str().unwrap()));
+        for config in [".claude/settings.json", ".claude/hooks"] {
+            let path = home.join(config).display().to_string();
+            assert!(joined.contains(&format!("--ro-bind {} {}", path, path)));
+        }
+        let claude_json = home.join(".claude.json").display().to_string();
+        assert!(joined.contains(&format!("--bind {} {}", copy.display(), claude_json)));
+        assert!(!joined.contains(&format!("--bind {0} {0}", claude_json)));
+
+        let firejail = SandboxConfig {
+            tool: Some(SandboxTool::Firejail),
+            ..Default::default()
+        };
+        let argv = build_argv(&firejail, &project, &worktree, &host, "claude");
+        assert!(argv.contains(&format!("--read-only={}", claude_json)));
+        assert!(argv.contains(&format!(
+            "--read-only={}",
+            home.join(".claude/settings.json").display()
+        )));
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = Path::new("/home/me");
+        assert_eq!(expand_home("~", Some(home)), PathBuf::from("/home/me"));
+        assert_eq!(
+            expand_home("~/.ssh", Some(home)),
+            PathBuf::from("/home/me/.ssh")
+        );
+        assert_eq!(expand_home("~other", Some(home)), PathBuf::from("~other"));
+        assert_eq!(expand_home("/opt", Some(home)), PathBuf::from("/opt"));
+    }
+
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn test_project_root_window_is_not_sandboxed() {
+        let (_dir, project, _worktree) = project();
+        let mut mock = super::super::MockTmuxOperations::new();
+        mock.expect_create_window()
+            .withf(|_, _, _, command, keep| command.as_deref() == Some("claude") && !keep)
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        let config = SandboxConfig {
+            enabled: Some(true),
+            ..Default::default()
+        };
+        let ops = sandboxed(Arc::new(mock), &config, &project);
+        ops.create_window(
+            "proj",
+            "orchestrator",
+            &project.to_string_lossy(),
+            Some("claude".to_string()),
+            false,
+        )
+        .unwrap();
+    }
+}
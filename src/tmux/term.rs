@@ -0,0 +1,137 @@
+//! Shell and terminal helpers shared by the session backends that don't run
+//! on tmux and by the agent sandbox.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Bracketed paste start/end markers (`ESC [200~` / `ESC [201~`)
+pub(super) const PASTE_START: &[u8] = b"\x1b[200~";
+pub(super) const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Single-quote `s` for `sh`
+pub(super) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\"'\"'"))
+}
+
+/// Split `session:window` into its parts; a bare session targets its current window.
+pub(super) fn split_target(target: &str) -> (&str, Option<&str>) {
+    match target.split_once(':') {
+        Some((session, tab)) if !tab.is_empty() => (session, Some(tab)),
+        Some((session, _)) => (session, None),
+        None => (target, None),
+    }
+}
+
+/// Translate a tmux `send-keys` key name (`Enter`, `C-c`, `M-Left`, ...) to
+/// the bytes a terminal would send. Returns None for plain text.
+pub(super) fn key_bytes(key: &str) -> Option<Vec<u8>> {
+    if let Some(rest) = key.strip_prefix("M-").filter(|r| !r.is_empty()) {
+        let mut bytes = vec![0x1b];
+        bytes.extend(key_bytes(rest).unwrap_or_else(|| rest.as_bytes().to_vec()));
+        return Some(bytes);
+    }
+    if let Some(rest) = key.strip_prefix("C-") {
+        let mut chars = rest.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            let byte = match c {
+                'a'..='z' | 'A'..='Z' => c.to_ascii_lowercase() as u8 & 0x1f,
+                '@' | ' ' => 0,
+                '[' => 0x1b,
+                '\\' => 0x1c,
+                ']' => 0x1d,
+                '^' => 0x1e,
+                '_' => 0x1f,
+                '?' => 0x7f,
+                _ => return None,
+            };
+            return Some(vec![byte]);
+        }
+    }
+    let seq: &[u8] = match key {
+        "Enter" => b"\r",
+        "Escape" => b"\x1b",
+        "BSpace" => b"\x7f",
+        "Tab" => b"\t",
+        "BTab" => b"\x1b[Z",
+        "Space" => b" ",
+        "Up" => b"\x1b[A",
+        "Down" => b"\x1b[B",
+        "Right" => b"\x1b[C",
+        "Left" => b"\x1b[D",
+        "Home" => b"\x1b[H",
+        "End" => b"\x1b[F",
+        "PageUp" | "PPage" => b"\x1b[5~",
+        "PageDown" | "NPage" => b"\x1b[6~",
+        "DC" => b"\x1b[3~",
+        "IC" => b"\x1b[2~",
+        "F1" => b"\x1bOP",
+        "F2" => b"\x1bOQ",
+        "F3" => b"\x1bOR",
+        "F4" => b"\x1bOS",
+        "F5" => b"\x1b[15~",
+        "F6" => b"\x1b[17~",
+        "F7" => b"\x1b[18~",
+        "F8" => b"\x1b[19~",
+        "F9" => b"\x1b[20~",
+        "F10" => b"\x1b[21~",
+        "F11" => b"\x1b[23~",
+        "F12" => b"\x1b[24~",
+        _ => return None,
+    };
+    Some(seq.to_vec())
+}
+
+/// Foreground process group on `pid`'s controlling terminal
+pub(super) fn foreground_pgid(pid: u32) -> Option<u32> {
+    ps_field("tpgid=", pid)?
+        .parse()
+        .ok()
+        .filter(|pgid| *pgid > 0)
+}
+
+/// Command name of a process, as tmux reports it in `pane_current_command`
+pub(super) fn process_name(pid: u32) -> Option<String> {
+    let comm = ps_field("comm=", pid)?;
+    Path::new(&comm)
+        .file_name()
+        .map(|n| n.to_string_lossy().trim_start_matches('-').to_string())
+}
+
+fn ps_field(field: &str, pid: u32) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-o", field, "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_target() {
+        assert_eq!(split_target("proj:task-1"), ("proj", Some("task-1")));
+        assert_eq!(split_target("proj:"), ("proj", None));
+        assert_eq!(split_target("proj"), ("proj", None));
+    }
+
+    #[test]
+    fn test_key_bytes() {
+        assert_eq!(key_bytes("Enter"), Some(b"\r".to_vec()));
+        assert_eq!(key_bytes("C-c"), Some(vec![3]));
+        assert_eq!(key_bytes("C-D"), Some(vec![4]));
+        assert_eq!(key_bytes("M-x"), Some(b"\x1bx".to_vec()));
+        assert_eq!(key_bytes("M-Left"), Some(b"\x1b\x1b[D".to_vec()));
+        assert_eq!(key_bytes("2"), None);
+        assert_eq!(key_bytes("/review"), None);
+    }
+
+    #[test]
+    fn test_process_name_of_self() {
+        let name = process_name(std::process::id()).unwrap();
+        assert!(!name.is_empty());
+        assert!(!name.contains('/'));
+    }
+}
@@ -0,0 +1,472 @@
+//! Zellij implementation of [`TmuxOperations`].
+//!
+//! Each project gets one zellij session and each agtx window is a tab in it,
+//! so targets keep the `session:window` form used everywhere else. Zellij CLI
+//! actions apply to the focused tab, which means every targeted action first
+//! switches to the tab by name. An exclusive file lock per session keeps those
+//! two steps together across threads and across processes (TUI, daemon, MCP
+//! server); a user attached to the session sees the focus jump between tabs
+//! while agtx works.
+//!
+//! Zellij has no equivalent of a few tmux queries, so they are approximated:
+//! the running command comes from the foreground process of the pane's shell
+//! (its pid is recorded when the tab is created), the cursor row is the last
+//! non-blank line of the screen, and panes can't be resized from outside.
+//! There is no `pipe-pane` either: transcripts are periodic full-scrollback
+//! dumps of the tab, written as plain text.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::term::{
+    foreground_pgid, key_bytes, process_name, shell_quote, split_target, PASTE_END, PASTE_START,
+};
+use super::TmuxOperations;
+
+/// How often a transcript is refreshed from the tab's scrollback
+const TRANSCRIPT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Session operations backed by the `zellij` CLI
+#[derive(Default)]
+pub struct ZellijOps {
+    /// Latest transcript generation per target; a capture thread stops once
+    /// its generation is replaced
+    transcripts: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl ZellijOps {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn action(&self, session: &str, args: &[&str]) -> Result<Output> {
+        let output = Command::new("zellij")
+            .args(["--session", session, "action"])
+            .args(args)
+            .output()
+            .context("Failed to run zellij")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "zellij action {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output)
+    }
+
+    fn tab_names(&self, session: &str) -> Result<Vec<String>> {
+        let output = self.action(session, &["query-tab-names"])?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    }
+
+    /// Take the session's focus lock, shared with every other agtx process.
+    /// Held across "go to tab" + action so actions land on the right tab;
+    /// released when the returned file is dropped.
+    fn lock_focus(&self, session: &str) -> Result<File> {
+        let file = open_lock_file(&session_dir(session).join("focus.lock"))?;
+        file.lock().context("Failed to lock zellij focus")?;
+        Ok(file)
+    }
+
+    /// Focus the tab for `target`. Must be called with the focus lock held.
+    fn focus(&self, target: &str) -> Result<()> {
+        let (session, tab) = split_target(target);
+        if let Some(tab) = tab {
+            // go-to-tab-name on a missing tab would leave another tab focused
+            if !self.tab_names(session)?.iter().any(|t| t == tab) {
+                anyhow::bail!("zellij tab not found: {}", target);
+            }
+            self.action(session, &["go-to-tab-name", tab])?;
+        }
+        Ok(())
+    }
+
+    /// Focus `target` and run one action on it
+    fn action_on(&self, target: &str, args: &[&str]) -> Result<Output> {
+        let _guard = self.lock_focus(split_target(target).0)?;
+        self.focus(target)?;
+        self.action(split_target(target).0, args)
+    }
+
+    /// Focus `target` and write raw bytes and/or text to its pane, in order
+    fn write_on(&self, target: &str, parts: &[Input]) -> Result<()> {
+        let _guard = self.lock_focus(split_target(target).0)?;
+        self.focus(target)?;
+        let session = split_target(target).0;
+        for part in parts {
+            match part {
+                Input::Bytes(bytes) => {
+                    let args: Vec<String> = bytes.iter().map(|b| b.to_string()).collect();
+                    let mut cmd = vec!["write"];
+                    cmd.extend(args.iter().map(String::as_str));
+                    self.action(session, &cmd)?;
+                }
+                Input::Text(text) => {
+                    self.action(session, &["write-chars", "--", text])?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Dump the focused pane of `target` to text; `full` includes scrollback
+    fn dump_screen(&self, target: &str, full: bool) -> Result<String> {
+        static DUMP_SEQ: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "agtx-zellij-{}-{}.dump",
+            std::process::id(),
+            DUMP_SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path_str = path.to_string_lossy().to_string();
+        let mut args = vec!["dump-screen", path_str.as_str()];
+        if full {
+            args.push("--full");
+        }
+        let result = self.action_on(target, &args);
+        let content = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        result?;
+        Ok(String::from_utf8_lossy(&content?).into_owned())
+    }
+}
+
+/// Keep `log_path` in sync with the full scrollback of `target` until the tab
+/// closes or another transcript replaces this one (`generation`).
+///
+/// Each refresh rewrites everything written since the capture began, so the
+/// log holds the latest dump rather than a growing pile of repeats. A lock
+/// file per tab makes sure only one agtx process captures it at a time; the
+/// others keep trying and take over when it exits.
+fn capture_transcript(
+    transcripts: Arc<Mutex<HashMap<String, u64>>>,
+    target: String,
+    log_path: PathBuf,
+    generation: u64,
+) {
+    let ops = ZellijOps::default();
+    let (session, tab) = split_target(&target);
+    let lock_path = session_dir(session).join(format!("{}.transcript.lock", tab.unwrap_or("")));
+    // The capture lock and where this capture's content starts in the log
+    let mut capture: Option<(File, u64)> = None;
+    loop {
+        std::thread::sleep(TRANSCRIPT_INTERVAL);
+        let current = transcripts
+            .lock()
+            .map(|t| t.get(&target) == Some(&generation))
+            .unwrap_or(false);
+        if !current || !ops.window_exists(&target).unwrap_or(false) {
+            break;
+        }
+        if capture.is_none() {
+            let Ok(lock) = open_lock_file(&lock_path) else {
+                continue;
+            };
+            if lock.try_lock().is_err() {
+                continue;
+            }
+            let Ok(start) = std::fs::metadata(&log_path).map(|m| m.len()) else {
+                break;
+            };
+            capture = Some((lock, start));
+        }
+        let Some((_, start)) = &capture else {
+            continue;
+        };
+        let Ok(content) = ops.dump_screen(&target, true) else {
+            continue;
+        };
+        let written = OpenOptions::new()
+            .write(true)
+            .open(&log_path)
+            .and_then(|mut file| {
+                file.set_len(*start)?;
+                file.seek(SeekFrom::Start(*start))?;
+                file.write_all(content.replace('\n', "\r\n").as_bytes())
+            });
+        if written.is_err() {
+            break;
+        }
+    }
+}
+
+enum Input<'a> {
+    Bytes(Vec<u8>),
+    Text(&'a str),
+}
+
+/// Per-session directory for pid and lock files
+fn session_dir(session: &str) -> PathBuf {
+    std::env::temp_dir().join("agtx-zellij").join(session)
+}
+
+/// Where the pid of the shell wrapping a tab's command is recorded
+fn pid_file(session: &str, tab: &str) -> PathBuf {
+    session_dir(session).join(format!("{}.pid", tab))
+}
+
+fn open_lock_file(path: &Path) -> Result<File> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Quote a string for a KDL layout file
+fn kdl_string(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+impl TmuxOperations for ZellijOps {
+    fn create_window(
+        &self,
+        session: &str,
+        window_name: &str,
+        working_dir: &str,
+        command: Option<String>,
+        keep_shell_on_exit: bool,
+    ) -> Result<()> {
+        let pid_path = pid_file(session, window_name);
+        if let Some(dir) = pid_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let record_pid = format!("echo $$ > {}", shell_quote(&pid_path.to_string_lossy()));
+        let script = match command {
+            Some(cmd) if keep_shell_on_exit => format!("{}; {}; exec $SHELL", record_pid, cmd),
+            Some(cmd) => format!("{}; {}", record_pid, cmd),
+            None => format!("{}; exec ${{SHELL:-sh}}", record_pid),
+        };
+
+        // The pane closes with its command, like a tmux window does
+        let layout = format!(
+            "layout {{\n    pane command=\"sh\" cwd={} close_on_exit=true {{\n        args \"-c\" {}\n    }}\n}}\n",
+            kdl_string(working_dir),
+            kdl_string(&script)
+        );
+        let layout_path = pid_path.with_extension("kdl");
+        std::fs::write(&layout_path, layout)?;
+        let layout_str = layout_path.to_string_lossy().to_string();
+        let result = self.action(
+            session,
+            &[
+                "new-tab",
+                "--layout",
+                &layout_str,
+                "--name",
+                window_name,
+                "--cwd",
+                working_dir,
+            ],
+        );
+        let _ = std::fs::remove_file(&layout_path);
+        result.context("Failed to create zellij tab")?;
+        Ok(())
+    }
+
+    fn kill_window(&self, target: &str) -> Result<()> {
+        let (session, tab) = split_target(target);
+        let Some(tab) = tab else { return Ok(()) };
+        let _guard = self.lock_focus(session)?;
+        if !self
+            .tab_names(session)
+            .unwrap_or_default()
+            .iter()
+            .any(|t| t == tab)
+        {
+            return Ok(());
+        }
+        self.action(session, &["go-to-tab-name", tab])?;
+        self.action(session, &["close-tab"])?;
+        let _ = std::fs::remove_file(pid_file(session, tab));
+        Ok(())
+    }
+
+    fn window_exists(&self, target: &str) -> Result<bool> {
+        let (session, tab) = split_target(target);
+        if !self.has_session(session) {
+            return Ok(false);
+        }
+        match tab {
+            Some(tab) => Ok(self.tab_names(session)?.iter().any(|t| t == tab)),
+            None => Ok(true),
+        }
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        self.write_on(target, &[Input::Text(keys), Input::Bytes(b"\r".to_vec())])
+    }
+
+    fn send_keys_literal(&self, target: &str, keys: &str) -> Result<()> {
+        match key_bytes(keys) {
+            Some(bytes) => self.write_on(target, &[Input::Bytes(bytes)]),
+            None => self.write_on(target, &[Input::Text(keys)]),
+        }
+    }
+
+    fn paste_text(&self, target: &str, text: &str) -> Result<()> {
+        self.write_on(
+            target,
+            &[
+                Input::Bytes(PASTE_START.to_vec()),
+                Input::Text(text),
+                Input::Bytes(PASTE_END.to_vec()),
+            ],
+        )
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        self.dump_screen(target, false)
+    }
+
+    fn capture_pane_with_history(&self, target: &str, history_lines: i32) -> Vec<u8> {
+        let Ok(content) = self.dump_screen(target, true) else {
+            return Vec::new();
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        let start = lines.len().saturating_sub(history_lines.max(0) as usize);
+        let mut out = lines[start..].join("\n");
+        out.push('\n');
+        out.into_bytes()
+    }
+
+    fn get_cursor_info(&self, target: &str) -> Option<(usize, usize)> {
+        let content = self.dump_screen(target, false).ok()?;
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.is_empty() {
+            return None;
+        }
+        let cursor_y = lines
+            .iter()
+            .rposition(|l| !l.trim().is_empty())
+            .unwrap_or(0);
+        Some((cursor_y, lines.len()))
+    }
+
+    fn resize_window(&self, _target: &str, _width: u16, _height: u16) -> Result<()> {
+        // Zellij sizes panes to the attached client; there is no external resize.
+        Ok(())
+    }
+
+    fn pane_current_command(&self, target: &str) -> Option<String> {
+        let (session, tab) = split_target(target);
+        let pid = std::fs::read_to_string(pid_file(session, tab?)).ok()?;
+        process_name(foreground_pgid(pid.trim().parse().ok()?)?)
+    }
+
+    fn has_session(&self, session: &str) -> bool {
+        let Ok(output) = Command::new("zellij")
+            .args(["list-sessions", "--no-formatting"])
+            .output()
+        else {
+            return false;
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|line| line.split_whitespace().next() == Some(session) && !line.contains("EXITED"))
+    }
+
+    fn create_session(&self, session: &str, working_dir: &str) -> Result<()> {
+        Command::new("zellij")
+            .args(["attach", "--create-background", session])
+            .current_dir(working_dir)
+            .output()
+            .context("Failed to run zellij")?;
+        Ok(())
+    }
+
+    fn pipe_pane(&self, target: &str, log_path: &Path) -> Result<()> {
+        if split_target(target).1.is_none() {
+            anyhow::bail!("zellij transcripts need a tab target, got {}", target);
+        }
+        let generation = {
+            let mut transcripts = self.transcripts.lock().unwrap_or_else(|e| e.into_inner());
+            let generation = transcripts.get(target).map_or(0, |g| g + 1);
+            transcripts.insert(target.to_string(), generation);
+            generation
+        };
+        let transcripts = Arc::clone(&self.transcripts);
+        let target = target.to_string();
+        let log_path = log_path.to_path_buf();
+        std::thread::spawn(move || capture_transcript(transcripts, target, log_path, generation));
+        Ok(())
+    }
+
+    fn is_inside_server(&self, session: &str) -> bool {
+        std::env::var("ZELLIJ_SESSION_NAME").is_ok_and(|name| name == session)
+    }
+
+    fn select_window(&self, target: &str) -> Result<()> {
+        let _guard = self.lock_focus(split_target(target).0)?;
+        self.focus(target)
+    }
+
+    fn attach(&self, session: &str, target: &str) -> Result<()> {
+        // The client opens on the session's focused tab
+        self.select_window(target)?;
+        Command::new("zellij")
+            .args(["attach", session])
+            .env_remove("ZELLIJ")
+            .env_remove("ZELLIJ_SESSION_NAME")
+            .status()
+            .context("Failed to attach to zellij session")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kdl_string_escapes() {
+        assert_eq!(kdl_string(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
+    }
+
+    #[test]
+    fn test_focus_lock_excludes_other_handles() {
+        let ops = ZellijOps::new();
+        let session = format!("agtx-test-{}", std::process::id());
+        let guard = ops.lock_focus(&session).unwrap();
+        // Another process (or thread) opens its own handle and must wait
+        let other = open_lock_file(&session_dir(&session).join("focus.lock")).unwrap();
+        assert!(other.try_lock().is_err());
+        drop(guard);
+        assert!(other.try_lock().is_ok());
+        drop(other);
+        let _ = std::fs::remove_dir_all(session_dir(&session));
+    }
+
+    #[test]
+    fn test_pipe_pane_replaces_earlier_transcript() {
+        let ops = ZellijOps::new();
+        let log = std::env::temp_dir().join("agtx-zellij-test.log");
+        assert!(ops.pipe_pane("no-such-session", &log).is_err());
+
+        ops.pipe_pane("no-such-session:task", &log).unwrap();
+        ops.pipe_pane("no-such-session:task", &log).unwrap();
+        assert_eq!(
+            ops.transcripts.lock().unwrap().get("no-such-session:task"),
+            Some(&1)
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Per-task agent transcripts under `.agtx/logs/<task-id>/<phase>.log`.
+//!
+//! While a task window is alive its pane output is streamed to the log for the
+//! current phase with `tmux pipe-pane`, ANSI escapes included, so the session
+//! can be replayed after the window is gone. Each time a pipe is (re)attached
+//! a header line is appended, so one log can hold several sessions of the
+//! same phase. `.agtx/logs/` is added to the repository's `.git/info/exclude`
+//! so transcripts never end up in a commit.
+
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::db::TaskStatus;
+use crate::tmux::TmuxOperations;
+
+/// Phases in workflow order; also the log file stems.
+pub const PHASES: [&str; 4] = ["research", "planning", "running", "review"];
+
+/// Line added to `.git/info/exclude` for the transcript directory.
+const EXCLUDE_PATTERN: &str = "/.agtx/logs/";
+
+/// Only this much of the end of a log is read when tailing it.
+const TAIL_READ_BYTES: u64 = 4 * 1024 * 1024;
+
+/// A transcript file that exists on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptFile {
+    pub phase: &'static str,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Directory holding a task's transcripts.
+pub fn transcript_dir(project_path: &Path, task_id: &str) -> PathBuf {
+    project_path.join(".agtx").join("logs").join(task_id)
+}
+
+/// Log file for one phase of a task.
+pub fn transcript_path(project_path: &Path, task_id: &str, phase: &str) -> PathBuf {
+    transcript_dir(project_path, task_id).join(format!("{}.log", phase))
+}
+
+/// Transcript phase for a task status; Done tasks have no live session.
+pub fn phase_for_status(status: TaskStatus) -> Option<&'static str> {
+    match status {
+        TaskStatus::Backlog => Some("research"),
+        TaskStatus::Planning => Some("planning"),
+        TaskStatus::Running => Some("running"),
+        TaskStatus::Review => Some("review"),
+        TaskStatus::Done => None,
+    }
+}
+
+/// Start streaming `target`'s pane into the log for `phase`.
+/// Replaces any pipe already attached to the pane.
+pub fn start(
+    tmux_ops: &dyn TmuxOperations,
+    target: &str,
+    project_path: &Path,
+    task_id: &str,
+    phase: &str,
+) -> Result<PathBuf> {
+    let dir = transcript_dir(project_path, task_id);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create transcript directory {}", dir.display()))?;
+    let path = transcript_path(project_path, task_id, phase);
+    // Pipe first so a backend that can't stream output leaves no log behind
+    tmux_ops.pipe_pane(target, &path)?;
+    if let Err(e) = exclude_from_git(project_path) {
+        tracing::warn!(error = %e, "Failed to add transcripts to .git/info/exclude");
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open transcript {}", path.display()))?;
+    writeln!(
+        file,
+        "\r\n=== agtx {} session {} ===\r",
+        phase,
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+    )?;
+    Ok(path)
+}
+
+/// Add `.agtx/logs/` to the repository's `info/exclude` unless it's there already.
+/// Projects that aren't git repositories are left alone.
+pub fn exclude_from_git(project_path: &Path) -> Result<()> {
+    let output = std::process::Command::new("git")
+        .current_dir(project_path)
+        .args(["rev-parse", "--git-path", "info/exclude"])
+        .output()
+        .context("Failed to run git rev-parse")?;
+    if !output.status.success() {
+        return Ok(());
+    }
+    let path = project_path.join(String::from_utf8_lossy(&output.stdout).trim());
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == EXCLUDE_PATTERN) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", EXCLUDE_PATTERN)?;
+    Ok(())
+}
+
+/// Transcripts recorded for a task, in phase order.
+pub fn list(project_path: &Path, task_id: &str) -> Vec<TranscriptFile> {
+    PHASES
+        .iter()
+        .filter_map(|phase| {
+            let path = transcript_path(project_path, task_id, phase);
+            let size = fs::metadata(&path).ok()?.len();
+            Some(TranscriptFile { phase, path, size })
+        })
+        .collect()
+}
+
+/// Read the last `max_lines` lines of a transcript, raw (ANSI preserved).
+pub fn read_tail(path: &Path, max_lines: usize) -> Result<Vec<u8>> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_READ_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    if start > 0 {
+        // Drop the partial first line
+        let cut = bytes.iter().position(|&b| b == b'\n').map_or(0, |i| i + 1);
+        bytes.drain(..cut);
+    }
+
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    let trailing = bytes.last() == Some(&b'\n');
+    let total_lines = newlines + usize::from(!trailing && !bytes.is_empty());
+    if total_lines > max_lines {
+        let skip = total_lines - max_lines;
+        let cut = bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| b == b'\n')
+            .nth(skip - 1)
+            .map_or(0, |(i, _)| i + 1);
+        bytes.drain(..cut);
+    }
+    Ok(bytes)
+}
+
+/// Remove ANSI escape sequences and carriage returns, leaving plain text.
+pub fn strip_ansi(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(&filter_escapes(bytes, false)).into_owned()
+}
+
+/// Like [`strip_ansi`] but keeps SGR (color/style) sequences, for rendering a
+/// transcript as styled lines without cursor movement or mode switches.
+pub fn keep_sgr_only(bytes: &[u8]) -> Vec<u8> {
+    filter_escapes(bytes, true)
+}
+
+fn filter_escapes(bytes: &[u8], keep_sgr: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            0x1b => {
+                let start = i;
+                i += 1;
+                match bytes.get(i) {
+                    // CSI: parameters up to a final byte in 0x40..=0x7e
+                    Some(b'[') => {
+                        i += 1;
+                        while i < bytes.len() && !(0x40..=0x7e).contains(&bytes[i]) {
+                            i += 1;
+                        }
+                        let is_sgr = bytes.get(i) == Some(&b'm')
+                            && bytes[start + 2..i]
+                                .iter()
+                                .all(|b| b.is_ascii_digit() || *b == b';');
+                        i += 1;
+                        if keep_sgr && is_sgr {
+                            out.extend_from_slice(&bytes[start..i]);
+                        }
+                    }
+                    // OSC: terminated by BEL or ESC \
+                    Some(b']') => {
+                        i += 1;
+                        while i < bytes.len() {
+                            if bytes[i] == 0x07 {
+                                i += 1;
+                                break;
+                            }
+                            if bytes[i] == 0x1b && bytes.get(i + 1) == Some(&b'\\') {
+                                i += 2;
+                                break;
+                            }
+                            i += 1;
+                        }
+                    }
+                    // Charset selection takes one more byte
+                    Some(b'(') | Some(b')') => i += 2,
+                    Some(_) => i += 1,
+                    None => {}
+                }
+            }
+            b'\r' => i += 1,
+            b => {
+                if b >= 0x20 || b == b'\n' || b == b'\t' {
+                    out.push(b);
+                }
+                i += 1;
+            }
+        }
+    }
+    out
+}
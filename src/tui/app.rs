@@ -15,17 +15,28 @@ use std::sync::{
 use std::time::Instant;
 
 use crate::agent::{self, AgentOperations};
-use crate::config::{GlobalConfig, MergedConfig, ProjectConfig, ThemeConfig, WorkflowPlugin};
-use crate::db::{Database, PhaseStatus, Task, TaskStatus, TransitionRequest};
+use crate::config::{
+    BoardView, GlobalConfig, MergedConfig, ProjectConfig, ThemeConfig, WorkflowPlugin,
+};
+use crate::db::{
+    Database, EventActor, PhaseStatus, Priority, SubtaskProgress, Task, TaskEventKind, TaskStatus,
+    TransitionRequest, Usage,
+};
 use crate::git::{
-    self, GitOperations, GitProviderOperations, PullRequestState, RealGitHubOps, RealGitOps,
+    self, collect_task_diff, GitOperations, GitProviderOperations, MergeStrategy, PullRequestState,
+    RealGitOps, RealGitProviderOps,
 };
+use crate::graph::GraphLine;
+use crate::mcp::policy::TaskPolicy;
 use crate::skills;
-use crate::tmux::{self, RealTmuxOps, TmuxOperations};
+use crate::stats::format_elapsed;
+use crate::tmux::{self, TmuxOperations};
 use crate::AppMode;
 
 use super::board::BoardState;
+use super::filter::BoardFilter;
 use super::input::InputMode;
+use super::keymap::{Action, KeyContext, Keymap};
 use super::shell_popup::{self, ShellPopup};
 
 /// Helper to convert hex color string to ratatui Color
@@ -35,46 +46,75 @@ fn hex_to_color(hex: &str) -> Color {
         .unwrap_or(Color::White)
 }
 
-/// Build footer help text based on current UI state
+/// Build footer help text based on current UI state and the user's keybindings
 fn build_footer_text(
     input_mode: InputMode,
     sidebar_focused: bool,
     selected_column: usize,
     has_cyclic_plugin: bool,
     fullscreen_on_enter: bool,
+    keys: &Keymap,
 ) -> String {
     match input_mode {
         InputMode::Normal => {
-            if sidebar_focused {
-                " [j/k] navigate  [Enter] open  [l] board  [e] hide sidebar  [q] quit ".to_string()
+            let hints = if sidebar_focused {
+                vec![
+                    nav_hint(keys, "navigate"),
+                    keys.hint(Action::Open, "open"),
+                    keys.hint(Action::NavRight, "board"),
+                    keys.hint(Action::ToggleSidebar, "hide sidebar"),
+                    keys.hint(Action::Help, "help"),
+                    keys.hint(Action::Quit, "quit"),
+                ]
             } else {
+                let mut hints = vec![
+                    keys.hint(Action::NewTask, "new"),
+                    keys.hint(Action::Search, "search"),
+                    keys.hint(Action::Filter, "filter"),
+                    keys.hint(Action::Open, "open"),
+                ];
+                // Tasks past Backlog have a session that can be attached fullscreen
+                if (1..=3).contains(&selected_column) && !fullscreen_on_enter {
+                    hints.push(keys.hint(Action::Fullscreen, "fullscreen"));
+                }
+                hints.push(keys.hint(Action::DeleteTask, "del"));
+                if selected_column <= 3 {
+                    hints.push(keys.hint(Action::OpenDiff, "diff"));
+                }
                 match selected_column {
-                    0 => " [o] new  [/] search  [Enter] open  [x] del  [d] diff  [R] research  [m] plan  [M] run  [e] sidebar  [q] quit".to_string(),
-                    1 => if fullscreen_on_enter {
-                        " [o] new  [/] search  [Enter] open  [x] del  [d] diff  [m] run  [e] sidebar  [q] quit".to_string()
-                    } else {
-                        " [o] new  [/] search  [Enter] open  [C-f] fullscreen  [x] del  [d] diff  [m] run  [e] sidebar  [q] quit".to_string()
-                    },
-                    2 => if fullscreen_on_enter {
-                        " [o] new  [/] search  [Enter] open  [x] del  [d] diff  [m] move  [r] move left  [e] sidebar  [q] quit".to_string()
-                    } else {
-                        " [o] new  [/] search  [Enter] open  [C-f] fullscreen  [x] del  [d] diff  [m] move  [r] move left  [e] sidebar  [q] quit".to_string()
-                    },
-                    3 if has_cyclic_plugin => if fullscreen_on_enter {
-                        " [o] new  [/] search  [Enter] open  [x] del  [d] diff  [m] done  [r] resume  [p] next phase  [e] sidebar  [q] quit".to_string()
-                    } else {
-                        " [o] new  [/] search  [Enter] open  [C-f] fullscreen  [x] del  [d] diff  [m] done  [r] resume  [p] next phase  [e] sidebar  [q] quit".to_string()
-                    },
-                    3 => if fullscreen_on_enter {
-                        " [o] new  [/] search  [Enter] open  [x] del  [d] diff  [m] move  [r] move left  [e] sidebar  [q] quit".to_string()
-                    } else {
-                        " [o] new  [/] search  [Enter] open  [C-f] fullscreen  [x] del  [d] diff  [m] move  [r] move left  [e] sidebar  [q] quit".to_string()
-                    },
-                    _ => " [o] new  [/] search  [Enter] open  [x] del  [e] sidebar  [q] quit".to_string(),
+                    0 => hints.extend([
+                        keys.hint(Action::Research, "research"),
+                        keys.hint(Action::MoveForward, "plan"),
+                        keys.hint(Action::RunTask, "run"),
+                    ]),
+                    1 => hints.push(keys.hint(Action::MoveForward, "run")),
+                    3 if has_cyclic_plugin => hints.extend([
+                        keys.hint(Action::MoveForward, "done"),
+                        keys.hint(Action::MoveBack, "resume"),
+                        keys.hint(Action::NextPhase, "next phase"),
+                    ]),
+                    2 | 3 => hints.extend([
+                        keys.hint(Action::MoveForward, "move"),
+                        keys.hint(Action::MoveBack, "move left"),
+                    ]),
+                    _ => {}
                 }
-            }
+                hints.extend([
+                    keys.hint(Action::ToggleSidebar, "sidebar"),
+                    keys.hint(Action::Help, "help"),
+                    keys.hint(Action::Quit, "quit"),
+                ]);
+                hints
+            };
+            format!(
+                " {} ",
+                hints.into_iter().flatten().collect::<Vec<_>>().join("  ")
+            )
+        }
+        InputMode::InputTitle => {
+            " Enter task title... [#label] [!high] [due:YYYY-MM-DD]  [Esc] cancel [Enter] next "
+                .to_string()
         }
-        InputMode::InputTitle => " Enter task title... [Esc] cancel [Enter] next ".to_string(),
         InputMode::SelectPlugin => {
             " [j/k] select plugin  [Tab] cycle  [Enter] next  [Esc] cancel ".to_string()
         }
@@ -85,11 +125,75 @@ fn build_footer_text(
     }
 }
 
+/// "[j/k] navigate" from the nav_down/nav_up bindings
+fn nav_hint(keys: &Keymap, text: &str) -> Option<String> {
+    let labels: Vec<String> = [Action::NavDown, Action::NavUp]
+        .into_iter()
+        .map(|a| keys.label(a))
+        .filter(|l| !l.is_empty())
+        .collect();
+    if labels.is_empty() {
+        None
+    } else {
+        Some(format!("[{}] {}", labels.join("/"), text))
+    }
+}
+
+/// Footer key labels for the shell popup
+fn shell_popup_keys(keys: &Keymap) -> shell_popup::ShellPopupKeys {
+    let pair = |a: Action, b: Action| {
+        let (a, b) = (keys.label(a), keys.label(b));
+        match (a.strip_prefix("C-"), b.strip_prefix("C-")) {
+            (Some(a), Some(b)) if a.chars().count() == 1 && b.chars().count() == 1 => {
+                format!("C-{}/{}", a, b)
+            }
+            _ => format!("{}/{}", a, b),
+        }
+    };
+    shell_popup::ShellPopupKeys {
+        scroll: pair(Action::ShellScrollDown, Action::ShellScrollUp),
+        page: pair(Action::ShellPageDown, Action::ShellPageUp),
+        bottom: keys.label(Action::ShellBottom),
+        fullscreen: keys.label(Action::Fullscreen),
+        close: keys.label(Action::ShellClose),
+    }
+}
+
+/// One line per bound action, grouped by where it applies
+fn build_help_lines(keys: &Keymap) -> Vec<(String, String)> {
+    let mut lines = Vec::new();
+    for context in KeyContext::ALL {
+        if !lines.is_empty() {
+            lines.push((String::new(), String::new()));
+        }
+        lines.push((context.title().to_string(), String::new()));
+        for action in Action::ALL
+            .iter()
+            .filter(|a| a.contexts().contains(&context))
+        {
+            let chords: Vec<String> = keys.chords(*action).iter().map(|c| c.to_string()).collect();
+            let chords = if chords.is_empty() {
+                "(unbound)".to_string()
+            } else {
+                chords.join(" ")
+            };
+            lines.push((
+                format!("  {:<14}", chords),
+                format!("{}  ({})", action.description(), action.name()),
+            ));
+        }
+    }
+    lines
+}
+
 type Terminal = ratatui::Terminal<AppBackend>;
 
-/// Backend abstraction: real CrosstermBackend in production, TestBackend in tests.
+/// Backend abstraction: real CrosstermBackend in production, TestBackend in tests
+/// and in headless daemon mode.
 enum AppBackend {
     Crossterm(CrosstermBackend<Stdout>),
+    /// Off-screen buffer for `agtx daemon`; never drawn to.
+    Headless(ratatui::backend::TestBackend),
     #[cfg(feature = "test-mocks")]
     Test(ratatui::backend::TestBackend),
 }
@@ -103,6 +207,7 @@ impl ratatui::backend::Backend for AppBackend {
     {
         match self {
             Self::Crossterm(b) => b.draw(content),
+            Self::Headless(b) => b.draw(content).map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .draw(content)
@@ -113,6 +218,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn hide_cursor(&mut self) -> io::Result<()> {
         match self {
             Self::Crossterm(b) => b.hide_cursor(),
+            Self::Headless(b) => b.hide_cursor().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .hide_cursor()
@@ -123,6 +229,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn show_cursor(&mut self) -> io::Result<()> {
         match self {
             Self::Crossterm(b) => b.show_cursor(),
+            Self::Headless(b) => b.show_cursor().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .show_cursor()
@@ -133,6 +240,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn get_cursor_position(&mut self) -> io::Result<ratatui::layout::Position> {
         match self {
             Self::Crossterm(b) => b.get_cursor_position(),
+            Self::Headless(b) => b.get_cursor_position().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .get_cursor_position()
@@ -147,6 +255,7 @@ impl ratatui::backend::Backend for AppBackend {
         match self {
             Self::Crossterm(b) => b.set_cursor_position(position),
             // TestBackend's set_cursor_position is also generic, so just forward
+            Self::Headless(b) => b.set_cursor_position(position).map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .set_cursor_position(position)
@@ -157,6 +266,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn clear(&mut self) -> io::Result<()> {
         match self {
             Self::Crossterm(b) => b.clear(),
+            Self::Headless(b) => b.clear().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .clear()
@@ -167,6 +277,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn clear_region(&mut self, clear_type: ratatui::backend::ClearType) -> io::Result<()> {
         match self {
             Self::Crossterm(b) => b.clear_region(clear_type),
+            Self::Headless(b) => b.clear_region(clear_type).map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .clear_region(clear_type)
@@ -177,6 +288,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn size(&self) -> io::Result<ratatui::layout::Size> {
         match self {
             Self::Crossterm(b) => b.size(),
+            Self::Headless(b) => b.size().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .size()
@@ -187,6 +299,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn window_size(&mut self) -> io::Result<ratatui::backend::WindowSize> {
         match self {
             Self::Crossterm(b) => b.window_size(),
+            Self::Headless(b) => b.window_size().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .window_size()
@@ -197,6 +310,7 @@ impl ratatui::backend::Backend for AppBackend {
     fn flush(&mut self) -> io::Result<()> {
         match self {
             Self::Crossterm(b) => b.flush(),
+            Self::Headless(b) => b.flush().map_err(io::Error::other),
             #[cfg(feature = "test-mocks")]
             Self::Test(b) => b
                 .flush()
@@ -225,6 +339,8 @@ struct AppState {
     wizard_selected_plugin: usize,
     wizard_plugin_options: Vec<PluginOption>,
     wizard_referenced_task_ids: HashSet<String>,
+    // Labels, priority and due date typed as trailing title tags
+    wizard_tags: TitleTags,
     db: Option<Database>,
     #[allow(dead_code)]
     global_db: Database,
@@ -233,6 +349,8 @@ struct AppState {
     project_name: String,
     tmux_project_name: String,
     available_agents: Vec<agent::Agent>,
+    // Agents from [[custom_agents]], for skill deployment and agent detection
+    custom_agents: Vec<agent::Agent>,
     // Tmux operations (injectable for testing)
     tmux_ops: Arc<dyn TmuxOperations>,
     // Git operations (injectable for testing)
@@ -266,12 +384,28 @@ struct AppState {
     review_to_running_task_id: Option<String>,
     // Git diff popup
     diff_popup: Option<DiffPopup>,
+    // Key binding help overlay (scroll offset)
+    help_popup: Option<usize>,
+    // Resolved keybindings (defaults + [keybindings] from the global config)
+    keymap: Keymap,
+    timeline_popup: Option<TimelinePopup>,
+    stats_popup: Option<StatsPopup>,
+    graph_popup: Option<GraphPopup>,
+    // Board filter and the saved view it came from
+    board_filter: BoardFilter,
+    active_view: Option<usize>,
+    filter_bar: Option<FilterBar>,
+    transcript_popup: Option<TranscriptPopup>,
     // Channel for receiving PR description generation results
     pr_generation_rx: Option<mpsc::Receiver<(String, String)>>,
     // PR creation status popup
     pr_status_popup: Option<PrStatusPopup>,
     // Channel for receiving PR creation results
     pr_creation_rx: Option<mpsc::Receiver<Result<(i32, String), String>>>,
+    // Local merge progress popup (finish_strategy = merge/squash/rebase)
+    local_merge_popup: Option<LocalMergePopup>,
+    // Channel for receiving background local merge results
+    local_merge_rx: Option<mpsc::Receiver<LocalMergeResult>>,
     // Confirmation popup for moving to Done with open PR
     done_confirm_popup: Option<DoneConfirmPopup>,
     // Confirmation popup for moving task when phase is incomplete
@@ -291,6 +425,18 @@ struct AppState {
     spinner_frame: usize,
     // Idle detection: (content_hash, last_change_time) per task
     pane_content_hashes: HashMap<String, (u64, Instant)>,
+    // Transcript phase each task's pane is currently piped to
+    transcript_pipes: HashMap<String, &'static str>,
+    // Token usage totals per task, shown on cards
+    task_usage: HashMap<String, Usage>,
+    // When each task's agent session logs were last read for usage
+    usage_collected_at: HashMap<String, Instant>,
+    // Guard: task IDs already escalated for going over budget
+    budget_escalated: HashSet<String>,
+    // Guard: the project budget notification has been sent
+    project_over_budget: bool,
+    // Whether the two guards above were seeded from earlier budget escalations in the DB
+    budget_escalations_loaded: bool,
     // Guard: task IDs for which merge-conflict check has already been performed
     merge_conflict_checked: HashSet<String>,
     // Guard: task IDs for which stuck-task notification has been fired (reset on phase advance)
@@ -311,6 +457,8 @@ struct AppState {
     orchestrator_last_content: String,
     orchestrator_stable_since: Option<Instant>,
     orchestrator_last_check: Instant,
+    // Last look for an orchestrator window started after we did (daemon only)
+    orchestrator_last_detect: Instant,
     // Background session refresh channel (non-blocking phase status polling)
     session_refresh_rx: Option<mpsc::Receiver<SessionRefreshResult>>,
     // Cache of dependency satisfaction per task ID (refreshed with tasks)
@@ -343,6 +491,30 @@ struct MoveConfirmPopup {
     to_status: TaskStatus,
 }
 
+/// State for the local merge popup shown while finishing a task without a PR
+#[derive(Debug, Clone)]
+struct LocalMergePopup {
+    task_title: String,
+    status: LocalMergeStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LocalMergeStatus {
+    Merging,
+    Success(String),
+    Error(String),
+}
+
+/// Result from a background local merge
+struct LocalMergeResult {
+    task_id: String,
+    task_title: String,
+    /// Who moved the task to Done; the status change is attributed to them
+    actor: EventActor,
+    /// Summary of the merge, or the error that left the task in Review
+    outcome: Result<String, String>,
+}
+
 /// Result from background worktree setup (research, planning, move-to-running)
 struct SetupResult {
     task_id: String,
@@ -353,6 +525,8 @@ struct SetupResult {
     agent: String,
     plugin: Option<String>,
     error: Option<String>,
+    /// Who started the setup; the resulting status change is attributed to them
+    actor: EventActor,
 }
 
 /// Pre-fetched info about a referenced task for worktree setup (avoids DB access in thread).
@@ -379,6 +553,10 @@ struct SessionTaskStatus {
     agent: String,
     /// Whether this task was already Ready before this refresh cycle.
     was_ready: bool,
+    /// Transcript phase the pane was just piped to, if it was (re)attached.
+    transcript_phase: Option<&'static str>,
+    /// Model calls read from the agents' session logs, when they were read.
+    usage: Option<Vec<crate::usage::UsageRecord>>,
 }
 
 /// Results sent back from the background session refresh thread.
@@ -410,6 +588,57 @@ struct DiffPopup {
     scroll_offset: usize,
 }
 
+/// State for task history (timeline) popup
+#[derive(Debug, Clone)]
+struct TimelinePopup {
+    task_title: String,
+    lines: Vec<String>,
+    scroll_offset: usize,
+}
+
+/// State for the project metrics popup
+#[derive(Debug, Clone)]
+struct StatsPopup {
+    lines: Vec<String>,
+    scroll_offset: usize,
+}
+
+/// State for the task dependency graph popup
+#[derive(Debug, Clone)]
+struct GraphPopup {
+    lines: Vec<GraphLine>,
+    scroll_offset: usize,
+}
+
+/// State for the board filter bar
+#[derive(Debug, Clone)]
+struct FilterBar {
+    query: String,
+    /// Filter in effect when the bar was opened, restored on Esc
+    previous: BoardFilter,
+    previous_view: Option<usize>,
+    /// Name being typed after Ctrl+s, to save the query as a view
+    view_name: Option<String>,
+}
+
+/// State for the agent transcript browser popup
+#[derive(Debug, Clone)]
+struct TranscriptPopup {
+    task_title: String,
+    files: Vec<crate::transcript::TranscriptFile>,
+    selected: usize,
+    lines: Vec<Line<'static>>,
+    /// Lines scrolled up from the end (transcripts open at the latest output)
+    scroll_from_bottom: usize,
+}
+
+/// How many trailing lines of a transcript the popup loads
+const TRANSCRIPT_POPUP_LINES: usize = 5000;
+
+/// Escalation notes for going over budget; also how earlier escalations are recognized
+const TASK_BUDGET_NOTE: &str = "Task budget exceeded";
+const PROJECT_BUDGET_NOTE: &str = "Project budget exceeded";
+
 /// State for task search popup
 #[derive(Debug, Clone)]
 struct TaskSearchState {
@@ -433,6 +662,8 @@ struct PrConfirmPopup {
 struct ProjectInfo {
     name: String,
     path: String,
+    /// Token usage across the project's tasks (dashboard only)
+    usage: Option<Usage>,
 }
 
 /// State for file search dropdown
@@ -513,13 +744,18 @@ pub struct App {
 
 impl App {
     pub fn new(mode: AppMode, flags: crate::FeatureFlags) -> Result<Self> {
+        let custom_agents = load_custom_agents(&mode);
+        let agent_registry =
+            agent::RealAgentRegistry::with_custom_agents("claude", &custom_agents)?;
+        let custom_agents = agent_registry.custom_agents().to_vec();
         Self::with_ops(
             mode,
             flags,
-            Arc::new(RealTmuxOps),
+            tmux::backend_ops(GlobalConfig::load().unwrap_or_default().session_backend),
             Arc::new(RealGitOps),
-            Arc::new(RealGitHubOps),
-            Arc::new(agent::RealAgentRegistry::new("claude")),
+            Arc::new(RealGitProviderOps),
+            Arc::new(agent_registry),
+            custom_agents,
         )
     }
 
@@ -530,6 +766,7 @@ impl App {
         git_ops: Arc<dyn GitOperations>,
         git_provider_ops: Arc<dyn GitProviderOperations>,
         agent_registry: Arc<dyn agent::AgentRegistry>,
+        custom_agents: Vec<agent::Agent>,
     ) -> Result<Self> {
         // Setup terminal
         enable_raw_mode()?;
@@ -538,12 +775,58 @@ impl App {
         let backend = CrosstermBackend::new(stdout);
         let terminal = ratatui::Terminal::new(AppBackend::Crossterm(backend))?;
 
+        Self::with_terminal(
+            terminal,
+            mode,
+            flags,
+            tmux_ops,
+            git_ops,
+            git_provider_ops,
+            agent_registry,
+            custom_agents,
+        )
+    }
+
+    /// Create an App with no terminal attached, for `agtx daemon`.
+    /// Only a project board can run headless: transition requests live in the project database.
+    pub fn new_headless(project_path: PathBuf, flags: crate::FeatureFlags) -> Result<Self> {
+        let mode = AppMode::Project(project_path);
+        let custom_agents = load_custom_agents(&mode);
+        let agent_registry =
+            agent::RealAgentRegistry::with_custom_agents("claude", &custom_agents)?;
+        let custom_agents = agent_registry.custom_agents().to_vec();
+        let terminal = ratatui::Terminal::new(AppBackend::Headless(
+            ratatui::backend::TestBackend::new(80, 24),
+        ))?;
+        Self::with_terminal(
+            terminal,
+            mode,
+            flags,
+            tmux::backend_ops(GlobalConfig::load().unwrap_or_default().session_backend),
+            Arc::new(RealGitOps),
+            Arc::new(RealGitProviderOps),
+            Arc::new(agent_registry),
+            custom_agents,
+        )
+    }
+
+    fn with_terminal(
+        terminal: Terminal,
+        mode: AppMode,
+        flags: crate::FeatureFlags,
+        tmux_ops: Arc<dyn TmuxOperations>,
+        git_ops: Arc<dyn GitOperations>,
+        git_provider_ops: Arc<dyn GitProviderOperations>,
+        agent_registry: Arc<dyn agent::AgentRegistry>,
+        custom_agents: Vec<agent::Agent>,
+    ) -> Result<Self> {
         // Load configs
         let global_config = GlobalConfig::load().unwrap_or_default();
         let global_db = Database::open_global()?;
 
         // Detect available agents
-        let available_agents = agent::detect_available_agents();
+        let mut available_agents = agent::detect_available_agents();
+        available_agents.extend(custom_agents.iter().filter(|a| a.is_available()).cloned());
 
         // Setup based on mode
         let (db, project_path, project_name, tmux_project_name, project_config, trust_warning) = match &mode {
@@ -569,15 +852,19 @@ impl App {
                 // Trust-on-first-use: suppress dangerous config fields from untrusted projects
                 let trust_store = crate::config::TrustStore::load().unwrap_or_default();
                 let trust_warning = if !trust_store.is_trusted(&canonical) {
-                    if project_config.init_script.is_some() || project_config.copy_files.is_some() || project_config.cleanup_script.is_some() {
+                    if project_config.init_script.is_some() || project_config.copy_files.is_some() || project_config.cleanup_script.is_some() || !project_config.custom_agents.is_empty() || project_config.gitlab_url.is_some() || project_config.gitlab_allow_http.is_some() || project_config.sandbox.is_some() {
                         tracing::warn!(
                             project = %canonical.display(),
-                            "Untrusted project config — init_script, cleanup_script, and copy_files suppressed"
+                            "Untrusted project config — init_script, cleanup_script, copy_files, custom_agents, gitlab_url, gitlab_allow_http, and sandbox suppressed"
                         );
                         project_config.init_script = None;
                         project_config.cleanup_script = None;
                         project_config.copy_files = None;
-                        Some("Untrusted project config: init_script, cleanup_script, and copy_files disabled. Run `agtx trust` to enable.".to_string())
+                        project_config.custom_agents.clear();
+                        project_config.gitlab_url = None;
+                        project_config.gitlab_allow_http = None;
+                        project_config.sandbox = None;
+                        Some("Untrusted project config: init_script, cleanup_script, copy_files, custom_agents, gitlab_url, gitlab_allow_http, and sandbox disabled. Run `agtx trust` to enable.".to_string())
                     } else {
                         None
                     }
@@ -597,6 +884,21 @@ impl App {
         };
 
         let config = MergedConfig::merge(&global_config, &project_config);
+        let tmux_ops = match &project_path {
+            Some(path) => tmux::sandboxed(tmux_ops, &config.sandbox, path),
+            None => tmux_ops,
+        };
+
+        let (keymap, keymap_problems) = Keymap::from_config(&global_config.keybindings);
+        for problem in &keymap_problems {
+            tracing::warn!(problem = %problem, "Ignoring [keybindings] entry");
+        }
+        let keymap_warning = keymap_problems.first().map(|problem| {
+            (
+                format!("[keybindings] {} (see log for details)", problem),
+                Instant::now(),
+            )
+        });
 
         // If the project is untrusted, also suppress plugin init_scripts
         // by forcing no_init_scripts in the flags
@@ -620,6 +922,7 @@ impl App {
                 wizard_selected_plugin: 0,
                 wizard_plugin_options: vec![],
                 wizard_referenced_task_ids: HashSet::new(),
+                wizard_tags: TitleTags::default(),
                 db,
                 global_db,
                 config,
@@ -627,6 +930,7 @@ impl App {
                 project_name: project_name.clone(),
                 tmux_project_name: tmux_project_name.clone(),
                 available_agents,
+                custom_agents,
                 tmux_ops,
                 git_ops,
                 git_provider_ops,
@@ -645,9 +949,20 @@ impl App {
                 pr_confirm_popup: None,
                 review_to_running_task_id: None,
                 diff_popup: None,
+                help_popup: None,
+                keymap,
+                timeline_popup: None,
+                stats_popup: None,
+                graph_popup: None,
+                board_filter: BoardFilter::default(),
+                active_view: None,
+                filter_bar: None,
+                transcript_popup: None,
                 pr_generation_rx: None,
                 pr_status_popup: None,
                 pr_creation_rx: None,
+                local_merge_popup: None,
+                local_merge_rx: None,
                 setup_rx: None,
                 done_confirm_popup: None,
                 move_confirm_popup: None,
@@ -658,17 +973,24 @@ impl App {
                 phase_status_cache: HashMap::new(),
                 spinner_frame: 0,
                 pane_content_hashes: HashMap::new(),
+                transcript_pipes: HashMap::new(),
+                task_usage: HashMap::new(),
+                usage_collected_at: HashMap::new(),
+                budget_escalated: HashSet::new(),
+                project_over_budget: false,
+                budget_escalations_loaded: false,
                 merge_conflict_checked: HashSet::new(),
                 stuck_task_notified: HashSet::new(),
                 stuck_task_idle_since: HashMap::new(),
                 cached_plugin: None,
-                warning_message: None,
+                warning_message: keymap_warning,
                 plugin_select_popup: None,
                 orchestrator_session: None,
                 orchestrator_ready: Arc::new(AtomicBool::new(false)),
                 orchestrator_last_content: String::new(),
                 orchestrator_stable_since: None,
                 orchestrator_last_check: Instant::now(),
+                orchestrator_last_detect: Instant::now(),
                 session_refresh_rx: None,
                 deps_satisfied_cache: HashMap::new(),
                 instance_id: uuid::Uuid::new_v4().to_string(),
@@ -722,23 +1044,7 @@ impl App {
             }
         }
 
-        if let Some(orch_target) = detect_existing_orchestrator(
-            app.state.flags.experimental,
-            app.state.tmux_ops.as_ref(),
-            &app.state.tmux_project_name,
-            app.state.db.as_ref(),
-            &app.state.board.tasks,
-            app.state.project_path.as_deref(),
-        ) {
-            app.state.orchestrator_session = Some(orch_target.clone());
-            let tmux_ops = Arc::clone(&app.state.tmux_ops);
-            let ready_flag = Arc::clone(&app.state.orchestrator_ready);
-            std::thread::spawn(move || {
-                if wait_for_agent_ready(&tmux_ops, &orch_target).is_some() {
-                    ready_flag.store(true, Ordering::Release);
-                }
-            });
-        }
+        app.attach_existing_orchestrator();
 
         // Display trust confirmation popup if project config was suppressed
         if trust_warning.is_some() {
@@ -789,6 +1095,7 @@ impl App {
         };
 
         let config = MergedConfig::merge(&GlobalConfig::default(), &ProjectConfig::default());
+        let keymap = Keymap::default();
 
         Ok(Self {
             terminal,
@@ -805,6 +1112,7 @@ impl App {
                 wizard_selected_plugin: 0,
                 wizard_plugin_options: vec![],
                 wizard_referenced_task_ids: HashSet::new(),
+                wizard_tags: TitleTags::default(),
                 db,
                 global_db,
                 config,
@@ -812,6 +1120,7 @@ impl App {
                 project_name,
                 tmux_project_name,
                 available_agents: vec![],
+                custom_agents: vec![],
                 tmux_ops,
                 git_ops,
                 git_provider_ops,
@@ -830,9 +1139,20 @@ impl App {
                 pr_confirm_popup: None,
                 review_to_running_task_id: None,
                 diff_popup: None,
+                help_popup: None,
+                keymap,
+                timeline_popup: None,
+                stats_popup: None,
+                graph_popup: None,
+                board_filter: BoardFilter::default(),
+                active_view: None,
+                filter_bar: None,
+                transcript_popup: None,
                 pr_generation_rx: None,
                 pr_status_popup: None,
                 pr_creation_rx: None,
+                local_merge_popup: None,
+                local_merge_rx: None,
                 setup_rx: None,
                 done_confirm_popup: None,
                 move_confirm_popup: None,
@@ -843,6 +1163,12 @@ impl App {
                 phase_status_cache: HashMap::new(),
                 spinner_frame: 0,
                 pane_content_hashes: HashMap::new(),
+                transcript_pipes: HashMap::new(),
+                task_usage: HashMap::new(),
+                usage_collected_at: HashMap::new(),
+                budget_escalated: HashSet::new(),
+                project_over_budget: false,
+                budget_escalations_loaded: false,
                 merge_conflict_checked: HashSet::new(),
                 stuck_task_notified: HashSet::new(),
                 stuck_task_idle_since: HashMap::new(),
@@ -854,6 +1180,7 @@ impl App {
                 orchestrator_last_content: String::new(),
                 orchestrator_stable_since: None,
                 orchestrator_last_check: Instant::now(),
+                orchestrator_last_detect: Instant::now(),
                 session_refresh_rx: None,
                 deps_satisfied_cache: HashMap::new(),
                 instance_id: uuid::Uuid::new_v4().to_string(),
@@ -878,55 +1205,13 @@ impl App {
             }
 
             // Check for PR creation completion
-            if let Some(ref rx) = self.state.pr_creation_rx {
-                if let Ok(result) = rx.try_recv() {
-                    match result {
-                        Ok((_, pr_url)) => {
-                            self.state.pr_status_popup = Some(PrStatusPopup {
-                                status: PrCreationStatus::Success,
-                                pr_url: Some(pr_url),
-                                error_message: None,
-                            });
-                        }
-                        Err(err) => {
-                            self.state.pr_status_popup = Some(PrStatusPopup {
-                                status: PrCreationStatus::Error,
-                                pr_url: None,
-                                error_message: Some(err),
-                            });
-                        }
-                    }
-                    self.state.pr_creation_rx = None;
-                    self.refresh_tasks()?;
-                }
-            }
+            self.poll_pr_creation()?;
+
+            // Check for local merge completion
+            self.poll_local_merge()?;
 
             // Check for worktree setup completion
-            if let Some(ref rx) = self.state.setup_rx {
-                if let Ok(result) = rx.try_recv() {
-                    self.state.setup_rx = None;
-                    if let Some(err) = result.error {
-                        self.state.warning_message = Some((err, Instant::now()));
-                    } else {
-                        // Update task with worktree info from background setup
-                        if let Some(db) = &self.state.db {
-                            if let Ok(Some(mut task)) = db.get_task(&result.task_id) {
-                                task.session_name = Some(result.session_name);
-                                task.worktree_path = Some(result.worktree_path);
-                                task.branch_name = Some(result.branch_name);
-                                task.agent = result.agent;
-                                task.plugin = result.plugin;
-                                if let Some(status) = result.new_status {
-                                    task.status = status;
-                                }
-                                task.updated_at = chrono::Utc::now();
-                                let _ = db.update_task(&task);
-                            }
-                        }
-                        self.refresh_tasks()?;
-                    }
-                }
-            }
+            self.poll_setup_result()?;
 
             // Process MCP transition requests from the command queue
             self.process_transition_requests()?;
@@ -953,19 +1238,7 @@ impl App {
             }
 
             // Apply results from background session refresh (non-blocking)
-            if let Some(ref rx) = self.state.session_refresh_rx {
-                match rx.try_recv() {
-                    Ok(result) => {
-                        self.state.session_refresh_rx = None;
-                        self.apply_session_refresh(result);
-                    }
-                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                        // Thread panicked or dropped sender — clear to allow future spawns
-                        self.state.session_refresh_rx = None;
-                    }
-                    Err(std::sync::mpsc::TryRecvError::Empty) => {}
-                }
-            }
+            self.poll_session_refresh();
             // Spawn background refresh if not already running and cache expired
             self.maybe_spawn_session_refresh();
 
@@ -983,6 +1256,214 @@ impl App {
         Ok(())
     }
 
+    /// Run the transition engine without a terminal until interrupted (`agtx daemon`).
+    /// Executes queued transition requests, refreshes session status, and delivers
+    /// orchestrator notifications — the same work the TUI loop does between frames.
+    pub async fn run_headless(&mut self) -> Result<()> {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_millis(500));
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
+
+        tracing::info!(
+            project = %self.state.project_name,
+            instance_id = %self.state.instance_id,
+            "Daemon started"
+        );
+        if self.state.trust_confirm_popup.take().is_some() {
+            eprintln!(
+                "agtx daemon: untrusted project config: init_script, cleanup_script, copy_files, and custom_agents disabled. Run `agtx trust` to enable."
+            );
+        }
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = ticker.tick() => {}
+            }
+            self.headless_tick()?;
+        }
+
+        tracing::info!(project = %self.state.project_name, "Daemon stopped");
+        Ok(())
+    }
+
+    /// One iteration of the daemon loop.
+    fn headless_tick(&mut self) -> Result<()> {
+        self.poll_setup_result()?;
+        self.poll_local_merge()?;
+        self.poll_pr_creation()?;
+        // The status popup has no one to show it to; log the outcome instead
+        if let Some(popup) = self.state.pr_status_popup.take() {
+            match popup.status {
+                PrCreationStatus::Success => {
+                    let pr_url = popup.pr_url.unwrap_or_default();
+                    tracing::info!(pr_url = %pr_url, "Pull request ready");
+                    eprintln!("agtx daemon: pull request ready: {}", pr_url);
+                }
+                PrCreationStatus::Error => {
+                    let err = popup.error_message.unwrap_or_default();
+                    tracing::error!(error = %err, "Pull request creation failed");
+                    eprintln!("agtx daemon: pull request failed: {}", err);
+                }
+                _ => {}
+            }
+        }
+
+        // A failed request is recorded on the request itself; keep serving the queue
+        if let Err(e) = self.process_transition_requests() {
+            tracing::error!(error = %e, "Failed to process transition requests");
+        }
+
+        // Tasks may be changed by TUIs, the CLI or the MCP server at any time
+        self.refresh_tasks()?;
+
+        self.poll_session_refresh();
+        self.maybe_spawn_session_refresh();
+        // The orchestrator is usually started from a TUI after the daemon is up
+        if self.state.orchestrator_session.is_none()
+            && self.state.orchestrator_last_detect.elapsed()
+                >= std::time::Duration::from_secs(ORCHESTRATOR_DETECT_INTERVAL_SECS)
+        {
+            self.state.orchestrator_last_detect = Instant::now();
+            self.attach_existing_orchestrator();
+        }
+        self.deliver_orchestrator_notifications();
+
+        // No footer to show warnings in — surface them on stderr and in the audit log
+        if let Some((message, _)) = self.state.warning_message.take() {
+            tracing::warn!(message = %message, "Daemon warning");
+            eprintln!("agtx daemon: {}", message);
+        }
+        Ok(())
+    }
+
+    /// Show the result of a background PR creation or push, if one has finished.
+    fn poll_pr_creation(&mut self) -> Result<()> {
+        let Some(ref rx) = self.state.pr_creation_rx else {
+            return Ok(());
+        };
+        let Ok(result) = rx.try_recv() else {
+            return Ok(());
+        };
+        self.state.pr_status_popup = Some(match result {
+            Ok((_, pr_url)) => PrStatusPopup {
+                status: PrCreationStatus::Success,
+                pr_url: Some(pr_url),
+                error_message: None,
+            },
+            Err(err) => PrStatusPopup {
+                status: PrCreationStatus::Error,
+                pr_url: None,
+                error_message: Some(err),
+            },
+        });
+        self.state.pr_creation_rx = None;
+        self.refresh_tasks()
+    }
+
+    /// Apply the result of a background worktree setup, if one has finished.
+    fn poll_setup_result(&mut self) -> Result<()> {
+        let Some(ref rx) = self.state.setup_rx else {
+            return Ok(());
+        };
+        let Ok(result) = rx.try_recv() else {
+            return Ok(());
+        };
+        self.state.setup_rx = None;
+        if let Some(err) = result.error {
+            self.state.warning_message = Some((err, Instant::now()));
+        } else {
+            // Update task with worktree info from background setup
+            if let Some(db) = &self.state.db {
+                let previous_actor = db.set_actor(result.actor);
+                if let Ok(Some(mut task)) = db.get_task(&result.task_id) {
+                    task.session_name = Some(result.session_name);
+                    task.worktree_path = Some(result.worktree_path);
+                    task.branch_name = Some(result.branch_name);
+                    task.agent = result.agent;
+                    task.plugin = result.plugin;
+                    if let Some(status) = result.new_status {
+                        task.status = status;
+                    }
+                    task.updated_at = chrono::Utc::now();
+                    let _ = db.update_task(&task);
+                }
+                db.set_actor(previous_actor);
+            }
+            self.refresh_tasks()?;
+        }
+        Ok(())
+    }
+
+    /// Apply the result of a background local merge, if one has finished.
+    fn poll_local_merge(&mut self) -> Result<()> {
+        let Some(ref rx) = self.state.local_merge_rx else {
+            return Ok(());
+        };
+        let Ok(result) = rx.try_recv() else {
+            return Ok(());
+        };
+        self.state.local_merge_rx = None;
+
+        let status = match result.outcome {
+            Ok(summary) => {
+                if let Some(db) = &self.state.db {
+                    let previous_actor = db.set_actor(result.actor);
+                    if let Ok(Some(mut task)) = db.get_task(&result.task_id) {
+                        task.session_name = None;
+                        task.worktree_path = None;
+                        task.status = TaskStatus::Done;
+                        task.escalation_note = None;
+                        task.updated_at = chrono::Utc::now();
+                        let _ = db.update_task(&task);
+                    }
+                    db.set_actor(previous_actor);
+                }
+                self.state.stuck_task_notified.remove(&result.task_id);
+                self.state.stuck_task_idle_since.remove(&result.task_id);
+                self.state.phase_status_cache.remove(&result.task_id);
+                LocalMergeStatus::Success(summary)
+            }
+            Err(err) => {
+                tracing::warn!(task_id = %result.task_id, error = %err, "Local merge failed");
+                self.state.warning_message =
+                    Some((format!("Local merge failed: {}", err), Instant::now()));
+                LocalMergeStatus::Error(err)
+            }
+        };
+        self.state.local_merge_popup = Some(LocalMergePopup {
+            task_title: result.task_title,
+            status,
+        });
+        self.refresh_tasks()
+    }
+
+    /// Who task changes are currently attributed to (the TUI user unless an
+    /// MCP/CLI transition request is being executed).
+    fn current_actor(&self) -> EventActor {
+        self.state
+            .db
+            .as_ref()
+            .map_or(EventActor::User, |db| db.actor())
+    }
+
+    /// Apply results from the background session refresh thread, if it has finished.
+    fn poll_session_refresh(&mut self) {
+        if let Some(ref rx) = self.state.session_refresh_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.state.session_refresh_rx = None;
+                    self.apply_session_refresh(result);
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    // Thread panicked or dropped sender — clear to allow future spawns
+                    self.state.session_refresh_rx = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            }
+        }
+    }
+
     pub fn draw(&mut self) -> Result<()> {
         let state = &self.state;
         self.terminal.draw(|frame| {
@@ -992,6 +1473,11 @@ impl App {
                 AppMode::Dashboard => Self::draw_dashboard(state, frame, area),
                 AppMode::Project(_) => Self::draw_board(state, frame, area),
             }
+
+            // Key binding help overlays either view
+            if let Some(scroll) = state.help_popup {
+                Self::draw_help_popup(state, frame, area, scroll);
+            }
         })?;
 
         Ok(())
@@ -1038,10 +1524,20 @@ impl App {
 
         // Header
         let plugin_label = state.config.workflow_plugin.as_deref().unwrap_or("agtx");
-        let left = Span::styled(
+        let mut left_spans = vec![Span::styled(
             format!(" {} ", state.project_name),
             Style::default().fg(Color::Cyan).bold(),
-        );
+        )];
+        if !state.board_filter.is_empty() {
+            let filter_label = match state.active_view.and_then(|i| state.config.views.get(i)) {
+                Some(view) => format!("view: {} ", view.name),
+                None => format!("filter: {} ", state.board_filter.query()),
+            };
+            left_spans.push(Span::styled(
+                filter_label,
+                Style::default().fg(hex_to_color(&state.config.theme.color_accent)),
+            ));
+        }
         let mut right_spans: Vec<Span> = Vec::new();
         if state.flags.experimental {
             let orch_active = state.orchestrator_session.is_some();
@@ -1067,10 +1563,11 @@ impl App {
                 Style::default().fg(hex_to_color(&state.config.theme.color_dimmed)),
             ),
         ]);
-        let left_len = state.project_name.len() + 2;
+        let left_len: usize = left_spans.iter().map(|s| s.width()).sum();
         let right_len: usize = right_spans.iter().map(|s| s.content.len()).sum();
         let padding = (chunks[0].width as usize).saturating_sub(left_len + right_len + 2); // 2 for borders
-        let mut spans = vec![left, Span::raw(" ".repeat(padding))];
+        let mut spans = left_spans;
+        spans.push(Span::raw(" ".repeat(padding)));
         spans.extend(right_spans);
         let header =
             Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL));
@@ -1089,12 +1586,7 @@ impl App {
             .split(chunks[1]);
 
         for (i, status) in TaskStatus::columns().iter().enumerate() {
-            let tasks: Vec<&Task> = state
-                .board
-                .tasks
-                .iter()
-                .filter(|t| t.status == *status)
-                .collect();
+            let tasks = state.board.tasks_in_column(i);
 
             let is_selected_column = state.board.selected_column == i;
 
@@ -1183,6 +1675,8 @@ impl App {
                     state.phase_status_cache.get(&task.id),
                     state.spinner_frame,
                     deps_blocked,
+                    state.board.subtask_progress(&task.id),
+                    state.task_usage.get(&task.id),
                 );
             }
 
@@ -1228,7 +1722,19 @@ impl App {
             .and_then(|t| t.plugin.as_ref())
             .and_then(|name| WorkflowPlugin::load(name, state.project_path.as_deref()).ok())
             .map_or(false, |p| p.cyclic);
-        let (footer_text, footer_style) = if let Some((ref msg, created)) = state.warning_message {
+        let (footer_text, footer_style) = if let Some(bar) = &state.filter_bar {
+            let text = match &bar.view_name {
+                Some(name) => format!(" Save view as: {}█  [Enter] save  [Esc] back", name),
+                None => format!(
+                    " Filter: {}█  [Enter] apply  [Esc] cancel  [C-s] save as view",
+                    bar.query
+                ),
+            };
+            (
+                text,
+                Style::default().fg(hex_to_color(&state.config.theme.color_accent)),
+            )
+        } else if let Some((ref msg, created)) = state.warning_message {
             if created.elapsed() < std::time::Duration::from_secs(5) {
                 (msg.clone(), Style::default().fg(Color::Yellow))
             } else {
@@ -1239,6 +1745,7 @@ impl App {
                         state.board.selected_column,
                         has_cyclic_plugin,
                         state.config.fullscreen_on_enter,
+                        &state.keymap,
                     ),
                     Style::default().fg(hex_to_color(&state.config.theme.color_dimmed)),
                 )
@@ -1251,6 +1758,7 @@ impl App {
                     state.board.selected_column,
                     has_cyclic_plugin,
                     state.config.fullscreen_on_enter,
+                    &state.keymap,
                 ),
                 Style::default().fg(hex_to_color(&state.config.theme.color_dimmed)),
             )
@@ -1617,7 +2125,7 @@ impl App {
 
         // Shell popup overlay
         if let Some(popup) = &state.shell_popup {
-            Self::draw_shell_popup(popup, frame, area, &state.config.theme);
+            Self::draw_shell_popup(popup, frame, area, &state.config.theme, &state.keymap);
         }
 
         // Task search popup
@@ -1881,28 +2389,80 @@ impl App {
             }
         }
 
-        // Done confirmation popup
-        if let Some(ref popup) = state.done_confirm_popup {
-            let popup_area = centered_rect(50, 25, area);
+        // Local merge popup (merging/success/error)
+        if let Some(ref popup) = state.local_merge_popup {
+            let popup_area = centered_rect(50, 20, area);
             frame.render_widget(Clear, popup_area);
 
+            let (title, color, text) = match &popup.status {
+                LocalMergeStatus::Merging => {
+                    let spinner_chars = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+                    let spinner_idx = (std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis()
+                        / 100) as usize
+                        % spinner_chars.len();
+                    (
+                        " Merging Locally ",
+                        Color::Cyan,
+                        format!(
+                            "{} Writing commit message and merging\n\n\"{}\"",
+                            spinner_chars[spinner_idx], popup.task_title
+                        ),
+                    )
+                }
+                LocalMergeStatus::Success(summary) => (
+                    " Task Merged ",
+                    Color::Green,
+                    format!("{}\n\n[Enter] to close", summary),
+                ),
+                LocalMergeStatus::Error(err) => (
+                    " Local Merge Failed ",
+                    Color::Red,
+                    format!("{}\n\nThe task stays in Review.\n\n[Enter] to close", err),
+                ),
+            };
+
             let main_block = Block::default()
-                .title(" Move to Done? ")
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(
-                    Style::default().fg(hex_to_color(&state.config.theme.color_selected)),
-                );
+                .border_style(Style::default().fg(color));
             frame.render_widget(main_block, popup_area);
 
             let inner = popup_area.inner(ratatui::layout::Margin {
                 horizontal: 2,
                 vertical: 2,
             });
-            let text = match popup.pr_state {
-                DoneConfirmPrState::Open => format!(
-                    "PR #{} is still open.\n\nAre you sure you want to move this task to Done?\n\nWorktree will be deleted, tmux coding session killed.\nBranch kept locally.\n\n[y] Yes, move to Done    [n/Esc] Cancel",
-                    popup.pr_number
-                ),
+            let content = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .alignment(ratatui::layout::Alignment::Center)
+                .wrap(Wrap { trim: false });
+            frame.render_widget(content, inner);
+        }
+
+        // Done confirmation popup
+        if let Some(ref popup) = state.done_confirm_popup {
+            let popup_area = centered_rect(50, 25, area);
+            frame.render_widget(Clear, popup_area);
+
+            let main_block = Block::default()
+                .title(" Move to Done? ")
+                .borders(Borders::ALL)
+                .border_style(
+                    Style::default().fg(hex_to_color(&state.config.theme.color_selected)),
+                );
+            frame.render_widget(main_block, popup_area);
+
+            let inner = popup_area.inner(ratatui::layout::Margin {
+                horizontal: 2,
+                vertical: 2,
+            });
+            let text = match popup.pr_state {
+                DoneConfirmPrState::Open => format!(
+                    "PR #{} is still open.\n\nAre you sure you want to move this task to Done?\n\nWorktree will be deleted, tmux coding session killed.\nBranch kept locally.\n\n[y] Yes, move to Done    [n/Esc] Cancel",
+                    popup.pr_number
+                ),
                 DoneConfirmPrState::Merged => format!(
                     "PR #{} was merged.\n\nWorktree will be deleted, tmux coding session killed.\nBranch kept locally.\n\n[y] Yes, move to Done    [n/Esc] Cancel",
                     popup.pr_number
@@ -2153,8 +2713,20 @@ impl App {
 
             // Footer with scroll info
             let total_lines = popup.diff_content.lines().count();
+            let keys = &state.keymap;
             let footer_text = format!(
-                " [j/k] scroll  [d/u] page  [g/G] top/bottom  [q/Esc] close  ({}/{}) ",
+                " [{}/{}] scroll  [{}/{}] page  [{}/{}] top/bottom  [{}] close  ({}/{}) ",
+                keys.label(Action::DiffDown),
+                keys.label(Action::DiffUp),
+                keys.label(Action::DiffPageDown),
+                keys.label(Action::DiffPageUp),
+                keys.label(Action::DiffTop),
+                keys.label(Action::DiffBottom),
+                keys.chords(Action::DiffClose)
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/"),
                 popup.scroll_offset + 1,
                 total_lines
             );
@@ -2165,9 +2737,319 @@ impl App {
             );
             frame.render_widget(footer, popup_chunks[2]);
         }
+
+        // Task timeline popup
+        if let Some(ref popup) = state.timeline_popup {
+            let popup_area = centered_rect(70, 70, area);
+            frame.render_widget(Clear, popup_area);
+
+            let popup_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1), // Title bar
+                    Constraint::Min(0),    // Events
+                    Constraint::Length(1), // Footer
+                ])
+                .split(popup_area);
+
+            let title = format!(" History: {} ", popup.task_title);
+            let title_bar = Paragraph::new(title).style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&state.config.theme.color_popup_header)),
+            );
+            frame.render_widget(title_bar, popup_chunks[0]);
+
+            let lines: Vec<Line> = popup
+                .lines
+                .iter()
+                .skip(popup.scroll_offset)
+                .take(popup_chunks[1].height.saturating_sub(2) as usize)
+                .map(|line| {
+                    let style = if line.starts_with("  ") {
+                        Style::default().fg(hex_to_color(&state.config.theme.color_dimmed))
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    Line::from(Span::styled(line.as_str(), style))
+                })
+                .collect();
+
+            let content =
+                Paragraph::new(lines).block(Block::default().borders(Borders::ALL).border_style(
+                    Style::default().fg(hex_to_color(&state.config.theme.color_popup_border)),
+                ));
+            frame.render_widget(content, popup_chunks[1]);
+
+            let footer = Paragraph::new(" [j/k] scroll  [q/Esc] close ").style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&state.config.theme.color_dimmed)),
+            );
+            frame.render_widget(footer, popup_chunks[2]);
+        }
+
+        // Project stats popup
+        if let Some(ref popup) = state.stats_popup {
+            let popup_area = centered_rect(70, 70, area);
+            frame.render_widget(Clear, popup_area);
+
+            let popup_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1), // Title bar
+                    Constraint::Min(0),    // Report
+                    Constraint::Length(1), // Footer
+                ])
+                .split(popup_area);
+
+            let title = format!(" Stats: {} ", state.project_name);
+            let title_bar = Paragraph::new(title).style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&state.config.theme.color_popup_header)),
+            );
+            frame.render_widget(title_bar, popup_chunks[0]);
+
+            let lines: Vec<Line> = popup
+                .lines
+                .iter()
+                .skip(popup.scroll_offset)
+                .take(popup_chunks[1].height.saturating_sub(2) as usize)
+                .map(|line| {
+                    // Section headings are the unindented lines
+                    let style = if line.starts_with(' ') {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default()
+                            .fg(hex_to_color(&state.config.theme.color_accent))
+                            .add_modifier(Modifier::BOLD)
+                    };
+                    Line::from(Span::styled(line.as_str(), style))
+                })
+                .collect();
+
+            let content =
+                Paragraph::new(lines).block(Block::default().borders(Borders::ALL).border_style(
+                    Style::default().fg(hex_to_color(&state.config.theme.color_popup_border)),
+                ));
+            frame.render_widget(content, popup_chunks[1]);
+
+            let footer = Paragraph::new(" [j/k] scroll  [q/Esc] close ").style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&state.config.theme.color_dimmed)),
+            );
+            frame.render_widget(footer, popup_chunks[2]);
+        }
+
+        // Dependency graph popup
+        if let Some(ref popup) = state.graph_popup {
+            let popup_area = centered_rect(80, 80, area);
+            frame.render_widget(Clear, popup_area);
+
+            let popup_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1), // Title bar
+                    Constraint::Min(0),    // Graph
+                    Constraint::Length(1), // Footer
+                ])
+                .split(popup_area);
+
+            let title = format!(" Dependencies: {} ", state.project_name);
+            let title_bar = Paragraph::new(title).style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&state.config.theme.color_popup_header)),
+            );
+            frame.render_widget(title_bar, popup_chunks[0]);
+
+            let lines: Vec<Line> = popup
+                .lines
+                .iter()
+                .skip(popup.scroll_offset)
+                .take(popup_chunks[1].height.saturating_sub(2) as usize)
+                .map(|line| {
+                    // Level headings are the unindented lines
+                    let style = if line.blocked {
+                        Style::default().fg(Color::Red)
+                    } else if line.text.starts_with(' ') {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default()
+                            .fg(hex_to_color(&state.config.theme.color_accent))
+                            .add_modifier(Modifier::BOLD)
+                    };
+                    Line::from(Span::styled(line.text.as_str(), style))
+                })
+                .collect();
+
+            let content =
+                Paragraph::new(lines).block(Block::default().borders(Borders::ALL).border_style(
+                    Style::default().fg(hex_to_color(&state.config.theme.color_popup_border)),
+                ));
+            frame.render_widget(content, popup_chunks[1]);
+
+            let footer = Paragraph::new(" \u{2298} blocked  [j/k] scroll  [q/Esc] close ").style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&state.config.theme.color_dimmed)),
+            );
+            frame.render_widget(footer, popup_chunks[2]);
+        }
+
+        // Agent transcript popup
+        if let Some(ref popup) = state.transcript_popup {
+            let theme = &state.config.theme;
+            let popup_area = centered_rect(85, 85, area);
+            frame.render_widget(Clear, popup_area);
+
+            let popup_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1), // Title bar
+                    Constraint::Length(1), // Phase tabs
+                    Constraint::Min(0),    // Transcript
+                    Constraint::Length(1), // Footer
+                ])
+                .split(popup_area);
+
+            let title = format!(" Transcript: {} ", popup.task_title);
+            let title_bar = Paragraph::new(title).style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&theme.color_popup_header)),
+            );
+            frame.render_widget(title_bar, popup_chunks[0]);
+
+            let tabs: Vec<Span> = if popup.files.is_empty() {
+                vec![Span::styled(
+                    " (no transcript recorded yet)",
+                    Style::default().fg(hex_to_color(&theme.color_dimmed)),
+                )]
+            } else {
+                popup
+                    .files
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        let label = format!(" {} ({}) ", f.phase, format_bytes(f.size));
+                        if i == popup.selected {
+                            Span::styled(
+                                label,
+                                Style::default()
+                                    .fg(hex_to_color(&theme.color_selected))
+                                    .add_modifier(Modifier::BOLD),
+                            )
+                        } else {
+                            Span::styled(
+                                label,
+                                Style::default().fg(hex_to_color(&theme.color_dimmed)),
+                            )
+                        }
+                    })
+                    .collect()
+            };
+            frame.render_widget(Paragraph::new(Line::from(tabs)), popup_chunks[1]);
+
+            let height = popup_chunks[2].height.saturating_sub(2) as usize;
+            let total = popup.lines.len();
+            let end = total
+                .saturating_sub(popup.scroll_from_bottom)
+                .max(height.min(total));
+            let start = end.saturating_sub(height);
+            let content = Paragraph::new(popup.lines[start..end].to_vec()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(hex_to_color(&theme.color_popup_border))),
+            );
+            frame.render_widget(content, popup_chunks[2]);
+
+            let footer_text = format!(
+                " [Tab/h/l] phase  [j/k] scroll  [u/d] page  [g/G] top/bottom  [q/Esc] close  {}/{} ",
+                end, total
+            );
+            let footer = Paragraph::new(footer_text).style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(hex_to_color(&theme.color_dimmed)),
+            );
+            frame.render_widget(footer, popup_chunks[3]);
+        }
+    }
+
+    fn draw_help_popup(state: &AppState, frame: &mut Frame, area: Rect, scroll: usize) {
+        let theme = &state.config.theme;
+        let popup_area = centered_rect(70, 80, area);
+        frame.render_widget(Clear, popup_area);
+
+        let popup_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Title bar
+                Constraint::Min(0),    // Bindings
+                Constraint::Length(1), // Footer
+            ])
+            .split(popup_area);
+
+        let title_bar = Paragraph::new(" Key Bindings ").style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(hex_to_color(&theme.color_popup_header)),
+        );
+        frame.render_widget(title_bar, popup_chunks[0]);
+
+        let lines: Vec<Line> = build_help_lines(&state.keymap)
+            .into_iter()
+            .skip(scroll)
+            .take(popup_chunks[1].height.saturating_sub(2) as usize)
+            .map(|(keys, description)| {
+                if description.is_empty() {
+                    // Context heading
+                    Line::from(Span::styled(
+                        keys,
+                        Style::default()
+                            .fg(hex_to_color(&theme.color_accent))
+                            .bold(),
+                    ))
+                } else {
+                    Line::from(vec![
+                        Span::styled(keys, Style::default().fg(Color::White)),
+                        Span::styled(
+                            description,
+                            Style::default().fg(hex_to_color(&theme.color_dimmed)),
+                        ),
+                    ])
+                }
+            })
+            .collect();
+
+        let content = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(hex_to_color(&theme.color_popup_border))),
+        );
+        frame.render_widget(content, popup_chunks[1]);
+
+        let footer = Paragraph::new(
+            " [j/k] scroll  [q/Esc] close  — rebind in [keybindings] of ~/.config/agtx/config.toml ",
+        )
+        .style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(hex_to_color(&theme.color_dimmed)),
+        );
+        frame.render_widget(footer, popup_chunks[2]);
     }
 
-    fn draw_shell_popup(popup: &ShellPopup, frame: &mut Frame, area: Rect, theme: &ThemeConfig) {
+    fn draw_shell_popup(
+        popup: &ShellPopup,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &ThemeConfig,
+        keymap: &Keymap,
+    ) {
         let popup_area =
             centered_rect_fixed_width(SHELL_POPUP_WIDTH, SHELL_POPUP_HEIGHT_PERCENT, area);
 
@@ -2185,7 +3067,14 @@ impl App {
             escalation_bg: Color::Yellow,
         };
 
-        shell_popup::render_shell_popup(popup, frame, popup_area, styled_lines, &colors);
+        shell_popup::render_shell_popup(
+            popup,
+            frame,
+            popup_area,
+            styled_lines,
+            &colors,
+            &shell_popup_keys(keymap),
+        );
     }
 
     fn draw_task_card(
@@ -2197,6 +3086,8 @@ impl App {
         phase_status: Option<&(PhaseStatus, Instant)>,
         spinner_frame: usize,
         deps_blocked: bool,
+        subtasks: Option<SubtaskProgress>,
+        usage: Option<&Usage>,
     ) {
         let border_style = if is_selected {
             Style::default().fg(hex_to_color(&theme.color_selected))
@@ -2245,7 +3136,21 @@ impl App {
         ) || (task.status == TaskStatus::Backlog
             && task.session_name.is_some());
 
-        if show_indicator {
+        if task.queued_phase.is_some() {
+            // Waiting for an agent slot
+            let queued_span = Span::styled(
+                "\u{29d7} ",
+                Style::default().fg(hex_to_color(&theme.color_accent)),
+            );
+            let title_spans = Line::from(vec![queued_span, Span::styled(title, title_style)]);
+            let title_area = Rect {
+                x: inner.x,
+                y: inner.y,
+                width: inner.width,
+                height: 1,
+            };
+            frame.render_widget(Paragraph::new(title_spans), title_area);
+        } else if show_indicator {
             const SPINNER_FRAMES: &[&str] = &[
                 "\u{280b}", "\u{2819}", "\u{2839}", "\u{2838}", "\u{283c}", "\u{2834}", "\u{2826}",
                 "\u{2827}", "\u{2807}", "\u{280f}",
@@ -2314,21 +3219,41 @@ impl App {
             frame.render_widget(title_line, title_area);
         }
 
-        // Footer line with agent name (for active tasks)
+        // Footer line with agent name (for active tasks), queue state and token usage
         let show_agent = task.status != TaskStatus::Backlog || task.session_name.is_some();
-        let footer_height = if show_agent && inner.height > 2 {
+        let usage = usage.filter(|u| !u.is_empty());
+        let footer_height = if (show_agent || task.queued_phase.is_some() || usage.is_some())
+            && inner.height > 2
+        {
             1u16
         } else {
             0u16
         };
 
+        // Subtask progress, priority, labels and due date (below title) when any is set
+        let meta = Self::task_meta_line(task, subtasks, theme, chrono::Local::now().date_naive());
+        let meta_height = if meta.is_some() && inner.height > 1 + footer_height {
+            1u16
+        } else {
+            0u16
+        };
+        if let Some(meta) = meta.filter(|_| meta_height > 0) {
+            let meta_area = Rect {
+                x: inner.x,
+                y: inner.y + 1,
+                width: inner.width,
+                height: 1,
+            };
+            frame.render_widget(Paragraph::new(meta), meta_area);
+        }
+
         // Preview area (below title) - always show description
-        if inner.height > 1 + footer_height {
+        if inner.height > 1 + meta_height + footer_height {
             let preview_area = Rect {
                 x: inner.x,
-                y: inner.y + 1,
+                y: inner.y + 1 + meta_height,
                 width: inner.width,
-                height: inner.height.saturating_sub(1 + footer_height),
+                height: inner.height.saturating_sub(1 + meta_height + footer_height),
             };
 
             // Show description or placeholder
@@ -2373,11 +3298,76 @@ impl App {
                 "codex" => Style::default().fg(Color::White).bg(Color::Rgb(20, 20, 20)), // white on black
                 _ => Style::default().fg(Color::White),
             };
-            let agent_label = Paragraph::new(format!(" {} ", task.agent))
-                .style(agent_style)
-                .alignment(Alignment::Right);
-            frame.render_widget(agent_label, footer_area);
+            if let Some(phase) = &task.queued_phase {
+                let queued_label = Paragraph::new(format!("queued → {}", phase))
+                    .style(Style::default().fg(hex_to_color(&theme.color_accent)));
+                frame.render_widget(queued_label, footer_area);
+            } else if let Some(usage) = usage {
+                let usage_label = Paragraph::new(crate::usage::format_usage(usage))
+                    .style(Style::default().fg(hex_to_color(&theme.color_dimmed)));
+                frame.render_widget(usage_label, footer_area);
+            }
+            if show_agent {
+                let label = format!(" {} ", task.agent);
+                let width = (label.chars().count() as u16).min(footer_area.width);
+                let label_area = Rect {
+                    x: footer_area.x + footer_area.width - width,
+                    width,
+                    ..footer_area
+                };
+                frame.render_widget(Paragraph::new(label).style(agent_style), label_area);
+            }
+        }
+    }
+
+    /// Card line with the task's subtask progress, priority (unless normal),
+    /// labels and due date
+    fn task_meta_line(
+        task: &Task,
+        subtasks: Option<SubtaskProgress>,
+        theme: &ThemeConfig,
+        today: chrono::NaiveDate,
+    ) -> Option<Line<'static>> {
+        let mut spans = Vec::new();
+        if let Some(progress) = subtasks {
+            let style = if progress.is_complete() {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(hex_to_color(&theme.color_text))
+            };
+            spans.push(Span::styled(
+                format!("\u{2611} {}/{} ", progress.done, progress.total),
+                style,
+            ));
         }
+        let priority_style = match task.priority {
+            Priority::Urgent => Some(Style::default().fg(Color::Red).bold()),
+            Priority::High => Some(Style::default().fg(Color::Yellow)),
+            Priority::Low => Some(Style::default().fg(hex_to_color(&theme.color_dimmed))),
+            Priority::Normal => None,
+        };
+        if let Some(style) = priority_style {
+            spans.push(Span::styled(format!("!{} ", task.priority.as_str()), style));
+        }
+        for label in &task.labels {
+            spans.push(Span::styled(
+                format!("#{} ", label),
+                Style::default().fg(hex_to_color(&theme.color_accent)),
+            ));
+        }
+        if let Some(due) = task.due_date {
+            let style = if task.status == TaskStatus::Done {
+                Style::default().fg(hex_to_color(&theme.color_dimmed))
+            } else if due < today {
+                Style::default().fg(Color::Red).bold()
+            } else if due == today {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(hex_to_color(&theme.color_text))
+            };
+            spans.push(Span::styled(format!("due {}", due.format("%b %-d")), style));
+        }
+        (!spans.is_empty()).then(|| Line::from(spans))
     }
 
     fn draw_sidebar(state: &AppState, frame: &mut Frame, area: Rect) {
@@ -2489,21 +3479,39 @@ impl App {
                     } else {
                         Style::default()
                     };
-                    ListItem::new(format!("  {}", project.name)).style(style)
+                    let mut line = vec![Span::raw(format!("  {}", project.name))];
+                    if let Some(usage) = &project.usage {
+                        line.push(Span::styled(
+                            format!("  {}", crate::usage::format_usage(usage)),
+                            if is_selected {
+                                Style::default()
+                            } else {
+                                Style::default().fg(dimmed_color)
+                            },
+                        ));
+                    }
+                    ListItem::new(Line::from(line)).style(style)
                 })
                 .collect();
 
+            let title = format!(
+                " Projects {} {} [Esc] back ",
+                nav_hint(&state.keymap, "navigate").unwrap_or_default(),
+                state.keymap.hint(Action::Open, "open").unwrap_or_default()
+            );
             let list = List::new(items).block(
                 Block::default()
-                    .title(" Projects [j/k] navigate [Enter] open [Esc] back ")
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(selected_color)),
             );
             frame.render_widget(list, chunks[1]);
         } else {
-            let options = Paragraph::new(
-                "\n  [p] Open existing project\n  [n] Create new project in current directory\n",
-            )
+            let options = Paragraph::new(format!(
+                "\n  [{}] Open existing project\n  [{}] Create new project in current directory\n",
+                state.keymap.label(Action::ProjectList),
+                state.keymap.label(Action::OpenCurrentDir)
+            ))
             .block(
                 Block::default()
                     .title(" Options ")
@@ -2514,7 +3522,16 @@ impl App {
         }
 
         // Footer
-        let footer = Paragraph::new(" [p] projects  [n] new project  [q] quit ")
+        let hints: Vec<String> = [
+            state.keymap.hint(Action::ProjectList, "projects"),
+            state.keymap.hint(Action::OpenCurrentDir, "new project"),
+            state.keymap.hint(Action::Help, "help"),
+            state.keymap.hint(Action::Quit, "quit"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let footer = Paragraph::new(format!(" {} ", hints.join("  ")))
             .style(Style::default().fg(dimmed_color))
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(footer, chunks[2]);
@@ -2534,6 +3551,16 @@ impl App {
             return Ok(());
         }
 
+        // Handle local merge popup if open (blocks input while merging)
+        if let Some(ref popup) = self.state.local_merge_popup {
+            if popup.status != LocalMergeStatus::Merging
+                && matches!(key.code, KeyCode::Enter | KeyCode::Esc)
+            {
+                self.state.local_merge_popup = None;
+            }
+            return Ok(());
+        }
+
         // Handle Move confirmation popup if open (phase incomplete)
         if self.state.move_confirm_popup.is_some() {
             return self.handle_move_confirm_key(key);
@@ -2559,11 +3586,36 @@ impl App {
             return self.handle_trust_confirm_key(key);
         }
 
+        // Handle key binding help if open
+        if self.state.help_popup.is_some() {
+            return self.handle_help_popup_key(key);
+        }
+
         // Handle diff popup if open
         if self.state.diff_popup.is_some() {
             return self.handle_diff_popup_key(key);
         }
 
+        // Handle task timeline popup if open
+        if self.state.timeline_popup.is_some() {
+            return self.handle_timeline_popup_key(key);
+        }
+
+        // Handle stats popup if open
+        if self.state.stats_popup.is_some() {
+            return self.handle_stats_popup_key(key);
+        }
+
+        // Handle dependency graph popup if open
+        if self.state.graph_popup.is_some() {
+            return self.handle_graph_popup_key(key);
+        }
+
+        // Handle transcript popup if open
+        if self.state.transcript_popup.is_some() {
+            return self.handle_transcript_popup_key(key);
+        }
+
         // Handle PR confirmation popup if open
         if self.state.pr_confirm_popup.is_some() {
             return self.handle_pr_confirm_key(key);
@@ -2579,6 +3631,11 @@ impl App {
             return self.handle_task_search_key(key);
         }
 
+        // Handle board filter bar if open
+        if self.state.filter_bar.is_some() {
+            return self.handle_filter_bar_key(key);
+        }
+
         // Handle shell popup if open
         if self.state.shell_popup.is_some() {
             return self.handle_shell_popup_key(key);
@@ -2586,23 +3643,9 @@ impl App {
 
         // Handle based on mode (Dashboard vs Project)
         match &self.state.mode {
-            AppMode::Dashboard => self.handle_dashboard_key(key.code),
+            AppMode::Dashboard => self.handle_dashboard_key(key),
             AppMode::Project(_) => match self.state.input_mode {
-                InputMode::Normal => {
-                    // Ctrl+f = fullscreen attach (handled here since handle_normal_key only gets KeyCode)
-                    if key.code == KeyCode::Char('f')
-                        && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL)
-                    {
-                        if let Some(task) = self.state.board.selected_task() {
-                            if let Some(window_name) = task.session_name.clone() {
-                                self.state.shell_popup = None;
-                                return self.attach_to_tmux_fullscreen(&window_name);
-                            }
-                        }
-                        return Ok(());
-                    }
-                    self.handle_normal_key(key.code)
-                }
+                InputMode::Normal => self.handle_normal_key(key),
                 InputMode::InputTitle => self.handle_title_input(key),
                 InputMode::SelectPlugin => self.handle_plugin_select_wizard(key),
                 InputMode::InputDescription => self.handle_description_input(key),
@@ -2806,6 +3849,18 @@ impl App {
     }
 
     fn force_move_to_done(&mut self, task_id: &str) -> Result<()> {
+        if let Some(strategy) = self.state.config.finish_strategy.local_merge() {
+            let task = match &self.state.db {
+                Some(db) => db.get_task(task_id)?,
+                None => None,
+            };
+            if let (Some(task), Some(project_path)) = (task, self.state.project_path.clone()) {
+                if task.pr_number.is_none() && task.branch_name.is_some() {
+                    return self.start_local_merge(&task, &project_path, strategy);
+                }
+            }
+        }
+
         if let (Some(db), Some(project_path)) = (&self.state.db, self.state.project_path.clone()) {
             if let Some(mut task) = db.get_task(task_id)? {
                 let session_name = task.session_name.clone();
@@ -3020,6 +4075,63 @@ impl App {
         Ok(())
     }
 
+    fn handle_filter_bar_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        use crossterm::event::KeyModifiers;
+
+        let Some(bar) = self.state.filter_bar.as_mut() else {
+            return Ok(());
+        };
+        if let Some(name) = bar.view_name.as_mut() {
+            match key.code {
+                KeyCode::Esc => bar.view_name = None,
+                KeyCode::Enter => {
+                    let name = name.trim().to_string();
+                    if name.is_empty() {
+                        return Ok(());
+                    }
+                    self.state.filter_bar = None;
+                    if let Err(e) = self.save_board_view(&name) {
+                        self.state.warning_message =
+                            Some((format!("Failed to save view: {}", e), Instant::now()));
+                    }
+                }
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Char(c) => name.push(c),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                let bar = self.state.filter_bar.take().unwrap();
+                self.set_board_filter(bar.previous, bar.previous_view);
+                return Ok(());
+            }
+            KeyCode::Enter => {
+                self.state.filter_bar = None;
+                return Ok(());
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if !bar.query.trim().is_empty() {
+                    bar.view_name = Some(String::new());
+                }
+                return Ok(());
+            }
+            KeyCode::Backspace => {
+                bar.query.pop();
+            }
+            KeyCode::Char(c) => bar.query.push(c),
+            _ => return Ok(()),
+        }
+        // Filter as you type; a saved view no longer applies once edited
+        let filter = BoardFilter::parse(&bar.query);
+        self.set_board_filter(filter, None);
+        Ok(())
+    }
+
     fn handle_task_search_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
         use crossterm::event::KeyModifiers;
 
@@ -3041,13 +4153,7 @@ impl App {
                         self.state.board.selected_column = col_idx;
 
                         // Find row index for this task
-                        let tasks_in_col: Vec<_> = self
-                            .state
-                            .board
-                            .tasks
-                            .iter()
-                            .filter(|t| t.status == status)
-                            .collect();
+                        let tasks_in_col = self.state.board.tasks_in_column(col_idx);
                         if let Some(row_idx) = tasks_in_col.iter().position(|t| t.id == task_id) {
                             self.state.board.selected_row = row_idx;
                         }
@@ -3173,11 +4279,8 @@ impl App {
     }
 
     fn handle_shell_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
-        use crossterm::event::KeyModifiers;
-
         if let Some(ref mut popup) = self.state.shell_popup {
             let window_name = popup.window_name.clone();
-            let has_ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
 
             // Dismiss escalation note on any key press (before forwarding)
             if popup.escalation_note.is_some() {
@@ -3200,39 +4303,27 @@ impl App {
                 return Ok(());
             }
 
-            match key.code {
-                // Ctrl+q = close popup
-                KeyCode::Char('q') if has_ctrl => {
+            match self.state.keymap.action(KeyContext::ShellPopup, &key) {
+                Some(Action::ShellClose) => {
                     self.state.shell_popup = None;
                 }
-                // Scroll up with Ctrl+k or Ctrl+p or Ctrl+Up
-                KeyCode::Char('k') | KeyCode::Char('p') | KeyCode::Up if has_ctrl => {
+                Some(Action::ShellScrollUp) => {
                     popup.scroll_up(5);
                 }
-                // Scroll down with Ctrl+j or Ctrl+n or Ctrl+Down
-                KeyCode::Char('j') | KeyCode::Char('n') | KeyCode::Down if has_ctrl => {
+                Some(Action::ShellScrollDown) => {
                     popup.scroll_down(5);
                 }
-                // Page up with Ctrl+u or PageUp
-                KeyCode::Char('u') if has_ctrl => {
+                Some(Action::ShellPageUp) => {
                     popup.scroll_up(20);
                 }
-                KeyCode::PageUp => {
-                    popup.scroll_up(20);
-                }
-                // Page down with Ctrl+d or PageDown
-                KeyCode::Char('d') if has_ctrl => {
-                    popup.scroll_down(20);
-                }
-                KeyCode::PageDown => {
+                Some(Action::ShellPageDown) => {
                     popup.scroll_down(20);
                 }
-                // Ctrl+g = go to bottom (current)
-                KeyCode::Char('g') if has_ctrl => {
+                Some(Action::ShellBottom) => {
                     popup.scroll_to_bottom();
                 }
-                // Ctrl+f = fullscreen attach to tmux session
-                KeyCode::Char('f') if has_ctrl => {
+                // Fullscreen attach to tmux session
+                Some(Action::Fullscreen) => {
                     // Close the popup first so the tmux window isn't stuck at popup dimensions
                     self.state.shell_popup = None;
                     self.attach_to_tmux_fullscreen(&window_name)?;
@@ -3271,31 +4362,148 @@ impl App {
         Ok(())
     }
 
-    fn handle_diff_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
-        if let Some(ref mut popup) = self.state.diff_popup {
+    fn handle_diff_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if let Some(ref mut popup) = self.state.diff_popup {
+            match self.state.keymap.action(KeyContext::DiffPopup, &key) {
+                Some(Action::DiffClose) => {
+                    self.state.diff_popup = None;
+                }
+                Some(Action::DiffDown) => {
+                    popup.scroll_offset = popup.scroll_offset.saturating_add(1);
+                }
+                Some(Action::DiffUp) => {
+                    popup.scroll_offset = popup.scroll_offset.saturating_sub(1);
+                }
+                Some(Action::DiffPageDown) => {
+                    popup.scroll_offset = popup.scroll_offset.saturating_add(20);
+                }
+                Some(Action::DiffPageUp) => {
+                    popup.scroll_offset = popup.scroll_offset.saturating_sub(20);
+                }
+                Some(Action::DiffTop) => {
+                    popup.scroll_offset = 0;
+                }
+                Some(Action::DiffBottom) => {
+                    // Go to end
+                    let line_count = popup.diff_content.lines().count();
+                    popup.scroll_offset = line_count.saturating_sub(10);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_help_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if let Some(ref mut scroll) = self.state.help_popup {
+            let help_action = self.state.keymap.action(KeyContext::Board, &key);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.state.help_popup = None,
+                _ if help_action == Some(Action::Help) => self.state.help_popup = None,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let last = build_help_lines(&self.state.keymap).len().saturating_sub(1);
+                    *scroll = (*scroll + 1).min(last);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    *scroll = scroll.saturating_sub(1);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_transcript_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if let Some(ref mut popup) = self.state.transcript_popup {
+            let max_scroll = popup.lines.len().saturating_sub(1);
+            let board_action = self.state.keymap.action(KeyContext::Board, &key);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.state.transcript_popup = None,
+                _ if board_action == Some(Action::OpenTranscript) => {
+                    self.state.transcript_popup = None
+                }
+                KeyCode::Tab | KeyCode::Char('l') | KeyCode::Right if !popup.files.is_empty() => {
+                    popup.selected = (popup.selected + 1) % popup.files.len();
+                    popup.lines = load_transcript_lines(&popup.files[popup.selected]);
+                    popup.scroll_from_bottom = 0;
+                }
+                KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Left
+                    if !popup.files.is_empty() =>
+                {
+                    popup.selected = (popup.selected + popup.files.len() - 1) % popup.files.len();
+                    popup.lines = load_transcript_lines(&popup.files[popup.selected]);
+                    popup.scroll_from_bottom = 0;
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    popup.scroll_from_bottom = (popup.scroll_from_bottom + 1).min(max_scroll);
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    popup.scroll_from_bottom = popup.scroll_from_bottom.saturating_sub(1);
+                }
+                KeyCode::Char('u') | KeyCode::PageUp => {
+                    popup.scroll_from_bottom = (popup.scroll_from_bottom + 20).min(max_scroll);
+                }
+                KeyCode::Char('d') | KeyCode::PageDown => {
+                    popup.scroll_from_bottom = popup.scroll_from_bottom.saturating_sub(20);
+                }
+                KeyCode::Char('g') => popup.scroll_from_bottom = max_scroll,
+                KeyCode::Char('G') => popup.scroll_from_bottom = 0,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_timeline_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if let Some(ref mut popup) = self.state.timeline_popup {
+            let board_action = self.state.keymap.action(KeyContext::Board, &key);
             match key.code {
-                KeyCode::Esc | KeyCode::Char('q') => {
-                    self.state.diff_popup = None;
-                }
+                KeyCode::Esc | KeyCode::Char('q') => self.state.timeline_popup = None,
+                _ if board_action == Some(Action::OpenTimeline) => self.state.timeline_popup = None,
                 KeyCode::Char('j') | KeyCode::Down => {
-                    popup.scroll_offset = popup.scroll_offset.saturating_add(1);
+                    let last = popup.lines.len().saturating_sub(1);
+                    popup.scroll_offset = (popup.scroll_offset + 1).min(last);
                 }
                 KeyCode::Char('k') | KeyCode::Up => {
                     popup.scroll_offset = popup.scroll_offset.saturating_sub(1);
                 }
-                KeyCode::Char('d') | KeyCode::PageDown => {
-                    popup.scroll_offset = popup.scroll_offset.saturating_add(20);
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_stats_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if let Some(ref mut popup) = self.state.stats_popup {
+            let board_action = self.state.keymap.action(KeyContext::Board, &key);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.state.stats_popup = None,
+                _ if board_action == Some(Action::OpenStats) => self.state.stats_popup = None,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let last = popup.lines.len().saturating_sub(1);
+                    popup.scroll_offset = (popup.scroll_offset + 1).min(last);
                 }
-                KeyCode::Char('u') | KeyCode::PageUp => {
-                    popup.scroll_offset = popup.scroll_offset.saturating_sub(20);
+                KeyCode::Char('k') | KeyCode::Up => {
+                    popup.scroll_offset = popup.scroll_offset.saturating_sub(1);
                 }
-                KeyCode::Char('g') => {
-                    popup.scroll_offset = 0;
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_graph_popup_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if let Some(ref mut popup) = self.state.graph_popup {
+            let board_action = self.state.keymap.action(KeyContext::Board, &key);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.state.graph_popup = None,
+                _ if board_action == Some(Action::OpenGraph) => self.state.graph_popup = None,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let last = popup.lines.len().saturating_sub(1);
+                    popup.scroll_offset = (popup.scroll_offset + 1).min(last);
                 }
-                KeyCode::Char('G') => {
-                    // Go to end
-                    let line_count = popup.diff_content.lines().count();
-                    popup.scroll_offset = line_count.saturating_sub(10);
+                KeyCode::Char('k') | KeyCode::Up => {
+                    popup.scroll_offset = popup.scroll_offset.saturating_sub(1);
                 }
                 _ => {}
             }
@@ -3303,21 +4511,30 @@ impl App {
         Ok(())
     }
 
-    fn handle_dashboard_key(&mut self, key: KeyCode) -> Result<()> {
+    fn handle_dashboard_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if key.code == KeyCode::Esc && self.state.show_project_list {
+            self.state.show_project_list = false;
+            return Ok(());
+        }
+        let action = self.state.keymap.action(KeyContext::Dashboard, &key);
+        if action == Some(Action::Help) {
+            self.state.help_popup = Some(0);
+            return Ok(());
+        }
         if self.state.show_project_list {
-            match key {
-                KeyCode::Char('q') => self.state.should_quit = true,
-                KeyCode::Char('j') | KeyCode::Down => {
+            match action {
+                Some(Action::Quit) => self.state.should_quit = true,
+                Some(Action::NavDown) => {
                     if self.state.selected_project < self.state.projects.len().saturating_sub(1) {
                         self.state.selected_project += 1;
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::NavUp) => {
                     if self.state.selected_project > 0 {
                         self.state.selected_project -= 1;
                     }
                 }
-                KeyCode::Enter => {
+                Some(Action::Open) => {
                     if let Some(project) = self
                         .state
                         .projects
@@ -3329,18 +4546,15 @@ impl App {
                         self.state.sidebar_visible = false;
                     }
                 }
-                KeyCode::Esc => {
-                    self.state.show_project_list = false;
-                }
                 _ => {}
             }
         } else {
-            match key {
-                KeyCode::Char('q') => self.state.should_quit = true,
-                KeyCode::Char('p') => {
+            match action {
+                Some(Action::Quit) => self.state.should_quit = true,
+                Some(Action::ProjectList) => {
                     self.state.show_project_list = true;
                 }
-                KeyCode::Char('n') => {
+                Some(Action::OpenCurrentDir) => {
                     let current_dir = std::env::current_dir()?;
                     if crate::git::is_git_repo(&current_dir) {
                         let canonical = current_dir.canonicalize().unwrap_or(current_dir);
@@ -3352,6 +4566,7 @@ impl App {
                         let project = ProjectInfo {
                             name: name.clone(),
                             path: canonical.to_string_lossy().to_string(),
+                            usage: None,
                         };
                         self.switch_to_project(&project)?;
                         self.state.mode = AppMode::Project(canonical);
@@ -3364,21 +4579,27 @@ impl App {
         Ok(())
     }
 
-    fn handle_normal_key(&mut self, key: KeyCode) -> Result<()> {
+    fn handle_normal_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
         // Handle sidebar navigation if focused
         if self.state.sidebar_focused && self.state.sidebar_visible {
-            match key {
-                KeyCode::Char('q') => self.state.should_quit = true,
-                KeyCode::Char('e') => {
+            if key.code == KeyCode::Esc {
+                // Move focus back to board
+                self.state.sidebar_focused = false;
+                return Ok(());
+            }
+            match self.state.keymap.action(KeyContext::Sidebar, &key) {
+                Some(Action::Quit) => self.state.should_quit = true,
+                Some(Action::Help) => self.state.help_popup = Some(0),
+                Some(Action::ToggleSidebar) => {
                     // Toggle sidebar visibility
                     self.state.sidebar_visible = false;
                     self.state.sidebar_focused = false;
                 }
-                KeyCode::Char('l') | KeyCode::Right | KeyCode::Esc => {
+                Some(Action::NavRight) => {
                     // Move focus back to board
                     self.state.sidebar_focused = false;
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
+                Some(Action::NavDown) => {
                     if self.state.selected_project < self.state.projects.len().saturating_sub(1) {
                         self.state.selected_project += 1;
                         // Switch to project immediately on cursor move
@@ -3392,7 +4613,7 @@ impl App {
                         }
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::NavUp) => {
                     if self.state.selected_project > 0 {
                         self.state.selected_project -= 1;
                         // Switch to project immediately on cursor move
@@ -3406,7 +4627,7 @@ impl App {
                         }
                     }
                 }
-                KeyCode::Enter => {
+                Some(Action::Open) => {
                     // Enter focuses the board (sidebar stays visible)
                     self.state.sidebar_focused = false;
                 }
@@ -3416,16 +4637,17 @@ impl App {
         }
 
         // Handle board navigation
-        match key {
-            KeyCode::Char('q') => self.state.should_quit = true,
-            KeyCode::Char('e') => {
+        match self.state.keymap.action(KeyContext::Board, &key) {
+            Some(Action::Quit) => self.state.should_quit = true,
+            Some(Action::Help) => self.state.help_popup = Some(0),
+            Some(Action::ToggleSidebar) => {
                 // Toggle sidebar visibility
                 self.state.sidebar_visible = !self.state.sidebar_visible;
                 if self.state.sidebar_visible {
                     self.refresh_projects()?;
                 }
             }
-            KeyCode::Char('h') | KeyCode::Left => {
+            Some(Action::NavLeft) => {
                 // Move to sidebar only if visible AND in first column (Backlog)
                 if self.state.sidebar_visible && self.state.board.selected_column == 0 {
                     self.state.sidebar_focused = true;
@@ -3434,17 +4656,17 @@ impl App {
                     self.state.board.move_left();
                 }
             }
-            KeyCode::Char('l') | KeyCode::Right => self.state.board.move_right(),
-            KeyCode::Char('j') | KeyCode::Down => self.state.board.move_down(),
-            KeyCode::Char('k') | KeyCode::Up => self.state.board.move_up(),
-            KeyCode::Char('o') => {
+            Some(Action::NavRight) => self.state.board.move_right(),
+            Some(Action::NavDown) => self.state.board.move_down(),
+            Some(Action::NavUp) => self.state.board.move_up(),
+            Some(Action::NewTask) => {
                 // New task
                 self.state.input_mode = InputMode::InputTitle;
                 self.state.input_buffer.clear();
                 self.state.pending_task_title.clear();
                 self.state.editing_task_id = None;
             }
-            KeyCode::Enter => {
+            Some(Action::Open) => {
                 if let Some(task) = self.state.board.selected_task() {
                     if task.status == TaskStatus::Backlog && task.session_name.is_some() {
                         // Backlog task with active research session
@@ -3457,7 +4679,7 @@ impl App {
                     } else if task.status == TaskStatus::Backlog {
                         // Edit task
                         self.state.editing_task_id = Some(task.id.clone());
-                        self.state.input_buffer = task.title.clone();
+                        self.state.input_buffer = title_with_tags(task);
                         self.state.input_cursor = self.state.input_buffer.len();
                         self.state.pending_task_title.clear();
                         self.state.input_mode = InputMode::InputTitle;
@@ -3472,11 +4694,26 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('x') => self.delete_selected_task()?,
-            KeyCode::Char('d') => self.show_task_diff()?,
-            KeyCode::Char('m') => self.move_task_right()?,
-            KeyCode::Char('M') => self.move_backlog_to_running()?,
-            KeyCode::Char('R') => {
+            Some(Action::Fullscreen) => {
+                if let Some(window_name) = self
+                    .state
+                    .board
+                    .selected_task()
+                    .and_then(|t| t.session_name.clone())
+                {
+                    self.state.shell_popup = None;
+                    self.attach_to_tmux_fullscreen(&window_name)?;
+                }
+            }
+            Some(Action::DeleteTask) => self.delete_selected_task()?,
+            Some(Action::OpenDiff) => self.show_task_diff()?,
+            Some(Action::OpenTimeline) => self.show_task_timeline()?,
+            Some(Action::OpenStats) => self.show_stats()?,
+            Some(Action::OpenGraph) => self.show_graph()?,
+            Some(Action::OpenTranscript) => self.show_task_transcript(),
+            Some(Action::MoveForward) => self.move_task_right()?,
+            Some(Action::RunTask) => self.move_backlog_to_running()?,
+            Some(Action::Research) => {
                 if let Some(task) = self.state.board.selected_task() {
                     if task.status == TaskStatus::Backlog && task.session_name.is_none() {
                         let task_id = task.id.clone();
@@ -3484,7 +4721,7 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('r') => {
+            Some(Action::MoveBack) => {
                 if let Some(task) = self.state.board.selected_task() {
                     let task_id = task.id.clone();
                     match task.status {
@@ -3496,7 +4733,7 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('p') => {
+            Some(Action::NextPhase) => {
                 // Cyclic: Review → Planning (next phase) — only when plugin is cyclic
                 if let Some(task) = self.state.board.selected_task() {
                     if task.status == TaskStatus::Review {
@@ -3508,7 +4745,7 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('/') => {
+            Some(Action::Search) => {
                 // Open task search
                 self.state.task_search = Some(TaskSearchState {
                     query: String::new(),
@@ -3516,11 +4753,20 @@ impl App {
                     selected: 0,
                 });
             }
-            KeyCode::Char('P') => {
+            Some(Action::Filter) => {
+                self.state.filter_bar = Some(FilterBar {
+                    query: self.state.board_filter.query().to_string(),
+                    previous: self.state.board_filter.clone(),
+                    previous_view: self.state.active_view,
+                    view_name: None,
+                });
+            }
+            Some(Action::NextView) => self.cycle_board_view(),
+            Some(Action::SelectPlugin) => {
                 // Open plugin selection popup
                 self.open_plugin_select_popup();
             }
-            KeyCode::Char('O') if self.state.flags.experimental => {
+            Some(Action::ToggleOrchestrator) if self.state.flags.experimental => {
                 // Toggle orchestrator agent (experimental)
                 self.toggle_orchestrator()?;
             }
@@ -3536,8 +4782,10 @@ impl App {
                 self.cancel_wizard();
             }
             KeyCode::Enter => {
-                if !self.state.input_buffer.is_empty() {
-                    self.state.pending_task_title = self.state.input_buffer.clone();
+                let tags = parse_title_tags(&self.state.input_buffer);
+                if !tags.title.is_empty() {
+                    self.state.pending_task_title = tags.title.clone();
+                    self.state.wizard_tags = tags;
                     self.state.input_buffer.clear();
                     self.state.input_cursor = 0;
                     self.advance_from_title();
@@ -3996,7 +5244,10 @@ impl App {
                 // Start with bundled skills (always available, no filesystem needed)
                 let mut seen = std::collections::HashSet::new();
                 let mut all_skills: Vec<SkillEntry> =
-                    skills::enumerate_available_skills(&self.state.config.default_agent)
+                    skills::enumerate_available_skills(&skills::skill_style(
+                        &self.state.config.default_agent,
+                        &self.state.custom_agents,
+                    ))
                         .into_iter()
                         .map(|(command, description)| {
                             seen.insert(command.clone());
@@ -4135,6 +5386,7 @@ impl App {
                 // Editing existing task
                 if let Some(mut task) = db.get_task(task_id)? {
                     task.title = self.state.pending_task_title.clone();
+                    self.state.wizard_tags.apply(&mut task);
                     task.description = if self.state.input_buffer.is_empty() {
                         None
                     } else {
@@ -4156,6 +5408,7 @@ impl App {
                 }
                 task.plugin = plugin;
                 task.referenced_tasks = refs;
+                self.state.wizard_tags.apply(&mut task);
                 // Task starts in Backlog without tmux window
                 db.create_task(&task)?;
 
@@ -4253,6 +5506,7 @@ impl App {
         self.state.highlighted_references.clear();
         self.state.wizard_plugin_options.clear();
         self.state.wizard_referenced_task_ids.clear();
+        self.state.wizard_tags = TitleTags::default();
         self.state.task_ref_search = None;
     }
 
@@ -4331,6 +5585,204 @@ impl App {
         Ok(())
     }
 
+    fn show_task_timeline(&mut self) -> Result<()> {
+        let (Some(task), Some(db)) = (self.state.board.selected_task(), &self.state.db) else {
+            return Ok(());
+        };
+        let events = db.get_task_events(&task.id)?;
+        self.state.timeline_popup = Some(TimelinePopup {
+            task_title: task.title.clone(),
+            lines: format_timeline(&events, chrono::Utc::now()),
+            scroll_offset: 0,
+        });
+        Ok(())
+    }
+
+    fn show_stats(&mut self) -> Result<()> {
+        let Some(db) = &self.state.db else {
+            return Ok(());
+        };
+        let stats = crate::stats::compute(
+            &db.get_all_tasks()?,
+            &db.get_all_task_events()?,
+            chrono::Utc::now(),
+            crate::stats::DEFAULT_WEEKS,
+        );
+        self.state.stats_popup = Some(StatsPopup {
+            lines: crate::stats::format_stats(&stats),
+            scroll_offset: 0,
+        });
+        Ok(())
+    }
+
+    fn show_graph(&mut self) -> Result<()> {
+        let Some(db) = &self.state.db else {
+            return Ok(());
+        };
+        self.state.graph_popup = Some(GraphPopup {
+            lines: crate::graph::format_graph(&db.get_all_tasks()?),
+            scroll_offset: 0,
+        });
+        Ok(())
+    }
+
+    /// Why another agent can't start in `phase` right now, if an agent limit
+    /// is reached. Limits are per project, so only this board's tasks count.
+    /// `task_id` is the task about to start; its own agent is not counted.
+    fn agent_limit_reached(&self, phase: &str, task_id: &str) -> Option<String> {
+        let limits = self.state.config.agent_limits;
+        if limits.is_empty() {
+            return None;
+        }
+        let mut total = 0;
+        let mut in_phase = 0;
+        for task in &self.state.board.tasks {
+            if task.id == task_id {
+                continue;
+            }
+            let Some(task_phase) = agent_slot_phase(task) else {
+                continue;
+            };
+            // Agents that finished their phase or exited don't hold a slot
+            if matches!(
+                self.state.phase_status_cache.get(&task.id),
+                Some((PhaseStatus::Ready | PhaseStatus::Exited, _))
+            ) {
+                continue;
+            }
+            total += 1;
+            if task_phase == phase {
+                in_phase += 1;
+            }
+        }
+        if let Some(max) = limits.for_phase(phase) {
+            if in_phase >= max {
+                return Some(format!(
+                    "{}/{} {} agents working in this project",
+                    in_phase, max, phase
+                ));
+            }
+        }
+        if let Some(max) = limits.total() {
+            if total >= max {
+                return Some(format!("{}/{} agents working in this project", total, max));
+            }
+        }
+        None
+    }
+
+    /// Check the agent limits before `task` starts an agent in `phase`.
+    /// Over a limit the task is queued and Ok(false) is returned; it is started
+    /// by `start_queued_tasks` once a slot frees up. Otherwise any earlier queue
+    /// entry is cleared and Ok(true) is returned.
+    fn admit_agent(&mut self, task: &mut Task, phase: &str) -> Result<bool> {
+        if let Some(reason) = self.agent_limit_reached(phase, &task.id) {
+            if task.queued_phase.as_deref() != Some(phase) {
+                task.queued_phase = Some(phase.to_string());
+                task.queued_at = Some(chrono::Utc::now());
+                task.updated_at = chrono::Utc::now();
+                if let Some(db) = &self.state.db {
+                    db.update_task(task)?;
+                }
+            }
+            self.state.warning_message = Some((
+                format!(
+                    "Agent limit reached ({}) — task queued for {}",
+                    reason, phase
+                ),
+                Instant::now(),
+            ));
+            return Ok(false);
+        }
+        if task.queued_phase.is_some() {
+            task.queued_phase = None;
+            task.queued_at = None;
+            if let Some(db) = &self.state.db {
+                db.update_task(task)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Start queued tasks, oldest first, while their phase has free slots.
+    fn start_queued_tasks(&mut self) -> Result<()> {
+        let mut queued: Vec<Task> = self
+            .state
+            .board
+            .tasks
+            .iter()
+            .filter(|t| t.queued_phase.is_some())
+            .cloned()
+            .collect();
+        queued.sort_by_key(|t| t.queued_at);
+        let Some(project_path) = self.state.project_path.clone() else {
+            return Ok(());
+        };
+
+        for mut task in queued {
+            let phase = task.queued_phase.clone().unwrap_or_default();
+            // Starting from Backlog may need a worktree setup, and those run one at a time
+            if task.status == TaskStatus::Backlog && self.state.setup_rx.is_some() {
+                continue;
+            }
+            if self.agent_limit_reached(&phase, &task.id).is_some() {
+                continue;
+            }
+            tracing::info!(task_id = %task.id, phase = %phase, "Starting queued task");
+            let result = match (phase.as_str(), task.status) {
+                ("research", TaskStatus::Backlog) => self.start_research(&task.id),
+                ("planning", TaskStatus::Backlog) | ("running", TaskStatus::Planning) => {
+                    self.execute_forward_transition(&mut task, &project_path)
+                }
+                ("running", TaskStatus::Backlog) => self.move_backlog_to_running_by_id(&task.id),
+                // The task was moved some other way in the meantime
+                _ => {
+                    task.queued_phase = None;
+                    task.queued_at = None;
+                    match &self.state.db {
+                        Some(db) => db.update_task(&task),
+                        None => Ok(()),
+                    }
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!(task_id = %task.id, error = %e, "Failed to start queued task");
+                self.state.warning_message = Some((
+                    format!("Failed to start queued task \"{}\": {}", task.title, e),
+                    Instant::now(),
+                ));
+            }
+            self.refresh_tasks()?;
+        }
+        Ok(())
+    }
+
+    fn show_task_transcript(&mut self) {
+        let (Some(task), Some(project_path)) =
+            (self.state.board.selected_task(), &self.state.project_path)
+        else {
+            return;
+        };
+        let files = crate::transcript::list(project_path, &task.id);
+        // Prefer the current phase, else the latest one recorded
+        let current = crate::transcript::phase_for_status(task.status);
+        let selected = files
+            .iter()
+            .position(|f| Some(f.phase) == current)
+            .unwrap_or(files.len().saturating_sub(1));
+        let lines = files
+            .get(selected)
+            .map(load_transcript_lines)
+            .unwrap_or_default();
+        self.state.transcript_popup = Some(TranscriptPopup {
+            task_title: task.title.clone(),
+            files,
+            selected,
+            lines,
+            scroll_from_bottom: 0,
+        });
+    }
+
     fn move_task_right(&mut self) -> Result<()> {
         let (mut task, project_path) = match (
             self.state.board.selected_task().cloned(),
@@ -4361,6 +5813,21 @@ impl App {
                 }
             }
 
+            // A parent task can only be finished after its subtasks
+            if new_status == TaskStatus::Done {
+                if let Some(db) = &self.state.db {
+                    let warning = match db.subtasks_done(&task) {
+                        Ok(true) => None,
+                        Ok(false) => Some("Subtasks not Done — cannot finish task".to_string()),
+                        Err(e) => Some(format!("Failed to check subtasks: {}", e)),
+                    };
+                    if let Some(warning) = warning {
+                        self.state.warning_message = Some((warning, Instant::now()));
+                        return Ok(());
+                    }
+                }
+            }
+
             if self.check_phase_incomplete(&task, current_status, new_status) {
                 return Ok(());
             }
@@ -4428,7 +5895,7 @@ impl App {
         }
         let agent_running = task.session_name.as_ref().map_or(false, |target| {
             self.state.tmux_ops.window_exists(target).unwrap_or(false)
-                && is_agent_active(&*self.state.tmux_ops, target)
+                && is_agent_active(&*self.state.tmux_ops, target, &self.state.custom_agents)
         });
         if agent_running {
             self.state.move_confirm_popup = Some(MoveConfirmPopup {
@@ -4467,6 +5934,10 @@ impl App {
             }
         }
 
+        if !self.admit_agent(task, "planning")? {
+            return Ok(true);
+        }
+
         let (planning_agent, agent_switch) =
             needs_agent_switch(&self.state.config, task, "planning");
 
@@ -4485,7 +5956,7 @@ impl App {
             let skill_cmd = resolve_skill_command(
                 &plugin,
                 planning_phase,
-                &planning_agent,
+                &skills::skill_style(&planning_agent, &self.state.custom_agents),
                 &task_content,
                 task.cycle,
             );
@@ -4510,6 +5981,7 @@ impl App {
                 task.worktree_path.clone(),
                 project_path.to_path_buf(),
                 plugin,
+                self.state.custom_agents.clone(),
             );
             task.agent = planning_agent;
             return Ok(false);
@@ -4525,7 +5997,7 @@ impl App {
         let skill_cmd = resolve_skill_command(
             &plugin,
             "planning",
-            &planning_agent,
+            &skills::skill_style(&planning_agent, &self.state.custom_agents),
             &task_content,
             task.cycle,
         );
@@ -4580,8 +6052,10 @@ impl App {
             })
             .unwrap_or_default();
 
+        let custom_agents = self.state.custom_agents.clone();
         let (tx, rx) = mpsc::channel();
         self.state.setup_rx = Some(rx);
+        let actor = self.current_actor();
 
         std::thread::spawn(move || {
             let mut tmp_task = Task::new(&task_title, &planning_agent_clone, &project_name);
@@ -4600,6 +6074,7 @@ impl App {
                 &plugin,
                 &planning_agent_clone,
                 &all_agents,
+                &custom_agents,
                 tmux_ops.as_ref(),
                 git_ops.as_ref(),
                 agent_ops.as_ref(),
@@ -4618,8 +6093,9 @@ impl App {
                         agent: planning_agent_clone.clone(),
                         plugin: plugin_name,
                         error: None,
+                        actor,
                     });
-                    if let Some(target) = wait_for_agent_ready(&tmux_ops, &target) {
+                    if let Some(target) = wait_for_agent_ready(&tmux_ops, &target, &custom_agents) {
                         send_skill_and_prompt(
                             &tmux_ops,
                             &target,
@@ -4643,6 +6119,7 @@ impl App {
                         agent: planning_agent_clone,
                         plugin: plugin_name,
                         error: Some(format!("Planning setup failed: {}", e)),
+                        actor,
                     });
                 }
             }
@@ -4652,8 +6129,11 @@ impl App {
     }
 
     /// Planning → Running: send execution skill/prompt to agent.
-    /// Always returns Ok(false) to continue with db update.
+    /// Returns Ok(true) if the task was queued by an agent limit, Ok(false) to continue with db update.
     fn transition_to_running(&mut self, task: &mut Task) -> Result<bool> {
+        if !self.admit_agent(task, "running")? {
+            return Ok(true);
+        }
         if let Some(session_name) = &task.session_name {
             let plugin = self.load_task_plugin(task);
             let (running_agent, agent_switch) =
@@ -4669,7 +6149,7 @@ impl App {
             let skill_cmd = resolve_skill_command(
                 &plugin,
                 run_phase,
-                &running_agent,
+                &skills::skill_style(&running_agent, &self.state.custom_agents),
                 &task_content,
                 task.cycle,
             );
@@ -4693,6 +6173,7 @@ impl App {
                 task.worktree_path.clone(),
                 self.state.project_path.clone().unwrap_or_default(),
                 plugin,
+                self.state.custom_agents.clone(),
             );
             task.agent = running_agent;
         }
@@ -4706,8 +6187,13 @@ impl App {
         if let Some(session_name) = &task.session_name {
             let plugin = self.load_task_plugin(task);
             let task_content = task.content_text();
-            let skill_cmd =
-                resolve_skill_command(&plugin, "review", &review_agent, &task_content, task.cycle);
+            let skill_cmd = resolve_skill_command(
+                &plugin,
+                "review",
+                &skills::skill_style(&review_agent, &self.state.custom_agents),
+                &task_content,
+                task.cycle,
+            );
             let prompt = resolve_prompt(&plugin, "review", &task_content, &task.id, task.cycle);
             let prompt_trigger = resolve_prompt_trigger(&plugin, "review");
             let auto_dismiss = plugin
@@ -4728,6 +6214,7 @@ impl App {
                 task.worktree_path.clone(),
                 project_path.to_path_buf(),
                 plugin,
+                self.state.custom_agents.clone(),
             );
         }
         task.agent = review_agent.clone();
@@ -4770,6 +6257,11 @@ impl App {
             return Ok(true);
         }
 
+        // Local finish strategies never open PRs — move straight to Review
+        if self.state.config.finish_strategy.local_merge().is_some() {
+            return Ok(false);
+        }
+
         // No PR yet — show confirmation popup
         self.state.review_confirm_popup = Some(ReviewConfirmPopup {
             task_id: task.id.clone(),
@@ -4778,8 +6270,8 @@ impl App {
         Ok(true)
     }
 
-    /// Review → Done: check PR state, uncommitted changes, or clean up.
-    /// Returns Ok(true) if a confirmation popup was shown, Ok(false) to continue with db update.
+    /// Review → Done: check PR state, merge locally, check uncommitted changes, or clean up.
+    /// Returns Ok(true) if a popup was shown, Ok(false) to continue with db update.
     fn transition_to_done(&mut self, task: &mut Task, project_path: &Path) -> Result<bool> {
         if let Some(pr_number) = task.pr_number {
             let pr_state = self
@@ -4800,6 +6292,16 @@ impl App {
             return Ok(true);
         }
 
+        // Local finish strategy — uncommitted work is committed as part of the merge
+        if let Some(strategy) = self.state.config.finish_strategy.local_merge() {
+            if task.branch_name.is_some() {
+                if let Err(e) = self.start_local_merge(task, project_path, strategy) {
+                    self.state.warning_message = Some((e.to_string(), Instant::now()));
+                }
+                return Ok(true);
+            }
+        }
+
         // No PR — check for uncommitted changes
         let has_uncommitted = task
             .worktree_path
@@ -4845,6 +6347,95 @@ impl App {
         Ok(false)
     }
 
+    /// Finish a task with a local merge: check that the base checkout is ready and the branch
+    /// merges cleanly, then generate the commit message, merge and clean up in the background.
+    fn start_local_merge(
+        &mut self,
+        task: &Task,
+        project_path: &Path,
+        strategy: MergeStrategy,
+    ) -> Result<()> {
+        if self.state.local_merge_rx.is_some() {
+            anyhow::bail!("Another local merge is still running, try again shortly");
+        }
+        let Some(branch) = task.branch_name.clone() else {
+            anyhow::bail!("Task has no branch to merge");
+        };
+        let configured_base = task
+            .base_branch
+            .clone()
+            .unwrap_or_else(|| self.state.config.base_branch.clone());
+        let base = if configured_base.trim().is_empty() {
+            git::detect_main_branch(project_path)?
+        } else {
+            configured_base.trim().to_string()
+        };
+
+        self.state
+            .git_ops
+            .check_base_checkout(project_path, &base)?;
+        let conflicts = self
+            .state
+            .git_ops
+            .merge_conflicts(project_path, &base, &branch)?;
+        if !conflicts.is_empty() {
+            anyhow::bail!(
+                "{} conflicts with {} in {} — resolve before moving to Done",
+                branch,
+                base,
+                conflicts.join(", ")
+            );
+        }
+
+        self.state.local_merge_popup = Some(LocalMergePopup {
+            task_title: task.title.clone(),
+            status: LocalMergeStatus::Merging,
+        });
+        let (tx, rx) = mpsc::channel();
+        self.state.local_merge_rx = Some(rx);
+
+        let mut task = task.clone();
+        let actor = self.current_actor();
+        let project_path = project_path.to_path_buf();
+        let git_ops = Arc::clone(&self.state.git_ops);
+        let tmux_ops = Arc::clone(&self.state.tmux_ops);
+        let agent_ops = self.state.agent_registry.get(&task.agent);
+        let cleanup_script = if self.state.flags.no_init_scripts {
+            None
+        } else {
+            self.state.config.cleanup_script.clone()
+        };
+        std::thread::spawn(move || {
+            let outcome = merge_task_locally(
+                &task,
+                &project_path,
+                &branch,
+                &base,
+                strategy,
+                git_ops.as_ref(),
+                agent_ops.as_ref(),
+            )
+            .map(|()| {
+                cleanup_task_for_done(
+                    &mut task,
+                    cleanup_script.as_deref(),
+                    &project_path,
+                    tmux_ops.as_ref(),
+                    git_ops.as_ref(),
+                );
+                format!("{} merged into {} ({})", branch, base, strategy.as_str())
+            })
+            .map_err(|e| e.to_string());
+            let _ = tx.send(LocalMergeResult {
+                task_id: task.id,
+                task_title: task.title,
+                actor,
+                outcome,
+            });
+        });
+        Ok(())
+    }
+
     /// Start a research session for a Backlog task (creates worktree, reused in planning)
     fn start_research(&mut self, task_id: &str) -> Result<()> {
         // Don't start if a setup is already in progress
@@ -4892,6 +6483,10 @@ impl App {
             return Ok(());
         }
 
+        if !self.admit_agent(&mut task, "research")? {
+            return Ok(());
+        }
+
         let agent_name = self.state.config.agent_for_phase("research").to_string();
 
         let task_content = task.content_text();
@@ -4923,8 +6518,10 @@ impl App {
             .as_ref()
             .map_or_else(Vec::new, |p| p.auto_dismiss.clone());
 
+        let custom_agents = self.state.custom_agents.clone();
         let (tx, rx) = mpsc::channel();
         self.state.setup_rx = Some(rx);
+        let actor = self.current_actor();
 
         std::thread::spawn(move || {
             // Create a temporary task to pass to setup_task_worktree
@@ -4946,6 +6543,7 @@ impl App {
                 &plugin,
                 &agent_name,
                 &all_agents,
+                &custom_agents,
                 tmux_ops.as_ref(),
                 git_ops.as_ref(),
                 agent_ops.as_ref(),
@@ -4984,7 +6582,7 @@ impl App {
                     let skill_cmd = resolve_skill_command(
                         &plugin,
                         research_phase,
-                        &agent_name,
+                        &skills::skill_style(&agent_name, &custom_agents),
                         &task_content,
                         task_cycle,
                     );
@@ -4999,10 +6597,11 @@ impl App {
                         agent: agent_name.clone(),
                         plugin: plugin_name,
                         error: None,
+                        actor,
                     });
 
                     // Wait for agent ready and send skill+prompt
-                    if let Some(target) = wait_for_agent_ready(&tmux_ops, &target) {
+                    if let Some(target) = wait_for_agent_ready(&tmux_ops, &target, &custom_agents) {
                         send_skill_and_prompt(
                             &tmux_ops,
                             &target,
@@ -5026,6 +6625,7 @@ impl App {
                         agent: agent_name,
                         plugin: plugin_name,
                         error: Some(format!("Research setup failed: {}", e)),
+                        actor,
                     });
                 }
             }
@@ -5100,6 +6700,11 @@ impl App {
             }
         }
 
+        if !self.admit_agent(&mut task, "running")? {
+            self.refresh_tasks()?;
+            return Ok(());
+        }
+
         // Build prompt - skip planning, go straight to implementation
         let task_content = task.content_text();
 
@@ -5111,7 +6716,7 @@ impl App {
         let skill_cmd = resolve_skill_command(
             &plugin,
             "running",
-            &running_agent,
+            &skills::skill_style(&running_agent, &self.state.custom_agents),
             &task_content,
             task.cycle,
         );
@@ -5145,6 +6750,7 @@ impl App {
                 task.worktree_path.clone(),
                 project_path.clone(),
                 plugin,
+                self.state.custom_agents.clone(),
             );
             task.agent = agent_switch_agent;
             task.status = TaskStatus::Running;
@@ -5177,8 +6783,10 @@ impl App {
         let task_title = task.title.clone();
         let running_agent_clone = running_agent.clone();
 
+        let custom_agents = self.state.custom_agents.clone();
         let (tx, rx) = mpsc::channel();
         self.state.setup_rx = Some(rx);
+        let actor = self.current_actor();
 
         std::thread::spawn(move || {
             let mut tmp_task = Task::new(&task_title, &running_agent_clone, &project_name);
@@ -5197,6 +6805,7 @@ impl App {
                 &plugin,
                 &running_agent_clone,
                 &all_agents,
+                &custom_agents,
                 tmux_ops.as_ref(),
                 git_ops.as_ref(),
                 agent_ops.as_ref(),
@@ -5215,9 +6824,10 @@ impl App {
                         agent: running_agent_clone.clone(),
                         plugin: plugin_name,
                         error: None,
+                        actor,
                     });
 
-                    if let Some(target) = wait_for_agent_ready(&tmux_ops, &target) {
+                    if let Some(target) = wait_for_agent_ready(&tmux_ops, &target, &custom_agents) {
                         send_skill_and_prompt(
                             &tmux_ops,
                             &target,
@@ -5241,6 +6851,7 @@ impl App {
                         agent: running_agent_clone,
                         plugin: plugin_name,
                         error: Some(format!("Running setup failed: {}", e)),
+                        actor,
                     });
                 }
             }
@@ -5269,6 +6880,7 @@ impl App {
                         let running_agent_clone = running_agent.clone();
                         let current_agent_clone = task.agent.clone();
                         let wt_path = task.worktree_path.clone();
+                        let custom_agents = self.state.custom_agents.clone();
                         std::thread::spawn(move || {
                             let agent_ops = agent_registry.get(&running_agent_clone);
                             ensure_window_or_recover(
@@ -5283,6 +6895,7 @@ impl App {
                                 &session_clone,
                                 &current_agent_clone,
                                 &new_cmd,
+                                &custom_agents,
                             );
                         });
                     }
@@ -5322,7 +6935,7 @@ impl App {
                 let skill_cmd = resolve_skill_command(
                     &plugin,
                     "planning",
-                    &planning_agent,
+                    &skills::skill_style(&planning_agent, &self.state.custom_agents),
                     &task_content,
                     task.cycle,
                 );
@@ -5341,6 +6954,7 @@ impl App {
                         .as_ref()
                         .map_or_else(Vec::new, |p| p.auto_dismiss.clone());
                     let wt_path = task.worktree_path.clone();
+                    let custom_agents = self.state.custom_agents.clone();
                     std::thread::spawn(move || {
                         let agent_ops = agent_registry.get(&planning_agent_clone);
                         // Recover window if it was lost
@@ -5357,8 +6971,9 @@ impl App {
                                 &session_clone,
                                 &current_agent_clone,
                                 &new_cmd,
+                                &custom_agents,
                             );
-                            let _ = wait_for_agent_ready(&tmux_ops, &session_clone);
+                            let _ = wait_for_agent_ready(&tmux_ops, &session_clone, &custom_agents);
                         }
                         send_skill_and_prompt(
                             &tmux_ops,
@@ -5402,6 +7017,7 @@ impl App {
                         let planning_agent_clone = planning_agent.clone();
                         let current_agent_clone = task.agent.clone();
                         let wt_path = task.worktree_path.clone();
+                        let custom_agents = self.state.custom_agents.clone();
                         std::thread::spawn(move || {
                             let agent_ops = agent_registry.get(&planning_agent_clone);
                             ensure_window_or_recover(
@@ -5416,6 +7032,7 @@ impl App {
                                 &session_clone,
                                 &current_agent_clone,
                                 &new_cmd,
+                                &custom_agents,
                             );
                         });
                     }
@@ -5457,7 +7074,16 @@ impl App {
                 continue;
             }
 
+            // Attribute everything this request causes to whoever queued it
+            let previous_actor = self
+                .state
+                .db
+                .as_ref()
+                .map(|db| db.set_actor(req.requested_by.unwrap_or(EventActor::Mcp)));
             let result = self.execute_transition_request(&req);
+            if let (Some(db), Some(actor)) = (&self.state.db, previous_actor) {
+                db.set_actor(actor);
+            }
             if let Some(db) = &self.state.db {
                 let _ = match &result {
                     Ok(()) => db.mark_transition_processed(&req.id, None),
@@ -5505,6 +7131,38 @@ impl App {
                 "Cannot advance task: dependencies not in Review/Done"
             );
         }
+        let to_done = req.action == "move_to_done"
+            || (req.action == "move_forward" && task.status == TaskStatus::Review);
+        if to_done && !db.subtasks_done(&task)? {
+            anyhow::bail!("Cannot move task to Done: not all subtasks are Done");
+        }
+        // The policy was checked when the request was queued, but the task
+        // may have moved since, and move_forward goes wherever it is now
+        if !matches!(req.requested_by, Some(EventActor::User | EventActor::Cli)) {
+            let git_ops = self.state.git_ops.as_ref();
+            let mut exclude: Vec<String> = crate::git::AGENT_CONFIG_DIRS
+                .iter()
+                .map(|d| d.to_string())
+                .collect();
+            exclude.extend(
+                self.load_task_plugin(&task)
+                    .map_or_else(Vec::new, |p| p.copy_dirs),
+            );
+            let policy = TaskPolicy::load(db, &task, Some(&project_path), || {
+                let Some(worktree) = task.worktree_path.as_deref() else {
+                    return Ok(Vec::new());
+                };
+                let exclude: Vec<&str> = exclude.iter().map(String::as_str).collect();
+                let base = crate::git::task_base_branch(task.base_branch.as_deref(), &project_path)
+                    .map_err(|e| format!("{:#}", e))?;
+                crate::git::collect_file_diffs(worktree, &base, git_ops, &exclude)
+                    .map(|files| files.into_iter().map(|f| f.path).collect())
+                    .map_err(|e| format!("{:#}", e))
+            });
+            if let Some(reason) = policy.denial(&req.action) {
+                anyhow::bail!(reason);
+            }
+        }
 
         match req.action.as_str() {
             "research" => {
@@ -5645,8 +7303,13 @@ impl App {
         if let Some(session_name) = &task.session_name {
             let plugin = self.load_task_plugin(task);
             let task_content = task.content_text();
-            let skill_cmd =
-                resolve_skill_command(&plugin, "review", &review_agent, &task_content, task.cycle);
+            let skill_cmd = resolve_skill_command(
+                &plugin,
+                "review",
+                &skills::skill_style(&review_agent, &self.state.custom_agents),
+                &task_content,
+                task.cycle,
+            );
             let prompt = resolve_prompt(&plugin, "review", &task_content, &task.id, task.cycle);
             let prompt_trigger = resolve_prompt_trigger(&plugin, "review");
             let auto_dismiss = plugin
@@ -5667,6 +7330,7 @@ impl App {
                 task.worktree_path.clone(),
                 self.state.project_path.clone().unwrap_or_default(),
                 plugin,
+                self.state.custom_agents.clone(),
             );
         }
         task.agent = review_agent;
@@ -5716,8 +7380,9 @@ impl App {
                 let tmux_ops = Arc::clone(&self.state.tmux_ops);
                 let ready_flag = Arc::clone(&self.state.orchestrator_ready);
                 let target = orch_target.clone();
+                let custom_agents = self.state.custom_agents.clone();
                 std::thread::spawn(move || {
-                    if wait_for_agent_ready(&tmux_ops, &target).is_some() {
+                    if wait_for_agent_ready(&tmux_ops, &target, &custom_agents).is_some() {
                         ready_flag.store(true, Ordering::Release);
                     }
                 });
@@ -5770,7 +7435,7 @@ impl App {
         let mcp_json = serde_json::json!({
             "type": "stdio",
             "command": agtx_bin,
-            "args": ["mcp-serve", &project_path_str]
+            "args": ["mcp-serve", &project_path_str, "--role", "orchestrator"]
         });
         let mcp_json_str = mcp_json.to_string().replace('\'', "'\\''");
 
@@ -5820,6 +7485,7 @@ impl App {
             "agtx-orchestrate",
             skills::ORCHESTRATE_SKILL,
             &default_agent,
+            &self.state.custom_agents,
         );
 
         if let Some(ref db) = self.state.db {
@@ -5827,13 +7493,15 @@ impl App {
         }
 
         // Send the /agtx:orchestrate command once the agent is ready
-        let skill_cmd = skills::transform_plugin_command("/agtx:orchestrate", &default_agent)
+        let style = skills::skill_style(&default_agent, &self.state.custom_agents);
+        let skill_cmd = skills::transform_plugin_command("/agtx:orchestrate", &style)
             .unwrap_or_else(|| "/agtx:orchestrate".to_string());
         let tmux_ops = Arc::clone(&self.state.tmux_ops);
         let ready_flag = Arc::clone(&self.state.orchestrator_ready);
         let target = orch_target;
+        let custom_agents = self.state.custom_agents.clone();
         std::thread::spawn(move || {
-            if let Some(ready_target) = wait_for_agent_ready(&tmux_ops, &target) {
+            if let Some(ready_target) = wait_for_agent_ready(&tmux_ops, &target, &custom_agents) {
                 let _ = tmux_ops.send_keys(&ready_target, &skill_cmd);
                 ready_flag.store(true, Ordering::Release);
             }
@@ -5868,6 +7536,7 @@ impl App {
                     // Clear stale phase status so it gets re-evaluated
                     self.state.phase_status_cache.remove(&task.id);
                     self.state.pane_content_hashes.remove(&task.id);
+                    self.state.transcript_pipes.remove(&task.id);
                 }
 
                 let task_id = task.id.clone();
@@ -5924,24 +7593,13 @@ impl App {
             return Ok(());
         }
 
-        let session = &self.state.tmux_project_name;
+        let session = self.state.tmux_project_name.clone();
 
-        // Check if we're already inside the agtx tmux server — if so, just
+        // Check if we're already inside the agent server — if so, just
         // switch windows instead of nesting with attach.
-        let inside_agtx = std::env::var("TMUX")
-            .map(|v| v.contains(tmux::AGENT_SERVER))
-            .unwrap_or(false);
-
-        if inside_agtx {
-            // Already inside agtx tmux — just switch to the task window.
+        if self.state.tmux_ops.is_inside_server(&session) {
             // window_name is already session:window format, use it directly.
-            let _ = std::process::Command::new("tmux")
-                .args([
-                    "-L", tmux::AGENT_SERVER,
-                    "select-window", "-t", window_name,
-                    ";", "resize-window", "-A",
-                ])
-                .output();
+            let _ = self.state.tmux_ops.select_window(window_name);
         } else {
             // Leave alternate screen and disable raw mode
             match self.terminal.backend_mut() {
@@ -5949,21 +7607,13 @@ impl App {
                     let _ = disable_raw_mode();
                     let _ = execute!(backend, LeaveAlternateScreen, DisableBracketedPaste);
                 }
+                AppBackend::Headless(_) => {}
                 #[cfg(feature = "test-mocks")]
                 AppBackend::Test(_) => {}
             }
 
-            // Attach to the agtx tmux server, select the task window, and resize.
-            // Unset $TMUX so tmux allows attaching when inside a different tmux.
-            let _ = std::process::Command::new("tmux")
-                .args([
-                    "-L", tmux::AGENT_SERVER,
-                    "attach", "-t", session,
-                    ";", "select-window", "-t", window_name,
-                    ";", "resize-window", "-A",
-                ])
-                .env_remove("TMUX")
-                .status();
+            // Attach to the session with the task window selected.
+            let _ = self.state.tmux_ops.attach(&session, window_name);
 
             // Restore terminal
             match self.terminal.backend_mut() {
@@ -5971,6 +7621,7 @@ impl App {
                     enable_raw_mode()?;
                     execute!(backend, EnterAlternateScreen, EnableBracketedPaste)?;
                 }
+                AppBackend::Headless(_) => {}
                 #[cfg(feature = "test-mocks")]
                 AppBackend::Test(_) => {}
             }
@@ -5995,6 +7646,7 @@ impl App {
     pub fn refresh_tasks(&mut self) -> Result<()> {
         if let Some(db) = &self.state.db {
             self.state.board.tasks = db.get_all_tasks()?;
+            self.state.task_usage = db.get_usage_totals()?;
             // Refresh dependency satisfaction cache for backlog tasks with references
             self.state.deps_satisfied_cache.clear();
             for task in &self.state.board.tasks {
@@ -6005,16 +7657,85 @@ impl App {
                 }
             }
         }
+        self.apply_board_filter();
+        Ok(())
+    }
+
+    /// Show only the tasks matching the board filter
+    fn apply_board_filter(&mut self) {
+        let filter = &self.state.board_filter;
+        let visible = (!filter.is_empty()).then(|| {
+            self.state
+                .board
+                .tasks
+                .iter()
+                .filter(|t| {
+                    let phase = self.state.phase_status_cache.get(&t.id).map(|(p, _)| *p);
+                    filter.matches(t, phase)
+                })
+                .map(|t| t.id.clone())
+                .collect()
+        });
+        self.state.board.set_visible(visible);
+    }
+
+    fn set_board_filter(&mut self, filter: BoardFilter, view: Option<usize>) {
+        self.state.board_filter = filter;
+        self.state.active_view = view;
+        self.apply_board_filter();
+    }
+
+    /// Switch to the next saved view, then back to the unfiltered board
+    fn cycle_board_view(&mut self) {
+        let views = &self.state.config.views;
+        if views.is_empty() {
+            self.state.warning_message = Some((
+                "No saved views — press f, type a filter and Ctrl+s to save one".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        let next = match self.state.active_view {
+            Some(i) if i + 1 >= views.len() => None,
+            Some(i) => Some(i + 1),
+            None => Some(0),
+        };
+        let filter = next.map_or_else(BoardFilter::default, |i| {
+            BoardFilter::parse(&views[i].filter)
+        });
+        self.set_board_filter(filter, next);
+    }
+
+    /// Save the current filter as a view in the project config, replacing a
+    /// view with the same name
+    fn save_board_view(&mut self, name: &str) -> Result<()> {
+        let Some(project_path) = self.state.project_path.clone() else {
+            return Ok(());
+        };
+        let view = BoardView {
+            name: name.to_string(),
+            filter: self.state.board_filter.query().to_string(),
+        };
+        let mut project = ProjectConfig::load(&project_path)?;
+        match project.views.iter_mut().find(|v| v.name == view.name) {
+            Some(existing) => *existing = view,
+            None => project.views.push(view),
+        }
+        project.save(&project_path)?;
+        self.state.active_view = project.views.iter().position(|v| v.name == name);
+        self.state.config.views = project.views;
         Ok(())
     }
 
     fn refresh_projects(&mut self) -> Result<()> {
         // Load projects from global database
         let db_projects = self.state.global_db.get_all_projects()?;
+        let with_usage = matches!(self.state.mode, AppMode::Dashboard);
 
         self.state.projects = db_projects
             .into_iter()
             .map(|p| ProjectInfo {
+                usage: with_usage.then(|| project_usage(&p.path)).flatten(),
                 name: p.name,
                 path: p.path,
             })
@@ -6041,6 +7762,30 @@ impl App {
         Ok(())
     }
 
+    /// Adopt an orchestrator window that is already running and wait in the
+    /// background for its agent to be ready before notifications are delivered.
+    fn attach_existing_orchestrator(&mut self) {
+        let Some(orch_target) = detect_existing_orchestrator(
+            self.state.flags.experimental,
+            self.state.tmux_ops.as_ref(),
+            &self.state.tmux_project_name,
+            self.state.db.as_ref(),
+            &self.state.board.tasks,
+            self.state.project_path.as_deref(),
+        ) else {
+            return;
+        };
+        self.state.orchestrator_session = Some(orch_target.clone());
+        let tmux_ops = Arc::clone(&self.state.tmux_ops);
+        let ready_flag = Arc::clone(&self.state.orchestrator_ready);
+        let custom_agents = self.state.custom_agents.clone();
+        std::thread::spawn(move || {
+            if wait_for_agent_ready(&tmux_ops, &orch_target, &custom_agents).is_some() {
+                ready_flag.store(true, Ordering::Release);
+            }
+        });
+    }
+
     /// Push queued notifications to the orchestrator's tmux pane, but only when idle.
     /// Runs every 2s. Idle = pane content unchanged for ≥3s.
     fn deliver_orchestrator_notifications(&mut self) {
@@ -6128,6 +7873,8 @@ impl App {
 
         let now = Instant::now();
         const CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(2);
+        // Session logs can be large; token counts don't need to be live
+        const USAGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 
         // Collect tasks that need checking (cache expired or never checked)
         let tasks_to_check: Vec<_> = self
@@ -6156,6 +7903,17 @@ impl App {
                     .phase_status_cache
                     .get(&t.id)
                     .map_or(true, |(prev, _)| *prev == PhaseStatus::Ready);
+                // Attach the transcript pipe when the task enters a new phase
+                // (or agtx restarted and no pipe was recorded yet)
+                let pipe_phase = crate::transcript::phase_for_status(t.status)
+                    .filter(|phase| self.state.transcript_pipes.get(&t.id) != Some(phase));
+                let usage_since = (t.worktree_path.is_some()
+                    && self
+                        .state
+                        .usage_collected_at
+                        .get(&t.id)
+                        .is_none_or(|at| now.duration_since(*at) >= USAGE_INTERVAL))
+                .then_some(t.created_at);
                 (
                     t.id.clone(),
                     t.status,
@@ -6165,11 +7923,28 @@ impl App {
                     t.cycle,
                     was_ready,
                     t.agent.clone(),
+                    pipe_phase,
+                    usage_since,
                 )
             })
             .collect();
+        for (task_id, .., usage_since) in &tasks_to_check {
+            if usage_since.is_some() {
+                self.state.usage_collected_at.insert(task_id.clone(), now);
+            }
+        }
 
         if tasks_to_check.is_empty() {
+            // No agent left to report on, so no refresh will free a slot —
+            // queued tasks can start right away
+            let tasks = &self.state.board.tasks;
+            if tasks.iter().any(|t| t.queued_phase.is_some())
+                && tasks.iter().all(|t| agent_slot_phase(t).is_none())
+            {
+                if let Err(e) = self.start_queued_tasks() {
+                    tracing::warn!(error = %e, "Failed to start queued tasks");
+                }
+            }
             self.state.spinner_frame = self.state.spinner_frame.wrapping_add(1);
             return;
         }
@@ -6193,6 +7968,8 @@ impl App {
                 cycle,
                 was_ready,
                 agent,
+                pipe_phase,
+                usage_since,
             ) in tasks_to_check
             {
                 let plugin =
@@ -6288,6 +8065,25 @@ impl App {
                     None
                 };
 
+                let transcript_phase = match (pipe_phase, &session_name, &project_path) {
+                    (Some(phase), Some(sn), Some(pp)) if !window_gone => {
+                        match crate::transcript::start(tmux_ops.as_ref(), sn, pp, &task_id, phase) {
+                            Ok(_) => Some(phase),
+                            Err(e) => {
+                                tracing::warn!(task_id = %task_id, error = %e, "Failed to start transcript capture");
+                                // Remember the phase anyway so this isn't retried every tick
+                                Some(phase)
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+
+                let usage = match (usage_since, &worktree_path) {
+                    (Some(since), Some(wt)) => Some(crate::usage::collect(Path::new(wt), since)),
+                    _ => None,
+                };
+
                 statuses.push(SessionTaskStatus {
                     task_id,
                     phase_status,
@@ -6297,6 +8093,8 @@ impl App {
                     session_name,
                     agent,
                     was_ready,
+                    transcript_phase,
+                    usage,
                 });
             }
 
@@ -6307,6 +8105,7 @@ impl App {
     /// Apply results from the background session refresh thread.
     fn apply_session_refresh(&mut self, result: SessionRefreshResult) {
         let now = Instant::now();
+        let usage_collected = result.statuses.iter().any(|s| s.usage.is_some());
 
         for task_status in result.statuses {
             let mut phase = task_status.phase_status;
@@ -6329,9 +8128,60 @@ impl App {
                 self.state.pane_content_hashes.remove(&task_status.task_id);
             } else if phase == PhaseStatus::Exited {
                 self.state.pane_content_hashes.remove(&task_status.task_id);
+                // A resumed session gets a fresh window that needs piping again
+                self.state.transcript_pipes.remove(&task_status.task_id);
+            }
+            if let Some(transcript_phase) = task_status.transcript_phase {
+                self.state
+                    .transcript_pipes
+                    .insert(task_status.task_id.clone(), transcript_phase);
+            }
+            if let (Some(records), Some(db)) = (&task_status.usage, &self.state.db) {
+                match record_task_usage(db, &task_status.task_id, records) {
+                    Ok(Some(total)) => {
+                        self.state
+                            .task_usage
+                            .insert(task_status.task_id.clone(), total);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!(task_id = %task_status.task_id, error = %e, "Failed to record token usage");
+                    }
+                }
             }
 
             let newly_ready = phase == PhaseStatus::Ready && !task_status.was_ready;
+            let previous = self
+                .state
+                .phase_status_cache
+                .get(&task_status.task_id)
+                .map(|(previous, _)| *previous);
+            let was_idle = previous.map(|previous| previous == PhaseStatus::Idle);
+            if let Some(db) = &self.state.db {
+                // Kept in the DB so MCP clients can see it
+                if previous != Some(phase) {
+                    if let Err(e) = db.set_phase_status(&task_status.task_id, phase) {
+                        tracing::warn!(task_id = %task_status.task_id, error = %e, "Failed to record phase status");
+                    }
+                }
+                // Output stopped when the pane content last changed, not when idleness was detected
+                let quiet_for = self
+                    .state
+                    .pane_content_hashes
+                    .get(&task_status.task_id)
+                    .map_or(std::time::Duration::ZERO, |(_, changed)| {
+                        now.duration_since(*changed)
+                    });
+                if let Err(e) = record_idle_change(
+                    db,
+                    &task_status.task_id,
+                    was_idle,
+                    phase == PhaseStatus::Idle,
+                    quiet_for,
+                ) {
+                    tracing::warn!(task_id = %task_status.task_id, error = %e, "Failed to record idle time");
+                }
+            }
             self.state
                 .phase_status_cache
                 .insert(task_status.task_id.clone(), (phase, now));
@@ -6400,13 +8250,14 @@ impl App {
                             let wt = wt.clone();
                             let sn = sn.clone();
                             let agent_name = task_status.agent.clone();
+                            let style = skills::skill_style(&agent_name, &self.state.custom_agents);
 
                             std::thread::spawn(move || {
                                 match git_ops.fetch_and_check_conflicts(Path::new(&wt)) {
                                     Ok(true) => {
                                         let skill_cmd = skills::transform_plugin_command(
                                             "/agtx:merge-conflicts",
-                                            &agent_name,
+                                            &style,
                                         );
                                         send_skill_and_prompt(
                                             &tmux_ops,
@@ -6488,9 +8339,154 @@ impl App {
             }
         }
 
+        if usage_collected && !self.state.config.budget.is_empty() {
+            if let Err(e) = self.enforce_budgets() {
+                tracing::warn!(error = %e, "Failed to check budgets");
+            }
+        }
+
+        // Finished or exited agents free their slot for queued tasks
+        if let Err(e) = self.start_queued_tasks() {
+            tracing::warn!(error = %e, "Failed to start queued tasks");
+        }
+
+        // is:idle, is:ready, ... depend on the phase statuses just updated
+        self.apply_board_filter();
+
         self.state.spinner_frame = self.state.spinner_frame.wrapping_add(1);
     }
 
+    /// Escalate Planning/Running tasks that went over the task budget, or all
+    /// of them once the project is over its budget. Each task is escalated once,
+    /// also across restarts.
+    fn enforce_budgets(&mut self) -> Result<()> {
+        let Some(db) = &self.state.db else {
+            return Ok(());
+        };
+        let budget = self.state.config.budget;
+        let now = chrono::Utc::now();
+
+        // Escalations are recorded as task events, so a restart doesn't notify
+        // or interrupt the same tasks again
+        if !self.state.budget_escalations_loaded {
+            self.state.budget_escalations_loaded = true;
+            for event in db.get_all_task_events()? {
+                let Some(note) = event
+                    .to_value
+                    .as_deref()
+                    .filter(|_| event.kind == TaskEventKind::Escalated)
+                else {
+                    continue;
+                };
+                if note.starts_with(PROJECT_BUDGET_NOTE) {
+                    self.state.project_over_budget = true;
+                } else if !note.starts_with(TASK_BUDGET_NOTE) {
+                    continue;
+                }
+                self.state.budget_escalated.insert(event.task_id);
+            }
+        }
+
+        let mut project_minutes = 0;
+        let mut over_budget: Vec<(String, String)> = Vec::new();
+        for task in &self.state.board.tasks {
+            let active = matches!(task.status, TaskStatus::Planning | TaskStatus::Running);
+            let needs_minutes =
+                budget.project.minutes.is_some() || (active && budget.task.minutes.is_some());
+            let minutes = if needs_minutes {
+                agent_minutes(&db.get_task_events(&task.id)?, now)
+            } else {
+                0
+            };
+            project_minutes += minutes;
+            if !active || self.state.budget_escalated.contains(&task.id) {
+                continue;
+            }
+            let usage = self
+                .state
+                .task_usage
+                .get(&task.id)
+                .copied()
+                .unwrap_or_default();
+            if let Some(reason) =
+                budget
+                    .task
+                    .exceeded(usage.total_tokens(), usage.cost_usd, minutes)
+            {
+                over_budget.push((task.id.clone(), format!("{}: {}", TASK_BUDGET_NOTE, reason)));
+            }
+        }
+
+        let project_usage = db.get_project_usage()?;
+        if let Some(reason) = budget.project.exceeded(
+            project_usage.total_tokens(),
+            project_usage.cost_usd,
+            project_minutes,
+        ) {
+            let note = format!("{}: {}", PROJECT_BUDGET_NOTE, reason);
+            let mut escalates = false;
+            for task in &self.state.board.tasks {
+                if matches!(task.status, TaskStatus::Planning | TaskStatus::Running)
+                    && !self.state.budget_escalated.contains(&task.id)
+                    && !over_budget.iter().any(|(id, _)| *id == task.id)
+                {
+                    over_budget.push((task.id.clone(), note.clone()));
+                    escalates = true;
+                }
+            }
+            // Sent along with a task escalation, whose event marks it as sent
+            if escalates && !self.state.project_over_budget {
+                self.state.project_over_budget = true;
+                if self.state.orchestrator_session.is_some() {
+                    let _ = db.create_notification(&crate::db::Notification::new(note));
+                }
+            }
+        }
+
+        if over_budget.is_empty() {
+            return Ok(());
+        }
+        for (task_id, note) in over_budget {
+            self.state.budget_escalated.insert(task_id.clone());
+            let Some(mut task) = db.get_task(&task_id)? else {
+                continue;
+            };
+            // Keep an escalation the user hasn't seen yet, but still record this one
+            if task.escalation_note.is_none() {
+                task.escalation_note = Some(note.clone());
+                task.updated_at = chrono::Utc::now();
+                db.update_task(&task)?;
+            } else {
+                let mut event =
+                    crate::db::TaskEvent::new(&task.id, TaskEventKind::Escalated, db.actor());
+                event.from_value = task.escalation_note.clone();
+                event.to_value = Some(note.clone());
+                db.add_task_event(&event)?;
+            }
+            if self.state.orchestrator_session.is_some() {
+                let short_id = if task.id.len() >= 8 {
+                    &task.id[..8]
+                } else {
+                    &task.id
+                };
+                let notif = crate::db::Notification::new(format!(
+                    "Task \"{}\" ({}) needs attention: {}",
+                    task.title, short_id, note
+                ));
+                let _ = db.create_notification(&notif);
+            }
+            if budget.interrupt {
+                if let Some(ref session_name) = task.session_name {
+                    let _ = self
+                        .state
+                        .tmux_ops
+                        .send_keys_literal(session_name, "Escape");
+                }
+            }
+        }
+        self.refresh_tasks()
+    }
+
     fn switch_to_project(&mut self, project: &ProjectInfo) -> Result<()> {
         self.switch_to_project_keep_sidebar(project)?;
         // Unfocus sidebar
@@ -6536,6 +8532,11 @@ impl App {
         self.state.merge_conflict_checked.clear();
         self.state.stuck_task_notified.clear();
         self.state.stuck_task_idle_since.clear();
+        self.state.budget_escalated.clear();
+        self.state.project_over_budget = false;
+        self.state.budget_escalations_loaded = false;
+        self.state.board_filter = BoardFilter::default();
+        self.state.active_view = None;
 
         // Reload tasks for new project
         self.refresh_tasks()?;
@@ -6551,12 +8552,35 @@ impl Drop for App {
                 let _ = disable_raw_mode();
                 let _ = execute!(backend, LeaveAlternateScreen, DisableBracketedPaste);
             }
+            AppBackend::Headless(_) => {}
             #[cfg(feature = "test-mocks")]
             AppBackend::Test(_) => {}
         }
     }
 }
 
+/// Collect `[[custom_agents]]` from the global config and, for trusted projects,
+/// the project config. Untrusted project agents are dropped for the same reason
+/// init_script is: they run arbitrary commands.
+fn load_custom_agents(mode: &AppMode) -> Vec<crate::config::CustomAgentConfig> {
+    let global_config = GlobalConfig::load().unwrap_or_default();
+    let project_config = match mode {
+        AppMode::Project(path) => {
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            let trusted = crate::config::TrustStore::load()
+                .unwrap_or_default()
+                .is_trusted(&canonical);
+            if trusted {
+                ProjectConfig::load(&canonical).unwrap_or_default()
+            } else {
+                ProjectConfig::default()
+            }
+        }
+        AppMode::Dashboard => ProjectConfig::default(),
+    };
+    MergedConfig::merge(&global_config, &project_config).custom_agents
+}
+
 /// Ensure tmux session exists for a project
 // =============================================================================
 // Orchestrator idle detection (extracted for testability)
@@ -6575,6 +8599,8 @@ enum OrchestratorIdleResult {
 
 /// Idle detection duration for the stability fallback (no `[agtx:idle]` signal).
 const ORCHESTRATOR_IDLE_FALLBACK_SECS: u64 = 15;
+/// How often the daemon looks for an orchestrator window it isn't attached to.
+const ORCHESTRATOR_DETECT_INTERVAL_SECS: u64 = 10;
 
 /// Pure idle-detection logic for the orchestrator pane.
 ///
@@ -6709,6 +8735,68 @@ fn generate_task_slug(task_id: &str, title: &str) -> String {
     format!("{}-{}", id_prefix, title_slug)
 }
 
+/// Labels, priority and due date typed after the title in the task wizard,
+/// e.g. "Fix login #auth #bug !high due:2026-03-01"
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TitleTags {
+    title: String,
+    labels: Vec<String>,
+    priority: Priority,
+    due_date: Option<chrono::NaiveDate>,
+}
+
+impl TitleTags {
+    fn apply(&self, task: &mut Task) {
+        task.set_labels(&self.labels);
+        task.priority = self.priority;
+        task.due_date = self.due_date;
+    }
+}
+
+/// Split trailing `#label`, `!priority` and `due:YYYY-MM-DD` tags off a
+/// wizard title. Words that aren't valid tags end the tag list and stay in
+/// the title, so "Fix #123 crash" keeps its issue number.
+fn parse_title_tags(input: &str) -> TitleTags {
+    let mut tags = TitleTags::default();
+    let mut title = input;
+    // The first word always stays in the title
+    while let Some((rest, word)) = title.trim_end().rsplit_once(char::is_whitespace) {
+        if let Some(label) = word
+            .strip_prefix('#')
+            .filter(|l| l.starts_with(|c: char| c.is_alphabetic()))
+        {
+            tags.labels.insert(0, label.to_string());
+        } else if let Some(priority) = word.strip_prefix('!').and_then(Priority::from_str) {
+            tags.priority = priority;
+        } else if let Some(date) = word
+            .strip_prefix("due:")
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        {
+            tags.due_date = Some(date);
+        } else {
+            break;
+        }
+        title = rest.trim_end();
+    }
+    tags.title = title.to_string();
+    tags
+}
+
+/// Wizard title for editing `task`: the title followed by its tags
+fn title_with_tags(task: &Task) -> String {
+    let mut text = task.title.clone();
+    for label in &task.labels {
+        text.push_str(&format!(" #{}", label));
+    }
+    if task.priority != Priority::Normal {
+        text.push_str(&format!(" !{}", task.priority.as_str()));
+    }
+    if let Some(due) = task.due_date {
+        text.push_str(&format!(" due:{}", due));
+    }
+    text
+}
+
 fn run_cleanup_script_for_worktree(cleanup_script: Option<&str>, worktree_path: &Path) {
     let Some(script) = cleanup_script else {
         return;
@@ -6847,6 +8935,7 @@ fn setup_task_worktree(
     plugin: &Option<WorkflowPlugin>,
     agent_name: &str,
     all_phase_agents: &[String],
+    custom_agents: &[agent::Agent],
     tmux_ops: &dyn TmuxOperations,
     git_ops: &dyn GitOperations,
     agent_ops: &dyn AgentOperations,
@@ -6908,7 +8997,13 @@ fn setup_task_worktree(
     // Write skills to worktree .agtx/skills/ and agent-native discovery paths
     // Deploy for all unique agents configured across phases
     let agent_refs: Vec<&str> = all_phase_agents.iter().map(|s| s.as_str()).collect();
-    write_skills_to_worktree(&worktree_path_str, project_path, plugin, &agent_refs);
+    write_skills_to_worktree(
+        &worktree_path_str,
+        project_path,
+        plugin,
+        &agent_refs,
+        custom_agents,
+    );
 
     // Copy referenced task artifacts into .agtx/references/
     if !referenced_tasks.is_empty() {
@@ -6985,8 +9080,14 @@ fn setup_task_worktree(
 
     // Build the interactive command. For agents with skill/command support,
     // start with no prompt — the skill command and task content are sent via send_keys.
-    let has_skill_support =
-        resolve_skill_command(plugin, "planning", agent_name, "", task.cycle).is_some();
+    let has_skill_support = resolve_skill_command(
+        plugin,
+        "planning",
+        &skills::skill_style(agent_name, custom_agents),
+        "",
+        task.cycle,
+    )
+    .is_some();
     let agent_cmd = if has_skill_support {
         agent_ops.build_interactive_command("")
     } else {
@@ -7018,6 +9119,87 @@ fn setup_task_worktree(
     Ok(target)
 }
 
+/// Token usage recorded in a project's database, if it has any
+fn project_usage(project_path: &str) -> Option<Usage> {
+    let path = Path::new(project_path);
+    if !Database::project_db_path(path).ok()?.exists() {
+        return None;
+    }
+    Database::open_project(path)
+        .and_then(|db| db.get_project_usage())
+        .ok()
+        .filter(|usage| !usage.is_empty())
+}
+
+/// Store a task's usage per phase from its agents' session logs and return
+/// the new total. Nothing is written when no calls were found, so logs the
+/// agent has since cleaned up don't erase what was recorded.
+/// Record the start or end of an idle period when a task's agent goes quiet
+/// or picks up work again. `was_idle` is None the first time this run sees
+/// the task; an idle period may then still be open from before a restart.
+fn record_idle_change(
+    db: &Database,
+    task_id: &str,
+    was_idle: Option<bool>,
+    is_idle: bool,
+    quiet_for: std::time::Duration,
+) -> Result<()> {
+    let was_idle = match was_idle {
+        Some(was_idle) => was_idle,
+        None => db
+            .get_task_events(task_id)?
+            .iter()
+            .rev()
+            .find(|e| {
+                matches!(
+                    e.kind,
+                    TaskEventKind::IdleStarted | TaskEventKind::IdleEnded
+                )
+            })
+            .is_some_and(|e| e.kind == TaskEventKind::IdleStarted),
+    };
+    if was_idle == is_idle {
+        return Ok(());
+    }
+    let kind = if is_idle {
+        TaskEventKind::IdleStarted
+    } else {
+        TaskEventKind::IdleEnded
+    };
+    let mut event = crate::db::TaskEvent::new(task_id, kind, db.actor());
+    if is_idle {
+        event.created_at -= chrono::Duration::from_std(quiet_for).unwrap_or_default();
+    }
+    db.add_task_event(&event)
+}
+
+/// Whole minutes a task's agents have spent in Planning and Running
+fn agent_minutes(events: &[crate::db::TaskEvent], now: chrono::DateTime<chrono::Utc>) -> u64 {
+    crate::db::status_durations(events, now)
+        .iter()
+        .filter(|(status, _)| matches!(status, TaskStatus::Planning | TaskStatus::Running))
+        .map(|(_, d)| d.num_minutes().max(0) as u64)
+        .sum()
+}
+
+fn record_task_usage(
+    db: &Database,
+    task_id: &str,
+    records: &[crate::usage::UsageRecord],
+) -> Result<Option<Usage>> {
+    if records.is_empty() {
+        return Ok(None);
+    }
+    let events = db.get_task_events(task_id)?;
+    let rows = crate::usage::by_phase(task_id, records, &events);
+    db.replace_task_usage(task_id, &rows)?;
+    let mut total = Usage::default();
+    for row in &rows {
+        total.add(&row.usage);
+    }
+    Ok(Some(total))
+}
+
 /// Delete task resources: kill tmux window, run cleanup script, remove worktree, delete branch
 fn delete_task_resources(
     task: &Task,
@@ -7041,64 +9223,56 @@ fn delete_task_resources(
     }
 }
 
-/// Collect git diff content from a worktree
-/// Returns formatted diff sections (unstaged, staged, untracked)
-fn collect_task_diff(
-    worktree_path: &str,
-    git_ops: &dyn GitOperations,
-    exclude_prefixes: &[&str],
-) -> String {
-    let worktree = Path::new(worktree_path);
-    let mut sections = Vec::new();
-
-    // Unstaged changes (modified tracked files)
-    let unstaged = git_ops.diff(worktree);
-    if !unstaged.trim().is_empty() {
-        sections.push(format!("=== Unstaged Changes ===\n\n{}", unstaged));
-    }
-
-    // Staged changes
-    let staged = git_ops.diff_cached(worktree);
-    if !staged.trim().is_empty() {
-        sections.push(format!("=== Staged Changes ===\n\n{}", staged));
+/// Helper function to create a centered rect
+/// Render a task's history for the timeline popup: a time-in-status summary
+/// followed by one line per event (newest last).
+fn format_timeline(
+    events: &[crate::db::TaskEvent],
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<String> {
+    if events.is_empty() {
+        return vec!["(no history recorded for this task)".to_string()];
     }
 
-    // Untracked files - show as diff (new file content)
-    let untracked = git_ops.list_untracked_files(worktree);
-    if !untracked.trim().is_empty() {
-        let mut untracked_section = String::from("=== Untracked Files ===\n");
-        for file in untracked.lines() {
-            let file = file.trim();
-            if file.is_empty() {
-                continue;
-            }
-            // Skip files in copied directories (agent configs, plugin dirs)
-            if exclude_prefixes
-                .iter()
-                .any(|prefix| file.starts_with(&format!("{}/", prefix.trim_end_matches('/'))))
-            {
-                continue;
-            }
-            // Show diff for untracked file (as if adding new file)
-            let file_diff = git_ops.diff_untracked_file(worktree, file);
-            if !file_diff.trim().is_empty() {
-                untracked_section.push_str(&format!("\n{}", file_diff));
-            } else {
-                // Fallback: just show file name
-                untracked_section.push_str(&format!("\n+++ new file: {}\n", file));
-            }
-        }
-        sections.push(untracked_section);
+    let mut lines = Vec::new();
+    let durations = crate::db::status_durations(events, now);
+    let idle = crate::db::idle_durations(events, now);
+    if !durations.is_empty() {
+        let summary: Vec<String> = durations
+            .iter()
+            .filter(|(status, _)| *status != TaskStatus::Done)
+            .map(|(status, d)| {
+                match idle
+                    .iter()
+                    .find(|(s, i)| s == status && i.num_seconds() > 0)
+                {
+                    Some((_, i)) => format!(
+                        "{} {} (idle {})",
+                        status.as_str(),
+                        format_elapsed(*d),
+                        format_elapsed(*i)
+                    ),
+                    None => format!("{} {}", status.as_str(), format_elapsed(*d)),
+                }
+            })
+            .collect();
+        lines.push(format!("Time in status: {}", summary.join(" · ")));
+        lines.push(String::new());
     }
 
-    if sections.is_empty() {
-        format!("(no changes)\n\nWorktree: {}", worktree_path)
-    } else {
-        sections.join("\n\n")
+    for event in events {
+        let local = event.created_at.with_timezone(&chrono::Local);
+        lines.push(format!(
+            "{}  {:<12}  {}",
+            local.format("%Y-%m-%d %H:%M"),
+            event.actor.as_str(),
+            event.kind.as_str().replace('_', " ")
+        ));
+        lines.push(format!("  {}", event.summary()));
     }
+    lines
 }
 
-/// Helper function to create a centered rect
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -7200,6 +9374,63 @@ pub(crate) fn generate_pr_description(
     (default_title, default_body)
 }
 
+/// Ask the agent for a commit message describing a task's changes.
+/// Falls back to the task title when there is no worktree or generation fails.
+pub(crate) fn generate_merge_commit_message(
+    task: &Task,
+    git_ops: &dyn GitOperations,
+    agent_ops: &dyn AgentOperations,
+) -> String {
+    let Some(worktree) = task.worktree_path.as_deref() else {
+        return task.title.clone();
+    };
+    let worktree_path = Path::new(worktree);
+    let diff_stat = git_ops.diff_stat_from_main(worktree_path);
+    let prompt = format!(
+        "Write a git commit message for these changes. Task: '{}'.\n\nChanged files:\n{}\n\nOutput only the commit message: a summary line under 72 characters, a blank line, then at most three short lines of detail. No markdown code blocks.",
+        task.title,
+        diff_stat.trim()
+    );
+    match agent_ops.generate_text(worktree_path, &prompt) {
+        Ok(text) if !text.trim().is_empty() => text.trim().to_string(),
+        _ => task.title.clone(),
+    }
+}
+
+/// Commit any leftover work in the task's worktree, then merge/squash/rebase its branch into `base`
+fn merge_task_locally(
+    task: &Task,
+    project_path: &Path,
+    branch: &str,
+    base: &str,
+    strategy: MergeStrategy,
+    git_ops: &dyn GitOperations,
+    agent_ops: &dyn AgentOperations,
+) -> Result<()> {
+    let message = format!(
+        "{}\n\nCo-Authored-By: {}",
+        generate_merge_commit_message(task, git_ops, agent_ops),
+        agent_ops.co_author_string()
+    );
+
+    if let Some(worktree) = task.worktree_path.as_deref() {
+        let worktree_path = Path::new(worktree);
+        git_ops.add_all(worktree_path)?;
+        if git_ops.has_changes(worktree_path) {
+            git_ops.commit(worktree_path, &message)?;
+        }
+    }
+
+    git_ops.merge_into_base(
+        project_path,
+        task.worktree_path.clone(),
+        branch,
+        base,
+        strategy,
+        &message,
+    )
+}
+
 /// Create a PR with provided title and body, return (pr_number, pr_url)
 fn create_pr_with_content(
     task: &Task,
@@ -7318,6 +9549,34 @@ fn send_key_to_tmux(
     let _ = tmux_ops.send_keys_literal(window_name, &key_str);
 }
 
+/// Phase whose agent limit a task's agent counts against: research (a Backlog
+/// task with a session), planning or running. Review and Done agents don't count.
+fn agent_slot_phase(task: &Task) -> Option<&'static str> {
+    match task.status {
+        TaskStatus::Backlog if task.session_name.is_some() => Some("research"),
+        TaskStatus::Planning => Some("planning"),
+        TaskStatus::Running => Some("running"),
+        _ => None,
+    }
+}
+
+/// Load the tail of a transcript as styled lines for the transcript popup.
+fn load_transcript_lines(file: &crate::transcript::TranscriptFile) -> Vec<Line<'static>> {
+    match crate::transcript::read_tail(&file.path, TRANSCRIPT_POPUP_LINES) {
+        Ok(bytes) => parse_ansi_to_lines(&crate::transcript::keep_sgr_only(&bytes)),
+        Err(e) => vec![Line::from(format!("(failed to read transcript: {})", e))],
+    }
+}
+
+/// Human-readable file size (e.g. "512 B", "3.4 KB", "1.2 MB").
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
 /// Parse ANSI escape sequences to ratatui Lines with colors
 fn parse_ansi_to_lines(bytes: &[u8]) -> Vec<Line<'static>> {
     let text = String::from_utf8_lossy(bytes);
@@ -7714,11 +9973,12 @@ fn resolve_prompt(
 }
 
 /// Resolve the skill command to send via send_keys for a given phase.
-/// Returns the plugin command transformed for the target agent, or None if no command is configured.
+/// Returns the plugin command transformed for the target agent (`style` is its
+/// `skills::skill_style`), or None if no command is configured.
 fn resolve_skill_command(
     plugin: &Option<WorkflowPlugin>,
     phase: &str,
-    agent_name: &str,
+    style: &str,
     task_content: &str,
     cycle: i32,
 ) -> Option<String> {
@@ -7759,7 +10019,7 @@ fn resolve_skill_command(
             cmd.replace("{task}", &task_oneline)
         };
     let expanded = expanded.replace("{phase}", &cycle.to_string());
-    skills::transform_plugin_command(&expanded, agent_name)
+    skills::transform_plugin_command(&expanded, style)
 }
 
 /// Spawn a background thread that optionally switches agent, waits for readiness,
@@ -7779,6 +10039,7 @@ fn spawn_send_to_agent(
     worktree_path: Option<String>,
     project_path: std::path::PathBuf,
     plugin: Option<WorkflowPlugin>,
+    custom_agents: Vec<agent::Agent>,
 ) {
     std::thread::spawn(move || {
         // If the tmux window is gone, recover it with the agent's resume command
@@ -7798,24 +10059,29 @@ fn spawn_send_to_agent(
             // with a different agent (e.g. Claude for planning) and a new agent
             // (e.g. OpenCode for review) is switched in later.
             if let Some(ref wt_path) = worktree_path {
-                let already_deployed = skills::agent_native_skill_dir(&target_agent)
-                    .map(|(base, namespace)| {
-                        let dir = if namespace.is_empty() {
-                            Path::new(wt_path).join(base)
-                        } else {
-                            Path::new(wt_path).join(base).join(namespace)
-                        };
-                        dir.exists()
-                    })
+                let already_deployed = skills::native_skill_path(&target_agent, &custom_agents)
+                    .map(|rel| Path::new(wt_path).join(rel).exists())
                     .unwrap_or(true); // no native path for this agent — nothing to deploy
                 if !already_deployed {
-                    write_skills_to_worktree(wt_path, &project_path, &plugin, &[&target_agent]);
+                    write_skills_to_worktree(
+                        wt_path,
+                        &project_path,
+                        &plugin,
+                        &[&target_agent],
+                        &custom_agents,
+                    );
                 }
             }
             let agent_ops = agent_registry.get(&target_agent);
             let new_cmd = agent_ops.build_interactive_command("");
-            switch_agent_in_tmux(tmux_ops.as_ref(), &target, &current_agent, &new_cmd);
-            let _ = wait_for_agent_ready(&tmux_ops, &target);
+            switch_agent_in_tmux(
+                tmux_ops.as_ref(),
+                &target,
+                &current_agent,
+                &new_cmd,
+                &custom_agents,
+            );
+            let _ = wait_for_agent_ready(&tmux_ops, &target, &custom_agents);
         }
         let clear_context = plugin
             .as_ref()
@@ -8278,9 +10544,16 @@ const AGENT_ACTIVE_INDICATORS: &[&str] = &[
 /// Check if the pane is running a shell (i.e. the agent has exited).
 /// Returns true when `pane_current_command` reports a shell (bash, zsh, sh, fish)
 /// rather than an agent process.
-fn is_pane_at_shell(tmux_ops: &dyn TmuxOperations, target: &str) -> bool {
+fn is_pane_at_shell(
+    tmux_ops: &dyn TmuxOperations,
+    target: &str,
+    custom_agents: &[agent::Agent],
+) -> bool {
     if let Some(cmd) = tmux_ops.pane_current_command(target) {
         !AGENT_COMMANDS.iter().any(|a| cmd.contains(a))
+            && !custom_agents
+                .iter()
+                .any(|a| Path::new(&a.command).file_name() == Path::new(&cmd).file_name())
     } else {
         false
     }
@@ -8372,9 +10645,13 @@ fn run_orchestrator_catchup(
 /// Check if an agent is actively running in the pane.
 /// Uses both `pane_current_command` (works for Claude, Codex, Copilot) and
 /// pane content indicators (works for Gemini which runs inside bash).
-fn is_agent_active(tmux_ops: &dyn TmuxOperations, target: &str) -> bool {
+fn is_agent_active(
+    tmux_ops: &dyn TmuxOperations,
+    target: &str,
+    custom_agents: &[agent::Agent],
+) -> bool {
     // Check 1: agent process visible in pane_current_command
-    if !is_pane_at_shell(tmux_ops, target) {
+    if !is_pane_at_shell(tmux_ops, target, custom_agents) {
         return true;
     }
     // Check 2: check the bottom of the visible pane for agent UI indicators.
@@ -8437,6 +10714,7 @@ fn switch_agent_in_tmux(
     target: &str,
     current_agent: &str,
     new_agent_cmd: &str,
+    custom_agents: &[agent::Agent],
 ) {
     // 1. Send the graceful exit command for the current agent.
     let exit_cmd = match current_agent {
@@ -8478,7 +10756,7 @@ fn switch_agent_in_tmux(
     for _ in 0..30 {
         // 3s
         std::thread::sleep(std::time::Duration::from_millis(100));
-        if !is_agent_active(tmux_ops, target) {
+        if !is_agent_active(tmux_ops, target, custom_agents) {
             found_shell = true;
             break;
         }
@@ -8511,7 +10789,7 @@ fn switch_agent_in_tmux(
         for _ in 0..50 {
             // 5s
             std::thread::sleep(std::time::Duration::from_millis(100));
-            if !is_agent_active(tmux_ops, target) {
+            if !is_agent_active(tmux_ops, target, custom_agents) {
                 found_shell = true;
                 break;
             }
@@ -8524,7 +10802,7 @@ fn switch_agent_in_tmux(
         for _ in 0..20 {
             // 2s
             std::thread::sleep(std::time::Duration::from_millis(100));
-            if !is_agent_active(tmux_ops, target) {
+            if !is_agent_active(tmux_ops, target, custom_agents) {
                 break;
             }
         }
@@ -8560,7 +10838,11 @@ fn switch_agent_in_tmux(
 /// 3s of no pane content changes = agent has finished loading its TUI.
 const CONTENT_STABLE_THRESHOLD: u32 = 3;
 
-fn wait_for_agent_ready(tmux_ops: &Arc<dyn TmuxOperations>, target: &str) -> Option<String> {
+fn wait_for_agent_ready(
+    tmux_ops: &Arc<dyn TmuxOperations>,
+    target: &str,
+    custom_agents: &[agent::Agent],
+) -> Option<String> {
     // Step 1: detect the ready signal (up to 30s).
     // Three detection methods, whichever fires first:
     //   1. Agent process detected via pane_current_command (Claude, Codex, Copilot)
@@ -8575,7 +10857,7 @@ fn wait_for_agent_ready(tmux_ops: &Arc<dyn TmuxOperations>, target: &str) -> Opt
         std::thread::sleep(std::time::Duration::from_secs(1));
 
         // Check 1: agent process detected via pane_current_command
-        if !is_pane_at_shell(tmux_ops.as_ref(), target) {
+        if !is_pane_at_shell(tmux_ops.as_ref(), target, custom_agents) {
             break;
         }
 
@@ -8688,6 +10970,7 @@ fn write_skills_to_worktree(
     project_path: &Path,
     plugin: &Option<WorkflowPlugin>,
     agent_names: &[&str],
+    custom_agents: &[agent::Agent],
 ) {
     let agtx_dir = Path::new(worktree_path).join(".agtx");
     let _ = std::fs::create_dir_all(&agtx_dir);
@@ -8727,25 +11010,22 @@ fn write_skills_to_worktree(
     // Write to agent-native discovery paths (e.g. .claude/commands/agtx/)
     // Deploy for all configured agents so skills are available across phase transitions
     for agent_name in agent_names {
-        if let Some((base_dir, namespace)) = skills::agent_native_skill_dir(agent_name) {
-            let native_dir = if namespace.is_empty() {
-                Path::new(worktree_path).join(base_dir)
-            } else {
-                Path::new(worktree_path).join(base_dir).join(namespace)
-            };
+        if let Some(rel_dir) = skills::native_skill_path(agent_name, custom_agents) {
+            let native_dir = Path::new(worktree_path).join(rel_dir);
             let _ = std::fs::create_dir_all(&native_dir);
+            let style = skills::skill_style(agent_name, custom_agents);
 
             for (skill_dir_name, default_content) in skills::BUILTIN_SKILLS {
                 let content =
                     resolve_skill_content(plugin, skill_dir_name, project_path, default_content);
 
-                match *agent_name {
+                match style.as_str() {
                     "gemini" => {
                         // Gemini uses .toml command files with description + prompt fields
                         let description = skills::extract_description(&content)
                             .unwrap_or_else(|| format!("agtx {} phase skill", skill_dir_name));
                         let toml_content = skills::skill_to_gemini_toml(&description, &content);
-                        let filename = skills::skill_dir_to_filename(skill_dir_name, agent_name);
+                        let filename = skills::skill_dir_to_filename(skill_dir_name, &style);
                         let _ = std::fs::write(native_dir.join(&filename), toml_content);
                     }
                     "codex" | "cursor" => {
@@ -8758,13 +11038,13 @@ fn write_skills_to_worktree(
                         // OpenCode uses flat .md command files: .opencode/command/agtx-research.md
                         // Commands have description frontmatter + prompt template
                         let oc_content = transform_skill_for_opencode(&content);
-                        let filename = skills::skill_dir_to_filename(skill_dir_name, agent_name);
+                        let filename = skills::skill_dir_to_filename(skill_dir_name, &style);
                         let _ = std::fs::write(native_dir.join(&filename), oc_content);
                     }
                     _ => {
                         // Claude and others: .md files with transformed frontmatter
                         let content = transform_skill_frontmatter(&content);
-                        let filename = skills::skill_dir_to_filename(skill_dir_name, agent_name);
+                        let filename = skills::skill_dir_to_filename(skill_dir_name, &style);
                         let _ = std::fs::write(native_dir.join(&filename), content);
                     }
                 }
@@ -8775,32 +11055,35 @@ fn write_skills_to_worktree(
 
 /// Deploy a single skill to a target directory for the given agent.
 /// Writes both the canonical `.agtx/skills/` copy and the agent-native discovery path.
-fn deploy_skill(target_dir: &Path, skill_name: &str, content: &str, agent_name: &str) {
+fn deploy_skill(
+    target_dir: &Path,
+    skill_name: &str,
+    content: &str,
+    agent_name: &str,
+    custom_agents: &[agent::Agent],
+) {
     // Write canonical copy
     let canonical_dir = target_dir.join(".agtx/skills").join(skill_name);
     let _ = std::fs::create_dir_all(&canonical_dir);
     let _ = std::fs::write(canonical_dir.join("SKILL.md"), content);
 
     // Write to agent-native discovery path
-    if let Some((base_dir, namespace)) = skills::agent_native_skill_dir(agent_name) {
-        let native_dir = if namespace.is_empty() {
-            target_dir.join(base_dir)
-        } else {
-            target_dir.join(base_dir).join(namespace)
-        };
+    if let Some(rel_dir) = skills::native_skill_path(agent_name, custom_agents) {
+        let native_dir = target_dir.join(rel_dir);
         let _ = std::fs::create_dir_all(&native_dir);
 
-        match agent_name {
+        let style = skills::skill_style(agent_name, custom_agents);
+        match style.as_str() {
             "claude" | "copilot" => {
                 let transformed = transform_skill_frontmatter(content);
-                let filename = skills::skill_dir_to_filename(skill_name, agent_name);
+                let filename = skills::skill_dir_to_filename(skill_name, &style);
                 let _ = std::fs::write(native_dir.join(&filename), transformed);
             }
             "gemini" => {
                 let description = skills::extract_description(content)
                     .unwrap_or_else(|| format!("agtx {} skill", skill_name));
                 let toml_content = skills::skill_to_gemini_toml(&description, content);
-                let filename = skills::skill_dir_to_filename(skill_name, agent_name);
+                let filename = skills::skill_dir_to_filename(skill_name, &style);
                 let _ = std::fs::write(native_dir.join(&filename), toml_content);
             }
             "codex" | "cursor" => {
@@ -8810,7 +11093,7 @@ fn deploy_skill(target_dir: &Path, skill_name: &str, content: &str, agent_name:
             }
             "opencode" => {
                 let oc_content = transform_skill_for_opencode(content);
-                let filename = skills::skill_dir_to_filename(skill_name, agent_name);
+                let filename = skills::skill_dir_to_filename(skill_name, &style);
                 let _ = std::fs::write(native_dir.join(&filename), oc_content);
             }
             _ => {}
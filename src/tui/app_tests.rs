@@ -207,8 +207,14 @@ fn test_create_pr_with_content_success() {
         plugin: None,
         cycle: 1,
         referenced_tasks: None,
+        parent_id: None,
         escalation_note: None,
         base_branch: None,
+        queued_phase: None,
+        queued_at: None,
+        labels: Vec::new(),
+        priority: Priority::Normal,
+        due_date: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -304,8 +310,14 @@ fn test_create_pr_with_content_no_changes() {
         plugin: None,
         cycle: 1,
         referenced_tasks: None,
+        parent_id: None,
         escalation_note: None,
         base_branch: None,
+        queued_phase: None,
+        queued_at: None,
+        labels: Vec::new(),
+        priority: Priority::Normal,
+        due_date: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -359,8 +371,14 @@ fn test_create_pr_with_content_push_failure() {
         plugin: None,
         cycle: 1,
         referenced_tasks: None,
+        parent_id: None,
         escalation_note: None,
         base_branch: None,
+        queued_phase: None,
+        queued_at: None,
+        labels: Vec::new(),
+        priority: Priority::Normal,
+        due_date: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -420,8 +438,14 @@ fn test_push_changes_to_existing_pr_success() {
         plugin: None,
         cycle: 1,
         referenced_tasks: None,
+        parent_id: None,
         escalation_note: None,
         base_branch: None,
+        queued_phase: None,
+        queued_at: None,
+        labels: Vec::new(),
+        priority: Priority::Normal,
+        due_date: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -475,8 +499,14 @@ fn test_push_changes_to_existing_pr_no_changes() {
         plugin: None,
         cycle: 1,
         referenced_tasks: None,
+        parent_id: None,
         escalation_note: None,
         base_branch: None,
+        queued_phase: None,
+        queued_at: None,
+        labels: Vec::new(),
+        priority: Priority::Normal,
+        due_date: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -513,8 +543,14 @@ fn test_push_changes_to_existing_pr_no_url() {
         plugin: None,
         cycle: 1,
         referenced_tasks: None,
+        parent_id: None,
         escalation_note: None,
         base_branch: None,
+        queued_phase: None,
+        queued_at: None,
+        labels: Vec::new(),
+        priority: Priority::Normal,
+        due_date: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     };
@@ -1407,7 +1443,7 @@ fn test_word_boundary_roundtrip() {
 
 #[test]
 fn test_footer_text_sidebar_focused() {
-    let text = build_footer_text(InputMode::Normal, true, 0, false, false);
+    let text = build_footer_text(InputMode::Normal, true, 0, false, false, &Keymap::default());
     assert!(text.contains("[j/k] navigate"));
     assert!(text.contains("[e] hide sidebar"));
     assert!(!text.contains("[o] new"));
@@ -1415,7 +1451,14 @@ fn test_footer_text_sidebar_focused() {
 
 #[test]
 fn test_footer_text_backlog_column() {
-    let text = build_footer_text(InputMode::Normal, false, 0, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        0,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("[M] run"));
     assert!(text.contains("[m] plan"));
     assert!(!text.contains("[r] move left"));
@@ -1423,7 +1466,14 @@ fn test_footer_text_backlog_column() {
 
 #[test]
 fn test_footer_text_planning_column() {
-    let text = build_footer_text(InputMode::Normal, false, 1, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        1,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("[m] run"));
     assert!(!text.contains("[M] run"));
     assert!(!text.contains("[r] move left"));
@@ -1431,7 +1481,14 @@ fn test_footer_text_planning_column() {
 
 #[test]
 fn test_footer_text_running_column() {
-    let text = build_footer_text(InputMode::Normal, false, 2, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        2,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("[r] move left"));
     assert!(text.contains("[m] move"));
 }
@@ -1440,26 +1497,55 @@ fn test_footer_text_running_column() {
 fn test_footer_text_fullscreen_on_enter_hides_ctrl_f() {
     // Columns 1-3 should hide [C-f] when fullscreen_on_enter is true
     for col in 1..=3 {
-        let text = build_footer_text(InputMode::Normal, false, col, false, true);
-        assert!(!text.contains("[C-f]"), "Column {} should hide [C-f] when fullscreen_on_enter=true", col);
+        let text = build_footer_text(
+            InputMode::Normal,
+            false,
+            col,
+            false,
+            true,
+            &Keymap::default(),
+        );
+        assert!(
+            !text.contains("[C-f]"),
+            "Column {} should hide [C-f] when fullscreen_on_enter=true",
+            col
+        );
     }
     // And show it when false
     for col in 1..=3 {
-        let text = build_footer_text(InputMode::Normal, false, col, false, false);
-        assert!(text.contains("[C-f]"), "Column {} should show [C-f] when fullscreen_on_enter=false", col);
+        let text = build_footer_text(
+            InputMode::Normal,
+            false,
+            col,
+            false,
+            false,
+            &Keymap::default(),
+        );
+        assert!(
+            text.contains("[C-f]"),
+            "Column {} should show [C-f] when fullscreen_on_enter=false",
+            col
+        );
     }
 }
 
 #[test]
 fn test_footer_text_review_column() {
-    let text = build_footer_text(InputMode::Normal, false, 3, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        3,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("[r] move left"));
     assert!(text.contains("[m] move"));
 }
 
 #[test]
 fn test_footer_text_review_column_cyclic() {
-    let text = build_footer_text(InputMode::Normal, false, 3, true, false);
+    let text = build_footer_text(InputMode::Normal, false, 3, true, false, &Keymap::default());
     assert!(text.contains("[p] next phase"));
     assert!(text.contains("[r] resume"));
     assert!(text.contains("[m] done"));
@@ -1467,7 +1553,14 @@ fn test_footer_text_review_column_cyclic() {
 
 #[test]
 fn test_footer_text_done_column() {
-    let text = build_footer_text(InputMode::Normal, false, 4, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        4,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(!text.contains("[m] move"));
     assert!(!text.contains("[r]"));
     assert!(!text.contains("[d] diff"));
@@ -1475,20 +1568,54 @@ fn test_footer_text_done_column() {
 
 #[test]
 fn test_footer_text_input_title() {
-    let text = build_footer_text(InputMode::InputTitle, false, 0, false, false);
+    let text = build_footer_text(
+        InputMode::InputTitle,
+        false,
+        0,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("Enter task title"));
     assert!(text.contains("[Esc] cancel"));
 }
 
 #[test]
 fn test_footer_text_input_description() {
-    let text = build_footer_text(InputMode::InputDescription, false, 0, false, false);
+    let text = build_footer_text(
+        InputMode::InputDescription,
+        false,
+        0,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("[#] files"));
     assert!(text.contains("[/] skills"));
     assert!(text.contains("[!] tasks"));
     assert!(text.contains("[\\+Enter] newline"));
 }
 
+#[test]
+fn test_footer_text_uses_custom_keybindings() {
+    let mut config = std::collections::BTreeMap::new();
+    config.insert(
+        "move_forward".to_string(),
+        crate::config::KeyBinding::One("n".to_string()),
+    );
+    config.insert(
+        "fullscreen".to_string(),
+        crate::config::KeyBinding::One("C-o".to_string()),
+    );
+    let (keymap, _) = Keymap::from_config(&config);
+
+    let text = build_footer_text(InputMode::Normal, false, 1, false, false, &keymap);
+    assert!(text.contains("[n] run"));
+    assert!(text.contains("[C-o] fullscreen"));
+    assert!(!text.contains("[m]"));
+    assert!(text.contains("[?] help"));
+}
+
 // =============================================================================
 // Tests for setup_task_worktree
 // =============================================================================
@@ -1540,6 +1667,7 @@ fn test_setup_task_worktree_success() {
         &None,
         "claude",
         &vec!["claude".to_string()],
+        &[],
         &mock_tmux,
         &mock_git,
         &mock_agent,
@@ -1594,6 +1722,7 @@ fn test_setup_task_worktree_sets_task_fields() {
         &None,
         "claude",
         &vec!["claude".to_string()],
+        &[],
         &mock_tmux,
         &mock_git,
         &mock_agent,
@@ -1656,6 +1785,7 @@ fn test_setup_task_worktree_worktree_creation_fails() {
         &None,
         "claude",
         &vec!["claude".to_string()],
+        &[],
         &mock_tmux,
         &mock_git,
         &mock_agent,
@@ -1713,6 +1843,7 @@ fn test_setup_task_worktree_tmux_window_fails() {
         &None,
         "claude",
         &vec!["claude".to_string()],
+        &[],
         &mock_tmux,
         &mock_git,
         &mock_agent,
@@ -1766,6 +1897,7 @@ fn test_setup_task_worktree_creates_session_when_missing() {
         &None,
         "claude",
         &vec!["claude".to_string()],
+        &[],
         &mock_tmux,
         &mock_git,
         &mock_agent,
@@ -1822,6 +1954,7 @@ fn test_setup_task_worktree_passes_init_config() {
         &None,
         "claude",
         &vec!["claude".to_string()],
+        &[],
         &mock_tmux,
         &mock_git,
         &mock_agent,
@@ -2538,7 +2671,14 @@ fn test_install_plugin_none_clears_config() {
 
 #[test]
 fn test_footer_text_backlog_includes_research() {
-    let text = build_footer_text(InputMode::Normal, false, 0, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        0,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("[R] research"));
 }
 
@@ -2924,7 +3064,7 @@ fn test_is_pane_at_shell_returns_true_for_bash() {
         .withf(|t| t == "sess:win")
         .returning(|_| Some("bash".to_string()));
 
-    assert!(is_pane_at_shell(&mock, "sess:win"));
+    assert!(is_pane_at_shell(&mock, "sess:win", &[]));
 }
 
 #[test]
@@ -2935,7 +3075,7 @@ fn test_is_pane_at_shell_returns_true_for_zsh() {
         .withf(|t| t == "sess:win")
         .returning(|_| Some("zsh".to_string()));
 
-    assert!(is_pane_at_shell(&mock, "sess:win"));
+    assert!(is_pane_at_shell(&mock, "sess:win", &[]));
 }
 
 #[test]
@@ -2946,7 +3086,7 @@ fn test_is_pane_at_shell_returns_true_for_fish() {
         .withf(|t| t == "sess:win")
         .returning(|_| Some("fish".to_string()));
 
-    assert!(is_pane_at_shell(&mock, "sess:win"));
+    assert!(is_pane_at_shell(&mock, "sess:win", &[]));
 }
 
 #[test]
@@ -2957,7 +3097,7 @@ fn test_is_pane_at_shell_returns_false_for_claude() {
         .withf(|t| t == "sess:win")
         .returning(|_| Some("claude".to_string()));
 
-    assert!(!is_pane_at_shell(&mock, "sess:win"));
+    assert!(!is_pane_at_shell(&mock, "sess:win", &[]));
 }
 
 #[test]
@@ -2972,7 +3112,7 @@ fn test_is_pane_at_shell_returns_true_for_node() {
         .withf(|t| t == "sess:win")
         .returning(|_| Some("node".to_string()));
 
-    assert!(is_pane_at_shell(&mock, "sess:win"));
+    assert!(is_pane_at_shell(&mock, "sess:win", &[]));
 }
 
 #[test]
@@ -2983,7 +3123,7 @@ fn test_is_pane_at_shell_returns_false_for_codex() {
         .withf(|t| t == "sess:win")
         .returning(|_| Some("codex".to_string()));
 
-    assert!(!is_pane_at_shell(&mock, "sess:win"));
+    assert!(!is_pane_at_shell(&mock, "sess:win", &[]));
 }
 
 #[test]
@@ -2994,7 +3134,29 @@ fn test_is_pane_at_shell_returns_false_when_none() {
         .withf(|t| t == "sess:win")
         .returning(|_| None);
 
-    assert!(!is_pane_at_shell(&mock, "sess:win"));
+    assert!(!is_pane_at_shell(&mock, "sess:win", &[]));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_is_pane_at_shell_matches_custom_agent_basename_exactly() {
+    let config = crate::config::CustomAgentConfig {
+        name: "sh-agent".to_string(),
+        command: "sh".to_string(),
+        ..Default::default()
+    };
+    let custom = vec![agent::Agent::from_custom(&config)];
+
+    let mut mock = MockTmuxOperations::new();
+    mock.expect_pane_current_command()
+        .returning(|_| Some("sh".to_string()));
+    assert!(!is_pane_at_shell(&mock, "sess:win", &custom));
+
+    // `bash` contains `sh` but is a different program
+    let mut mock = MockTmuxOperations::new();
+    mock.expect_pane_current_command()
+        .returning(|_| Some("bash".to_string()));
+    assert!(is_pane_at_shell(&mock, "sess:win", &custom));
 }
 
 // === kill_windows_by_name tests ===
@@ -3089,7 +3251,7 @@ fn test_switch_agent_claude_sends_exit() {
         .returning(|_| Some("bash".to_string()));
     mock.expect_capture_pane().returning(|_| Ok(String::new()));
 
-    switch_agent_in_tmux(&mock, "sess:win", "claude", "codex");
+    switch_agent_in_tmux(&mock, "sess:win", "claude", "codex", &[]);
     assert!(
         exit_sent.load(Ordering::SeqCst),
         "/exit should be sent for claude"
@@ -3122,7 +3284,7 @@ fn test_switch_agent_gemini_sends_quit() {
         .returning(|_| Some("zsh".to_string()));
     mock.expect_capture_pane().returning(|_| Ok(String::new()));
 
-    switch_agent_in_tmux(&mock, "sess:win", "gemini", "claude");
+    switch_agent_in_tmux(&mock, "sess:win", "gemini", "claude", &[]);
     assert!(
         quit_sent.load(Ordering::SeqCst),
         "/quit should be sent for gemini"
@@ -3150,7 +3312,7 @@ fn test_switch_agent_codex_sends_ctrl_c() {
         .returning(|_| Some("bash".to_string()));
     mock.expect_capture_pane().returning(|_| Ok(String::new()));
 
-    switch_agent_in_tmux(&mock, "sess:win", "codex", "claude");
+    switch_agent_in_tmux(&mock, "sess:win", "codex", "claude", &[]);
     assert!(
         ctrl_c_sent.load(Ordering::SeqCst),
         "Ctrl+C should be sent for codex"
@@ -3325,7 +3487,14 @@ fn test_determine_phase_variant_review_passthrough() {
 
 #[test]
 fn test_footer_text_review_non_cyclic_no_next_phase() {
-    let text = build_footer_text(InputMode::Normal, false, 3, false, false);
+    let text = build_footer_text(
+        InputMode::Normal,
+        false,
+        3,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(!text.contains("[p] next phase"));
     assert!(text.contains("[m] move"));
 }
@@ -3712,7 +3881,7 @@ fn test_wait_for_agent_ready_detects_agent_process() {
     mock.expect_capture_pane().returning(|_| Ok(String::new()));
 
     let tmux: std::sync::Arc<dyn TmuxOperations> = std::sync::Arc::new(mock);
-    let result = wait_for_agent_ready(&tmux, "sess:win");
+    let result = wait_for_agent_ready(&tmux, "sess:win", &[]);
     assert_eq!(result, Some("sess:win".to_string()));
 }
 
@@ -3726,7 +3895,7 @@ fn test_wait_for_agent_ready_detects_ready_indicator() {
         .returning(|_| Ok("Welcome to Gemini\nType your message".to_string()));
 
     let tmux: std::sync::Arc<dyn TmuxOperations> = std::sync::Arc::new(mock);
-    let result = wait_for_agent_ready(&tmux, "sess:win");
+    let result = wait_for_agent_ready(&tmux, "sess:win", &[]);
     assert_eq!(result, Some("sess:win".to_string()));
 }
 
@@ -3747,7 +3916,7 @@ fn test_wait_for_agent_ready_claude_bypass_accept() {
     });
 
     let tmux: std::sync::Arc<dyn TmuxOperations> = std::sync::Arc::new(mock);
-    let result = wait_for_agent_ready(&tmux, "sess:win");
+    let result = wait_for_agent_ready(&tmux, "sess:win", &[]);
     assert_eq!(result, Some("sess:win".to_string()));
     let calls = literal_calls.lock().unwrap();
     assert!(
@@ -3766,7 +3935,7 @@ fn test_write_skills_to_worktree_claude() {
     let dir = tempfile::tempdir().unwrap();
     let wt = dir.path().to_string_lossy().to_string();
 
-    write_skills_to_worktree(&wt, dir.path(), &None, &["claude"]);
+    write_skills_to_worktree(&wt, dir.path(), &None, &["claude"], &[]);
 
     // Canonical skills
     assert!(dir.path().join(".agtx/skills/agtx-plan/SKILL.md").exists());
@@ -3798,7 +3967,7 @@ fn test_write_skills_to_worktree_gemini_toml() {
     let dir = tempfile::tempdir().unwrap();
     let wt = dir.path().to_string_lossy().to_string();
 
-    write_skills_to_worktree(&wt, dir.path(), &None, &["gemini"]);
+    write_skills_to_worktree(&wt, dir.path(), &None, &["gemini"], &[]);
 
     let toml_path = dir.path().join(".gemini/commands/agtx/plan.toml");
     assert!(toml_path.exists());
@@ -3818,7 +3987,7 @@ fn test_write_skills_to_worktree_codex() {
     let dir = tempfile::tempdir().unwrap();
     let wt = dir.path().to_string_lossy().to_string();
 
-    write_skills_to_worktree(&wt, dir.path(), &None, &["codex"]);
+    write_skills_to_worktree(&wt, dir.path(), &None, &["codex"], &[]);
 
     // Codex uses subdirectories with SKILL.md
     assert!(dir.path().join(".codex/skills/agtx-plan/SKILL.md").exists());
@@ -3833,7 +4002,7 @@ fn test_write_skills_to_worktree_opencode() {
     let dir = tempfile::tempdir().unwrap();
     let wt = dir.path().to_string_lossy().to_string();
 
-    write_skills_to_worktree(&wt, dir.path(), &None, &["opencode"]);
+    write_skills_to_worktree(&wt, dir.path(), &None, &["opencode"], &[]);
 
     let md_path = dir.path().join(".opencode/command/agtx-plan.md");
     assert!(md_path.exists());
@@ -4460,6 +4629,144 @@ fn test_wizard_uses_config_default_agent() {
     assert_eq!(tasks[0].agent, app.state.config.default_agent);
 }
 
+#[test]
+fn test_parse_title_tags() {
+    let tags = parse_title_tags("Fix #123 crash #bug #auth !high due:2026-03-01");
+    assert_eq!(tags.title, "Fix #123 crash");
+    assert_eq!(tags.labels, ["bug", "auth"]);
+    assert_eq!(tags.priority, Priority::High);
+    assert_eq!(tags.due_date.unwrap().to_string(), "2026-03-01");
+
+    // Invalid tags and a lone first word stay in the title
+    let tags = parse_title_tags("Ship it !asap due:soon");
+    assert_eq!(tags.title, "Ship it !asap due:soon");
+    assert_eq!(tags.priority, Priority::Normal);
+    assert_eq!(parse_title_tags("#bug").title, "#bug");
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_wizard_title_tags_set_labels_priority_and_due_date() {
+    let mut app = make_test_app();
+    press_key(&mut app, KeyCode::Char('o'));
+    type_str(&mut app, "Fix login #auth !urgent due:2026-03-01");
+    press_key(&mut app, KeyCode::Enter);
+    assert_eq!(app.state.pending_task_title, "Fix login");
+    press_key(&mut app, KeyCode::Enter);
+
+    let task = app.state.board.tasks[0].clone();
+    assert_eq!(task.title, "Fix login");
+    assert_eq!(task.labels, ["auth"]);
+    assert_eq!(task.priority, Priority::Urgent);
+    assert_eq!(task.due_date.unwrap().to_string(), "2026-03-01");
+
+    // Editing starts from the title with its tags; removing them clears the fields
+    press_key(&mut app, KeyCode::Enter);
+    assert_eq!(
+        app.state.input_buffer,
+        "Fix login #auth !urgent due:2026-03-01"
+    );
+    app.state.input_buffer = "Fix login #auth".to_string();
+    press_key(&mut app, KeyCode::Enter);
+    press_key(&mut app, KeyCode::Enter);
+    let task = app.state.board.tasks[0].clone();
+    assert_eq!(task.labels, ["auth"]);
+    assert_eq!(task.priority, Priority::Normal);
+    assert_eq!(task.due_date, None);
+}
+
+// --- Board filter and views ---
+
+#[cfg(feature = "test-mocks")]
+fn visible_titles(app: &App) -> Vec<String> {
+    app.state
+        .board
+        .tasks_in_column(0)
+        .iter()
+        .map(|t| t.title.clone())
+        .collect()
+}
+
+#[cfg(feature = "test-mocks")]
+fn make_test_app_with_labeled_tasks() -> App {
+    let mut app = make_test_app();
+    for title in ["Fix login #auth", "Write docs #docs"] {
+        press_key(&mut app, KeyCode::Char('o'));
+        type_str(&mut app, title);
+        press_key(&mut app, KeyCode::Enter);
+        press_key(&mut app, KeyCode::Enter);
+    }
+    app
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_filter_bar_filters_as_you_type() {
+    let mut app = make_test_app_with_labeled_tasks();
+    assert_eq!(visible_titles(&app).len(), 2);
+
+    press_key(&mut app, KeyCode::Char('f'));
+    assert!(app.state.filter_bar.is_some());
+    type_str(&mut app, "#auth");
+    assert_eq!(visible_titles(&app), ["Fix login"]);
+
+    // Enter keeps the filter, also across refreshes
+    press_key(&mut app, KeyCode::Enter);
+    assert!(app.state.filter_bar.is_none());
+    app.refresh_tasks().unwrap();
+    assert_eq!(visible_titles(&app), ["Fix login"]);
+
+    // Esc restores the filter the bar was opened with
+    press_key(&mut app, KeyCode::Char('f'));
+    press_key(&mut app, KeyCode::Backspace);
+    press_key(&mut app, KeyCode::Backspace);
+    press_key(&mut app, KeyCode::Backspace);
+    press_key(&mut app, KeyCode::Backspace);
+    press_key(&mut app, KeyCode::Backspace);
+    assert_eq!(visible_titles(&app).len(), 2);
+    press_key(&mut app, KeyCode::Esc);
+    assert_eq!(app.state.board_filter.query(), "#auth");
+    assert_eq!(visible_titles(&app), ["Fix login"]);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_save_and_cycle_board_views() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = make_test_app_with_labeled_tasks();
+    app.state.project_path = Some(dir.path().to_path_buf());
+
+    // Without saved views there is nothing to cycle
+    press_key(&mut app, KeyCode::Char('v'));
+    assert!(app.state.warning_message.is_some());
+    assert!(app.state.board_filter.is_empty());
+
+    press_key(&mut app, KeyCode::Char('f'));
+    type_str(&mut app, "label:docs");
+    app.handle_key(crossterm::event::KeyEvent::new(
+        KeyCode::Char('s'),
+        crossterm::event::KeyModifiers::CONTROL,
+    ))
+    .unwrap();
+    type_str(&mut app, "Docs");
+    press_key(&mut app, KeyCode::Enter);
+    assert!(app.state.filter_bar.is_none());
+    assert_eq!(app.state.active_view, Some(0));
+
+    let saved = crate::config::ProjectConfig::load(dir.path()).unwrap();
+    assert_eq!(saved.views.len(), 1);
+    assert_eq!(saved.views[0].name, "Docs");
+    assert_eq!(saved.views[0].filter, "label:docs");
+
+    // v steps through the views and back to the unfiltered board
+    press_key(&mut app, KeyCode::Char('v'));
+    assert_eq!(app.state.active_view, None);
+    assert_eq!(visible_titles(&app).len(), 2);
+    press_key(&mut app, KeyCode::Char('v'));
+    assert_eq!(app.state.active_view, Some(0));
+    assert_eq!(visible_titles(&app), ["Write docs"]);
+}
+
 // --- Trigger Swap: / for skills, ! for task refs ---
 
 #[test]
@@ -4793,7 +5100,14 @@ fn test_cursor_display_pos_emoji_is_wide() {
 #[test]
 #[cfg(feature = "test-mocks")]
 fn test_footer_text_select_plugin() {
-    let text = build_footer_text(InputMode::SelectPlugin, false, 0, false, false);
+    let text = build_footer_text(
+        InputMode::SelectPlugin,
+        false,
+        0,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(text.contains("select plugin"));
     assert!(text.contains("Tab"));
     assert!(text.contains("Enter"));
@@ -4803,7 +5117,14 @@ fn test_footer_text_select_plugin() {
 #[test]
 #[cfg(feature = "test-mocks")]
 fn test_footer_text_description_shows_all_triggers() {
-    let text = build_footer_text(InputMode::InputDescription, false, 0, false, false);
+    let text = build_footer_text(
+        InputMode::InputDescription,
+        false,
+        0,
+        false,
+        false,
+        &Keymap::default(),
+    );
     assert!(
         text.contains("[#] files"),
         "Missing files trigger: {}",
@@ -5387,6 +5708,25 @@ fn test_transition_to_review_existing_pr_spawns_push() {
     assert!(app.state.pr_creation_rx.is_some());
 }
 
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_poll_pr_creation_reports_result_and_clears_receiver() {
+    let mut app = make_test_app();
+    let (tx, rx) = mpsc::channel();
+    app.state.pr_creation_rx = Some(rx);
+
+    app.poll_pr_creation().unwrap();
+    assert!(app.state.pr_creation_rx.is_some());
+
+    tx.send(Ok((7, "https://example.com/pull/7".to_string())))
+        .unwrap();
+    app.poll_pr_creation().unwrap();
+    assert!(app.state.pr_creation_rx.is_none());
+    let popup = app.state.pr_status_popup.as_ref().unwrap();
+    assert!(matches!(popup.status, PrCreationStatus::Success));
+    assert_eq!(popup.pr_url.as_deref(), Some("https://example.com/pull/7"));
+}
+
 // --- transition_to_done ---
 
 #[test]
@@ -5494,6 +5834,153 @@ fn test_transition_to_done_clean_clears_session_and_worktree() {
     assert!(app.state.done_confirm_popup.is_none());
 }
 
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transition_to_review_local_strategy_skips_pr_popup() {
+    // Local finish strategy → no PR will be created, so move straight to Review
+    let mut app = make_test_app();
+    app.state.config.finish_strategy = crate::config::FinishStrategy::Squash;
+
+    let mut task = make_test_task("t1", "Implement feature", TaskStatus::Running);
+    task.pr_number = None;
+
+    let result = app.transition_to_review(&mut task, Path::new("/tmp/test-project"));
+
+    assert!(!result.unwrap());
+    assert!(app.state.review_confirm_popup.is_none());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transition_to_done_local_squash_merges_and_moves_to_done() {
+    let mut mock_tmux = MockTmuxOperations::new();
+    mock_tmux.expect_kill_window().returning(|_| Ok(()));
+
+    let mut mock_git = MockGitOperations::new();
+    mock_git
+        .expect_check_base_checkout()
+        .withf(|_, base| base == "main")
+        .returning(|_, _| Ok(()));
+    mock_git
+        .expect_merge_conflicts()
+        .returning(|_, _, _| Ok(vec![]));
+    mock_git
+        .expect_diff_stat_from_main()
+        .returning(|_| " src/lib.rs | 2 +-".to_string());
+    mock_git.expect_add_all().returning(|_| Ok(()));
+    mock_git.expect_has_changes().returning(|_| false);
+    mock_git
+        .expect_merge_into_base()
+        .withf(|_, wt, branch, base, strategy, message| {
+            wt.as_deref() == Some("/tmp/wt")
+                && branch == "task/t1"
+                && base == "main"
+                && *strategy == crate::git::MergeStrategy::Squash
+                && message.starts_with("Add feature\n\nCo-Authored-By: Test <test@test.com>")
+        })
+        .times(1)
+        .returning(|_, _, _, _, _, _| Ok(()));
+    mock_git.expect_remove_worktree().returning(|_, _| Ok(()));
+
+    let mut mock_agent_ops = MockAgentOperations::new();
+    mock_agent_ops
+        .expect_generate_text()
+        .returning(|_, _| Ok("Add feature".to_string()));
+    mock_agent_ops
+        .expect_co_author_string()
+        .return_const("Test <test@test.com>".to_string());
+    let mock_agent_arc: Arc<dyn AgentOperations> = Arc::new(mock_agent_ops);
+    let mut mock_registry = MockAgentRegistry::new();
+    mock_registry
+        .expect_get()
+        .returning(move |_| Arc::clone(&mock_agent_arc));
+
+    let mut app = App::new_for_test(
+        Some(PathBuf::from("/tmp/test-project")),
+        Arc::new(mock_tmux),
+        Arc::new(mock_git),
+        Arc::new(MockGitProviderOperations::new()),
+        Arc::new(mock_registry),
+    )
+    .unwrap();
+    app.state.config.finish_strategy = crate::config::FinishStrategy::Squash;
+
+    let mut task = make_test_task("t1", "My task", TaskStatus::Review);
+    task.session_name = Some("test-project:task-t1".to_string());
+    task.worktree_path = Some("/tmp/wt".to_string());
+    task.branch_name = Some("task/t1".to_string());
+    task.base_branch = Some("main".to_string());
+    app.state.db.as_ref().unwrap().create_task(&task).unwrap();
+
+    let result = app.transition_to_done(&mut task, Path::new("/tmp/test-project"));
+    assert!(result.unwrap());
+    assert_eq!(
+        app.state.local_merge_popup.as_ref().unwrap().status,
+        LocalMergeStatus::Merging
+    );
+
+    // Poll like the run loop until the background merge reports back
+    let deadline = Instant::now() + std::time::Duration::from_secs(5);
+    while app.state.local_merge_rx.is_some() && Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        app.poll_local_merge().unwrap();
+    }
+
+    let updated = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .get_task("t1")
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.status, TaskStatus::Done);
+    assert!(updated.worktree_path.is_none());
+    assert!(updated.session_name.is_none());
+    assert!(app.state.local_merge_rx.is_none());
+    assert!(matches!(
+        app.state.local_merge_popup.as_ref().unwrap().status,
+        LocalMergeStatus::Success(_)
+    ));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transition_to_done_local_merge_conflicts_stays_in_review() {
+    let mut mock_git = MockGitOperations::new();
+    mock_git
+        .expect_check_base_checkout()
+        .returning(|_, _| Ok(()));
+    mock_git
+        .expect_merge_conflicts()
+        .returning(|_, _, _| Ok(vec!["src/lib.rs".to_string()]));
+    mock_git.expect_merge_into_base().never();
+
+    let mut app = App::new_for_test(
+        Some(PathBuf::from("/tmp/test-project")),
+        Arc::new(MockTmuxOperations::new()),
+        Arc::new(mock_git),
+        Arc::new(MockGitProviderOperations::new()),
+        Arc::new(MockAgentRegistry::new()),
+    )
+    .unwrap();
+    app.state.config.finish_strategy = crate::config::FinishStrategy::Merge;
+
+    let mut task = make_test_task("t1", "My task", TaskStatus::Review);
+    task.worktree_path = Some("/tmp/wt".to_string());
+    task.branch_name = Some("task/t1".to_string());
+    task.base_branch = Some("main".to_string());
+
+    let result = app.transition_to_done(&mut task, Path::new("/tmp/test-project"));
+
+    assert!(result.unwrap());
+    assert!(app.state.local_merge_rx.is_none());
+    assert!(app.state.local_merge_popup.is_none());
+    let (warning, _) = app.state.warning_message.as_ref().unwrap();
+    assert!(warning.contains("src/lib.rs"));
+    assert_eq!(task.worktree_path.as_deref(), Some("/tmp/wt"));
+}
+
 // =============================================================================
 // Tests for apply_session_refresh
 // =============================================================================
@@ -5515,6 +6002,8 @@ fn make_session_task_status(
         session_name: None,
         agent: "claude".to_string(),
         was_ready,
+        transcript_phase: None,
+        usage: None,
     }
 }
 
@@ -5581,6 +6070,8 @@ fn test_apply_session_refresh_working_becomes_idle_after_15s() {
             session_name: None,
             agent: "claude".to_string(),
             was_ready: false,
+            transcript_phase: None,
+            usage: None,
         }],
     };
     app.apply_session_refresh(result);
@@ -5608,6 +6099,8 @@ fn test_apply_session_refresh_working_stays_working_hash_changed() {
             session_name: None,
             agent: "claude".to_string(),
             was_ready: false,
+            transcript_phase: None,
+            usage: None,
         }],
     };
     app.apply_session_refresh(result);
@@ -5711,9 +6204,234 @@ fn test_apply_session_refresh_multiple_tasks() {
     assert_eq!(app.state.phase_status_cache["t3"].0, PhaseStatus::Idle);
 }
 
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_apply_session_refresh_records_phase_status() {
+    // The phase status is written to the DB for MCP clients when it changes
+    let mut app = make_test_app();
+    app.apply_session_refresh(SessionRefreshResult {
+        statuses: vec![make_session_task_status(
+            "t1",
+            TaskStatus::Running,
+            PhaseStatus::Working,
+            false,
+        )],
+    });
+    let db = app.state.db.as_ref().unwrap();
+    assert_eq!(
+        db.get_phase_status("t1").unwrap(),
+        Some(PhaseStatus::Working)
+    );
+
+    app.apply_session_refresh(SessionRefreshResult {
+        statuses: vec![make_session_task_status(
+            "t1",
+            TaskStatus::Running,
+            PhaseStatus::Ready,
+            false,
+        )],
+    });
+    let db = app.state.db.as_ref().unwrap();
+    assert_eq!(db.get_phase_status("t1").unwrap(), Some(PhaseStatus::Ready));
+    assert_eq!(db.get_phase_status("t2").unwrap(), None);
+}
+
 // =============================================================================
-// Tests for popup confirmation handlers
-// =============================================================================
+// Tests for popup confirmation handlers
+// =============================================================================
+
+#[cfg(feature = "test-mocks")]
+fn make_budget_app(mock_tmux: MockTmuxOperations, budget: crate::config::BudgetConfig) -> App {
+    let mut app = App::new_for_test(
+        Some(PathBuf::from("/tmp/test-project")),
+        Arc::new(mock_tmux),
+        Arc::new(MockGitOperations::new()),
+        Arc::new(MockGitProviderOperations::new()),
+        Arc::new(MockAgentRegistry::new()),
+    )
+    .unwrap();
+    app.state.config.budget = budget;
+    app.state.orchestrator_session = Some("orch-session".to_string());
+    app
+}
+
+#[cfg(feature = "test-mocks")]
+fn add_task_with_cost(app: &mut App, id: &str, cost_usd: f64) {
+    let db = app.state.db.as_ref().unwrap();
+    let mut task = Task::new(id, "claude", "test-project");
+    task.id = id.to_string();
+    task.status = TaskStatus::Running;
+    task.session_name = Some(format!("proj:{}", id));
+    db.create_task(&task).unwrap();
+    let usage = crate::db::Usage {
+        output_tokens: 1000,
+        cost_usd,
+        ..Default::default()
+    };
+    db.replace_task_usage(
+        id,
+        &[crate::db::TaskUsage {
+            task_id: id.to_string(),
+            phase: "running".to_string(),
+            agent: "claude".to_string(),
+            usage,
+            updated_at: chrono::Utc::now(),
+        }],
+    )
+    .unwrap();
+    app.refresh_tasks().unwrap();
+}
+
+#[cfg(feature = "test-mocks")]
+fn usage_refresh(task_id: &str) -> SessionRefreshResult {
+    let mut status =
+        make_session_task_status(task_id, TaskStatus::Running, PhaseStatus::Working, false);
+    status.usage = Some(vec![]);
+    SessionRefreshResult {
+        statuses: vec![status],
+    }
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_task_over_budget_is_escalated_and_interrupted_once() {
+    let mut mock_tmux = MockTmuxOperations::new();
+    mock_tmux.expect_window_exists().returning(|_| Ok(false));
+    mock_tmux
+        .expect_send_keys_literal()
+        .withf(|target, keys| target == "proj:t1" && keys == "Escape")
+        .times(1)
+        .returning(|_, _| Ok(()));
+    let budget = crate::config::BudgetConfig {
+        task: crate::config::BudgetLimits {
+            usd: Some(1.0),
+            ..Default::default()
+        },
+        interrupt: true,
+        ..Default::default()
+    };
+    let mut app = make_budget_app(mock_tmux, budget);
+    add_task_with_cost(&mut app, "t1", 1.5);
+    add_task_with_cost(&mut app, "t2", 0.5);
+
+    app.apply_session_refresh(usage_refresh("t1"));
+    app.apply_session_refresh(usage_refresh("t1"));
+
+    let db = app.state.db.as_ref().unwrap();
+    let t1 = db.get_task("t1").unwrap().unwrap();
+    assert_eq!(
+        t1.escalation_note.as_deref(),
+        Some("Task budget exceeded: $1.50 of $1.00")
+    );
+    assert!(db
+        .get_task("t2")
+        .unwrap()
+        .unwrap()
+        .escalation_note
+        .is_none());
+    let notifs = db.peek_notifications().unwrap();
+    assert_eq!(notifs.len(), 1);
+    assert!(notifs[0].message.contains("needs attention"));
+}
+
+#[cfg(feature = "test-mocks")]
+fn forget_budget_guards(app: &mut App) {
+    // What a restart loses
+    app.state.budget_escalated.clear();
+    app.state.project_over_budget = false;
+    app.state.budget_escalations_loaded = false;
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_budget_escalation_survives_restart() {
+    let mut mock_tmux = MockTmuxOperations::new();
+    mock_tmux.expect_window_exists().returning(|_| Ok(false));
+    mock_tmux
+        .expect_send_keys_literal()
+        .times(2)
+        .returning(|_, _| Ok(()));
+    let budget = crate::config::BudgetConfig {
+        task: crate::config::BudgetLimits {
+            usd: Some(1.0),
+            ..Default::default()
+        },
+        project: crate::config::BudgetLimits {
+            usd: Some(2.0),
+            ..Default::default()
+        },
+        interrupt: true,
+        ..Default::default()
+    };
+    let mut app = make_budget_app(mock_tmux, budget);
+    add_task_with_cost(&mut app, "t1", 1.5);
+    // Already escalated for something else: the budget escalation is still recorded
+    add_task_with_cost(&mut app, "t2", 1.0);
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let mut t2 = db.get_task("t2").unwrap().unwrap();
+        t2.escalation_note = Some("Tests keep failing".to_string());
+        db.update_task(&t2).unwrap();
+    }
+    app.refresh_tasks().unwrap();
+
+    app.apply_session_refresh(usage_refresh("t1"));
+    let notified = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .peek_notifications()
+        .unwrap()
+        .len();
+    assert!(notified > 0);
+
+    forget_budget_guards(&mut app);
+    app.apply_session_refresh(usage_refresh("t1"));
+
+    let db = app.state.db.as_ref().unwrap();
+    assert_eq!(db.peek_notifications().unwrap().len(), notified);
+    assert_eq!(
+        db.get_task("t2")
+            .unwrap()
+            .unwrap()
+            .escalation_note
+            .as_deref(),
+        Some("Tests keep failing")
+    );
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_project_over_budget_escalates_every_active_task() {
+    let mut mock_tmux = MockTmuxOperations::new();
+    mock_tmux.expect_window_exists().returning(|_| Ok(false));
+    mock_tmux.expect_send_keys_literal().never();
+    let budget = crate::config::BudgetConfig {
+        project: crate::config::BudgetLimits {
+            usd: Some(1.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut app = make_budget_app(mock_tmux, budget);
+    add_task_with_cost(&mut app, "t1", 0.6);
+    add_task_with_cost(&mut app, "t2", 0.6);
+
+    app.apply_session_refresh(usage_refresh("t1"));
+
+    let db = app.state.db.as_ref().unwrap();
+    for id in ["t1", "t2"] {
+        let task = db.get_task(id).unwrap().unwrap();
+        assert_eq!(
+            task.escalation_note.as_deref(),
+            Some("Project budget exceeded: $1.20 of $1.00")
+        );
+    }
+    let notifs = db.peek_notifications().unwrap();
+    assert_eq!(notifs.len(), 3);
+    assert_eq!(notifs[0].message, "Project budget exceeded: $1.20 of $1.00");
+}
 
 // --- handle_done_confirm_key ---
 
@@ -6398,6 +7116,76 @@ fn test_execute_transition_request_move_to_done_calls_force_move() {
     assert_eq!(updated.status, TaskStatus::Done);
 }
 
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_execute_transition_request_rechecks_orchestrator_policy() {
+    // Queued while the task was Running, so move_forward meant Review; by the
+    // time it runs the task is in Review and the same action means Done
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(project.path().join(".agtx")).unwrap();
+    std::fs::write(
+        project.path().join(".agtx/orchestrator.toml"),
+        "[sign_off]\nlabels = [\"security\"]\n",
+    )
+    .unwrap();
+    let mut app = App::new_for_test(
+        Some(project.path().to_path_buf()),
+        Arc::new(MockTmuxOperations::new()),
+        Arc::new(MockGitOperations::new()),
+        Arc::new(MockGitProviderOperations::new()),
+        Arc::new(MockAgentRegistry::new()),
+    )
+    .unwrap();
+    let db = app.state.db.as_ref().unwrap();
+    let mut task = Task::new("Rotate keys", "claude", "test-project");
+    task.id = "t1".to_string();
+    task.status = TaskStatus::Review;
+    task.labels = vec!["security".to_string()];
+    db.create_task(&task).unwrap();
+    app.refresh_tasks().unwrap();
+
+    let mut req = crate::db::TransitionRequest::new("t1", "move_forward");
+    req.requested_by = Some(crate::db::EventActor::Orchestrator);
+    let err = app.execute_transition_request(&req).unwrap_err();
+    assert!(err.to_string().contains("human sign-off"));
+    let task = app.state.db.as_ref().unwrap().get_task("t1").unwrap();
+    assert_eq!(task.unwrap().status, TaskStatus::Review);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_parent_task_cannot_reach_done_before_subtasks() {
+    let mut app = make_test_app();
+    let db = app.state.db.as_ref().unwrap();
+    let mut parent = Task::new("Auth epic", "claude", "test-project");
+    parent.id = "parent".to_string();
+    parent.status = TaskStatus::Review;
+    db.create_task(&parent).unwrap();
+    let mut child = Task::new("Login form", "claude", "test-project");
+    child.set_parent(&parent);
+    child.status = TaskStatus::Running;
+    db.create_task(&child).unwrap();
+    app.refresh_tasks().unwrap();
+
+    let req = crate::db::TransitionRequest::new("parent", "move_to_done");
+    let err = app.execute_transition_request(&req).unwrap_err();
+    assert!(err.to_string().contains("subtasks"));
+    let req = crate::db::TransitionRequest::new("parent", "move_forward");
+    assert!(app.execute_transition_request(&req).is_err());
+
+    // Moving it from the board shows a warning instead
+    app.state.board.selected_column = 3;
+    app.state.board.selected_row = 0;
+    press_key(&mut app, KeyCode::Char('m'));
+    assert!(app.state.warning_message.is_some());
+    assert_eq!(
+        app.state.board.subtask_progress("parent"),
+        Some(SubtaskProgress { done: 0, total: 1 })
+    );
+    let parent = app.state.db.as_ref().unwrap().get_task("parent").unwrap();
+    assert_eq!(parent.unwrap().status, TaskStatus::Review);
+}
+
 #[test]
 #[cfg(feature = "test-mocks")]
 fn test_process_transition_requests_marks_processed() {
@@ -6447,6 +7235,483 @@ fn test_process_transition_requests_marks_processed() {
     );
 }
 
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_headless_tick_executes_queued_requests() {
+    let mut app = make_test_app();
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let mut task = Task::new("Escalate me", "claude", "test-project");
+        task.id = "t1".to_string();
+        task.status = TaskStatus::Running;
+        db.create_task(&task).unwrap();
+
+        let mut req = crate::db::TransitionRequest::new("t1", "escalate_to_user");
+        req.reason = Some("Blocked on credentials".to_string());
+        db.create_transition_request(&req).unwrap();
+    }
+
+    app.headless_tick().unwrap();
+
+    let db = app.state.db.as_ref().unwrap();
+    assert!(db.get_pending_transition_requests().unwrap().is_empty());
+    let task = db.get_task("t1").unwrap().unwrap();
+    assert_eq!(
+        task.escalation_note.as_deref(),
+        Some("Blocked on credentials")
+    );
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_headless_tick_survives_failed_request_and_picks_up_new_tasks() {
+    let mut app = make_test_app();
+    let req = crate::db::TransitionRequest::new("missing-task", "move_forward");
+    {
+        let db = app.state.db.as_ref().unwrap();
+        db.create_transition_request(&req).unwrap();
+        // Created out-of-band, e.g. by `agtx task add`
+        db.create_task(&Task::new("From CLI", "claude", "test-project"))
+            .unwrap();
+    }
+
+    app.headless_tick().unwrap();
+
+    let fresh = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .get_transition_request(&req.id)
+        .unwrap()
+        .unwrap();
+    assert!(fresh.processed_at.is_some());
+    assert!(fresh.error.is_some());
+    assert_eq!(app.state.board.tasks.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_headless_tick_attaches_orchestrator_started_later() {
+    let mut mock_tmux = MockTmuxOperations::new();
+    mock_tmux
+        .expect_window_exists()
+        .withf(|t| t.ends_with(":orchestrator"))
+        .returning(|_| Ok(true));
+    mock_tmux.expect_window_exists().returning(|_| Ok(false));
+    mock_tmux.expect_has_session().returning(|_| false);
+    // Readiness wait in the background thread
+    mock_tmux.expect_pane_current_command().returning(|_| None);
+    mock_tmux
+        .expect_capture_pane()
+        .returning(|_| Ok(String::new()));
+    let mut app = App::new_for_test(
+        Some(PathBuf::from("/tmp/test-project")),
+        Arc::new(mock_tmux),
+        Arc::new(MockGitOperations::new()),
+        Arc::new(MockGitProviderOperations::new()),
+        Arc::new(MockAgentRegistry::new()),
+    )
+    .unwrap();
+    app.state.flags.experimental = true;
+    assert!(app.state.orchestrator_session.is_none());
+
+    // Throttled: nothing happens until the detect interval has passed
+    app.headless_tick().unwrap();
+    assert!(app.state.orchestrator_session.is_none());
+
+    app.state.orchestrator_last_detect =
+        Instant::now() - std::time::Duration::from_secs(ORCHESTRATOR_DETECT_INTERVAL_SECS);
+    app.headless_tick().unwrap();
+    assert_eq!(
+        app.state.orchestrator_session.as_deref(),
+        Some("test-project:orchestrator")
+    );
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transition_request_events_attributed_to_requester() {
+    let mut app = make_test_app();
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let mut task = Task::new("Escalate me", "claude", "test-project");
+        task.id = "t1".to_string();
+        task.status = TaskStatus::Running;
+        db.create_task(&task).unwrap();
+
+        let mut req = crate::db::TransitionRequest::new("t1", "escalate_to_user");
+        req.requested_by = Some(crate::db::EventActor::Orchestrator);
+        db.create_transition_request(&req).unwrap();
+    }
+
+    app.process_transition_requests().unwrap();
+
+    let db = app.state.db.as_ref().unwrap();
+    let events = db.get_task_events("t1").unwrap();
+    let escalated = events
+        .iter()
+        .find(|e| e.kind == crate::db::TaskEventKind::Escalated)
+        .expect("escalation event recorded");
+    assert_eq!(escalated.actor, crate::db::EventActor::Orchestrator);
+    // Actor is restored once the request is done
+    assert_eq!(db.actor(), crate::db::EventActor::User);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_timeline_popup_opens_and_closes() {
+    let mut app = make_test_app();
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let mut task = Task::new("Has history", "claude", "test-project");
+        db.create_task(&task).unwrap();
+        task.status = TaskStatus::Planning;
+        db.update_task(&task).unwrap();
+    }
+    app.refresh_tasks().unwrap();
+    app.state.board.selected_column = 1;
+    app.state.board.selected_row = 0;
+
+    press_key(&mut app, KeyCode::Char('t'));
+    let popup = app
+        .state
+        .timeline_popup
+        .as_ref()
+        .expect("timeline popup open");
+    assert_eq!(popup.task_title, "Has history");
+    assert!(popup.lines[0].starts_with("Time in status:"));
+    assert!(popup.lines.iter().any(|l| l.contains("backlog → planning")));
+    app.draw().unwrap();
+
+    press_key(&mut app, KeyCode::Esc);
+    assert!(app.state.timeline_popup.is_none());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_apply_session_refresh_records_idle_periods() {
+    let mut app = make_test_app();
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let mut task = Task::new("Idles", "claude", "test-project");
+        task.id = "t1".to_string();
+        task.status = TaskStatus::Running;
+        db.create_task(&task).unwrap();
+    }
+    let refresh = |phase: PhaseStatus| SessionRefreshResult {
+        statuses: vec![make_session_task_status(
+            "t1",
+            TaskStatus::Running,
+            phase,
+            false,
+        )],
+    };
+    let idle_kinds = |app: &App| -> Vec<crate::db::TaskEventKind> {
+        app.state
+            .db
+            .as_ref()
+            .unwrap()
+            .get_task_events("t1")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.kind)
+            .filter(|k| *k != crate::db::TaskEventKind::Created)
+            .collect()
+    };
+
+    app.apply_session_refresh(refresh(PhaseStatus::Working));
+    assert!(idle_kinds(&app).is_empty());
+    app.apply_session_refresh(refresh(PhaseStatus::Idle));
+    app.apply_session_refresh(refresh(PhaseStatus::Idle));
+    app.apply_session_refresh(refresh(PhaseStatus::Working));
+    assert_eq!(
+        idle_kinds(&app),
+        [
+            crate::db::TaskEventKind::IdleStarted,
+            crate::db::TaskEventKind::IdleEnded
+        ]
+    );
+
+    // An idle period left open by a previous run is closed on first sight
+    app.apply_session_refresh(refresh(PhaseStatus::Idle));
+    app.state.phase_status_cache.clear();
+    app.apply_session_refresh(refresh(PhaseStatus::Ready));
+    assert_eq!(
+        idle_kinds(&app).last(),
+        Some(&crate::db::TaskEventKind::IdleEnded)
+    );
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_stats_popup_opens_and_closes() {
+    let mut app = make_test_app();
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let mut task = Task::new("Finished", "claude", "test-project");
+        db.create_task(&task).unwrap();
+        task.status = TaskStatus::Done;
+        db.update_task(&task).unwrap();
+    }
+    app.refresh_tasks().unwrap();
+
+    press_key(&mut app, KeyCode::Char('S'));
+    let popup = app.state.stats_popup.as_ref().expect("stats popup open");
+    assert_eq!(popup.lines[0], "Tasks: 1 finished of 1");
+    app.draw().unwrap();
+
+    press_key(&mut app, KeyCode::Char('q'));
+    assert!(app.state.stats_popup.is_none());
+    assert!(!app.state.should_quit);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_graph_popup_highlights_blocked_tasks() {
+    let mut app = make_test_app();
+    {
+        let db = app.state.db.as_ref().unwrap();
+        let schema = Task::new("Schema", "claude", "test-project");
+        db.create_task(&schema).unwrap();
+        let mut api = Task::new("API", "claude", "test-project");
+        api.referenced_tasks = Some(schema.id.clone());
+        db.create_task(&api).unwrap();
+    }
+    app.refresh_tasks().unwrap();
+
+    press_key(&mut app, KeyCode::Char('g'));
+    let popup = app.state.graph_popup.as_ref().expect("graph popup open");
+    let blocked: Vec<&str> = popup
+        .lines
+        .iter()
+        .filter(|l| l.blocked)
+        .map(|l| l.text.as_str())
+        .collect();
+    assert_eq!(blocked.len(), 1);
+    assert!(blocked[0].contains("API"));
+    app.draw().unwrap();
+
+    press_key(&mut app, KeyCode::Esc);
+    assert!(app.state.graph_popup.is_none());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_apply_session_refresh_tracks_transcript_pipes() {
+    let mut app = make_test_app();
+    let mut status =
+        make_session_task_status("t1", TaskStatus::Planning, PhaseStatus::Working, false);
+    status.transcript_phase = Some("planning");
+    app.apply_session_refresh(SessionRefreshResult {
+        statuses: vec![status],
+    });
+    assert_eq!(app.state.transcript_pipes.get("t1"), Some(&"planning"));
+
+    // Window gone → forget the pipe so a resumed window is piped again
+    app.apply_session_refresh(SessionRefreshResult {
+        statuses: vec![make_session_task_status(
+            "t1",
+            TaskStatus::Planning,
+            PhaseStatus::Exited,
+            false,
+        )],
+    });
+    assert!(!app.state.transcript_pipes.contains_key("t1"));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transcript_popup_browses_phases() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = make_test_app();
+    app.state.project_path = Some(dir.path().to_path_buf());
+    let mut task = Task::new("Logged", "claude", "test-project");
+    task.status = TaskStatus::Running;
+    app.state.db.as_ref().unwrap().create_task(&task).unwrap();
+    app.refresh_tasks().unwrap();
+    app.state.board.selected_column = 2;
+    app.state.board.selected_row = 0;
+
+    let logs = crate::transcript::transcript_dir(dir.path(), &task.id);
+    std::fs::create_dir_all(&logs).unwrap();
+    std::fs::write(logs.join("planning.log"), "plan output\r\n").unwrap();
+    std::fs::write(
+        logs.join("running.log"),
+        "\x1b[?25l\x1b[32mbuilding\x1b[0m\r\ndone\r\n",
+    )
+    .unwrap();
+
+    press_key(&mut app, KeyCode::Char('L'));
+    let text = |app: &App| -> Vec<String> {
+        let popup = app
+            .state
+            .transcript_popup
+            .as_ref()
+            .expect("transcript popup open");
+        popup.lines.iter().map(|l| l.to_string()).collect()
+    };
+    let popup = app.state.transcript_popup.as_ref().unwrap();
+    assert_eq!(popup.files.len(), 2);
+    // Opens on the task's current phase, escape noise removed
+    assert_eq!(popup.files[popup.selected].phase, "running");
+    assert_eq!(text(&app), vec!["building", "done"]);
+    app.draw().unwrap();
+
+    press_key(&mut app, KeyCode::Tab);
+    assert_eq!(text(&app), vec!["plan output"]);
+
+    press_key(&mut app, KeyCode::Char('q'));
+    assert!(app.state.transcript_popup.is_none());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transcript_popup_without_logs() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = make_test_app();
+    app.state.project_path = Some(dir.path().to_path_buf());
+    let task = Task::new("Fresh", "claude", "test-project");
+    app.state.db.as_ref().unwrap().create_task(&task).unwrap();
+    app.refresh_tasks().unwrap();
+
+    press_key(&mut app, KeyCode::Char('L'));
+    let popup = app.state.transcript_popup.as_ref().expect("popup open");
+    assert!(popup.files.is_empty());
+    assert!(popup.lines.is_empty());
+    app.draw().unwrap();
+    // Phase switching is a no-op with nothing recorded
+    press_key(&mut app, KeyCode::Tab);
+    press_key(&mut app, KeyCode::Esc);
+    assert!(app.state.transcript_popup.is_none());
+}
+
+/// App with one Running task holding an agent slot and a selected Planning task.
+#[cfg(feature = "test-mocks")]
+fn make_limited_app(limits: crate::config::AgentLimits) -> (App, String, String) {
+    let mut app = make_test_app();
+    app.state.config.agent_limits = limits;
+    let db = app.state.db.as_ref().unwrap();
+    let mut busy = Task::new("Busy", "claude", "test-project");
+    busy.status = TaskStatus::Running;
+    db.create_task(&busy).unwrap();
+    let mut next = Task::new("Next", "claude", "test-project");
+    next.status = TaskStatus::Planning;
+    db.create_task(&next).unwrap();
+    app.refresh_tasks().unwrap();
+    app.state.board.selected_column = 1;
+    app.state.board.selected_row = 0;
+    (app, busy.id, next.id)
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_move_to_running_queued_when_phase_limit_reached() {
+    let (mut app, _, next_id) = make_limited_app(crate::config::AgentLimits {
+        running: Some(1),
+        ..Default::default()
+    });
+
+    press_key(&mut app, KeyCode::Char('m'));
+
+    let task = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .get_task(&next_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(task.status, TaskStatus::Planning);
+    assert_eq!(task.queued_phase.as_deref(), Some("running"));
+    assert!(task.queued_at.is_some());
+    let (msg, _) = app.state.warning_message.as_ref().unwrap();
+    assert!(msg.contains("1/1 running agents"), "{}", msg);
+    app.draw().unwrap();
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_queued_task_starts_when_slot_frees() {
+    let (mut app, busy_id, next_id) = make_limited_app(crate::config::AgentLimits {
+        total: Some(1),
+        ..Default::default()
+    });
+    press_key(&mut app, KeyCode::Char('m'));
+    let queued = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .get_task(&next_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(queued.queued_phase.as_deref(), Some("running"));
+
+    // Still working → stays queued
+    app.apply_session_refresh(SessionRefreshResult {
+        statuses: vec![make_session_task_status(
+            &busy_id,
+            TaskStatus::Running,
+            PhaseStatus::Working,
+            false,
+        )],
+    });
+    let task = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .get_task(&next_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(task.status, TaskStatus::Planning);
+
+    // The busy agent finished its phase → its slot goes to the queued task
+    app.apply_session_refresh(SessionRefreshResult {
+        statuses: vec![make_session_task_status(
+            &busy_id,
+            TaskStatus::Running,
+            PhaseStatus::Ready,
+            false,
+        )],
+    });
+    let task = app
+        .state
+        .db
+        .as_ref()
+        .unwrap()
+        .get_task(&next_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(task.status, TaskStatus::Running);
+    assert!(task.queued_phase.is_none());
+    assert!(task.queued_at.is_none());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_agent_limit_ignores_other_phases_and_zero() {
+    let (app, _, next_id) = make_limited_app(crate::config::AgentLimits {
+        planning: Some(1),
+        running: Some(0),
+        ..Default::default()
+    });
+    // The only working agent is in Running; 0 means unlimited
+    assert!(app.agent_limit_reached("running", &next_id).is_none());
+    // The Planning task itself doesn't count against its own start
+    assert!(app.agent_limit_reached("planning", &next_id).is_none());
+    assert!(app.agent_limit_reached("planning", "other").is_some());
+}
+
+#[test]
+fn test_format_elapsed() {
+    assert_eq!(format_elapsed(chrono::Duration::seconds(42)), "42s");
+    assert_eq!(format_elapsed(chrono::Duration::minutes(12)), "12m");
+    assert_eq!(format_elapsed(chrono::Duration::minutes(185)), "3h 05m");
+    assert_eq!(format_elapsed(chrono::Duration::hours(52)), "2d 4h");
+}
+
 // =============================================================================
 // Tests for parse_ansi_to_lines and parse_sgr
 // =============================================================================
@@ -8052,7 +9317,7 @@ fn test_is_pane_at_shell_returns_true_for_shell_command() {
     mock_tmux
         .expect_pane_current_command()
         .returning(|_| Some("bash".to_string()));
-    assert!(is_pane_at_shell(&mock_tmux, "proj:task"));
+    assert!(is_pane_at_shell(&mock_tmux, "proj:task", &[]));
 }
 
 #[test]
@@ -8060,7 +9325,7 @@ fn test_is_pane_at_shell_returns_true_for_shell_command() {
 fn test_is_pane_at_shell_returns_false_when_no_command() {
     let mut mock_tmux = MockTmuxOperations::new();
     mock_tmux.expect_pane_current_command().returning(|_| None);
-    assert!(!is_pane_at_shell(&mock_tmux, "proj:task"));
+    assert!(!is_pane_at_shell(&mock_tmux, "proj:task", &[]));
 }
 
 // --- is_agent_active ---
@@ -8072,7 +9337,7 @@ fn test_is_agent_active_true_when_agent_process_running() {
     mock_tmux
         .expect_pane_current_command()
         .returning(|_| Some("claude".to_string()));
-    assert!(is_agent_active(&mock_tmux, "proj:task"));
+    assert!(is_agent_active(&mock_tmux, "proj:task", &[]));
 }
 
 #[test]
@@ -8086,7 +9351,7 @@ fn test_is_agent_active_true_when_gemini_indicator_in_pane() {
     mock_tmux
         .expect_capture_pane()
         .returning(|_| Ok("some output\nType your message\n".to_string()));
-    assert!(is_agent_active(&mock_tmux, "proj:task"));
+    assert!(is_agent_active(&mock_tmux, "proj:task", &[]));
 }
 
 #[test]
@@ -8099,7 +9364,7 @@ fn test_is_agent_active_false_when_at_shell_no_indicator() {
     mock_tmux
         .expect_capture_pane()
         .returning(|_| Ok("$ ".to_string()));
-    assert!(!is_agent_active(&mock_tmux, "proj:task"));
+    assert!(!is_agent_active(&mock_tmux, "proj:task", &[]));
 }
 
 // --- collect_task_diff ---
@@ -8539,6 +9804,7 @@ fn test_switch_agent_claude_sends_exit_then_new_cmd() {
         "proj:task",
         "claude",
         "claude --dangerously-skip-permissions",
+        &[],
     );
 }
 
@@ -8565,7 +9831,7 @@ fn test_switch_agent_codex_sends_ctrl_c_not_exit() {
         .times(1)
         .returning(|_, _| Ok(()));
 
-    switch_agent_in_tmux(&mock_tmux, "proj:task", "codex", "codex --full-auto");
+    switch_agent_in_tmux(&mock_tmux, "proj:task", "codex", "codex --full-auto", &[]);
 }
 
 #[test]
@@ -8609,7 +9875,7 @@ fn test_switch_agent_retries_with_ctrl_c_when_shell_not_found() {
         .times(1)
         .returning(|_, _| Ok(()));
 
-    switch_agent_in_tmux(&mock_tmux, "proj:task", "claude", "newagent");
+    switch_agent_in_tmux(&mock_tmux, "proj:task", "claude", "newagent", &[]);
 }
 
 #[test]
@@ -8644,7 +9910,7 @@ fn test_switch_agent_sends_ctrl_d_as_last_resort() {
         .times(1)
         .returning(|_, _| Ok(()));
 
-    switch_agent_in_tmux(&mock_tmux, "proj:task", "claude", "newagent");
+    switch_agent_in_tmux(&mock_tmux, "proj:task", "claude", "newagent", &[]);
 }
 
 #[test]
@@ -8669,7 +9935,7 @@ fn test_switch_agent_always_sends_new_agent_cmd() {
         .times(1)
         .returning(|_, _| Ok(()));
 
-    switch_agent_in_tmux(&mock_tmux, "proj:task", "claude", "my-new-agent");
+    switch_agent_in_tmux(&mock_tmux, "proj:task", "claude", "my-new-agent", &[]);
 }
 
 // --- wait_for_agent_ready ---
@@ -8690,6 +9956,7 @@ fn test_wait_for_agent_ready_returns_when_process_detected() {
     let result = wait_for_agent_ready(
         &(Arc::new(mock_tmux) as Arc<dyn TmuxOperations>),
         "proj:task",
+        &[],
     );
     assert_eq!(result, Some("proj:task".to_string()));
 }
@@ -8709,6 +9976,7 @@ fn test_wait_for_agent_ready_returns_when_ready_indicator_in_pane() {
     let result = wait_for_agent_ready(
         &(Arc::new(mock_tmux) as Arc<dyn TmuxOperations>),
         "proj:task",
+        &[],
     );
     assert_eq!(result, Some("proj:task".to_string()));
 }
@@ -8740,6 +10008,7 @@ fn test_wait_for_agent_ready_handles_claude_bypass_prompt() {
     let result = wait_for_agent_ready(
         &(Arc::new(mock_tmux) as Arc<dyn TmuxOperations>),
         "proj:task",
+        &[],
     );
     assert_eq!(result, Some("proj:task".to_string()));
 }
@@ -8771,6 +10040,7 @@ fn test_wait_for_agent_ready_returns_when_content_stabilizes() {
     let result = wait_for_agent_ready(
         &(Arc::new(mock_tmux) as Arc<dyn TmuxOperations>),
         "proj:task",
+        &[],
     );
     assert_eq!(result, Some("proj:task".to_string()));
 }
@@ -8796,6 +10066,7 @@ fn test_wait_for_agent_ready_always_returns_some() {
     let result = wait_for_agent_ready(
         &(Arc::new(mock_tmux) as Arc<dyn TmuxOperations>),
         "proj:task",
+        &[],
     );
     assert_eq!(result, Some("proj:task".to_string()));
 }
@@ -8813,7 +10084,7 @@ fn test_is_pane_at_shell_returns_true_for_shell_process() {
         mock.expect_pane_current_command()
             .returning(move |_| Some(shell_str.clone()));
         assert!(
-            is_pane_at_shell(&mock, "t"),
+            is_pane_at_shell(&mock, "t", &[]),
             "should be at shell for {}",
             shell
         );
@@ -8829,7 +10100,7 @@ fn test_is_pane_at_shell_returns_false_for_agent_processes() {
         mock.expect_pane_current_command()
             .returning(move |_| Some(agent_str.clone()));
         assert!(
-            !is_pane_at_shell(&mock, "t"),
+            !is_pane_at_shell(&mock, "t", &[]),
             "should not be at shell for {}",
             agent
         );
@@ -8845,7 +10116,7 @@ fn test_is_agent_active_detects_claude_via_indicator() {
     mock.expect_capture_pane()
         .returning(|_| Ok("Claude Code v2.1.72\n> ".to_string()));
     assert!(
-        is_agent_active(&mock, "t"),
+        is_agent_active(&mock, "t", &[]),
         "Claude Code indicator should trigger is_agent_active"
     );
 }
@@ -8859,7 +10130,7 @@ fn test_is_agent_active_detects_gemini_via_indicator() {
     mock.expect_capture_pane()
         .returning(|_| Ok("some output\nType your message".to_string()));
     assert!(
-        is_agent_active(&mock, "t"),
+        is_agent_active(&mock, "t", &[]),
         "Gemini indicator should trigger is_agent_active"
     );
 }
@@ -8873,7 +10144,7 @@ fn test_is_agent_active_detects_opencode_via_indicator() {
     mock.expect_capture_pane()
         .returning(|_| Ok("some output\nAsk anything".to_string()));
     assert!(
-        is_agent_active(&mock, "t"),
+        is_agent_active(&mock, "t", &[]),
         "OpenCode indicator should trigger is_agent_active"
     );
 }
@@ -8887,7 +10158,7 @@ fn test_is_agent_active_detects_cursor_via_indicator() {
     mock.expect_capture_pane()
         .returning(|_| Ok("some output\nCursor Agent\n> ".to_string()));
     assert!(
-        is_agent_active(&mock, "t"),
+        is_agent_active(&mock, "t", &[]),
         "Cursor indicator should trigger is_agent_active"
     );
 }
@@ -8901,7 +10172,7 @@ fn test_is_agent_active_detects_codex_via_indicator() {
     mock.expect_capture_pane()
         .returning(|_| Ok("some output\nOpenAI Codex".to_string()));
     assert!(
-        is_agent_active(&mock, "t"),
+        is_agent_active(&mock, "t", &[]),
         "Codex indicator should trigger is_agent_active"
     );
 }
@@ -8915,7 +10186,7 @@ fn test_is_agent_active_returns_false_when_no_indicator() {
     mock.expect_capture_pane()
         .returning(|_| Ok("just some shell output".to_string()));
     assert!(
-        !is_agent_active(&mock, "t"),
+        !is_agent_active(&mock, "t", &[]),
         "no indicator should return false"
     );
 }
@@ -8933,7 +10204,11 @@ fn test_wait_for_agent_ready_detects_claude_via_banner() {
         .returning(|_| Some("bash".to_string()));
     mock.expect_capture_pane()
         .returning(|_| Ok("Claude Code v2.1.72\nsome context".to_string()));
-    let result = wait_for_agent_ready(&(Arc::new(mock) as Arc<dyn TmuxOperations>), "proj:task");
+    let result = wait_for_agent_ready(
+        &(Arc::new(mock) as Arc<dyn TmuxOperations>),
+        "proj:task",
+        &[],
+    );
     assert_eq!(result, Some("proj:task".to_string()));
 }
 
@@ -8945,7 +10220,11 @@ fn test_wait_for_agent_ready_detects_cursor_via_banner() {
         .returning(|_| Some("bash".to_string()));
     mock.expect_capture_pane()
         .returning(|_| Ok("Cursor Agent\n> ".to_string()));
-    let result = wait_for_agent_ready(&(Arc::new(mock) as Arc<dyn TmuxOperations>), "proj:task");
+    let result = wait_for_agent_ready(
+        &(Arc::new(mock) as Arc<dyn TmuxOperations>),
+        "proj:task",
+        &[],
+    );
     assert_eq!(result, Some("proj:task".to_string()));
 }
 
@@ -8957,7 +10236,11 @@ fn test_wait_for_agent_ready_detects_opencode_via_banner() {
         .returning(|_| Some("bash".to_string()));
     mock.expect_capture_pane()
         .returning(|_| Ok("Ask anything\n> ".to_string()));
-    let result = wait_for_agent_ready(&(Arc::new(mock) as Arc<dyn TmuxOperations>), "proj:task");
+    let result = wait_for_agent_ready(
+        &(Arc::new(mock) as Arc<dyn TmuxOperations>),
+        "proj:task",
+        &[],
+    );
     assert_eq!(result, Some("proj:task".to_string()));
 }
 
@@ -8969,7 +10252,11 @@ fn test_wait_for_agent_ready_detects_codex_via_banner() {
         .returning(|_| Some("bash".to_string()));
     mock.expect_capture_pane()
         .returning(|_| Ok("OpenAI Codex\nsome output".to_string()));
-    let result = wait_for_agent_ready(&(Arc::new(mock) as Arc<dyn TmuxOperations>), "proj:task");
+    let result = wait_for_agent_ready(
+        &(Arc::new(mock) as Arc<dyn TmuxOperations>),
+        "proj:task",
+        &[],
+    );
     assert_eq!(result, Some("proj:task".to_string()));
 }
 
@@ -8999,7 +10286,7 @@ fn test_switch_agent_cursor_sends_ctrl_c_not_exit() {
         .times(1)
         .returning(|_, _| Ok(()));
 
-    switch_agent_in_tmux(&mock_tmux, "proj:task", "cursor", "agent --yolo");
+    switch_agent_in_tmux(&mock_tmux, "proj:task", "cursor", "agent --yolo", &[]);
 }
 
 #[test]
@@ -9027,7 +10314,7 @@ fn test_switch_agent_opencode_sends_exit() {
         .expect_capture_pane()
         .returning(|_| Ok(String::new()));
 
-    switch_agent_in_tmux(&mock_tmux, "proj:task", "opencode", "opencode");
+    switch_agent_in_tmux(&mock_tmux, "proj:task", "opencode", "opencode", &[]);
     assert!(
         exit_sent.load(std::sync::atomic::Ordering::SeqCst),
         "/exit should be sent for opencode"
@@ -9126,7 +10413,7 @@ fn test_write_skills_to_worktree_cursor() {
     let dir = tempfile::tempdir().unwrap();
     let wt = dir.path().to_string_lossy().to_string();
 
-    write_skills_to_worktree(&wt, dir.path(), &None, &["cursor"]);
+    write_skills_to_worktree(&wt, dir.path(), &None, &["cursor"], &[]);
 
     // Cursor uses subdirectories with SKILL.md (same structure as Codex)
     assert!(
@@ -9356,7 +10643,7 @@ fn test_deploy_skill_writes_canonical_path() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan\n---\nPlan the work.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "claude");
+    deploy_skill(dir.path(), "agtx-plan", content, "claude", &[]);
 
     assert!(
         dir.path().join(".agtx/skills/agtx-plan/SKILL.md").exists(),
@@ -9369,7 +10656,7 @@ fn test_deploy_skill_claude_transforms_frontmatter() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan\n---\nPlan the work.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "claude");
+    deploy_skill(dir.path(), "agtx-plan", content, "claude", &[]);
 
     let native = dir.path().join(".claude/commands/agtx/plan.md");
     assert!(native.exists(), ".claude/commands/agtx/plan.md should be written");
@@ -9385,7 +10672,7 @@ fn test_deploy_skill_gemini_writes_toml() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan the work\n---\nPlan it.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "gemini");
+    deploy_skill(dir.path(), "agtx-plan", content, "gemini", &[]);
 
     let native = dir.path().join(".gemini/commands/agtx/plan.toml");
     assert!(native.exists(), ".gemini/commands/agtx/plan.toml should be written");
@@ -9399,7 +10686,7 @@ fn test_deploy_skill_codex_writes_skill_subdir() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan\n---\nPlan it.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "codex");
+    deploy_skill(dir.path(), "agtx-plan", content, "codex", &[]);
 
     assert!(
         dir.path().join(".codex/skills/agtx-plan/SKILL.md").exists(),
@@ -9412,7 +10699,7 @@ fn test_deploy_skill_opencode_writes_flat_md() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan the work\n---\nPlan it.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "opencode");
+    deploy_skill(dir.path(), "agtx-plan", content, "opencode", &[]);
 
     let native = dir.path().join(".opencode/command/agtx-plan.md");
     assert!(native.exists(), ".opencode/command/agtx-plan.md should be written");
@@ -9428,7 +10715,7 @@ fn test_deploy_skill_cursor_writes_skill_subdir() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan\n---\nPlan it.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "cursor");
+    deploy_skill(dir.path(), "agtx-plan", content, "cursor", &[]);
 
     assert!(
         dir.path().join(".cursor/skills/agtx-plan/SKILL.md").exists(),
@@ -9442,7 +10729,7 @@ fn test_deploy_skill_unknown_agent_only_canonical() {
     let dir = tempfile::tempdir().unwrap();
     let content = "---\nname: agtx-plan\ndescription: Plan\n---\nPlan it.";
 
-    deploy_skill(dir.path(), "agtx-plan", content, "unknownagent");
+    deploy_skill(dir.path(), "agtx-plan", content, "unknownagent", &[]);
 
     assert!(
         dir.path().join(".agtx/skills/agtx-plan/SKILL.md").exists(),
@@ -9946,3 +11233,107 @@ fn test_handle_paste_noop_in_normal_mode() {
 
     assert!(app.state.input_buffer.is_empty());
 }
+
+// =============================================================================
+// Tests for configurable keybindings
+// =============================================================================
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_rebound_key_triggers_action_and_frees_default() {
+    let mut app = make_test_app();
+    let mut config = std::collections::BTreeMap::new();
+    config.insert(
+        "new_task".to_string(),
+        crate::config::KeyBinding::One("a".to_string()),
+    );
+    app.state.keymap = Keymap::from_config(&config).0;
+
+    press_key(&mut app, KeyCode::Char('o'));
+    assert_eq!(app.state.input_mode, InputMode::Normal);
+
+    press_key(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.state.input_mode, InputMode::InputTitle);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_rebound_stats_key_toggles_popup() {
+    let mut app = make_test_app();
+    let mut config = std::collections::BTreeMap::new();
+    config.insert(
+        "open_stats".to_string(),
+        crate::config::KeyBinding::One("F5".to_string()),
+    );
+    app.state.keymap = Keymap::from_config(&config).0;
+
+    press_key(&mut app, KeyCode::F(5));
+    assert!(app.state.stats_popup.is_some());
+    // The old default no longer closes it
+    press_key(&mut app, KeyCode::Char('S'));
+    assert!(app.state.stats_popup.is_some());
+    press_key(&mut app, KeyCode::F(5));
+    assert!(app.state.stats_popup.is_none());
+
+    press_key(&mut app, KeyCode::F(5));
+    press_key(&mut app, KeyCode::Esc);
+    assert!(app.state.stats_popup.is_none());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_help_popup_opens_and_closes() {
+    let mut app = make_test_app();
+
+    press_key(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.state.help_popup, Some(0));
+
+    // Board keys are swallowed while the help is open
+    press_key(&mut app, KeyCode::Char('o'));
+    assert_eq!(app.state.input_mode, InputMode::Normal);
+    press_key(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.state.help_popup, Some(1));
+
+    press_key(&mut app, KeyCode::Esc);
+    assert!(app.state.help_popup.is_none());
+}
+
+#[test]
+fn test_help_lines_list_user_bindings() {
+    let mut config = std::collections::BTreeMap::new();
+    config.insert(
+        "open_diff".to_string(),
+        crate::config::KeyBinding::Many(vec!["D".to_string(), "C-d".to_string()]),
+    );
+    let (keymap, _) = Keymap::from_config(&config);
+
+    let lines = build_help_lines(&keymap);
+    let diff = lines
+        .iter()
+        .find(|(_, description)| description.contains("(open_diff)"))
+        .unwrap();
+    assert_eq!(diff.0.trim(), "D C-d");
+    assert!(lines.iter().any(|(heading, _)| heading == "Diff popup"));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_diff_popup_uses_keymap() {
+    let mut app = make_test_app();
+    let mut config = std::collections::BTreeMap::new();
+    config.insert(
+        "diff_close".to_string(),
+        crate::config::KeyBinding::One("x".to_string()),
+    );
+    app.state.keymap = Keymap::from_config(&config).0;
+    app.state.diff_popup = Some(DiffPopup {
+        task_title: "t".to_string(),
+        diff_content: "a\nb\nc".to_string(),
+        scroll_offset: 0,
+    });
+
+    press_key(&mut app, KeyCode::Esc);
+    assert!(app.state.diff_popup.is_some());
+    press_key(&mut app, KeyCode::Char('x'));
+    assert!(app.state.diff_popup.is_none());
+}
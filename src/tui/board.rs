@@ -1,4 +1,7 @@
-use crate::db::{Task, TaskStatus};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use crate::db::{SubtaskProgress, Task, TaskStatus};
 
 /// State for the kanban board view
 #[derive(Debug)]
@@ -6,6 +9,8 @@ pub struct BoardState {
     pub tasks: Vec<Task>,
     pub selected_column: usize,
     pub selected_row: usize,
+    /// IDs of the tasks matching the board filter; `None` shows every task
+    pub visible: Option<HashSet<String>>,
 }
 
 impl BoardState {
@@ -14,14 +19,24 @@ impl BoardState {
             tasks: vec![],
             selected_column: 0,
             selected_row: 0,
+            visible: None,
         }
     }
 
-    /// Get tasks in a specific column
+    /// Get the visible tasks in a specific column, highest priority first
+    /// (otherwise in board order)
     pub fn tasks_in_column(&self, column: usize) -> Vec<&Task> {
         let status = TaskStatus::columns().get(column).copied();
         match status {
-            Some(s) => self.tasks.iter().filter(|t| t.status == s).collect(),
+            Some(s) => {
+                let mut tasks: Vec<&Task> = self
+                    .tasks
+                    .iter()
+                    .filter(|t| t.status == s && self.is_visible(t))
+                    .collect();
+                tasks.sort_by_key(|t| Reverse(t.priority));
+                tasks
+            }
             None => vec![],
         }
     }
@@ -40,15 +55,34 @@ impl BoardState {
             .tasks
             .iter()
             .enumerate()
-            .filter(|(_, t)| t.status == status)
+            .filter(|(_, t)| t.status == status && self.is_visible(t))
             .map(|(i, _)| i)
             .collect();
+        matching_indices.sort_by_key(|&i| Reverse(self.tasks[i].priority));
 
         matching_indices
             .get(self.selected_row)
             .and_then(|&idx| self.tasks.get_mut(idx))
     }
 
+    /// Progress of `parent_id`'s subtasks across the whole board, filtered or not
+    pub fn subtask_progress(&self, parent_id: &str) -> Option<SubtaskProgress> {
+        SubtaskProgress::of(parent_id, &self.tasks)
+    }
+
+    fn is_visible(&self, task: &Task) -> bool {
+        self.visible
+            .as_ref()
+            .is_none_or(|visible| visible.contains(&task.id))
+    }
+
+    /// Restrict the board to `visible` task IDs (`None` shows every task),
+    /// keeping the selection in range
+    pub fn set_visible(&mut self, visible: Option<HashSet<String>>) {
+        self.visible = visible;
+        self.clamp_row();
+    }
+
     /// Move selection left
     pub fn move_left(&mut self) {
         if self.selected_column > 0 {
@@ -0,0 +1,119 @@
+//! Board filter queries.
+//!
+//! A query is a list of whitespace-separated terms and a task has to match
+//! all of them:
+//!
+//! - `label:bug` or `#bug` — has the label
+//! - `agent:codex`, `plugin:gsd` — runs with that agent or plugin (`plugin:agtx`
+//!   also matches tasks without a plugin)
+//! - `is:escalated`, `is:idle`, `is:ready`, `is:exited`, `is:working` — the
+//!   task is escalated to the user, or its agent is in that phase status
+//! - anything else — case-insensitive text in the title or description
+//!
+//! Prefixing a term with `-` inverts it (`-label:wip`).
+
+use crate::db::{PhaseStatus, Task};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Label(String),
+    Agent(String),
+    Plugin(String),
+    Escalated,
+    Phase(PhaseStatus),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    condition: Condition,
+    negated: bool,
+}
+
+/// A parsed board filter query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoardFilter {
+    query: String,
+    terms: Vec<Term>,
+}
+
+impl BoardFilter {
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .map(|word| {
+                let (negated, word) = match word.strip_prefix('-') {
+                    Some(rest) if !rest.is_empty() => (true, rest),
+                    _ => (false, word),
+                };
+                Term {
+                    condition: parse_condition(word),
+                    negated,
+                }
+            })
+            .collect();
+        Self {
+            query: query.trim().to_string(),
+            terms,
+        }
+    }
+
+    /// The query as typed, without surrounding whitespace
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether `task` passes every term. `phase` is the agent's current phase
+    /// status, if it has a live session.
+    pub fn matches(&self, task: &Task, phase: Option<PhaseStatus>) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.condition.matches(task, phase) != term.negated)
+    }
+}
+
+fn parse_condition(word: &str) -> Condition {
+    let lower = word.to_lowercase();
+    if let Some(label) = lower.strip_prefix('#').filter(|l| !l.is_empty()) {
+        return Condition::Label(label.to_string());
+    }
+    let Some((key, value)) = lower.split_once(':').filter(|(_, v)| !v.is_empty()) else {
+        return Condition::Text(lower);
+    };
+    match (key, value) {
+        ("label", _) => Condition::Label(value.to_string()),
+        ("agent", _) => Condition::Agent(value.to_string()),
+        ("plugin", _) => Condition::Plugin(value.to_string()),
+        ("is", "escalated") => Condition::Escalated,
+        ("is", "idle") => Condition::Phase(PhaseStatus::Idle),
+        ("is", "ready") => Condition::Phase(PhaseStatus::Ready),
+        ("is", "exited") => Condition::Phase(PhaseStatus::Exited),
+        ("is", "working") => Condition::Phase(PhaseStatus::Working),
+        _ => Condition::Text(lower),
+    }
+}
+
+impl Condition {
+    fn matches(&self, task: &Task, phase: Option<PhaseStatus>) -> bool {
+        match self {
+            Condition::Label(label) => task.labels.iter().any(|l| l.to_lowercase() == *label),
+            Condition::Agent(agent) => task.agent.to_lowercase() == *agent,
+            Condition::Plugin(plugin) => {
+                task.plugin.as_deref().unwrap_or("agtx").to_lowercase() == *plugin
+            }
+            Condition::Escalated => task.escalation_note.is_some(),
+            Condition::Phase(status) => phase == Some(*status),
+            Condition::Text(text) => {
+                task.title.to_lowercase().contains(text)
+                    || task
+                        .description
+                        .as_deref()
+                        .is_some_and(|d| d.to_lowercase().contains(text))
+            }
+        }
+    }
+}
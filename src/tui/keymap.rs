@@ -0,0 +1,471 @@
+//! Named key actions and the user's `[keybindings]` overrides.
+//!
+//! Every rebindable key in the board, sidebar, dashboard, shell popup and diff
+//! popup is an [`Action`] with default chords. `Keymap` resolves a key event to
+//! the action bound to it in the current [`KeyContext`] and renders the chord
+//! labels shown in footers and the help overlay.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeyBinding;
+
+/// Where a key press is handled; actions only conflict within one context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Board,
+    Sidebar,
+    Dashboard,
+    ShellPopup,
+    DiffPopup,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 5] = [
+        KeyContext::Board,
+        KeyContext::Sidebar,
+        KeyContext::Dashboard,
+        KeyContext::ShellPopup,
+        KeyContext::DiffPopup,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Board => "Board",
+            KeyContext::Sidebar => "Project sidebar",
+            KeyContext::Dashboard => "Dashboard",
+            KeyContext::ShellPopup => "Task popup",
+            KeyContext::DiffPopup => "Diff popup",
+        }
+    }
+}
+
+macro_rules! actions {
+    ($( $variant:ident => $name:literal, $desc:literal, [$($ctx:ident),*], [$($chord:literal),*]; )*) => {
+        /// A rebindable action, named in `[keybindings]` by its snake_case name.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Action {
+            $($variant,)*
+        }
+
+        impl Action {
+            pub const ALL: &'static [Action] = &[$(Action::$variant,)*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Action::$variant => $name,)*
+                }
+            }
+
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(Action::$variant => $desc,)*
+                }
+            }
+
+            pub fn contexts(&self) -> &'static [KeyContext] {
+                match self {
+                    $(Action::$variant => &[$(KeyContext::$ctx),*],)*
+                }
+            }
+
+            fn default_chords(&self) -> &'static [&'static str] {
+                match self {
+                    $(Action::$variant => &[$($chord),*],)*
+                }
+            }
+        }
+    };
+}
+
+actions! {
+    Quit => "quit", "Quit agtx", [Board, Sidebar, Dashboard], ["q"];
+    Help => "help", "Show key bindings", [Board, Sidebar, Dashboard], ["?"];
+    ToggleSidebar => "toggle_sidebar", "Show or hide the project sidebar", [Board, Sidebar], ["e"];
+    NavLeft => "nav_left", "Previous column (or focus the sidebar)", [Board], ["h", "Left"];
+    NavRight => "nav_right", "Next column (from the sidebar: focus the board)", [Board, Sidebar], ["l", "Right"];
+    NavUp => "nav_up", "Select previous item", [Board, Sidebar, Dashboard], ["k", "Up"];
+    NavDown => "nav_down", "Select next item", [Board, Sidebar, Dashboard], ["j", "Down"];
+    Open => "open", "Open task, edit backlog task, or open project", [Board, Sidebar, Dashboard], ["Enter"];
+    Fullscreen => "fullscreen", "Attach to the task's tmux window fullscreen", [Board, ShellPopup], ["C-f"];
+    NewTask => "new_task", "Create a task", [Board], ["o"];
+    DeleteTask => "delete_task", "Delete the selected task", [Board], ["x"];
+    OpenDiff => "open_diff", "Show the task's diff", [Board], ["d"];
+    OpenTimeline => "open_timeline", "Show the task's history", [Board], ["t"];
+    OpenStats => "open_stats", "Show project stats (lead time, cycle time, throughput)", [Board], ["S"];
+    OpenGraph => "open_graph", "Show the task dependency graph", [Board], ["g"];
+    OpenTranscript => "open_transcript", "Browse the task's recorded agent transcripts", [Board], ["L"];
+    MoveForward => "move_forward", "Move the task to the next column", [Board], ["m"];
+    RunTask => "run_task", "Move a backlog task straight to Running", [Board], ["M"];
+    Research => "research", "Start a research session for a backlog task", [Board], ["R"];
+    MoveBack => "move_back", "Move the task back (Running → Planning, Review → Running)", [Board], ["r"];
+    NextPhase => "next_phase", "Start the next cycle of a cyclic plugin (Review → Planning)", [Board], ["p"];
+    Search => "search", "Search tasks", [Board], ["/"];
+    Filter => "filter", "Filter the board by label, agent, plugin, status or text", [Board], ["f"];
+    NextView => "next_view", "Cycle through the saved board views", [Board], ["v"];
+    SelectPlugin => "select_plugin", "Choose the project's workflow plugin", [Board], ["P"];
+    ToggleOrchestrator => "toggle_orchestrator", "Start or stop the orchestrator (experimental)", [Board], ["O"];
+    ProjectList => "project_list", "Show the project list", [Dashboard], ["p"];
+    OpenCurrentDir => "open_current_dir", "Open the current directory as a project", [Dashboard], ["n"];
+    ShellClose => "shell_close", "Close the task popup", [ShellPopup], ["C-q"];
+    ShellScrollUp => "shell_scroll_up", "Scroll up", [ShellPopup], ["C-k", "C-p", "C-Up"];
+    ShellScrollDown => "shell_scroll_down", "Scroll down", [ShellPopup], ["C-j", "C-n", "C-Down"];
+    ShellPageUp => "shell_page_up", "Page up", [ShellPopup], ["C-u", "PageUp"];
+    ShellPageDown => "shell_page_down", "Page down", [ShellPopup], ["C-d", "PageDown"];
+    ShellBottom => "shell_bottom", "Jump to the live output", [ShellPopup], ["C-g"];
+    DiffClose => "diff_close", "Close the diff", [DiffPopup], ["Esc", "q"];
+    DiffDown => "diff_down", "Scroll down", [DiffPopup], ["j", "Down"];
+    DiffUp => "diff_up", "Scroll up", [DiffPopup], ["k", "Up"];
+    DiffPageDown => "diff_page_down", "Page down", [DiffPopup], ["d", "PageDown"];
+    DiffPageUp => "diff_page_up", "Page up", [DiffPopup], ["u", "PageUp"];
+    DiffTop => "diff_top", "Jump to the top", [DiffPopup], ["g"];
+    DiffBottom => "diff_bottom", "Jump to the end", [DiffPopup], ["G"];
+}
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+/// A key plus the modifiers that matter for matching.
+/// Shift is carried by the character itself (`M` vs `m`), so it is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyChord {
+    /// Parse `"m"`, `"C-f"`, `"ctrl+f"`, `"M-x"`, `"alt+x"`, `"Enter"`, `"PageUp"`, `"F5"`, ...
+    pub fn parse(s: &str) -> Option<KeyChord> {
+        let mut rest = s.trim();
+        let mut ctrl = false;
+        let mut alt = false;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if rest.len() > 2 && lower.starts_with("c-") {
+                ctrl = true;
+                rest = &rest[2..];
+            } else if rest.len() > 5 && lower.starts_with("ctrl+") {
+                ctrl = true;
+                rest = &rest[5..];
+            } else if rest.len() > 2 && (lower.starts_with("m-") || lower.starts_with("a-")) {
+                alt = true;
+                rest = &rest[2..];
+            } else if rest.len() > 4 && lower.starts_with("alt+") {
+                alt = true;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f if f.starts_with('f') => {
+                    KeyCode::F(f[1..].parse().ok().filter(|n| (1..=12).contains(n))?)
+                }
+                _ => return None,
+            },
+        };
+        Some(KeyChord { code, ctrl, alt })
+    }
+
+    pub fn from_event(key: &KeyEvent) -> KeyChord {
+        KeyChord {
+            code: key.code,
+            ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+            alt: key.modifiers.contains(KeyModifiers::ALT),
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "C-")?;
+        }
+        if self.alt {
+            write!(f, "M-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Resolved bindings: defaults with the user's `[keybindings]` applied.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|action| {
+                let chords = action
+                    .default_chords()
+                    .iter()
+                    .map(|c| KeyChord::parse(c).expect("default chord parses"))
+                    .collect();
+                (*action, chords)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Apply `[keybindings]` on top of the defaults.
+    ///
+    /// A rebound chord is taken away from any default binding in the same
+    /// context, so `move_forward = "n"` doesn't leave `n` doing two things.
+    /// Unknown actions, unparseable chords and clashes between two user
+    /// bindings are skipped and reported.
+    pub fn from_config(config: &BTreeMap<String, KeyBinding>) -> (Keymap, Vec<String>) {
+        let mut keymap = Keymap::default();
+        let mut problems = Vec::new();
+        let mut overridden: Vec<Action> = Vec::new();
+
+        for (name, binding) in config {
+            let Some(action) = Action::from_name(name) else {
+                problems.push(format!("unknown action '{}'", name));
+                continue;
+            };
+            let mut chords = Vec::new();
+            for raw in binding.chords() {
+                let Some(chord) = KeyChord::parse(raw) else {
+                    problems.push(format!("can't parse key '{}' for {}", raw, name));
+                    continue;
+                };
+                let clash = overridden.iter().find(|other| {
+                    shares_context(action, **other) && keymap.bindings[*other].contains(&chord)
+                });
+                if let Some(other) = clash {
+                    problems.push(format!(
+                        "'{}' is bound to both {} and {}",
+                        raw,
+                        other.name(),
+                        name
+                    ));
+                    continue;
+                }
+                chords.push(chord);
+            }
+            keymap.bindings.insert(action, chords);
+            overridden.push(action);
+        }
+
+        // Free the user's chords from the defaults they would shadow
+        for action in Action::ALL {
+            if overridden.contains(action) {
+                continue;
+            }
+            let taken: Vec<KeyChord> = overridden
+                .iter()
+                .filter(|other| shares_context(*action, **other))
+                .flat_map(|other| keymap.bindings[other].clone())
+                .collect();
+            if let Some(chords) = keymap.bindings.get_mut(action) {
+                chords.retain(|c| !taken.contains(c));
+            }
+        }
+
+        (keymap, problems)
+    }
+
+    /// The action bound to `key` in `context`, if any.
+    pub fn action(&self, context: KeyContext, key: &KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from_event(key);
+        Action::ALL.iter().copied().find(|action| {
+            action.contexts().contains(&context) && self.bindings[action].contains(&chord)
+        })
+    }
+
+    pub fn chords(&self, action: Action) -> &[KeyChord] {
+        &self.bindings[&action]
+    }
+
+    /// Label of the primary chord, e.g. `"C-f"`; empty when the action is unbound.
+    pub fn label(&self, action: Action) -> String {
+        self.bindings[&action]
+            .first()
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Footer hint like `"[m] move"`, or None when the action is unbound.
+    pub fn hint(&self, action: Action, text: &str) -> Option<String> {
+        self.bindings[&action]
+            .first()
+            .map(|c| format!("[{}] {}", c, text))
+    }
+}
+
+fn shares_context(a: Action, b: Action) -> bool {
+    a.contexts().iter().any(|c| b.contexts().contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_chords_do_not_clash_within_a_context() {
+        let keymap = Keymap::default();
+        for context in KeyContext::ALL {
+            let mut seen: HashMap<KeyChord, Action> = HashMap::new();
+            for action in Action::ALL
+                .iter()
+                .filter(|a| a.contexts().contains(&context))
+            {
+                for chord in keymap.chords(*action) {
+                    if let Some(other) = seen.insert(*chord, *action) {
+                        panic!(
+                            "{} bound to {:?} and {:?} in {:?}",
+                            chord, other, action, context
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn bindings(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyBinding> {
+        entries
+            .iter()
+            .map(|(name, chords)| {
+                (
+                    name.to_string(),
+                    KeyBinding::Many(chords.iter().map(|c| c.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        let ctrl_f = KeyChord {
+            code: KeyCode::Char('f'),
+            ctrl: true,
+            alt: false,
+        };
+        assert_eq!(KeyChord::parse("C-f"), Some(ctrl_f));
+        assert_eq!(KeyChord::parse("ctrl+f"), Some(ctrl_f));
+        assert_eq!(KeyChord::parse("Ctrl+f"), Some(ctrl_f));
+        assert_eq!(KeyChord::parse("M").unwrap().code, KeyCode::Char('M'));
+        assert!(KeyChord::parse("M-x").unwrap().alt);
+        assert_eq!(KeyChord::parse("PageDown").unwrap().code, KeyCode::PageDown);
+        assert_eq!(KeyChord::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(KeyChord::parse("space").unwrap().code, KeyCode::Char(' '));
+        assert_eq!(KeyChord::parse("F13"), None);
+        assert_eq!(KeyChord::parse("hyper+x"), None);
+        assert_eq!(KeyChord::parse(""), None);
+    }
+
+    #[test]
+    fn test_chord_display_round_trips() {
+        for raw in ["m", "C-f", "M-x", "Enter", "PgUp", "F5", "Space", "C-Up"] {
+            assert_eq!(KeyChord::parse(raw).unwrap().to_string(), raw);
+        }
+    }
+
+    #[test]
+    fn test_shift_is_ignored_for_characters() {
+        let keymap = Keymap::default();
+        let key = press(KeyCode::Char('M'), KeyModifiers::SHIFT);
+        assert_eq!(
+            keymap.action(KeyContext::Board, &key),
+            Some(Action::RunTask)
+        );
+    }
+
+    #[test]
+    fn test_override_takes_chord_from_defaults() {
+        let (keymap, problems) = Keymap::from_config(&bindings(&[("move_forward", &["n"])]));
+        assert!(problems.is_empty());
+        let n = press(KeyCode::Char('n'), KeyModifiers::NONE);
+        let m = press(KeyCode::Char('m'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action(KeyContext::Board, &n),
+            Some(Action::MoveForward)
+        );
+        assert_eq!(keymap.action(KeyContext::Board, &m), None);
+        assert_eq!(
+            keymap.hint(Action::MoveForward, "move").unwrap(),
+            "[n] move"
+        );
+    }
+
+    #[test]
+    fn test_override_shadows_default_only_in_shared_context() {
+        // `d` is open_diff on the board; rebinding it to delete_task leaves open_diff
+        // unbound there but keeps d as page down in the diff popup
+        let (keymap, _) = Keymap::from_config(&bindings(&[("delete_task", &["d"])]));
+        assert!(keymap.chords(Action::OpenDiff).is_empty());
+        assert_eq!(keymap.label(Action::DiffPageDown), "d");
+        assert_eq!(keymap.hint(Action::OpenDiff, "diff"), None);
+    }
+
+    #[test]
+    fn test_config_problems_are_reported() {
+        let (keymap, problems) = Keymap::from_config(&bindings(&[
+            ("move_forward", &["n"]),
+            ("research", &["n", "???"]),
+            ("teleport", &["z"]),
+        ]));
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems
+            .iter()
+            .any(|p| p.contains("unknown action 'teleport'")));
+        assert!(problems.iter().any(|p| p.contains("'???'")));
+        assert!(problems
+            .iter()
+            .any(|p| p.contains("'n' is bound to both move_forward and research")));
+        // The clashing chord stays with the first binding
+        let n = press(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action(KeyContext::Board, &n),
+            Some(Action::MoveForward)
+        );
+    }
+
+    #[test]
+    fn test_action_names_are_unique() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(*action));
+        }
+    }
+}
@@ -1,6 +1,8 @@
 mod app;
 pub mod board;
+pub mod filter;
 mod input;
+mod keymap;
 pub mod shell_popup;
 
 pub use app::App;
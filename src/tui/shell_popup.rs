@@ -115,15 +115,45 @@ pub fn compute_visible_lines<'a>(
     (visible_lines, start_line, total_lines)
 }
 
+/// Key labels shown in the shell popup footer (from the user's keybindings)
+#[derive(Debug, Clone)]
+pub struct ShellPopupKeys {
+    pub scroll: String,
+    pub page: String,
+    pub bottom: String,
+    pub fullscreen: String,
+    pub close: String,
+}
+
+impl Default for ShellPopupKeys {
+    fn default() -> Self {
+        Self {
+            scroll: "C-j/k".to_string(),
+            page: "C-d/u".to_string(),
+            bottom: "C-g".to_string(),
+            fullscreen: "C-f".to_string(),
+            close: "C-q".to_string(),
+        }
+    }
+}
+
 /// Build the footer text for the shell popup
-pub fn build_footer_text(scroll_offset: i32, start_line: usize) -> String {
+pub fn build_footer_text(scroll_offset: i32, start_line: usize, keys: &ShellPopupKeys) -> String {
+    let common = format!("[{}] scroll [{}] page", keys.scroll, keys.page);
     if scroll_offset < 0 {
         format!(
-            " [C-j/k] scroll [C-d/u] page [C-g] bottom [C-f] fullscreen [C-q] close | Line {} ",
+            " {} [{}] bottom [{}] fullscreen [{}] close | Line {} ",
+            common,
+            keys.bottom,
+            keys.fullscreen,
+            keys.close,
             start_line + 1
         )
     } else {
-        " [C-j/k] scroll [C-d/u] page [C-f] fullscreen [C-q] close | At bottom ".to_string()
+        format!(
+            " {} [{}] fullscreen [{}] close | At bottom ",
+            common, keys.fullscreen, keys.close
+        )
     }
 }
 
@@ -249,6 +279,7 @@ pub fn render_shell_popup(
     popup_area: Rect,
     styled_lines: Vec<Line<'_>>,
     colors: &ShellPopupColors,
+    keys: &ShellPopupKeys,
 ) {
     frame.render_widget(Clear, popup_area);
 
@@ -313,7 +344,7 @@ pub fn render_shell_popup(
     frame.render_widget(content, popup_chunks[2]);
 
     // Footer with scroll indicator (pad to fill width)
-    let footer_text = build_footer_text(popup.scroll_offset, start_line);
+    let footer_text = build_footer_text(popup.scroll_offset, start_line, keys);
     let padded_footer = format!(
         "{:<width$}",
         footer_text,
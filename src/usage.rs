@@ -0,0 +1,396 @@
+//! Token and cost tracking from the agents' own session logs.
+//!
+//! Claude, Codex and Gemini each record token counts for every model call in
+//! files under the home directory, keyed by the directory the agent ran in.
+//! Since every task runs in its own worktree, reading the sessions for that
+//! worktree gives the task's usage; each call is then attributed to the phase
+//! the task was in at the time, from its status history. Cost is estimated
+//! from list prices per million tokens.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::db::{TaskEvent, TaskEventKind, TaskStatus, TaskUsage, Usage};
+
+/// One model call made by an agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageRecord {
+    pub agent: &'static str,
+    pub model: String,
+    pub timestamp: DateTime<Utc>,
+    pub usage: Usage,
+}
+
+/// Usage of every agent session that ran in `worktree`, from session files
+/// modified since `since`.
+pub fn collect(worktree: &Path, since: DateTime<Utc>) -> Vec<UsageRecord> {
+    match std::env::var_os("HOME") {
+        Some(home) => collect_in(Path::new(&home), worktree, since),
+        None => Vec::new(),
+    }
+}
+
+/// [`collect`] with an explicit home directory
+pub fn collect_in(home: &Path, worktree: &Path, since: DateTime<Utc>) -> Vec<UsageRecord> {
+    // Agents see the resolved path if the worktree sits behind a symlink
+    let mut dirs = vec![worktree.to_path_buf()];
+    if let Ok(canonical) = worktree.canonicalize() {
+        if canonical != worktree {
+            dirs.push(canonical);
+        }
+    }
+    let since = SystemTime::from(since);
+
+    let mut records = Vec::new();
+    for dir in &dirs {
+        records.extend(claude_records(home, dir, since));
+        records.extend(gemini_records(home, dir, since));
+    }
+    records.extend(codex_records(home, &dirs, since));
+    records.sort_by_key(|r| r.timestamp);
+    records
+}
+
+/// `~/.claude/projects/<cwd with every non-alphanumeric char as '-'>/*.jsonl`,
+/// one line per message. Streamed messages repeat with growing usage, so the
+/// last line for each message ID wins.
+fn claude_records(home: &Path, cwd: &Path, since: SystemTime) -> Vec<UsageRecord> {
+    let encoded: String = cwd
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let dir = home.join(".claude").join("projects").join(encoded);
+
+    let mut by_id: BTreeMap<String, UsageRecord> = BTreeMap::new();
+    for path in files_modified_since(&dir, "jsonl", since) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Ok(entry) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            if entry["type"] != "assistant" {
+                continue;
+            }
+            let message = &entry["message"];
+            let (Some(id), Some(timestamp)) = (message["id"].as_str(), timestamp(&entry)) else {
+                continue;
+            };
+            let usage = &message["usage"];
+            if usage.is_null() {
+                continue;
+            }
+            let model = message["model"].as_str().unwrap_or_default().to_string();
+            let usage = priced(
+                &model,
+                Usage {
+                    input_tokens: count(&usage["input_tokens"]),
+                    output_tokens: count(&usage["output_tokens"]),
+                    cache_read_tokens: count(&usage["cache_read_input_tokens"]),
+                    cache_write_tokens: count(&usage["cache_creation_input_tokens"]),
+                    cost_usd: 0.0,
+                },
+            );
+            by_id.insert(
+                id.to_string(),
+                UsageRecord {
+                    agent: "claude",
+                    model,
+                    timestamp,
+                    usage,
+                },
+            );
+        }
+    }
+    by_id.into_values().collect()
+}
+
+/// `~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl`. The first line names the
+/// session's working directory; each `token_count` event carries the usage
+/// of the last turn, with cached input counted inside `input_tokens`.
+fn codex_records(home: &Path, cwds: &[PathBuf], since: SystemTime) -> Vec<UsageRecord> {
+    let mut files = Vec::new();
+    walk_files(
+        &home.join(".codex").join("sessions"),
+        "jsonl",
+        since,
+        &mut files,
+    );
+
+    let mut records = Vec::new();
+    for path in files {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut lines = content.lines();
+        let Some(meta) = lines
+            .next()
+            .and_then(|l| serde_json::from_str::<Value>(l).ok())
+        else {
+            continue;
+        };
+        let Some(cwd) = meta["payload"]["cwd"].as_str() else {
+            continue;
+        };
+        if !cwds.iter().any(|dir| Path::new(cwd) == dir) {
+            continue;
+        }
+
+        let mut model = String::new();
+        for line in lines {
+            let Ok(entry) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            let payload = &entry["payload"];
+            match (entry["type"].as_str(), payload["type"].as_str()) {
+                (Some("turn_context"), _) => {
+                    model = payload["model"].as_str().unwrap_or_default().to_string();
+                }
+                (Some("event_msg"), Some("token_count")) => {
+                    let last = &payload["info"]["last_token_usage"];
+                    let Some(timestamp) = timestamp(&entry).filter(|_| !last.is_null()) else {
+                        continue;
+                    };
+                    let cached = count(&last["cached_input_tokens"]);
+                    let usage = priced(
+                        &model,
+                        Usage {
+                            input_tokens: count(&last["input_tokens"]).saturating_sub(cached),
+                            output_tokens: count(&last["output_tokens"]),
+                            cache_read_tokens: cached,
+                            cache_write_tokens: 0,
+                            cost_usd: 0.0,
+                        },
+                    );
+                    records.push(UsageRecord {
+                        agent: "codex",
+                        model: model.clone(),
+                        timestamp,
+                        usage,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    records
+}
+
+/// `~/.gemini/tmp/<sha256 of cwd>/chats/session-*.json`, a JSON document
+/// whose `gemini` messages carry token counts. Thinking tokens are billed as
+/// output.
+fn gemini_records(home: &Path, cwd: &Path, since: SystemTime) -> Vec<UsageRecord> {
+    use sha2::{Digest, Sha256};
+    let hash: String = Sha256::digest(cwd.to_string_lossy().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let dir = home.join(".gemini").join("tmp").join(hash).join("chats");
+
+    let mut records = Vec::new();
+    for path in files_modified_since(&dir, "json", since) {
+        let Some(session) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+        else {
+            continue;
+        };
+        for message in session["messages"].as_array().into_iter().flatten() {
+            let tokens = &message["tokens"];
+            if message["type"] != "gemini" || tokens.is_null() {
+                continue;
+            }
+            let Some(timestamp) = timestamp(message) else {
+                continue;
+            };
+            let model = message["model"].as_str().unwrap_or_default().to_string();
+            let cached = count(&tokens["cached"]);
+            let usage = priced(
+                &model,
+                Usage {
+                    input_tokens: count(&tokens["input"]).saturating_sub(cached),
+                    output_tokens: count(&tokens["output"]) + count(&tokens["thoughts"]),
+                    cache_read_tokens: cached,
+                    cache_write_tokens: 0,
+                    cost_usd: 0.0,
+                },
+            );
+            records.push(UsageRecord {
+                agent: "gemini",
+                model,
+                timestamp,
+                usage,
+            });
+        }
+    }
+    records
+}
+
+fn count(value: &Value) -> u64 {
+    value.as_u64().unwrap_or(0)
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value["timestamp"].as_str()?)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn modified_since(path: &Path, since: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified >= since)
+}
+
+fn files_modified_since(dir: &Path, extension: &str, since: SystemTime) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == extension))
+        .filter(|p| modified_since(p, since))
+        .collect()
+}
+
+fn walk_files(dir: &Path, extension: &str, since: SystemTime, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            walk_files(&path, extension, since, out);
+        } else if path.extension().is_some_and(|ext| ext == extension)
+            && modified_since(&path, since)
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// List price in USD per million tokens: input, output, cache write, cache read
+struct Price(f64, f64, f64, f64);
+
+/// Matched against the model name in order, so specific names come first
+const PRICES: &[(&str, Price)] = &[
+    ("claude-3-opus", Price(15.0, 75.0, 18.75, 1.5)),
+    ("opus-4-0", Price(15.0, 75.0, 18.75, 1.5)),
+    ("opus-4-1", Price(15.0, 75.0, 18.75, 1.5)),
+    ("opus-4-2025", Price(15.0, 75.0, 18.75, 1.5)),
+    ("opus", Price(5.0, 25.0, 6.25, 0.5)),
+    ("sonnet", Price(3.0, 15.0, 3.75, 0.3)),
+    ("3-5-haiku", Price(0.8, 4.0, 1.0, 0.08)),
+    ("haiku", Price(1.0, 5.0, 1.25, 0.1)),
+    ("gpt-5-nano", Price(0.05, 0.4, 0.0, 0.005)),
+    ("gpt-5-mini", Price(0.25, 2.0, 0.0, 0.025)),
+    ("codex-mini", Price(0.25, 2.0, 0.0, 0.025)),
+    ("gpt-5", Price(1.25, 10.0, 0.0, 0.125)),
+    ("o4-mini", Price(1.1, 4.4, 0.0, 0.275)),
+    ("o3", Price(2.0, 8.0, 0.0, 0.5)),
+    ("gpt-4.1", Price(2.0, 8.0, 0.0, 0.5)),
+    ("gemini-3-pro", Price(2.0, 12.0, 0.0, 0.2)),
+    ("gemini-2.5-pro", Price(1.25, 10.0, 0.0, 0.31)),
+    ("flash-lite", Price(0.1, 0.4, 0.0, 0.025)),
+    ("flash", Price(0.3, 2.5, 0.0, 0.075)),
+];
+
+/// Estimated cost of `usage` on `model`; 0 when the model's price is unknown.
+pub fn cost_usd(model: &str, usage: &Usage) -> f64 {
+    let model = model.to_lowercase();
+    let Some((_, Price(input, output, cache_write, cache_read))) =
+        PRICES.iter().find(|(name, _)| model.contains(name))
+    else {
+        return 0.0;
+    };
+    (usage.input_tokens as f64 * input
+        + usage.output_tokens as f64 * output
+        + usage.cache_write_tokens as f64 * cache_write
+        + usage.cache_read_tokens as f64 * cache_read)
+        / 1_000_000.0
+}
+
+fn priced(model: &str, mut usage: Usage) -> Usage {
+    usage.cost_usd = cost_usd(model, &usage);
+    usage
+}
+
+/// Sum `records` per phase and agent. Each record counts toward the phase the
+/// task was in when it was made, according to the task's status `events`.
+pub fn by_phase(task_id: &str, records: &[UsageRecord], events: &[TaskEvent]) -> Vec<TaskUsage> {
+    let entered: Vec<(DateTime<Utc>, TaskStatus)> = events
+        .iter()
+        .filter(|e| {
+            matches!(
+                e.kind,
+                TaskEventKind::Created | TaskEventKind::StatusChanged
+            )
+        })
+        .filter_map(|e| {
+            e.to_value
+                .as_deref()
+                .and_then(TaskStatus::from_str)
+                .map(|s| (e.created_at, s))
+        })
+        .collect();
+
+    let mut totals: BTreeMap<(&'static str, &'static str), Usage> = BTreeMap::new();
+    for record in records {
+        let status = entered
+            .iter()
+            .take_while(|(at, _)| *at <= record.timestamp)
+            .last()
+            .or(entered.first())
+            .map_or(TaskStatus::Backlog, |(_, s)| *s);
+        // Agents don't run in Done; anything logged late belongs to review
+        let phase = crate::transcript::phase_for_status(status).unwrap_or("review");
+        totals
+            .entry((phase, record.agent))
+            .or_default()
+            .add(&record.usage);
+    }
+
+    let now = Utc::now();
+    let mut rows: Vec<TaskUsage> = totals
+        .into_iter()
+        .map(|((phase, agent), usage)| TaskUsage {
+            task_id: task_id.to_string(),
+            phase: phase.to_string(),
+            agent: agent.to_string(),
+            usage,
+            updated_at: now,
+        })
+        .collect();
+    rows.sort_by_key(|row| {
+        crate::transcript::PHASES
+            .iter()
+            .position(|p| *p == row.phase)
+            .unwrap_or(usize::MAX)
+    });
+    rows
+}
+
+/// Compact token count for cards: 950, 12.3k, 4.1M
+pub fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        0..=999 => tokens.to_string(),
+        1_000..=999_999 => format!("{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("{:.1}M", tokens as f64 / 1_000_000.0),
+    }
+}
+
+/// Tokens and, when known, cost: "12.3k tok $0.42"
+pub fn format_usage(usage: &Usage) -> String {
+    let tokens = format!("{} tok", format_tokens(usage.total_tokens()));
+    if usage.cost_usd > 0.0 {
+        format!("{} ${:.2}", tokens, usage.cost_usd)
+    } else {
+        tokens
+    }
+}
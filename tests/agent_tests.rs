@@ -218,3 +218,146 @@ fn test_copilot_has_no_transform() {
     // Copilot: no interactive command transform
     assert_eq!(transform_plugin_command("/agtx:plan", "copilot"), None);
 }
+
+// =============================================================================
+// Tests for custom agents
+// =============================================================================
+
+fn aider_config() -> agtx::config::CustomAgentConfig {
+    agtx::config::CustomAgentConfig {
+        name: "aider".to_string(),
+        command: "aider".to_string(),
+        interactive: Some("aider --yes-always".to_string()),
+        interactive_prompt: Some("aider --yes-always --message {prompt}".to_string()),
+        print: Some("aider --no-stream --message {prompt}".to_string()),
+        resume: Some("aider --yes-always --restore-chat-history".to_string()),
+        orchestrator: Some("AGTX_MCP='{mcp_json}' {interactive}".to_string()),
+        co_author: Some("Aider <noreply@aider.chat>".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_custom_agent_interactive_templates() {
+    let agent = agtx::agent::Agent::from_custom(&aider_config());
+    assert_eq!(agent.build_interactive_command(""), "aider --yes-always");
+    assert_eq!(
+        agent.build_interactive_command("it's done"),
+        "aider --yes-always --message 'it'\"'\"'s done'"
+    );
+    assert_eq!(
+        agent.build_resume_command(),
+        "aider --yes-always --restore-chat-history"
+    );
+    assert_eq!(agent.co_author, "Aider <noreply@aider.chat>");
+}
+
+#[test]
+fn test_custom_agent_defaults_without_templates() {
+    let config = agtx::config::CustomAgentConfig {
+        name: "wrapper".to_string(),
+        command: "my-wrapper".to_string(),
+        ..Default::default()
+    };
+    let agent = agtx::agent::Agent::from_custom(&config);
+    assert_eq!(agent.build_interactive_command(""), "my-wrapper");
+    assert_eq!(agent.build_interactive_command("go"), "my-wrapper 'go'");
+    assert_eq!(agent.build_resume_command(), "my-wrapper");
+    assert_eq!(agent.co_author, "wrapper <noreply@agtx>");
+    assert_eq!(
+        agent.build_print_command("hi there"),
+        Some(("my-wrapper".to_string(), vec!["hi there".to_string()]))
+    );
+}
+
+#[test]
+fn test_custom_agent_print_command_keeps_prompt_as_single_arg() {
+    let agent = agtx::agent::Agent::from_custom(&aider_config());
+    let (program, args) = agent
+        .build_print_command("fix the `bug`; rm -rf /")
+        .unwrap();
+    assert_eq!(program, "aider");
+    assert_eq!(
+        args,
+        vec!["--no-stream", "--message", "fix the `bug`; rm -rf /"]
+    );
+}
+
+#[test]
+fn test_custom_agent_print_command_honors_quoted_arguments() {
+    let mut config = aider_config();
+    config.print = Some("aider --system \"be brief\" --message {prompt}".to_string());
+    let (program, args) = agtx::agent::Agent::from_custom(&config)
+        .build_print_command("hi")
+        .unwrap();
+    assert_eq!(program, "aider");
+    assert_eq!(args, vec!["--system", "be brief", "--message", "hi"]);
+}
+
+#[test]
+fn test_builtin_agent_has_no_print_template() {
+    let agents = known_agents();
+    let claude = agents.iter().find(|a| a.name == "claude").unwrap();
+    assert!(claude.build_print_command("x").is_none());
+}
+
+#[test]
+fn test_custom_agent_orchestrator_template() {
+    let ops = CodingAgent::new(agtx::agent::Agent::from_custom(&aider_config()));
+    assert_eq!(
+        ops.build_orchestrator_command("{\"type\":\"stdio\"}", "/usr/bin/agtx"),
+        "AGTX_MCP='{\"type\":\"stdio\"}' aider --yes-always"
+    );
+}
+
+#[test]
+fn test_registry_rejects_invalid_custom_agents() {
+    let bad = agtx::config::CustomAgentConfig {
+        name: "codex".to_string(),
+        command: "my-codex".to_string(),
+        ..Default::default()
+    };
+    assert!(agtx::agent::RealAgentRegistry::with_custom_agents("claude", &[bad]).is_err());
+}
+
+#[test]
+fn test_registry_merges_custom_agents_with_builtins() {
+    use agtx::agent::AgentRegistry;
+    // `sh` exists everywhere, so the custom agent is detected as available
+    let config = agtx::config::CustomAgentConfig {
+        name: "shell-agent".to_string(),
+        command: "sh".to_string(),
+        interactive: Some("sh -i".to_string()),
+        skills: Some(agtx::config::CustomAgentSkills {
+            dir: ".shell-agent/skills".to_string(),
+            namespace: String::new(),
+            style: "codex".to_string(),
+        }),
+        ..Default::default()
+    };
+    let registry =
+        agtx::agent::RealAgentRegistry::with_custom_agents("shell-agent", &[config]).unwrap();
+    assert_eq!(
+        registry.get("shell-agent").build_interactive_command(""),
+        "sh -i"
+    );
+    assert!(registry
+        .custom_agents()
+        .iter()
+        .any(|a| a.name == "shell-agent"));
+    // Custom agents stay in the registry rather than the global built-in list
+    assert!(!known_agents().iter().any(|a| a.name == "shell-agent"));
+    assert!(known_agents().iter().any(|a| a.name == "claude"));
+
+    // Skill layout follows the declared style
+    assert_eq!(
+        agtx::skills::native_skill_path("shell-agent", registry.custom_agents()),
+        Some(std::path::PathBuf::from(".shell-agent/skills"))
+    );
+    let style = agtx::skills::skill_style("shell-agent", registry.custom_agents());
+    assert_eq!(style, "codex");
+    assert_eq!(
+        transform_plugin_command("/agtx:plan", &style),
+        Some("$agtx-plan".to_string())
+    );
+}
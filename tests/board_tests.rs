@@ -1,4 +1,4 @@
-use agtx::db::{Task, TaskStatus};
+use agtx::db::{Priority, Task, TaskStatus};
 use agtx::tui::board::BoardState;
 
 fn create_test_task(title: &str, status: TaskStatus) -> Task {
@@ -223,3 +223,76 @@ fn test_selected_task_mut() {
 
     assert_eq!(board.tasks[0].title, "Modified Task");
 }
+
+#[test]
+fn test_tasks_in_column_sorted_by_priority() {
+    let mut board = BoardState::new();
+    let mut urgent = create_test_task("Urgent", TaskStatus::Backlog);
+    urgent.priority = Priority::Urgent;
+    let mut low = create_test_task("Low", TaskStatus::Backlog);
+    low.priority = Priority::Low;
+    board.tasks = vec![
+        low,
+        create_test_task("Normal 1", TaskStatus::Backlog),
+        urgent,
+        create_test_task("Normal 2", TaskStatus::Backlog),
+    ];
+
+    let titles: Vec<&str> = board
+        .tasks_in_column(0)
+        .iter()
+        .map(|t| t.title.as_str())
+        .collect();
+    assert_eq!(titles, ["Urgent", "Normal 1", "Normal 2", "Low"]);
+
+    // Selection follows the displayed order
+    board.selected_row = 0;
+    assert_eq!(board.selected_task_mut().unwrap().title, "Urgent");
+    board.selected_row = 3;
+    assert_eq!(board.selected_task().unwrap().title, "Low");
+    assert_eq!(board.selected_task_mut().unwrap().title, "Low");
+}
+
+#[test]
+fn test_set_visible_hides_filtered_tasks() {
+    let mut board = BoardState::new();
+    board.tasks = vec![
+        create_test_task("Task 1", TaskStatus::Backlog),
+        create_test_task("Task 2", TaskStatus::Backlog),
+        create_test_task("Task 3", TaskStatus::Backlog),
+    ];
+    board.selected_row = 2;
+
+    let keep = board.tasks[0].id.clone();
+    board.set_visible(Some([keep].into_iter().collect()));
+    let titles: Vec<&str> = board
+        .tasks_in_column(0)
+        .iter()
+        .map(|t| t.title.as_str())
+        .collect();
+    assert_eq!(titles, ["Task 1"]);
+    // Selection is clamped to what is shown
+    assert_eq!(board.selected_row, 0);
+    assert_eq!(board.selected_task_mut().unwrap().title, "Task 1");
+
+    board.set_visible(None);
+    assert_eq!(board.tasks_in_column(0).len(), 3);
+}
+
+#[test]
+fn test_subtask_progress_ignores_filter() {
+    let mut board = BoardState::new();
+    let parent = create_test_task("Epic", TaskStatus::Running);
+    let mut done = create_test_task("Done child", TaskStatus::Done);
+    done.parent_id = Some(parent.id.clone());
+    let mut open = create_test_task("Open child", TaskStatus::Backlog);
+    open.parent_id = Some(parent.id.clone());
+    let parent_id = parent.id.clone();
+    board.tasks = vec![parent, done, open];
+
+    board.set_visible(Some([parent_id.clone()].into_iter().collect()));
+    let progress = board.subtask_progress(&parent_id).unwrap();
+    assert_eq!((progress.done, progress.total), (1, 2));
+    assert!(!progress.is_complete());
+    assert!(board.subtask_progress(&board.tasks[1].id).is_none());
+}
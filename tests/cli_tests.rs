@@ -0,0 +1,385 @@
+use agtx::cli::{
+    format_migration_status, parse_db_args, parse_stats_args, parse_task_args, TaskCommand,
+};
+#[cfg(feature = "test-mocks")]
+use agtx::cli::{run_task_command, TaskArgs, TaskContext};
+#[cfg(feature = "test-mocks")]
+use agtx::db::{Database, Priority, Task, TaskStatus};
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[cfg(feature = "test-mocks")]
+fn ctx() -> TaskContext {
+    TaskContext {
+        project_name: "myproject".to_string(),
+        default_agent: "claude".to_string(),
+        default_plugin: Some("agtx".to_string()),
+    }
+}
+
+#[cfg(feature = "test-mocks")]
+fn run(db: &Database, list: &[&str]) -> anyhow::Result<String> {
+    let parsed: TaskArgs = parse_task_args(&args(list))?;
+    run_task_command(db, &ctx(), &parsed)
+}
+
+// === Argument Parsing ===
+
+#[test]
+fn test_parse_add_with_options() {
+    let parsed = parse_task_args(&args(&[
+        "add",
+        "Fix bug",
+        "--description",
+        "details",
+        "--json",
+        "--project",
+        "/tmp/p",
+    ]))
+    .unwrap();
+    assert!(parsed.json);
+    assert_eq!(
+        parsed.project.as_deref(),
+        Some(std::path::Path::new("/tmp/p"))
+    );
+    match parsed.command {
+        TaskCommand::Add { title, fields } => {
+            assert_eq!(title, "Fix bug");
+            assert_eq!(fields.description.as_deref(), Some("details"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_rejects_unknown_and_incomplete() {
+    assert!(parse_task_args(&args(&[])).is_err());
+    assert!(parse_task_args(&args(&["frobnicate"])).is_err());
+    assert!(parse_task_args(&args(&["move", "abc"])).is_err());
+    assert!(parse_task_args(&args(&["list", "--bogus"])).is_err());
+    assert!(parse_task_args(&args(&["list", "--status", "nope"])).is_err());
+    assert!(parse_task_args(&args(&["add", "x", "--description"])).is_err());
+}
+
+#[test]
+fn test_parse_ignores_global_flags() {
+    let parsed = parse_task_args(&args(&["list", "--experimental"])).unwrap();
+    assert_eq!(parsed.command, TaskCommand::List { status: None });
+}
+
+// === Commands ===
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_add_applies_config_defaults() {
+    let db = Database::open_in_memory_project().unwrap();
+    let out = run(&db, &["add", "New task", "--json"]).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["status"], "backlog");
+    assert_eq!(json["agent"], "claude");
+    assert_eq!(json["plugin"], "agtx");
+
+    let tasks = db.get_all_tasks().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].project_id, "myproject");
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_add_resolves_dependency_prefixes() {
+    let db = Database::open_in_memory_project().unwrap();
+    let dep = Task::new("Dep", "claude", "myproject");
+    db.create_task(&dep).unwrap();
+
+    run(&db, &["add", "Child", "--depends-on", &dep.id[..6]]).unwrap();
+    let child = db
+        .get_all_tasks()
+        .unwrap()
+        .into_iter()
+        .find(|t| t.title == "Child")
+        .unwrap();
+    assert_eq!(child.referenced_tasks.as_deref(), Some(dep.id.as_str()));
+
+    assert!(run(&db, &["add", "Bad", "--depends-on", "zzzzzz"]).is_err());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_list_filters_by_status() {
+    let db = Database::open_in_memory_project().unwrap();
+    db.create_task(&Task::new("One", "claude", "p")).unwrap();
+    let mut running = Task::new("Two", "claude", "p");
+    running.status = TaskStatus::Running;
+    db.create_task(&running).unwrap();
+
+    let out = run(&db, &["list", "--status", "running", "--json"]).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["title"], "Two");
+
+    let table = run(&db, &["list"]).unwrap();
+    assert!(table.starts_with("ID"));
+    assert!(table.contains("One") && table.contains("Two"));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_show_rejects_ambiguous_prefix() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut a = Task::new("A", "claude", "p");
+    a.id = "abc-1".to_string();
+    let mut b = Task::new("B", "claude", "p");
+    b.id = "abc-2".to_string();
+    db.create_task(&a).unwrap();
+    db.create_task(&b).unwrap();
+
+    assert!(run(&db, &["show", "abc"]).is_err());
+    assert!(run(&db, &["show", "abc-1"])
+        .unwrap()
+        .contains("Title:       A"));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_move_queues_transition_request() {
+    let db = Database::open_in_memory_project().unwrap();
+    let task = Task::new("Plan me", "claude", "p");
+    db.create_task(&task).unwrap();
+
+    let out = run(&db, &["move", &task.id, "planning", "--json"]).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["queued"], true);
+    assert_eq!(json["action"], "move_to_planning");
+
+    let pending = db.get_pending_transition_requests().unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].task_id, task.id);
+    // Status is unchanged until an agtx instance executes the request
+    let stored = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(stored.status, TaskStatus::Backlog);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_move_running_from_review_resumes() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut task = Task::new("Review me", "claude", "p");
+    task.status = TaskStatus::Review;
+    task.session_name = Some("proj:task".to_string());
+    db.create_task(&task).unwrap();
+
+    let out = run(&db, &["move", &task.id, "running", "--json"]).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["action"], "resume");
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_move_validates_status_and_dependencies() {
+    let db = Database::open_in_memory_project().unwrap();
+    let dep = Task::new("Dep", "claude", "p");
+    db.create_task(&dep).unwrap();
+    let mut blocked = Task::new("Blocked", "claude", "p");
+    blocked.referenced_tasks = Some(dep.id.clone());
+    db.create_task(&blocked).unwrap();
+
+    assert!(run(&db, &["move", &blocked.id, "planning"]).is_err());
+    assert!(run(&db, &["move", &dep.id, "review"]).is_err());
+    assert!(run(&db, &["move", &dep.id, "sideways"]).is_err());
+    assert!(db.get_pending_transition_requests().unwrap().is_empty());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_move_applies_side_effect_free_moves_directly() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut running = Task::new("Running", "claude", "p");
+    running.status = TaskStatus::Running;
+    db.create_task(&running).unwrap();
+    let idle = Task::new("Idle", "claude", "p");
+    db.create_task(&idle).unwrap();
+
+    run(
+        &db,
+        &["move", &running.id, "escalate", "--reason", "Need input"],
+    )
+    .unwrap();
+    run(&db, &["move", &idle.id, "done"]).unwrap();
+
+    let running = db.get_task(&running.id).unwrap().unwrap();
+    assert_eq!(running.escalation_note.as_deref(), Some("Need input"));
+    assert_eq!(
+        db.get_task(&idle.id).unwrap().unwrap().status,
+        TaskStatus::Done
+    );
+    assert!(db.get_pending_transition_requests().unwrap().is_empty());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_delete_refuses_tasks_with_sessions() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut active = Task::new("Active", "claude", "p");
+    active.status = TaskStatus::Running;
+    active.session_name = Some("proj:task".to_string());
+    db.create_task(&active).unwrap();
+    let idle = Task::new("Idle", "claude", "p");
+    db.create_task(&idle).unwrap();
+
+    assert!(run(&db, &["delete", &active.id]).is_err());
+    run(&db, &["delete", &idle.id]).unwrap();
+    assert!(db.get_task(&idle.id).unwrap().is_none());
+    assert!(db.get_task(&active.id).unwrap().is_some());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_edit_updates_fields_and_enforces_backlog_rules() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut task = Task::new("Old", "claude", "p");
+    task.status = TaskStatus::Running;
+    db.create_task(&task).unwrap();
+
+    run(
+        &db,
+        &["edit", &task.id, "--title", "New", "--description", "d"],
+    )
+    .unwrap();
+    let stored = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(stored.title, "New");
+    assert_eq!(stored.description.as_deref(), Some("d"));
+
+    assert!(run(&db, &["edit", &task.id, "--plugin", "gsd"]).is_err());
+    assert!(run(&db, &["edit", &task.id]).is_err());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_edit_labels_priority_and_due_date() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut task = Task::new("Fix login", "claude", "p");
+    task.status = TaskStatus::Running;
+    db.create_task(&task).unwrap();
+
+    run(
+        &db,
+        &[
+            "edit",
+            &task.id,
+            "--labels",
+            "bug,auth",
+            "--priority",
+            "urgent",
+            "--due",
+            "2026-03-01",
+        ],
+    )
+    .unwrap();
+    let stored = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(stored.labels, ["auth", "bug"]);
+    assert_eq!(stored.priority, Priority::Urgent);
+    assert_eq!(stored.due_date.unwrap().to_string(), "2026-03-01");
+
+    run(&db, &["edit", &task.id, "--labels", "", "--due", ""]).unwrap();
+    let stored = db.get_task(&task.id).unwrap().unwrap();
+    assert!(stored.labels.is_empty());
+    assert!(stored.due_date.is_none());
+
+    assert!(run(&db, &["edit", &task.id, "--priority", "asap"]).is_err());
+    assert!(run(&db, &["edit", &task.id, "--due", "March 1st"]).is_err());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_add_subtask_and_finish_parent_last() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut parent = Task::new("Auth epic", "claude", "p");
+    parent.plugin = Some("gsd".to_string());
+    parent.base_branch = Some("feature/auth".to_string());
+    db.create_task(&parent).unwrap();
+
+    let out = run(
+        &db,
+        &["add", "Login form", "--parent", &parent.id[..6], "--json"],
+    )
+    .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["parent_id"], parent.id.as_str());
+    assert_eq!(json["plugin"], "gsd");
+    assert_eq!(json["base_branch"], "feature/auth");
+    let child_id = json["id"].as_str().unwrap().to_string();
+
+    let out = run(&db, &["show", &parent.id]).unwrap();
+    assert!(out.contains("Subtasks:    0/1 done"));
+
+    assert!(run(&db, &["move", &parent.id, "done"]).is_err());
+    run(&db, &["move", &child_id, "done"]).unwrap();
+    run(&db, &["move", &parent.id, "done"]).unwrap();
+
+    assert!(run(&db, &["edit", &child_id, "--parent", &parent.id]).is_err());
+}
+
+// === agtx db ===
+
+#[test]
+fn test_parse_db_migrate_args() {
+    let parsed = parse_db_args(&args(&["migrate", "--dry-run", "--project", "/tmp/p"])).unwrap();
+    assert!(parsed.dry_run);
+    assert_eq!(
+        parsed.project.as_deref(),
+        Some(std::path::Path::new("/tmp/p"))
+    );
+
+    assert!(!parse_db_args(&args(&["migrate"])).unwrap().dry_run);
+    assert!(parse_db_args(&args(&[])).is_err());
+    assert!(parse_db_args(&args(&["vacuum"])).is_err());
+    assert!(parse_db_args(&args(&["migrate", "--force"])).is_err());
+}
+
+// === agtx stats ===
+
+#[test]
+fn test_parse_stats_args() {
+    let parsed = parse_stats_args(&args(&["--weeks", "12", "--json"])).unwrap();
+    assert_eq!(parsed.weeks, 12);
+    assert!(parsed.json);
+    assert_eq!(parse_stats_args(&args(&[])).unwrap().weeks, 8);
+
+    assert!(parse_stats_args(&args(&["--weeks", "0"])).is_err());
+    assert!(parse_stats_args(&args(&["--weeks"])).is_err());
+    assert!(parse_stats_args(&args(&["extra"])).is_err());
+}
+
+#[test]
+fn test_format_migration_status() {
+    use agtx::db::{MigrationStatus, PendingMigration, SchemaKind};
+
+    let mut status = MigrationStatus {
+        kind: SchemaKind::Project,
+        current_version: 3,
+        latest_version: 5,
+        pending: vec![
+            PendingMigration {
+                version: 4,
+                description: "Four",
+            },
+            PendingMigration {
+                version: 5,
+                description: "Five",
+            },
+        ],
+    };
+    let path = std::path::Path::new("/x/p.db");
+    let out = format_migration_status(&status, path, true);
+    assert!(out.starts_with("project database (/x/p.db): would migrate from schema version 3 to 5"));
+    assert!(out.contains("4  Four") && out.contains("5  Five"));
+    assert!(format_migration_status(&status, path, false).contains(": migrated from"));
+
+    status.current_version = 5;
+    status.pending.clear();
+    assert!(
+        format_migration_status(&status, path, true).ends_with("up to date at schema version 5")
+    );
+}
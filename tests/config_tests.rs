@@ -1,6 +1,7 @@
 use agtx::config::{
-    determine_first_run_action, FirstRunAction, GlobalConfig, MergedConfig, PhaseAgentsConfig,
-    ProjectConfig, ThemeConfig, WorktreeConfig,
+    determine_first_run_action, CustomAgentConfig, FinishStrategy, FirstRunAction, GlobalConfig,
+    KeyBinding, MergedConfig, PhaseAgentsConfig, ProjectConfig, SandboxTool, SessionBackend,
+    ThemeConfig, WorktreeConfig,
 };
 
 // === ThemeConfig Tests ===
@@ -109,10 +110,19 @@ fn test_merged_config_project_overrides() {
         base_branch: Some("develop".to_string()),
         worktree_dir: None,
         github_url: Some("https://github.com/user/repo".to_string()),
+        git_provider: None,
+        gitlab_url: None,
+        gitlab_allow_http: None,
         copy_files: Some(".env, .env.local".to_string()),
         init_script: Some("npm install".to_string()),
         cleanup_script: Some("scripts/cleanup.sh".to_string()),
         workflow_plugin: None,
+        custom_agents: vec![],
+        finish_strategy: None,
+        max_agents_per_project: None,
+        sandbox: None,
+        budget: None,
+        views: vec![],
     };
 
     let merged = MergedConfig::merge(&global, &project);
@@ -155,6 +165,174 @@ fn test_merged_config_worktree_dir_global() {
     assert_eq!(merged.worktree_dir, ".wt");
 }
 
+#[test]
+fn test_finish_strategy_defaults_to_pr() {
+    let merged = MergedConfig::merge(&GlobalConfig::default(), &ProjectConfig::default());
+    assert_eq!(merged.finish_strategy, FinishStrategy::Pr);
+    assert_eq!(merged.finish_strategy.local_merge(), None);
+}
+
+#[test]
+fn test_finish_strategy_project_overrides_global() {
+    let global: GlobalConfig = toml::from_str(r#"finish_strategy = "squash""#).unwrap();
+    let project: ProjectConfig = toml::from_str(r#"finish_strategy = "rebase""#).unwrap();
+    assert_eq!(
+        MergedConfig::merge(&global, &ProjectConfig::default()).finish_strategy,
+        FinishStrategy::Squash
+    );
+
+    let merged = MergedConfig::merge(&global, &project);
+    assert_eq!(merged.finish_strategy, FinishStrategy::Rebase);
+    assert_eq!(
+        merged.finish_strategy.local_merge(),
+        Some(agtx::git::MergeStrategy::Rebase)
+    );
+}
+
+#[test]
+fn test_session_backend_defaults_to_tmux() {
+    assert_eq!(
+        GlobalConfig::default().session_backend,
+        SessionBackend::Tmux
+    );
+    let global: GlobalConfig = toml::from_str(r#"session_backend = "zellij""#).unwrap();
+    assert_eq!(global.session_backend, SessionBackend::Zellij);
+    let global: GlobalConfig = toml::from_str(r#"session_backend = "pty""#).unwrap();
+    assert_eq!(global.session_backend, SessionBackend::Pty);
+    assert!(toml::from_str::<GlobalConfig>(r#"session_backend = "screen""#).is_err());
+}
+
+#[test]
+fn test_agent_limits_parse_and_project_override() {
+    let global: GlobalConfig = toml::from_str(
+        r#"
+        [max_agents_per_project]
+        total = 6
+        running = 3
+        "#,
+    )
+    .unwrap();
+    let project: ProjectConfig = toml::from_str(
+        r#"
+        [max_agents_per_project]
+        running = 1
+        research = 0
+        "#,
+    )
+    .unwrap();
+
+    let limits = MergedConfig::merge(&global, &project).agent_limits;
+    assert_eq!(limits.total(), Some(6));
+    assert_eq!(limits.for_phase("running"), Some(1));
+    // 0 and unset both mean no limit
+    assert_eq!(limits.for_phase("research"), None);
+    assert_eq!(limits.for_phase("planning"), None);
+    assert_eq!(limits.for_phase("review"), None);
+
+    let unlimited = MergedConfig::merge(&GlobalConfig::default(), &ProjectConfig::default());
+    assert!(unlimited.agent_limits.is_empty());
+    assert!(!toml::to_string(&GlobalConfig::default())
+        .unwrap()
+        .contains("max_agents_per_project"));
+}
+
+#[test]
+fn test_sandbox_project_profile_overrides_global() {
+    let global: GlobalConfig = toml::from_str(
+        r#"
+        [sandbox]
+        enabled = true
+        network = false
+        writable = ["~/.npm"]
+        "#,
+    )
+    .unwrap();
+    let project: ProjectConfig = toml::from_str(
+        r#"
+        [sandbox]
+        tool = "firejail"
+        network = true
+        hidden = ["~/.ssh"]
+        "#,
+    )
+    .unwrap();
+
+    let sandbox = MergedConfig::merge(&global, &project).sandbox;
+    assert!(sandbox.is_enabled());
+    assert_eq!(sandbox.tool(), SandboxTool::Firejail);
+    assert!(sandbox.allows_network());
+    assert_eq!(sandbox.writable, vec!["~/.npm".to_string()]);
+    assert_eq!(sandbox.hidden, vec!["~/.ssh".to_string()]);
+
+    let off = MergedConfig::merge(&GlobalConfig::default(), &ProjectConfig::default()).sandbox;
+    assert!(!off.is_enabled());
+    assert_eq!(off.tool(), SandboxTool::Bubblewrap);
+    assert!(off.allows_network());
+    assert!(!toml::to_string(&GlobalConfig::default())
+        .unwrap()
+        .contains("sandbox"));
+}
+
+#[test]
+fn test_budget_limits_report_first_exceeded() {
+    let project: ProjectConfig = toml::from_str(
+        r#"
+        [budget]
+        interrupt = true
+
+        [budget.task]
+        usd = 5.0
+        minutes = 90
+
+        [budget.project]
+        tokens = 0
+        "#,
+    )
+    .unwrap();
+
+    let budget = MergedConfig::merge(&GlobalConfig::default(), &project).budget;
+    assert!(budget.interrupt);
+    assert!(!budget.task.is_empty());
+    // A zero limit means no limit
+    assert!(budget.project.is_empty());
+    assert_eq!(budget.task.exceeded(1_000_000, 4.99, 89), None);
+    assert_eq!(
+        budget.task.exceeded(0, 5.123, 0).as_deref(),
+        Some("$5.12 of $5.00")
+    );
+    assert_eq!(
+        budget.task.exceeded(0, 0.0, 95).as_deref(),
+        Some("95 of 90 minutes")
+    );
+    assert_eq!(budget.project.exceeded(u64::MAX, 1e9, u64::MAX), None);
+
+    let none = MergedConfig::merge(&GlobalConfig::default(), &ProjectConfig::default()).budget;
+    assert!(none.is_empty());
+    assert!(!none.interrupt);
+}
+
+#[test]
+fn test_keybindings_parse_single_and_multiple_chords() {
+    let config: GlobalConfig = toml::from_str(
+        r#"
+        [keybindings]
+        move_forward = "n"
+        nav_down = ["j", "C-n"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.keybindings.get("move_forward"),
+        Some(&KeyBinding::One("n".to_string()))
+    );
+    assert_eq!(
+        config.keybindings["nav_down"].chords(),
+        &["j".to_string(), "C-n".to_string()]
+    );
+    assert!(GlobalConfig::default().keybindings.is_empty());
+}
+
 // === FirstRunAction Tests ===
 
 #[test]
@@ -324,6 +502,142 @@ default_agent = "claude"
     assert_eq!(config.agents.review, None);
 }
 
+// === Custom Agent Tests ===
+
+fn custom_agent(name: &str, command: &str) -> CustomAgentConfig {
+    CustomAgentConfig {
+        name: name.to_string(),
+        command: command.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_custom_agents_parse_from_toml() {
+    let toml_str = r#"
+default_agent = "aider"
+
+[[custom_agents]]
+name = "aider"
+command = "aider"
+interactive = "aider --yes-always"
+print = "aider --message {prompt}"
+
+[custom_agents.skills]
+dir = ".aider/commands"
+namespace = "agtx"
+"#;
+    let config: GlobalConfig = toml::from_str(toml_str).unwrap();
+    assert_eq!(config.custom_agents.len(), 1);
+    let aider = &config.custom_agents[0];
+    assert_eq!(aider.interactive.as_deref(), Some("aider --yes-always"));
+    assert_eq!(aider.resume, None);
+    let skills = aider.skills.as_ref().unwrap();
+    assert_eq!(skills.dir, ".aider/commands");
+    assert_eq!(skills.style, "claude", "style defaults to claude");
+    assert!(CustomAgentConfig::validate_all(&config.custom_agents).is_ok());
+}
+
+#[test]
+fn test_custom_agents_default_to_empty_and_are_not_serialized() {
+    let config: GlobalConfig = toml::from_str("").unwrap();
+    assert!(config.custom_agents.is_empty());
+    let out = toml::to_string_pretty(&GlobalConfig::default()).unwrap();
+    assert!(!out.contains("custom_agents"));
+}
+
+#[test]
+fn test_custom_agent_validate_rejects_builtin_name() {
+    let err = custom_agent("claude", "my-claude").validate().unwrap_err();
+    assert!(err.to_string().contains("built-in"));
+}
+
+#[test]
+fn test_custom_agent_validate_rejects_bad_name_and_empty_command() {
+    assert!(custom_agent("", "aider").validate().is_err());
+    assert!(custom_agent("my agent", "aider").validate().is_err());
+    assert!(custom_agent("../x", "aider").validate().is_err());
+    assert!(custom_agent("aider", "  ").validate().is_err());
+}
+
+#[test]
+fn test_custom_agent_validate_requires_prompt_placeholder() {
+    let mut agent = custom_agent("aider", "aider");
+    agent.print = Some("aider --message".to_string());
+    assert!(agent.validate().is_err());
+    agent.print = Some("aider --message {prompt}".to_string());
+    agent.interactive_prompt = Some("aider".to_string());
+    assert!(agent.validate().is_err());
+}
+
+#[test]
+fn test_custom_agent_validate_rejects_unbalanced_print_quotes() {
+    let mut agent = custom_agent("aider", "aider");
+    agent.print = Some("aider --message '{prompt}".to_string());
+    assert!(agent.validate().is_err());
+}
+
+#[test]
+fn test_custom_agent_validate_skills_layout() {
+    let mut agent = custom_agent("aider", "aider");
+    agent.skills = Some(agtx::config::CustomAgentSkills {
+        dir: "../outside".to_string(),
+        namespace: String::new(),
+        style: "claude".to_string(),
+    });
+    assert!(agent.validate().is_err());
+
+    agent.skills.as_mut().unwrap().dir = ".aider/commands".to_string();
+    agent.skills.as_mut().unwrap().style = "vim".to_string();
+    let err = agent.validate().unwrap_err();
+    assert!(err.to_string().contains("skills.style"));
+
+    agent.skills.as_mut().unwrap().style = "codex".to_string();
+    assert!(agent.validate().is_ok());
+}
+
+#[test]
+fn test_custom_agent_validate_all_rejects_duplicates() {
+    let agents = vec![
+        custom_agent("aider", "aider"),
+        custom_agent("aider", "aider2"),
+    ];
+    let err = CustomAgentConfig::validate_all(&agents).unwrap_err();
+    assert!(err.to_string().contains("more than once"));
+}
+
+#[test]
+fn test_merged_config_custom_agents_project_overrides_by_name() {
+    let global = GlobalConfig {
+        custom_agents: vec![
+            custom_agent("aider", "aider"),
+            custom_agent("goose", "goose"),
+        ],
+        ..Default::default()
+    };
+    let project = ProjectConfig {
+        custom_agents: vec![
+            custom_agent("aider", "./bin/aider"),
+            custom_agent("mine", "mine"),
+        ],
+        ..Default::default()
+    };
+
+    let merged = MergedConfig::merge(&global, &project);
+    let names: Vec<&str> = merged
+        .custom_agents
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["goose", "aider", "mine"]);
+    let aider = merged
+        .custom_agents
+        .iter()
+        .find(|a| a.name == "aider")
+        .unwrap();
+    assert_eq!(aider.command, "./bin/aider");
+}
+
 #[test]
 fn test_fullscreen_on_enter_defaults_to_false() {
     let config: GlobalConfig = toml::from_str("").unwrap();
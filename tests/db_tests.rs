@@ -1,4 +1,8 @@
-use agtx::db::{Database, Notification, Project, Task, TaskStatus, TransitionRequest};
+use agtx::db::{
+    idle_durations, status_durations, Database, EventActor, Notification, PhaseStatus, Priority,
+    Project, SchemaKind, SubtaskProgress, Task, TaskEvent, TaskEventKind, TaskStatus, TaskUsage,
+    TransitionRequest, Usage,
+};
 
 // === TaskStatus Tests ===
 
@@ -165,6 +169,36 @@ fn test_in_memory_project_db_update_task() {
     assert_eq!(retrieved.session_name.as_deref(), Some("session-1"));
 }
 
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_labels_priority_and_due_date_roundtrip() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut task = Task::new("Fix login", "claude", "proj-1");
+    task.set_labels(["#bug", " auth ", "bug", ""]);
+    task.priority = Priority::High;
+    task.due_date = Some(chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+    db.create_task(&task).unwrap();
+    let mut other = Task::new("Docs", "claude", "proj-1");
+    other.set_labels(["docs", "auth"]);
+    db.create_task(&other).unwrap();
+
+    let fetched = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(fetched.labels, ["auth", "bug"]);
+    assert_eq!(fetched.priority, Priority::High);
+    assert_eq!(fetched.due_date, task.due_date);
+    assert_eq!(db.get_labels().unwrap(), ["auth", "bug", "docs"]);
+
+    // Labels are replaced on update and dropped with the task
+    task.set_labels(["frontend"]);
+    task.due_date = None;
+    db.update_task(&task).unwrap();
+    let fetched = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(fetched.labels, ["frontend"]);
+    assert_eq!(fetched.due_date, None);
+    db.delete_task(&other.id).unwrap();
+    assert_eq!(db.get_labels().unwrap(), ["frontend"]);
+}
+
 #[test]
 #[cfg(feature = "test-mocks")]
 fn test_in_memory_project_db_list_tasks() {
@@ -307,6 +341,151 @@ fn test_deps_not_satisfied_dep_in_planning() {
     assert!(!db.deps_satisfied(&task));
 }
 
+#[test]
+fn test_dependencies_keep_their_order() {
+    let db = Database::open_in_memory_project().unwrap();
+    let first = Task::new("First", "claude", "proj");
+    let second = Task::new("Second", "claude", "proj");
+    db.create_task(&first).unwrap();
+    db.create_task(&second).unwrap();
+
+    let mut task = Task::new("Main task", "claude", "proj");
+    task.referenced_tasks = Some(format!("{}, {},{}", second.id, first.id, second.id));
+    db.create_task(&task).unwrap();
+
+    let stored = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(
+        stored.referenced_tasks,
+        Some(format!("{},{}", second.id, first.id))
+    );
+
+    // Deleting a dependency drops it from its dependents
+    db.delete_task(&second.id).unwrap();
+    let stored = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(stored.referenced_tasks, Some(first.id.clone()));
+}
+
+#[test]
+fn test_dependency_cycle_is_rejected() {
+    let mut db = Database::open_in_memory_project().unwrap();
+    let mut a = Task::new("Schema", "claude", "proj");
+    db.create_task(&a).unwrap();
+    let mut b = Task::new("API", "claude", "proj");
+    b.referenced_tasks = Some(a.id.clone());
+    db.create_task(&b).unwrap();
+
+    a.referenced_tasks = Some(b.id.clone());
+    let err = db.update_task(&a).unwrap_err();
+    assert_eq!(err.to_string(), "Dependency cycle: Schema → API → Schema");
+    let stored = db.get_task(&a.id).unwrap().unwrap();
+    assert_eq!(stored.referenced_tasks, None, "the update is rolled back");
+
+    let mut own = Task::new("Loop", "claude", "proj");
+    own.referenced_tasks = Some(own.id.clone());
+    assert!(db.create_task(&own).is_err());
+    assert!(db.get_task(&own.id).unwrap().is_none());
+
+    let mut c = Task::new("C", "claude", "proj");
+    let mut d = Task::new("D", "claude", "proj");
+    c.referenced_tasks = Some(d.id.clone());
+    d.referenced_tasks = Some(c.id.clone());
+    let err = db.create_tasks_batch(&[c.clone(), d]).unwrap_err();
+    assert!(err.to_string().starts_with("Dependency cycle: C → D → C"));
+    assert!(db.get_task(&c.id).unwrap().is_none());
+}
+
+// === Subtask Tests ===
+
+#[test]
+fn test_set_parent_inherits_plugin_and_base_branch() {
+    let mut parent = Task::new("Auth epic", "claude", "proj");
+    parent.plugin = Some("gsd".to_string());
+    parent.base_branch = Some("feature/auth".to_string());
+
+    let mut child = Task::new("Login form", "claude", "proj");
+    child.set_parent(&parent);
+    assert_eq!(child.parent_id.as_deref(), Some(parent.id.as_str()));
+    assert_eq!(child.plugin.as_deref(), Some("gsd"));
+    assert_eq!(child.base_branch.as_deref(), Some("feature/auth"));
+
+    // Its own settings win
+    let mut custom = Task::new("Docs", "claude", "proj");
+    custom.plugin = Some("void".to_string());
+    custom.set_parent(&parent);
+    assert_eq!(custom.plugin.as_deref(), Some("void"));
+    assert_eq!(custom.base_branch.as_deref(), Some("feature/auth"));
+}
+
+#[test]
+fn test_subtasks_roll_up_to_parent() {
+    let mut db = Database::open_in_memory_project().unwrap();
+    let mut parent = Task::new("Auth epic", "claude", "proj");
+    parent.status = TaskStatus::Review;
+    db.create_task(&parent).unwrap();
+    assert!(db.subtasks_done(&parent).unwrap());
+    assert_eq!(db.subtask_progress(&parent).unwrap(), None);
+
+    let mut first = Task::new("Login form", "claude", "proj");
+    first.set_parent(&parent);
+    first.status = TaskStatus::Done;
+    let mut second = Task::new("Logout", "claude", "proj");
+    second.set_parent(&parent);
+    db.create_tasks_batch(&[first, second.clone()]).unwrap();
+
+    let subtasks = db.get_subtasks(&parent.id).unwrap();
+    let titles: Vec<&str> = subtasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["Login form", "Logout"]);
+    assert_eq!(
+        db.subtask_progress(&parent).unwrap(),
+        Some(SubtaskProgress { done: 1, total: 2 })
+    );
+    assert!(!db.subtasks_done(&parent).unwrap());
+
+    second.status = TaskStatus::Done;
+    db.update_task(&second).unwrap();
+    assert!(db.subtasks_done(&parent).unwrap());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_unreadable_subtasks_block_done() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("subtasks.db");
+    let db = Database::open_project_at_path(&db_path).unwrap();
+    let parent = Task::new("Auth epic", "claude", "proj");
+    db.create_task(&parent).unwrap();
+    let mut child = Task::new("Login form", "claude", "proj");
+    child.set_parent(&parent);
+    db.create_task(&child).unwrap();
+
+    // A row that no longer decodes as a task
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "UPDATE tasks SET title = X'00FF' WHERE id = ?1",
+        [&child.id],
+    )
+    .unwrap();
+    drop(conn);
+
+    assert!(db.get_subtasks(&parent.id).is_err());
+    assert!(db.subtask_progress(&parent).is_err());
+    assert!(db.subtasks_done(&parent).is_err());
+}
+
+#[test]
+fn test_delete_parent_keeps_subtasks() {
+    let db = Database::open_in_memory_project().unwrap();
+    let parent = Task::new("Auth epic", "claude", "proj");
+    db.create_task(&parent).unwrap();
+    let mut child = Task::new("Login form", "claude", "proj");
+    child.set_parent(&parent);
+    db.create_task(&child).unwrap();
+
+    db.delete_task(&parent.id).unwrap();
+    let child = db.get_task(&child.id).unwrap().unwrap();
+    assert_eq!(child.parent_id, None);
+}
+
 // === transition_request claim tests ===
 
 #[test]
@@ -558,3 +737,408 @@ fn test_global_db_file_permissions_are_0600() {
         assert_eq!(mode, 0o600, "Global DB file should be owner-only read/write");
     }
 }
+
+// === Task Event History Tests ===
+
+#[test]
+fn test_task_event_diff_detects_changes() {
+    let old = Task::new("T", "claude", "proj");
+    let mut new = old.clone();
+    new.status = TaskStatus::Running;
+    new.agent = "codex".to_string();
+    new.cycle = 2;
+    new.pr_url = Some("https://example.com/pr/1".to_string());
+    new.escalation_note = Some("Needs review".to_string());
+
+    let kinds: Vec<TaskEventKind> = TaskEvent::diff(&old, &new, EventActor::Mcp)
+        .iter()
+        .map(|e| e.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TaskEventKind::StatusChanged,
+            TaskEventKind::AgentSwitched,
+            TaskEventKind::CycleAdvanced,
+            TaskEventKind::PrCreated,
+            TaskEventKind::Escalated,
+        ]
+    );
+
+    // Unchanged task produces no events; clearing a note is recorded
+    assert!(TaskEvent::diff(&new, &new, EventActor::User).is_empty());
+    let mut cleared = new.clone();
+    cleared.escalation_note = None;
+    let events = TaskEvent::diff(&new, &cleared, EventActor::User);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, TaskEventKind::EscalationCleared);
+}
+
+#[test]
+fn test_status_durations_accumulate_per_status() {
+    let start = chrono::Utc::now() - chrono::Duration::minutes(60);
+    let at = |mins: i64, kind: TaskEventKind, to: TaskStatus| {
+        let mut e = TaskEvent::new("t", kind, EventActor::User);
+        e.to_value = Some(to.as_str().to_string());
+        e.created_at = start + chrono::Duration::minutes(mins);
+        e
+    };
+    let events = vec![
+        at(0, TaskEventKind::Created, TaskStatus::Backlog),
+        at(10, TaskEventKind::StatusChanged, TaskStatus::Planning),
+        at(20, TaskEventKind::StatusChanged, TaskStatus::Running),
+        at(30, TaskEventKind::StatusChanged, TaskStatus::Planning),
+        at(35, TaskEventKind::StatusChanged, TaskStatus::Running),
+    ];
+    let now = start + chrono::Duration::minutes(50);
+    let durations = status_durations(&events, now);
+    let get = |s: TaskStatus| durations.iter().find(|(st, _)| *st == s).unwrap().1;
+    assert_eq!(get(TaskStatus::Backlog).num_minutes(), 10);
+    assert_eq!(get(TaskStatus::Planning).num_minutes(), 15);
+    assert_eq!(get(TaskStatus::Running).num_minutes(), 25);
+    assert!(durations.iter().all(|(s, _)| *s != TaskStatus::Review));
+}
+
+#[test]
+fn test_idle_durations_split_at_status_changes() {
+    let start = chrono::Utc::now() - chrono::Duration::minutes(60);
+    let at = |mins: i64, kind: TaskEventKind, to: Option<TaskStatus>| {
+        let mut e = TaskEvent::new("t", kind, EventActor::User);
+        e.to_value = to.map(|s| s.as_str().to_string());
+        e.created_at = start + chrono::Duration::minutes(mins);
+        e
+    };
+    let events = vec![
+        at(0, TaskEventKind::Created, Some(TaskStatus::Planning)),
+        at(5, TaskEventKind::IdleStarted, None),
+        at(8, TaskEventKind::IdleEnded, None),
+        at(12, TaskEventKind::IdleStarted, None),
+        at(20, TaskEventKind::StatusChanged, Some(TaskStatus::Running)),
+        at(25, TaskEventKind::IdleEnded, None),
+        at(30, TaskEventKind::IdleStarted, None),
+    ];
+    let now = start + chrono::Duration::minutes(40);
+    let idle = idle_durations(&events, now);
+    let get = |s: TaskStatus| idle.iter().find(|(st, _)| *st == s).unwrap().1;
+    assert_eq!(get(TaskStatus::Planning).num_minutes(), 11);
+    // 5 minutes carried over the status change plus the open period
+    assert_eq!(get(TaskStatus::Running).num_minutes(), 15);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_add_task_event_and_list_all_events() {
+    let db = Database::open_in_memory_project().unwrap();
+    let a = Task::new("A", "claude", "proj");
+    let b = Task::new("B", "claude", "proj");
+    db.create_task(&a).unwrap();
+    db.create_task(&b).unwrap();
+    db.add_task_event(&TaskEvent::new(
+        &a.id,
+        TaskEventKind::IdleStarted,
+        EventActor::User,
+    ))
+    .unwrap();
+
+    let all = db.get_all_task_events().unwrap();
+    assert_eq!(all.len(), 3);
+    let idle = db.get_task_events(&a.id).unwrap();
+    assert_eq!(idle.last().unwrap().kind, TaskEventKind::IdleStarted);
+    assert_eq!(idle.last().unwrap().summary(), "agent idle");
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_create_and_update_task_record_events() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut task = Task::new("Tracked", "claude", "proj");
+    db.create_task(&task).unwrap();
+
+    db.set_actor(EventActor::Orchestrator);
+    task.status = TaskStatus::Planning;
+    db.update_task(&task).unwrap();
+    // Saving without changes records nothing
+    db.update_task(&task).unwrap();
+
+    let events = db.get_task_events(&task.id).unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind, TaskEventKind::Created);
+    assert_eq!(events[0].actor, EventActor::User);
+    assert_eq!(events[1].kind, TaskEventKind::StatusChanged);
+    assert_eq!(events[1].from_value.as_deref(), Some("backlog"));
+    assert_eq!(events[1].to_value.as_deref(), Some("planning"));
+    assert_eq!(events[1].actor, EventActor::Orchestrator);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_phase_status_roundtrip() {
+    let db = Database::open_in_memory_project().unwrap();
+    let task = Task::new("Watched", "claude", "proj");
+    db.create_task(&task).unwrap();
+    assert_eq!(db.get_phase_status(&task.id).unwrap(), None);
+
+    db.set_phase_status(&task.id, PhaseStatus::Working).unwrap();
+    db.set_phase_status(&task.id, PhaseStatus::Idle).unwrap();
+    assert_eq!(
+        db.get_phase_status(&task.id).unwrap(),
+        Some(PhaseStatus::Idle)
+    );
+
+    db.delete_task(&task.id).unwrap();
+    assert_eq!(db.get_phase_status(&task.id).unwrap(), None);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_queued_phase_roundtrip_and_event() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut task = Task::new("Waiting", "claude", "proj");
+    db.create_task(&task).unwrap();
+
+    task.queued_phase = Some("running".to_string());
+    task.queued_at = Some(chrono::Utc::now());
+    db.update_task(&task).unwrap();
+    let fetched = db.get_task(&task.id).unwrap().unwrap();
+    assert_eq!(fetched.queued_phase.as_deref(), Some("running"));
+    assert_eq!(
+        fetched.queued_at.map(|t| t.timestamp()),
+        task.queued_at.map(|t| t.timestamp())
+    );
+
+    task.queued_phase = None;
+    task.queued_at = None;
+    db.update_task(&task).unwrap();
+    let fetched = db.get_task(&task.id).unwrap().unwrap();
+    assert!(fetched.queued_phase.is_none());
+    assert!(fetched.queued_at.is_none());
+
+    // Queuing is recorded; leaving the queue is not
+    let events = db.get_task_events(&task.id).unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].kind, TaskEventKind::Queued);
+    assert_eq!(events[1].to_value.as_deref(), Some("running"));
+    assert_eq!(
+        events[1].summary(),
+        "queued for running (agent limit reached)"
+    );
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_batch_create_and_delete_task_events() {
+    let mut db = Database::open_in_memory_project().unwrap();
+    let tasks = vec![Task::new("A", "claude", "p"), Task::new("B", "claude", "p")];
+    db.create_tasks_batch(&tasks).unwrap();
+    assert_eq!(db.get_task_events(&tasks[0].id).unwrap().len(), 1);
+
+    db.delete_task(&tasks[0].id).unwrap();
+    assert!(db.get_task(&tasks[0].id).unwrap().is_none());
+    // History is an audit trail and outlives the task
+    assert_eq!(db.get_task_events(&tasks[0].id).unwrap().len(), 1);
+    assert_eq!(db.get_task_events(&tasks[1].id).unwrap().len(), 1);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_transition_request_requested_by_roundtrip() {
+    let db = Database::open_in_memory_project().unwrap();
+    let mut req = TransitionRequest::new("t1", "move_forward");
+    req.requested_by = Some(EventActor::Cli);
+    db.create_transition_request(&req).unwrap();
+    let legacy = TransitionRequest::new("t2", "move_forward");
+    db.create_transition_request(&legacy).unwrap();
+
+    let fetched = db.get_transition_request(&req.id).unwrap().unwrap();
+    assert_eq!(fetched.requested_by, Some(EventActor::Cli));
+    let fetched = db.get_transition_request(&legacy.id).unwrap().unwrap();
+    assert_eq!(fetched.requested_by, None);
+}
+
+// === Schema Migration Tests ===
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_legacy_database_is_upgraded() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("legacy.db");
+
+    // Pre-versioning layout: some of the later columns already added, no schema_version
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE tasks (
+            id TEXT PRIMARY KEY, title TEXT NOT NULL, description TEXT,
+            status TEXT NOT NULL DEFAULT 'backlog', agent TEXT NOT NULL,
+            project_id TEXT NOT NULL, session_name TEXT, worktree_path TEXT,
+            branch_name TEXT, pr_number INTEGER, pr_url TEXT, plugin TEXT,
+            created_at TEXT NOT NULL, updated_at TEXT NOT NULL
+        );
+        INSERT INTO tasks (id, title, status, agent, project_id, created_at, updated_at)
+        VALUES ('old-1', 'Old task', 'review', 'claude', 'p',
+                '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');",
+    )
+    .unwrap();
+    drop(conn);
+
+    let db = Database::open_project_at_path(&db_path).unwrap();
+    assert_eq!(
+        db.schema_version().unwrap(),
+        SchemaKind::Project.latest_version()
+    );
+    let task = db.get_task("old-1").unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Review);
+    assert_eq!(task.cycle, 1);
+    assert_eq!(task.priority, Priority::Normal);
+    assert!(task.labels.is_empty());
+    assert!(db.get_task_events("old-1").unwrap().is_empty());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_dependency_lists_move_into_their_own_table() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("deps.db");
+    drop(Database::open_project_at_path(&db_path).unwrap());
+
+    // Roll back to before task_dependencies, when dependencies were a CSV column
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute_batch(
+        "DELETE FROM schema_version WHERE version >= 10;
+        DROP TABLE task_dependencies;
+        INSERT INTO tasks (id, title, status, agent, project_id, referenced_tasks, created_at, updated_at)
+        VALUES ('a', 'A', 'done', 'claude', 'p', NULL, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z'),
+               ('b', 'B', 'backlog', 'claude', 'p', '', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z'),
+               ('c', 'C', 'backlog', 'claude', 'p', 'b, a,,b', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');",
+    )
+    .unwrap();
+    drop(conn);
+
+    let db = Database::open_project_at_path(&db_path).unwrap();
+    assert_eq!(
+        db.schema_version().unwrap(),
+        SchemaKind::Project.latest_version()
+    );
+    let c = db.get_task("c").unwrap().unwrap();
+    assert_eq!(c.referenced_tasks.as_deref(), Some("b,a"));
+    assert_eq!(db.get_task("b").unwrap().unwrap().referenced_tasks, None);
+    assert!(!db.deps_satisfied(&c));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_newer_schema_version_is_refused() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("future.db");
+    drop(Database::open_project_at_path(&db_path).unwrap());
+
+    let future = SchemaKind::Project.latest_version() + 1;
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, 'future', '')",
+        [future],
+    )
+    .unwrap();
+    drop(conn);
+
+    let err = Database::open_project_at_path(&db_path).err().unwrap();
+    assert!(err.to_string().contains("newer version of agtx"));
+    assert!(Database::migration_status(&db_path, SchemaKind::Project).is_err());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_migration_status_does_not_modify_database() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("status.db");
+
+    let status = Database::migration_status(&db_path, SchemaKind::Project).unwrap();
+    assert_eq!(status.current_version, 0);
+    assert_eq!(
+        status.pending.len() as u32,
+        SchemaKind::Project.latest_version()
+    );
+    assert!(!db_path.exists(), "dry run must not create the database");
+
+    drop(Database::open_project_at_path(&db_path).unwrap());
+    let status = Database::migration_status(&db_path, SchemaKind::Project).unwrap();
+    assert_eq!(status.current_version, status.latest_version);
+    assert!(status.pending.is_empty());
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_reopening_applies_no_migrations_twice() {
+    let db = Database::open_in_memory_global().unwrap();
+    assert_eq!(
+        db.schema_version().unwrap(),
+        SchemaKind::Global.latest_version()
+    );
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("twice.db");
+    drop(Database::open_project_at_path(&db_path).unwrap());
+    let db = Database::open_project_at_path(&db_path).unwrap();
+    assert_eq!(
+        db.schema_version().unwrap(),
+        SchemaKind::Project.latest_version()
+    );
+}
+
+// === Token Usage Tests ===
+
+fn usage_row(
+    task_id: &str,
+    phase: &str,
+    agent: &str,
+    output_tokens: u64,
+    cost_usd: f64,
+) -> TaskUsage {
+    TaskUsage {
+        task_id: task_id.to_string(),
+        phase: phase.to_string(),
+        agent: agent.to_string(),
+        usage: Usage {
+            input_tokens: 100,
+            output_tokens,
+            cost_usd,
+            ..Default::default()
+        },
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_task_usage_replace_and_totals() {
+    let db = Database::open_in_memory_project().unwrap();
+    let task = Task::new("Costly", "claude", "proj");
+    db.create_task(&task).unwrap();
+
+    db.replace_task_usage(
+        &task.id,
+        &[usage_row(&task.id, "running", "claude", 50, 1.0)],
+    )
+    .unwrap();
+    db.replace_task_usage(
+        &task.id,
+        &[
+            usage_row(&task.id, "running", "claude", 500, 2.0),
+            usage_row(&task.id, "planning", "codex", 200, 0.5),
+        ],
+    )
+    .unwrap();
+
+    let rows = db.get_task_usage(&task.id).unwrap();
+    let phases: Vec<&str> = rows.iter().map(|r| r.phase.as_str()).collect();
+    assert_eq!(phases, ["planning", "running"]);
+    assert_eq!(rows[1].usage.output_tokens, 500);
+
+    let totals = db.get_usage_totals().unwrap();
+    let total = totals[&task.id];
+    assert_eq!(total.total_tokens(), 900);
+    assert!((total.cost_usd - 2.5).abs() < 1e-9);
+    assert_eq!(db.get_project_usage().unwrap(), total);
+
+    db.delete_task(&task.id).unwrap();
+    assert!(db.get_task_usage(&task.id).unwrap().is_empty());
+    assert!(db.get_project_usage().unwrap().is_empty());
+}
@@ -0,0 +1,64 @@
+use agtx::db::{PhaseStatus, Task};
+use agtx::tui::filter::BoardFilter;
+
+fn task(title: &str, agent: &str, plugin: Option<&str>, labels: &[&str]) -> Task {
+    let mut task = Task::new(title, agent, "proj");
+    task.plugin = plugin.map(String::from);
+    task.set_labels(labels.iter().copied());
+    task
+}
+
+#[test]
+fn test_empty_filter_matches_everything() {
+    let filter = BoardFilter::parse("   ");
+    assert!(filter.is_empty());
+    assert_eq!(filter.query(), "");
+    assert!(filter.matches(&task("Fix login", "claude", None, &[]), None));
+}
+
+#[test]
+fn test_filter_by_label_agent_and_plugin() {
+    let login = task("Fix login", "claude", None, &["bug", "auth"]);
+    let docs = task("Write docs", "codex", Some("gsd"), &["docs"]);
+
+    let matching = |query: &str| -> Vec<String> {
+        let filter = BoardFilter::parse(query);
+        [&login, &docs]
+            .into_iter()
+            .filter(|t| filter.matches(t, None))
+            .map(|t| t.title.clone())
+            .collect()
+    };
+    assert_eq!(matching("label:bug"), ["Fix login"]);
+    assert_eq!(matching("#Docs"), ["Write docs"]);
+    assert_eq!(matching("agent:codex"), ["Write docs"]);
+    assert_eq!(matching("plugin:agtx"), ["Fix login"]);
+    assert_eq!(matching("plugin:gsd"), ["Write docs"]);
+    assert_eq!(matching("LOGIN"), ["Fix login"]);
+    // Terms are combined, `-` inverts one
+    assert_eq!(matching("agent:claude #docs"), Vec::<String>::new());
+    assert_eq!(matching("-label:bug"), ["Write docs"]);
+}
+
+#[test]
+fn test_filter_text_searches_description() {
+    let mut task = task("Fix login", "claude", None, &[]);
+    task.description = Some("Sessions expire after an hour".to_string());
+    assert!(BoardFilter::parse("expire").matches(&task, None));
+    assert!(!BoardFilter::parse("refresh").matches(&task, None));
+}
+
+#[test]
+fn test_filter_by_escalation_and_phase_status() {
+    let mut task = task("Fix login", "claude", None, &[]);
+    assert!(!BoardFilter::parse("is:escalated").matches(&task, None));
+    task.escalation_note = Some("Which auth provider?".to_string());
+    assert!(BoardFilter::parse("is:escalated").matches(&task, None));
+
+    let idle = BoardFilter::parse("is:idle");
+    assert!(idle.matches(&task, Some(PhaseStatus::Idle)));
+    assert!(!idle.matches(&task, Some(PhaseStatus::Working)));
+    // No live session, no phase status
+    assert!(!idle.matches(&task, None));
+    assert!(BoardFilter::parse("-is:exited").matches(&task, None));
+}
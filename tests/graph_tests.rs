@@ -0,0 +1,116 @@
+use agtx::db::{Priority, Task, TaskStatus};
+use agtx::graph::{self, GraphLine};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+
+fn task(id: &str, status: TaskStatus, deps: &[&str]) -> Task {
+    let mut task = Task::new(id.to_uppercase(), "claude", "proj");
+    task.id = id.to_string();
+    task.status = status;
+    if !deps.is_empty() {
+        task.referenced_tasks = Some(deps.join(","));
+    }
+    task
+}
+
+fn edges(pairs: &[(&str, &str)]) -> HashMap<String, Vec<String>> {
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    for (from, to) in pairs {
+        edges
+            .entry(from.to_string())
+            .or_default()
+            .push(to.to_string());
+    }
+    edges
+}
+
+#[test]
+fn test_find_cycle_returns_path_back_to_task() {
+    let edges = edges(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")]);
+    assert_eq!(
+        graph::find_cycle(&edges, "a"),
+        Some(vec!["a".into(), "b".into(), "c".into(), "a".into()])
+    );
+    assert_eq!(graph::find_cycle(&edges, "d"), None);
+
+    let self_loop = self::edges(&[("x", "x")]);
+    assert_eq!(
+        graph::find_cycle(&self_loop, "x"),
+        Some(vec!["x".into(), "x".into()])
+    );
+}
+
+#[test]
+fn test_find_cycle_ignores_cycles_elsewhere() {
+    // b and c form a cycle, but nothing leads back to a
+    let edges = edges(&[("a", "b"), ("b", "c"), ("c", "b")]);
+    assert_eq!(graph::find_cycle(&edges, "a"), None);
+}
+
+#[test]
+fn test_ready_tasks_need_dependencies_in_review_or_done() {
+    let mut urgent = task("urgent", TaskStatus::Backlog, &["review"]);
+    urgent.priority = Priority::Urgent;
+    let mut older = task("older", TaskStatus::Backlog, &[]);
+    older.created_at = Utc::now() - Duration::days(1);
+    let tasks = vec![
+        task("review", TaskStatus::Review, &[]),
+        task("running", TaskStatus::Running, &[]),
+        task("blocked", TaskStatus::Backlog, &["running", "review"]),
+        task("newer", TaskStatus::Backlog, &["deleted"]),
+        urgent,
+        older,
+    ];
+
+    let ready: Vec<&str> = graph::ready_tasks(&tasks)
+        .iter()
+        .map(|t| t.id.as_str())
+        .collect();
+    assert_eq!(ready, ["urgent", "older", "newer"]);
+}
+
+#[test]
+fn test_format_graph_groups_tasks_by_level() {
+    let tasks = vec![
+        task("schema", TaskStatus::Done, &[]),
+        task("api", TaskStatus::Running, &["schema"]),
+        task("auth", TaskStatus::Backlog, &["schema"]),
+        task("e2e", TaskStatus::Backlog, &["api", "auth"]),
+        task("docs", TaskStatus::Backlog, &[]),
+    ];
+    let lines = graph::format_graph(&tasks);
+    let text: Vec<&str> = lines.iter().map(|l| l.text.as_str()).collect();
+
+    assert_eq!(text[0], "Level 0");
+    assert!(text[1].contains("SCHEMA"));
+    assert_eq!(text[3], "Level 1");
+    assert_eq!(text[7], "Level 2");
+    assert!(text[8].ends_with("E2E  ← API, AUTH"));
+    assert_eq!(text.last(), Some(&"  (1 other tasks have no dependencies)"));
+
+    let blocked: Vec<&str> = lines
+        .iter()
+        .filter(|l| l.blocked)
+        .map(|l| l.text.as_str())
+        .collect();
+    assert_eq!(blocked.len(), 1);
+    assert!(blocked[0].contains("E2E"));
+}
+
+#[test]
+fn test_format_graph_survives_stored_cycles() {
+    let tasks = vec![
+        task("a", TaskStatus::Backlog, &["b"]),
+        task("b", TaskStatus::Backlog, &["a"]),
+    ];
+    let lines = graph::format_graph(&tasks);
+    assert_eq!(lines.iter().filter(|l| l.text.contains('←')).count(), 2);
+
+    assert_eq!(
+        graph::format_graph(&[task("solo", TaskStatus::Backlog, &[])]),
+        [GraphLine {
+            text: "No dependencies between tasks".to_string(),
+            blocked: false
+        }]
+    );
+}
@@ -1,4 +1,13 @@
-use agtx::db::{Database, Notification, Project, Task, TaskStatus, TransitionRequest};
+use agtx::config::McpRoleConfig;
+use agtx::db::{
+    Database, EventActor, Notification, Priority, Project, Task, TaskStatus, TransitionRequest,
+};
+use agtx::mcp::policy::{self, OrchestratorPolicy, QuietHours};
+use agtx::mcp::resources::{self, ResourceUri, Subscriptions};
+use agtx::mcp::roles::McpRole;
+use agtx::mcp::{http, ServeOptions, ServerMode};
+use chrono::NaiveTime;
+use rmcp::transport::StreamableHttpServerConfig;
 
 // === TransitionRequest Model Tests ===
 
@@ -550,3 +559,631 @@ fn test_send_to_task_requires_active_phase() {
         );
     }
 }
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_batch_create_tasks_with_labels_and_priority() {
+    let mut db = Database::open_in_memory_project().unwrap();
+
+    let mut urgent = Task::new("Hotfix", "claude", "my-project");
+    urgent.priority = Priority::Urgent;
+    urgent.set_labels(["bug"]);
+    let mut later = Task::new("Cleanup", "claude", "my-project");
+    later.set_labels(["chore", "bug"]);
+    db.create_tasks_batch(&[urgent.clone(), later.clone()])
+        .unwrap();
+
+    let fetched = db.get_task(&urgent.id).unwrap().unwrap();
+    assert_eq!(fetched.priority, Priority::Urgent);
+    assert_eq!(fetched.labels, ["bug"]);
+    let fetched = db.get_task(&later.id).unwrap().unwrap();
+    assert_eq!(fetched.labels, ["bug", "chore"]);
+    assert_eq!(fetched.priority, Priority::Normal);
+}
+
+// === mcp-serve Options and HTTP Transport Tests ===
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_serve_options_parse() {
+    let options = ServeOptions::parse(&args(&["/repo", "--http", "127.0.0.1:8765"])).unwrap();
+    assert_eq!(
+        options.project_path,
+        Some(std::path::PathBuf::from("/repo"))
+    );
+    assert_eq!(options.http, Some("127.0.0.1:8765".parse().unwrap()));
+    assert_eq!(options.role, None);
+
+    let options = ServeOptions::parse(&args(&["--role", "sweep"])).unwrap();
+    assert_eq!(options.role.as_deref(), Some("sweep"));
+    assert_eq!(options.project_path, None);
+
+    // stdio, global mode
+    assert_eq!(ServeOptions::parse(&[]).unwrap(), ServeOptions::default());
+
+    assert!(ServeOptions::parse(&args(&["--http"])).is_err());
+    assert!(ServeOptions::parse(&args(&["--http", "localhost"])).is_err());
+    assert!(ServeOptions::parse(&args(&["/a", "/b"])).is_err());
+    assert!(ServeOptions::parse(&args(&["--role"])).is_err());
+
+    let options = ServeOptions::parse(&args(&[
+        "--role=readonly",
+        "--http=127.0.0.1:8765",
+        "--experimental",
+    ]))
+    .unwrap();
+    assert_eq!(options.role.as_deref(), Some("readonly"));
+    assert_eq!(options.http, Some("127.0.0.1:8765".parse().unwrap()));
+
+    // A mistyped flag must not start an unrestricted server
+    for typo in [
+        &["--rol", "readonly"][..],
+        &["--roles=sweep"],
+        &["--experimental=1"],
+    ] {
+        assert!(ServeOptions::parse(&args(typo))
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown mcp-serve option"));
+    }
+}
+
+#[test]
+fn test_bearer_token_is_created_once_and_checked() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let path = tmp.path().join("agtx").join("mcp-token");
+    let token = http::load_or_create_token(&path).unwrap();
+    assert_eq!(token.len(), 64);
+    assert_eq!(http::load_or_create_token(&path).unwrap(), token);
+
+    assert!(http::is_authorized(
+        Some(&format!("Bearer {}", token)),
+        &token
+    ));
+    assert!(!http::is_authorized(
+        Some(&format!("Bearer {}x", token)),
+        &token
+    ));
+    assert!(!http::is_authorized(Some(&token), &token));
+    assert!(!http::is_authorized(None, &token));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_transport_requires_bearer_token() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    let app = http::router(
+        ServerMode::Global,
+        McpRole::unrestricted(),
+        "secret".to_string(),
+        StreamableHttpServerConfig::default(),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let status = |authorization: Option<&'static str>| {
+        let url = url.clone();
+        tokio::task::spawn_blocking(move || {
+            let agent: ureq::Agent = ureq::Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .into();
+            let mut request = agent
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .header("Accept", "application/json, text/event-stream");
+            if let Some(authorization) = authorization {
+                request = request.header("Authorization", authorization);
+            }
+            let response = request
+                .send(
+                    r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+                )
+                .unwrap();
+            (
+                response.status().as_u16(),
+                response.headers().contains_key("mcp-session-id"),
+            )
+        })
+    };
+
+    assert_eq!(status(None).await.unwrap(), (401, false));
+    assert_eq!(status(Some("Bearer wrong")).await.unwrap(), (401, false));
+    assert_eq!(status(Some("Bearer secret")).await.unwrap(), (200, true));
+}
+
+#[test]
+fn test_resource_uri_roundtrip() {
+    let uris = [
+        ResourceUri::Project {
+            project_id: "p1".to_string(),
+        },
+        ResourceUri::Task {
+            project_id: "p1".to_string(),
+            task_id: "t1".to_string(),
+        },
+        ResourceUri::Artifact {
+            project_id: "p1".to_string(),
+            task_id: "t1".to_string(),
+            phase: "planning".to_string(),
+        },
+    ];
+    for uri in uris {
+        assert_eq!(ResourceUri::parse(&uri.to_string()), Some(uri.clone()));
+        assert_eq!(uri.project_id(), "p1");
+    }
+    assert_eq!(
+        ResourceUri::parse("agtx://project/p1/task/t1")
+            .unwrap()
+            .to_string(),
+        "agtx://project/p1/task/t1"
+    );
+
+    for invalid in [
+        "agtx://project/",
+        "agtx://project/p1/",
+        "agtx://project/p1/task",
+        "agtx://project/p1/task/t1/artifact/deploy",
+        "file:///etc/passwd",
+    ] {
+        assert_eq!(ResourceUri::parse(invalid), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn test_find_artifact_fills_in_placeholders_and_globs() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let phase_dir = tmp.path().join(".planning/phases/01-auth");
+    std::fs::create_dir_all(&phase_dir).unwrap();
+    std::fs::write(phase_dir.join("01-PLAN.md"), "plan").unwrap();
+    std::fs::create_dir_all(tmp.path().join(".omc/specs")).unwrap();
+    std::fs::write(tmp.path().join(".omc/specs/deep-interview-abc.md"), "spec").unwrap();
+
+    let template = ".planning/phases/*/{phase}-PLAN.md";
+    assert_eq!(
+        resources::find_artifact(tmp.path(), template, "t1", 1),
+        Some(phase_dir.join("01-PLAN.md"))
+    );
+    assert_eq!(
+        resources::find_artifact(tmp.path(), template, "t1", 2),
+        None
+    );
+    assert!(
+        resources::find_artifact(tmp.path(), ".omc/specs/deep-interview-*.md", "t1", 1).is_some()
+    );
+    assert_eq!(
+        resources::find_artifact(tmp.path(), ".agtx/{task_id}.md", "t1", 1),
+        None
+    );
+}
+
+#[test]
+fn test_task_artifacts_lists_existing_files() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".agtx")).unwrap();
+    std::fs::write(tmp.path().join(".agtx/research.md"), "notes").unwrap();
+    std::fs::write(tmp.path().join(".agtx/plan.md"), "plan").unwrap();
+
+    let mut task = Task::new("Artifacts", "claude", "proj");
+    task.worktree_path = Some(tmp.path().to_string_lossy().to_string());
+    let plugin = agtx::skills::load_bundled_plugin("agtx").unwrap();
+    let phases: Vec<&str> = resources::task_artifacts(&task, &plugin)
+        .into_iter()
+        .map(|(phase, _)| phase)
+        .collect();
+    assert_eq!(phases, vec!["research", "planning"]);
+
+    task.worktree_path = None;
+    assert!(resources::task_artifacts(&task, &plugin).is_empty());
+}
+
+#[test]
+fn test_subscriptions_report_changes() {
+    let mut subscriptions = Subscriptions::default();
+    assert!(!subscriptions.keep_watching());
+
+    subscriptions.subscribe(
+        "agtx://project/p/task/a",
+        Some("running||working".to_string()),
+    );
+    subscriptions.subscribe("agtx://project/p/task/b", Some("review||".to_string()));
+    assert!(subscriptions.start_watching());
+    assert!(!subscriptions.start_watching());
+
+    let state = |uri: &str| match uri {
+        "agtx://project/p/task/a" => Some("running||idle".to_string()),
+        _ => Some("review||".to_string()),
+    };
+    assert_eq!(subscriptions.update(state), vec!["agtx://project/p/task/a"]);
+    // Reported once, not on every check
+    assert!(subscriptions.update(state).is_empty());
+
+    // A deleted task counts as a change
+    assert_eq!(
+        subscriptions.update(|_| None),
+        vec!["agtx://project/p/task/a", "agtx://project/p/task/b"]
+    );
+
+    subscriptions.unsubscribe("agtx://project/p/task/a");
+    subscriptions.unsubscribe("agtx://project/p/task/b");
+    assert!(!subscriptions.keep_watching());
+    assert!(subscriptions.start_watching());
+}
+
+// === Role Tests ===
+
+#[test]
+fn test_built_in_roles() {
+    let none = std::collections::BTreeMap::new();
+
+    let readonly = McpRole::resolve("readonly", &none).unwrap();
+    assert!(readonly.allows_tool("get_task"));
+    assert!(readonly.allows_tool("get_task_diff"));
+    assert!(!readonly.allows_tool("move_task"));
+    assert!(!readonly.allows_tool("get_notifications"));
+
+    let sweep = McpRole::resolve("sweep", &none).unwrap();
+    assert!(sweep.allows_tool("create_tasks_batch"));
+    assert!(sweep.allows_tool("move_task"));
+    assert!(!sweep.allows_tool("send_to_task"));
+
+    let orchestrator = McpRole::resolve("orchestrator", &none).unwrap();
+    assert!(orchestrator.allows_tool("send_to_task"));
+    assert!(!orchestrator.restricts_projects());
+    assert!(McpRole::unrestricted().allows_tool("delete_task"));
+
+    let err = McpRole::resolve("admin", &none).unwrap_err();
+    assert!(err.to_string().contains("orchestrator, sweep, readonly"));
+}
+
+#[test]
+fn test_role_actor() {
+    let none = std::collections::BTreeMap::new();
+    let orchestrator = McpRole::resolve("orchestrator", &none).unwrap();
+    assert_eq!(orchestrator.actor(true, false), EventActor::Orchestrator);
+    // Global servers and HTTP sessions serve other clients
+    assert_eq!(orchestrator.actor(false, false), EventActor::Mcp);
+    assert_eq!(orchestrator.actor(true, true), EventActor::Mcp);
+
+    for role in [
+        McpRole::resolve("sweep", &none).unwrap(),
+        McpRole::resolve("readonly", &none).unwrap(),
+        McpRole::unrestricted(),
+    ] {
+        assert_eq!(role.actor(true, false), EventActor::Mcp, "{}", role.name);
+    }
+}
+
+#[test]
+fn test_configured_role() {
+    let mut roles = std::collections::BTreeMap::new();
+    roles.insert(
+        "app-sweep".to_string(),
+        McpRoleConfig {
+            tools: Some(args(&["readonly", "create_task"])),
+            projects: Some(args(&["app"])),
+        },
+    );
+    roles.insert("everything".to_string(), McpRoleConfig::default());
+
+    let role = McpRole::resolve("app-sweep", &roles).unwrap();
+    assert!(role.allows_tool("list_tasks"));
+    assert!(role.allows_tool("create_task"));
+    assert!(!role.allows_tool("delete_task"));
+    assert!(role.restricts_projects());
+    assert!(role.allows_project(&Project::new("app", "/code/app")));
+    assert!(!role.allows_project(&Project::new("other", "/code/other")));
+    let mut by_id = Project::new("renamed", "/code/renamed");
+    by_id.id = "app".to_string();
+    assert!(role.allows_project(&by_id));
+
+    let everything = McpRole::resolve("everything", &roles).unwrap();
+    assert!(everything.allows_tool("send_to_task"));
+    assert!(everything.allows_project(&Project::new("other", "/code/other")));
+
+    // Typos are caught against the server's tool list
+    roles.insert(
+        "typo".to_string(),
+        McpRoleConfig {
+            tools: Some(args(&["list_task"])),
+            projects: None,
+        },
+    );
+    let typo = McpRole::resolve("typo", &roles).unwrap();
+    assert_eq!(
+        typo.unknown_tools(&args(&["list_tasks"])),
+        vec!["list_task"]
+    );
+
+    // Built-in names can't be redefined
+    roles.insert("readonly".to_string(), McpRoleConfig::default());
+    assert!(McpRole::resolve("app-sweep", &roles).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_role_hides_and_denies_tools() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    let role = McpRole::resolve("readonly", &Default::default()).unwrap();
+    let app = http::router(
+        ServerMode::Global,
+        role,
+        "secret".to_string(),
+        StreamableHttpServerConfig::default(),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let bodies = tokio::task::spawn_blocking(move || {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+        let post = |session: Option<&str>, body: &str| {
+            let mut request = agent
+                .post(&url)
+                .header("Authorization", "Bearer secret")
+                .header("Content-Type", "application/json")
+                .header("Accept", "application/json, text/event-stream");
+            if let Some(session) = session {
+                request = request.header("mcp-session-id", session);
+            }
+            request.send(body).unwrap()
+        };
+
+        let response = post(
+            None,
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+        );
+        let session = response.headers()["mcp-session-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        post(
+            Some(&session),
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        );
+        let mut tools = post(
+            Some(&session),
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        );
+        let mut denied = post(
+            Some(&session),
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"send_to_task","arguments":{"task_id":"t1","message":"hi"}}}"#,
+        );
+        (
+            tools.body_mut().read_to_string().unwrap(),
+            denied.body_mut().read_to_string().unwrap(),
+        )
+    })
+    .await
+    .unwrap();
+
+    let (tools, denied) = bodies;
+    assert!(tools.contains("\"get_task\""));
+    assert!(!tools.contains("\"send_to_task\""));
+    assert!(denied.contains("Permission denied: role 'readonly' may not call send_to_task"));
+    assert!(denied.contains("\"isError\":true"));
+}
+
+// === Orchestrator Policy Tests ===
+
+fn noon() -> NaiveTime {
+    NaiveTime::from_hms_opt(12, 0, 0).unwrap()
+}
+
+fn task_in(status: TaskStatus) -> Task {
+    let mut task = Task::new("Policy", "claude", "proj");
+    task.status = status;
+    task
+}
+
+#[test]
+fn test_policy_load_and_validate() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    assert_eq!(OrchestratorPolicy::load(tmp.path()).unwrap(), None);
+
+    let path = tmp.path().join(".agtx/orchestrator.toml");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &path,
+        r#"
+columns = ["planning", "running", "review"]
+allow_done = false
+
+[max_retries]
+running = 2
+
+[sign_off]
+labels = ["security"]
+paths = ["migrations/**"]
+
+[quiet_hours]
+start = "22:00"
+end = "07:00"
+"#,
+    )
+    .unwrap();
+    let policy = OrchestratorPolicy::load(tmp.path()).unwrap().unwrap();
+    assert!(!policy.allow_done);
+    assert_eq!(policy.max_retries.get("running"), Some(&2));
+    assert_eq!(policy.sign_off.labels, vec!["security"]);
+
+    std::fs::write(&path, "").unwrap();
+    assert_eq!(
+        OrchestratorPolicy::load(tmp.path()).unwrap(),
+        Some(OrchestratorPolicy::default())
+    );
+
+    for invalid in [
+        r#"columns = ["doing"]"#,
+        "[max_retries]\ndeploy = 1",
+        "[quiet_hours]\nstart = \"late\"\nend = \"07:00\"",
+        "allow_done = \"no\"",
+    ] {
+        std::fs::write(&path, invalid).unwrap();
+        assert!(OrchestratorPolicy::load(tmp.path()).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_policy_columns_and_done() {
+    let policy = OrchestratorPolicy {
+        columns: Some(vec!["planning".to_string(), "running".to_string()]),
+        allow_done: false,
+        ..Default::default()
+    };
+    let no_paths = || Ok(Vec::new());
+
+    let planning = task_in(TaskStatus::Planning);
+    assert!(policy
+        .check(&planning, "move_forward", 0, no_paths, noon())
+        .is_ok());
+    let running = task_in(TaskStatus::Running);
+    assert!(policy
+        .check(&running, "move_forward", 0, no_paths, noon())
+        .unwrap_err()
+        .contains("review"));
+    assert!(policy
+        .check(&running, "escalate_to_user", 0, no_paths, noon())
+        .is_ok());
+
+    let policy = OrchestratorPolicy {
+        allow_done: false,
+        ..Default::default()
+    };
+    let review = task_in(TaskStatus::Review);
+    assert!(policy
+        .check(&review, "move_to_done", 0, no_paths, noon())
+        .unwrap_err()
+        .contains("Done"));
+    assert!(policy.check(&review, "resume", 0, no_paths, noon()).is_ok());
+}
+
+#[test]
+fn test_policy_max_retries() {
+    let mut policy = OrchestratorPolicy::default();
+    policy.max_retries.insert("running".to_string(), 1);
+    let review = task_in(TaskStatus::Review);
+
+    assert!(policy
+        .check(&review, "resume", 1, || Ok(Vec::new()), noon())
+        .is_ok());
+    assert!(policy
+        .check(&review, "resume", 2, || Ok(Vec::new()), noon())
+        .unwrap_err()
+        .contains("max_retries.running = 1"));
+    // Other phases are unlimited
+    let running = task_in(TaskStatus::Running);
+    assert!(policy
+        .check(&running, "move_forward", 5, || Ok(Vec::new()), noon())
+        .is_ok());
+}
+
+#[test]
+fn test_policy_sign_off() {
+    let mut policy = OrchestratorPolicy::default();
+    policy.sign_off.labels = vec!["security".to_string()];
+    policy.sign_off.paths = vec!["migrations/**".to_string(), "src/*.sql".to_string()];
+
+    let mut review = task_in(TaskStatus::Review);
+    let touched = || Ok(vec!["src/main.rs".to_string()]);
+    assert!(policy
+        .check(&review, "move_to_done", 0, touched, noon())
+        .is_ok());
+    assert!(policy
+        .check(
+            &review,
+            "move_to_done",
+            0,
+            || Ok(vec!["migrations/2024/01_init.sql".to_string()]),
+            noon()
+        )
+        .unwrap_err()
+        .contains("migrations/**"));
+    // Changes that can't be read need sign-off too
+    assert!(policy
+        .check(
+            &review,
+            "move_to_done",
+            0,
+            || Err("git diff failed".to_string()),
+            noon()
+        )
+        .unwrap_err()
+        .contains("git diff failed"));
+
+    review.labels = vec!["security".to_string()];
+    assert!(policy
+        .check(&review, "move_to_done", 0, touched, noon())
+        .unwrap_err()
+        .contains("'security'"));
+
+    // Sign-off only gates moves to Done: the task can still go back for fixes
+    assert!(policy
+        .check(&review, "resume", 0, || unreachable!(), noon())
+        .is_ok());
+    let mut running = task_in(TaskStatus::Running);
+    running.labels = vec!["security".to_string()];
+    assert!(policy
+        .check(&running, "move_forward", 0, || unreachable!(), noon())
+        .is_ok());
+}
+
+#[test]
+fn test_policy_quiet_hours() {
+    let policy = OrchestratorPolicy {
+        quiet_hours: Some(QuietHours {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+        }),
+        ..Default::default()
+    };
+    let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    let quiet = policy.quiet_hours.as_ref().unwrap();
+    assert!(quiet.contains(at(23, 30)));
+    assert!(quiet.contains(at(6, 59)));
+    assert!(!quiet.contains(at(7, 0)));
+    assert!(!quiet.contains(at(21, 59)));
+
+    let running = task_in(TaskStatus::Running);
+    assert!(policy
+        .check(&running, "move_forward", 0, || Ok(Vec::new()), at(2, 0))
+        .unwrap_err()
+        .contains("quiet hours"));
+    assert!(policy
+        .check(&running, "escalate_to_user", 0, || Ok(Vec::new()), at(2, 0))
+        .is_ok());
+    assert!(policy
+        .check(&running, "move_forward", 0, || Ok(Vec::new()), at(9, 0))
+        .is_ok());
+}
+
+#[test]
+fn test_policy_glob_matches() {
+    assert!(policy::glob_matches("migrations/**", "migrations/01.sql"));
+    assert!(policy::glob_matches("migrations/**", "migrations/a/b.sql"));
+    assert!(policy::glob_matches("**/*.sql", "db/schema.sql"));
+    assert!(policy::glob_matches("**/*.sql", "schema.sql"));
+    assert!(policy::glob_matches("src/auth/*", "src/auth/mod.rs"));
+    assert!(!policy::glob_matches("src/auth/*", "src/auth/jwt/keys.rs"));
+    assert!(!policy::glob_matches("*.sql", "db/schema.sql"));
+    assert!(!policy::glob_matches("src/*.rs", "src/main.rsx"));
+}
+
+#[test]
+fn test_policy_target_status() {
+    assert_eq!(
+        policy::target_status("move_forward", TaskStatus::Running),
+        Some(TaskStatus::Review)
+    );
+    assert_eq!(
+        policy::target_status("resume", TaskStatus::Review),
+        Some(TaskStatus::Running)
+    );
+    assert_eq!(
+        policy::target_status("escalate_to_user", TaskStatus::Running),
+        None
+    );
+}
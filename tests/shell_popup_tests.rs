@@ -1,6 +1,7 @@
 use agtx::tui::shell_popup::{
     build_footer_text, compute_visible_lines, render_shell_popup, trim_content_to_cursor,
-    trim_trailing_empty_lines, ShellPopup, ShellPopupColors, MAX_TRAILING_EMPTY_LINES,
+    trim_trailing_empty_lines, ShellPopup, ShellPopupColors, ShellPopupKeys,
+    MAX_TRAILING_EMPTY_LINES,
 };
 use ratatui::backend::TestBackend;
 use ratatui::prelude::*;
@@ -185,21 +186,32 @@ fn test_compute_visible_lines_scrolled_to_top() {
 
 #[test]
 fn test_build_footer_text_at_bottom() {
-    let footer = build_footer_text(0, 10);
+    let footer = build_footer_text(0, 10, &ShellPopupKeys::default());
     assert!(footer.contains("At bottom"));
     assert!(!footer.contains("Line"));
 }
 
 #[test]
 fn test_build_footer_text_scrolled_up() {
-    let footer = build_footer_text(-5, 10);
+    let footer = build_footer_text(-5, 10, &ShellPopupKeys::default());
     assert!(footer.contains("Line 11")); // start_line + 1
     assert!(footer.contains("bottom")); // Ctrl+g option visible
 }
 
+#[test]
+fn test_build_footer_text_uses_custom_keys() {
+    let keys = ShellPopupKeys {
+        close: "M-x".to_string(),
+        ..Default::default()
+    };
+    let footer = build_footer_text(0, 0, &keys);
+    assert!(footer.contains("[M-x] close"));
+    assert!(!footer.contains("C-q"));
+}
+
 #[test]
 fn test_build_footer_text_at_top() {
-    let footer = build_footer_text(-100, 0);
+    let footer = build_footer_text(-100, 0, &ShellPopupKeys::default());
     assert!(footer.contains("Line 1"));
 }
 
@@ -223,7 +235,14 @@ fn test_render_shell_popup_basic() {
     terminal
         .draw(|frame| {
             let area = Rect::new(0, 0, 80, 24);
-            render_shell_popup(&popup, frame, area, lines, &colors);
+            render_shell_popup(
+                &popup,
+                frame,
+                area,
+                lines,
+                &colors,
+                &ShellPopupKeys::default(),
+            );
         })
         .unwrap();
 
@@ -252,7 +271,14 @@ fn test_render_shell_popup_with_content() {
     terminal
         .draw(|frame| {
             let area = Rect::new(0, 0, 80, 24);
-            render_shell_popup(&popup, frame, area, lines, &colors);
+            render_shell_popup(
+                &popup,
+                frame,
+                area,
+                lines,
+                &colors,
+                &ShellPopupKeys::default(),
+            );
         })
         .unwrap();
 
@@ -279,7 +305,14 @@ fn test_render_shell_popup_scrolled_up() {
     terminal
         .draw(|frame| {
             let area = Rect::new(0, 0, 80, 24);
-            render_shell_popup(&popup, frame, area, lines, &colors);
+            render_shell_popup(
+                &popup,
+                frame,
+                area,
+                lines,
+                &colors,
+                &ShellPopupKeys::default(),
+            );
         })
         .unwrap();
 
@@ -304,7 +337,14 @@ fn test_render_shell_popup_empty_content() {
     terminal
         .draw(|frame| {
             let area = Rect::new(0, 0, 80, 24);
-            render_shell_popup(&popup, frame, area, lines, &colors);
+            render_shell_popup(
+                &popup,
+                frame,
+                area,
+                lines,
+                &colors,
+                &ShellPopupKeys::default(),
+            );
         })
         .unwrap();
 
@@ -338,7 +378,14 @@ fn test_render_shell_popup_custom_colors() {
     terminal
         .draw(|frame| {
             let area = Rect::new(0, 0, 80, 24);
-            render_shell_popup(&popup, frame, area, lines, &colors);
+            render_shell_popup(
+                &popup,
+                frame,
+                area,
+                lines,
+                &colors,
+                &ShellPopupKeys::default(),
+            );
         })
         .unwrap();
 
@@ -360,7 +407,14 @@ fn test_render_shell_popup_small_area() {
     terminal
         .draw(|frame| {
             let area = Rect::new(0, 0, 40, 10);
-            render_shell_popup(&popup, frame, area, lines, &colors);
+            render_shell_popup(
+                &popup,
+                frame,
+                area,
+                lines,
+                &colors,
+                &ShellPopupKeys::default(),
+            );
         })
         .unwrap();
 
@@ -0,0 +1,187 @@
+use agtx::db::{EventActor, Task, TaskEvent, TaskEventKind, TaskStatus};
+use agtx::stats::{self, ReviewLoops};
+use chrono::{DateTime, Duration, Utc};
+
+fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+fn task(id: &str, agent: &str, plugin: Option<&str>, status: TaskStatus, created: &str) -> Task {
+    let mut task = Task::new(id, agent, "proj");
+    task.id = id.to_string();
+    task.plugin = plugin.map(String::from);
+    task.status = status;
+    task.created_at = at(created);
+    task
+}
+
+/// Events moving `task_id` through `path` ("status@time"), plus idle markers ("idle@time", "active@time")
+fn history(task_id: &str, path: &[&str]) -> Vec<TaskEvent> {
+    let mut previous: Option<&str> = None;
+    path.iter()
+        .map(|step| {
+            let (what, time) = step.split_once('@').unwrap();
+            let kind = match what {
+                "idle" => TaskEventKind::IdleStarted,
+                "active" => TaskEventKind::IdleEnded,
+                _ if previous.is_none() => TaskEventKind::Created,
+                _ => TaskEventKind::StatusChanged,
+            };
+            let mut event = TaskEvent::new(task_id, kind, EventActor::User);
+            event.created_at = at(time);
+            if matches!(kind, TaskEventKind::Created | TaskEventKind::StatusChanged) {
+                event.from_value = previous.map(String::from);
+                event.to_value = Some(what.to_string());
+                previous = Some(what);
+            }
+            event
+        })
+        .collect()
+}
+
+fn fixture() -> (Vec<Task>, Vec<TaskEvent>) {
+    let tasks = vec![
+        task(
+            "a",
+            "claude",
+            None,
+            TaskStatus::Done,
+            "2026-03-02T08:00:00Z",
+        ),
+        task(
+            "b",
+            "codex",
+            Some("gsd"),
+            TaskStatus::Done,
+            "2026-03-02T08:00:00Z",
+        ),
+        task(
+            "c",
+            "claude",
+            None,
+            TaskStatus::Running,
+            "2026-03-02T08:00:00Z",
+        ),
+    ];
+    let mut events = history(
+        "a",
+        &[
+            "backlog@2026-03-02T08:00:00Z",
+            "planning@2026-03-02T10:00:00Z",
+            "running@2026-03-02T11:00:00Z",
+            "idle@2026-03-02T11:30:00Z",
+            "active@2026-03-02T11:50:00Z",
+            "review@2026-03-02T13:00:00Z",
+            "done@2026-03-02T14:00:00Z",
+        ],
+    );
+    events.extend(history(
+        "b",
+        &[
+            "backlog@2026-03-02T08:00:00Z",
+            "planning@2026-03-02T12:00:00Z",
+            "running@2026-03-02T13:00:00Z",
+            "review@2026-03-02T14:00:00Z",
+            "running@2026-03-02T15:00:00Z",
+            "review@2026-03-02T16:00:00Z",
+            "running@2026-03-02T17:00:00Z",
+            "review@2026-03-02T18:00:00Z",
+            "done@2026-03-10T08:00:00Z",
+        ],
+    ));
+    events.extend(history(
+        "c",
+        &[
+            "backlog@2026-03-02T08:00:00Z",
+            "running@2026-03-02T09:00:00Z",
+        ],
+    ));
+    events.sort_by_key(|e| e.created_at);
+    (tasks, events)
+}
+
+#[test]
+fn test_lead_and_cycle_time_average_finished_tasks() {
+    let (tasks, events) = fixture();
+    let stats = stats::compute(&tasks, &events, at("2026-03-11T12:00:00Z"), 4);
+
+    assert_eq!(stats.total_tasks, 3);
+    assert_eq!(stats.finished_tasks, 2);
+    // a: 6h, b: 8d; c is still running and not counted
+    let lead = (Duration::hours(6) + Duration::days(8)).num_seconds() / 2;
+    assert_eq!(stats.avg_lead_time_secs, Some(lead));
+    // a: 4h from planning, b: 7d 20h
+    let cycle = (Duration::hours(4) + Duration::hours(188)).num_seconds() / 2;
+    assert_eq!(stats.avg_cycle_time_secs, Some(cycle));
+}
+
+#[test]
+fn test_time_per_phase_includes_idle_time() {
+    let (tasks, events) = fixture();
+    let stats = stats::compute(&tasks, &events, at("2026-03-11T12:00:00Z"), 4);
+
+    let phases: Vec<&str> = stats.phases.iter().map(|p| p.status.as_str()).collect();
+    assert_eq!(phases, ["backlog", "planning", "running", "review"]);
+    let running = &stats.phases[2];
+    assert_eq!(running.tasks, 2);
+    // a: 2h, b: 3h
+    assert_eq!(running.avg_secs, Duration::minutes(150).num_seconds());
+    // 20 idle minutes of a, averaged over both tasks
+    assert_eq!(running.avg_idle_secs, Duration::minutes(10).num_seconds());
+}
+
+#[test]
+fn test_finished_per_week_ends_with_current_week() {
+    let (tasks, events) = fixture();
+    let stats = stats::compute(&tasks, &events, at("2026-03-11T12:00:00Z"), 3);
+
+    let weeks: Vec<(&str, usize)> = stats
+        .finished_per_week
+        .iter()
+        .map(|w| (w.week.as_str(), w.finished))
+        .collect();
+    assert_eq!(weeks, [("2026-W09", 0), ("2026-W10", 1), ("2026-W11", 1)]);
+}
+
+#[test]
+fn test_review_loops_per_plugin_and_agent() {
+    let (tasks, events) = fixture();
+    let stats = stats::compute(&tasks, &events, at("2026-03-11T12:00:00Z"), 4);
+
+    let loops = |name: &str, tasks: usize, avg_loops: f64| ReviewLoops {
+        name: name.to_string(),
+        tasks,
+        avg_loops,
+    };
+    assert_eq!(
+        stats.review_loops_by_plugin,
+        [loops("agtx", 1, 0.0), loops("gsd", 1, 2.0)]
+    );
+    assert_eq!(
+        stats.review_loops_by_agent,
+        [loops("claude", 1, 0.0), loops("codex", 1, 2.0)]
+    );
+}
+
+#[test]
+fn test_format_stats() {
+    let (tasks, events) = fixture();
+    let lines = stats::format_stats(&stats::compute(
+        &tasks,
+        &events,
+        at("2026-03-11T12:00:00Z"),
+        2,
+    ));
+    assert_eq!(lines[0], "Tasks: 2 finished of 3");
+    assert!(lines.contains(&"  running       2h 30m  idle 10m".to_string()));
+    assert!(lines.contains(&"  2026-W11    1 █".to_string()));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("  by plugin") && l.contains("gsd 2.0 (1 tasks)")));
+
+    let empty = stats::format_stats(&stats::compute(&[], &[], Utc::now(), 2));
+    assert_eq!(
+        empty,
+        ["Tasks: 0 finished of 0", "  (no finished tasks yet)"]
+    );
+}
@@ -0,0 +1,176 @@
+use agtx::db::TaskStatus;
+use agtx::transcript;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+// =============================================================================
+// Paths and phases
+// =============================================================================
+
+#[test]
+fn test_transcript_path() {
+    let project = PathBuf::from("/home/user/project");
+    assert_eq!(
+        transcript::transcript_path(&project, "task-123", "planning"),
+        PathBuf::from("/home/user/project/.agtx/logs/task-123/planning.log")
+    );
+}
+
+#[test]
+fn test_phase_for_status() {
+    assert_eq!(
+        transcript::phase_for_status(TaskStatus::Backlog),
+        Some("research")
+    );
+    assert_eq!(
+        transcript::phase_for_status(TaskStatus::Running),
+        Some("running")
+    );
+    assert_eq!(transcript::phase_for_status(TaskStatus::Done), None);
+}
+
+#[test]
+fn test_list_returns_existing_logs_in_phase_order() {
+    let tmp = TempDir::new().unwrap();
+    let dir = transcript::transcript_dir(tmp.path(), "t1");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("review.log"), "r").unwrap();
+    std::fs::write(dir.join("planning.log"), "plan").unwrap();
+
+    let files = transcript::list(tmp.path(), "t1");
+    let phases: Vec<_> = files.iter().map(|f| f.phase).collect();
+    assert_eq!(phases, vec!["planning", "review"]);
+    assert_eq!(files[0].size, 4);
+
+    assert!(transcript::list(tmp.path(), "other").is_empty());
+}
+
+// =============================================================================
+// Reading
+// =============================================================================
+
+#[test]
+fn test_read_tail_keeps_last_lines() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("running.log");
+    std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+
+    assert_eq!(transcript::read_tail(&path, 2).unwrap(), b"three\nfour\n");
+    assert_eq!(
+        transcript::read_tail(&path, 10).unwrap(),
+        b"one\ntwo\nthree\nfour\n"
+    );
+}
+
+#[test]
+fn test_read_tail_counts_unterminated_last_line() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("running.log");
+    std::fs::write(&path, "one\ntwo\nthree").unwrap();
+
+    assert_eq!(transcript::read_tail(&path, 2).unwrap(), b"two\nthree");
+}
+
+#[test]
+fn test_read_tail_missing_file_errors() {
+    let tmp = TempDir::new().unwrap();
+    assert!(transcript::read_tail(&tmp.path().join("nope.log"), 5).is_err());
+}
+
+#[test]
+fn test_strip_ansi() {
+    let raw = b"\x1b[1;32mok\x1b[0m done\r\n\x1b[?25l\x1b]0;title\x07next\x1b[2K\n";
+    assert_eq!(transcript::strip_ansi(raw), "ok done\nnext\n");
+}
+
+#[test]
+fn test_keep_sgr_only() {
+    let raw = b"\x1b[?2004h\x1b[31mred\x1b[0m\x1b[3A\r\n";
+    assert_eq!(transcript::keep_sgr_only(raw), b"\x1b[31mred\x1b[0m\n");
+}
+
+// =============================================================================
+// Starting capture
+// =============================================================================
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_start_writes_header_and_pipes_pane() {
+    use agtx::tmux::MockTmuxOperations;
+    use mockall::predicate::*;
+
+    let tmp = TempDir::new().unwrap();
+    let expected = transcript::transcript_path(tmp.path(), "t1", "planning");
+
+    let mut mock = MockTmuxOperations::new();
+    mock.expect_pipe_pane()
+        .with(eq("proj:task-t1"), eq(expected.clone()))
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    let path = transcript::start(&mock, "proj:task-t1", tmp.path(), "t1", "planning").unwrap();
+    assert_eq!(path, expected);
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("=== agtx planning session "));
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_start_appends_to_existing_log() {
+    use agtx::tmux::MockTmuxOperations;
+
+    let tmp = TempDir::new().unwrap();
+    let mut mock = MockTmuxOperations::new();
+    mock.expect_pipe_pane().times(2).returning(|_, _| Ok(()));
+
+    transcript::start(&mock, "w", tmp.path(), "t1", "running").unwrap();
+    let path = transcript::start(&mock, "w", tmp.path(), "t1", "running").unwrap();
+    let content = std::fs::read_to_string(path).unwrap();
+    assert_eq!(content.matches("=== agtx running session ").count(), 2);
+}
+
+#[test]
+#[cfg(feature = "test-mocks")]
+fn test_start_leaves_no_log_when_pipe_fails() {
+    use agtx::tmux::MockTmuxOperations;
+
+    let tmp = TempDir::new().unwrap();
+    let mut mock = MockTmuxOperations::new();
+    mock.expect_pipe_pane()
+        .returning(|_, _| Err(anyhow::anyhow!("unsupported")));
+
+    assert!(transcript::start(&mock, "w", tmp.path(), "t1", "running").is_err());
+    assert!(transcript::list(tmp.path(), "t1").is_empty());
+}
+
+#[test]
+fn test_exclude_from_git_adds_logs_once() {
+    let tmp = TempDir::new().unwrap();
+    std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+
+    transcript::exclude_from_git(tmp.path()).unwrap();
+    transcript::exclude_from_git(tmp.path()).unwrap();
+
+    let exclude = std::fs::read_to_string(tmp.path().join(".git/info/exclude")).unwrap();
+    assert_eq!(exclude.lines().filter(|l| *l == "/.agtx/logs/").count(), 1);
+
+    std::fs::create_dir_all(tmp.path().join(".agtx/logs/t1")).unwrap();
+    std::fs::write(tmp.path().join(".agtx/logs/t1/running.log"), "x").unwrap();
+    let status = std::process::Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&status.stdout).contains(".agtx/logs"));
+}
+
+#[test]
+fn test_exclude_from_git_ignores_non_repositories() {
+    let tmp = TempDir::new().unwrap();
+    transcript::exclude_from_git(tmp.path()).unwrap();
+    assert!(!tmp.path().join(".git").exists());
+}
@@ -0,0 +1,232 @@
+use agtx::db::{EventActor, TaskEvent, TaskEventKind, Usage};
+use agtx::usage::{self, UsageRecord};
+use chrono::{DateTime, Duration, Utc};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+fn long_ago() -> DateTime<Utc> {
+    Utc::now() - Duration::days(1)
+}
+
+fn write(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+// =============================================================================
+// Collectors
+// =============================================================================
+
+#[test]
+fn test_claude_sessions_are_read_for_the_worktree_only() {
+    let home = TempDir::new().unwrap();
+    let worktree = Path::new("/work/repo/.agtx/worktrees/fix-bug");
+    let sessions = home
+        .path()
+        .join(".claude/projects/-work-repo--agtx-worktrees-fix-bug");
+    // The same message is logged twice while streaming; the last line wins
+    write(
+        &sessions.join("a.jsonl"),
+        concat!(
+            r#"{"type":"user","timestamp":"2026-01-01T10:00:00Z","message":{"role":"user"}}"#,
+            "\n",
+            r#"{"type":"assistant","timestamp":"2026-01-01T10:00:01Z","message":{"id":"m1","model":"claude-sonnet-4-5","usage":{"input_tokens":10,"output_tokens":1,"cache_read_input_tokens":0,"cache_creation_input_tokens":0}}}"#,
+            "\n",
+            r#"{"type":"assistant","timestamp":"2026-01-01T10:00:02Z","message":{"id":"m1","model":"claude-sonnet-4-5","usage":{"input_tokens":10,"output_tokens":200,"cache_read_input_tokens":1000,"cache_creation_input_tokens":500}}}"#,
+            "\n",
+        ),
+    );
+    write(
+        &home.path().join(".claude/projects/-work-other/b.jsonl"),
+        r#"{"type":"assistant","timestamp":"2026-01-01T10:00:02Z","message":{"id":"x","model":"claude-sonnet-4-5","usage":{"input_tokens":99,"output_tokens":99}}}"#,
+    );
+
+    let records = usage::collect_in(home.path(), worktree, long_ago());
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record.agent, "claude");
+    assert_eq!(record.usage.output_tokens, 200);
+    assert_eq!(record.usage.cache_read_tokens, 1000);
+    assert_eq!(record.usage.cache_write_tokens, 500);
+    assert_eq!(record.usage.total_tokens(), 1710);
+    // 10 * $3 + 200 * $15 + 500 * $3.75 + 1000 * $0.30 per million
+    assert!((record.usage.cost_usd - 0.005205).abs() < 1e-9);
+}
+
+#[test]
+fn test_codex_rollouts_match_on_session_cwd() {
+    let home = TempDir::new().unwrap();
+    let worktree = Path::new("/work/repo/.agtx/worktrees/fix-bug");
+    let rollout = |cwd: &str| {
+        format!(
+            "{}\n{}\n{}\n",
+            format_args!(
+                r#"{{"timestamp":"2026-01-01T10:00:00Z","type":"session_meta","payload":{{"cwd":"{}"}}}}"#,
+                cwd
+            ),
+            r#"{"timestamp":"2026-01-01T10:00:00Z","type":"turn_context","payload":{"model":"gpt-5-codex"}}"#,
+            r#"{"timestamp":"2026-01-01T10:00:05Z","type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":1200,"cached_input_tokens":1000,"output_tokens":300}}}}"#,
+        )
+    };
+    let day = home.path().join(".codex/sessions/2026/01/01");
+    write(
+        &day.join("rollout-a.jsonl"),
+        &rollout("/work/repo/.agtx/worktrees/fix-bug"),
+    );
+    write(&day.join("rollout-b.jsonl"), &rollout("/work/repo"));
+
+    let records = usage::collect_in(home.path(), worktree, long_ago());
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].agent, "codex");
+    assert_eq!(records[0].model, "gpt-5-codex");
+    assert_eq!(records[0].usage.input_tokens, 200);
+    assert_eq!(records[0].usage.cache_read_tokens, 1000);
+    assert_eq!(records[0].usage.output_tokens, 300);
+}
+
+#[test]
+fn test_gemini_chats_count_thoughts_as_output() {
+    use sha2::{Digest, Sha256};
+    let home = TempDir::new().unwrap();
+    let worktree = Path::new("/work/repo/.agtx/worktrees/fix-bug");
+    let hash: String = Sha256::digest(worktree.to_string_lossy().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    write(
+        &home
+            .path()
+            .join(".gemini/tmp")
+            .join(hash)
+            .join("chats/session-1.json"),
+        r#"{"messages":[
+            {"type":"user","timestamp":"2026-01-01T10:00:00Z"},
+            {"type":"gemini","timestamp":"2026-01-01T10:00:03Z","model":"gemini-2.5-pro",
+             "tokens":{"input":500,"output":40,"cached":100,"thoughts":60,"tool":0,"total":600}}
+        ]}"#,
+    );
+
+    let records = usage::collect_in(home.path(), worktree, long_ago());
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].usage.input_tokens, 400);
+    assert_eq!(records[0].usage.output_tokens, 100);
+    assert_eq!(records[0].usage.cache_read_tokens, 100);
+}
+
+#[test]
+fn test_files_older_than_the_task_are_skipped() {
+    let home = TempDir::new().unwrap();
+    write(
+        &home.path().join(".claude/projects/-w/a.jsonl"),
+        r#"{"type":"assistant","timestamp":"2026-01-01T10:00:02Z","message":{"id":"m","model":"x","usage":{"output_tokens":5}}}"#,
+    );
+    let future = Utc::now() + Duration::hours(1);
+    assert!(usage::collect_in(home.path(), Path::new("/w"), future).is_empty());
+    assert_eq!(
+        usage::collect_in(home.path(), Path::new("/w"), long_ago()).len(),
+        1
+    );
+}
+
+// =============================================================================
+// Phases and pricing
+// =============================================================================
+
+fn record(agent: &'static str, timestamp: &str, output_tokens: u64) -> UsageRecord {
+    UsageRecord {
+        agent,
+        model: String::new(),
+        timestamp: at(timestamp),
+        usage: Usage {
+            output_tokens,
+            ..Default::default()
+        },
+    }
+}
+
+fn status_event(kind: TaskEventKind, to: &str, timestamp: &str) -> TaskEvent {
+    let mut event = TaskEvent::new("t1", kind, EventActor::User);
+    event.to_value = Some(to.to_string());
+    event.created_at = at(timestamp);
+    event
+}
+
+#[test]
+fn test_by_phase_attributes_calls_to_the_status_at_the_time() {
+    let events = vec![
+        status_event(TaskEventKind::Created, "backlog", "2026-01-01T09:00:00Z"),
+        status_event(
+            TaskEventKind::StatusChanged,
+            "planning",
+            "2026-01-01T10:00:00Z",
+        ),
+        status_event(
+            TaskEventKind::StatusChanged,
+            "running",
+            "2026-01-01T11:00:00Z",
+        ),
+        status_event(TaskEventKind::StatusChanged, "done", "2026-01-01T12:00:00Z"),
+    ];
+    let records = vec![
+        record("claude", "2026-01-01T09:30:00Z", 1),
+        record("claude", "2026-01-01T10:30:00Z", 10),
+        record("claude", "2026-01-01T11:10:00Z", 100),
+        record("codex", "2026-01-01T11:20:00Z", 1000),
+        record("claude", "2026-01-01T11:30:00Z", 100),
+        record("claude", "2026-01-01T12:30:00Z", 5),
+    ];
+
+    let rows = usage::by_phase("t1", &records, &events);
+    let summary: Vec<(&str, &str, u64)> = rows
+        .iter()
+        .map(|r| (r.phase.as_str(), r.agent.as_str(), r.usage.output_tokens))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("research", "claude", 1),
+            ("planning", "claude", 10),
+            ("running", "claude", 200),
+            ("running", "codex", 1000),
+            ("review", "claude", 5),
+        ]
+    );
+}
+
+#[test]
+fn test_cost_uses_model_prices() {
+    let usage = Usage {
+        input_tokens: 1_000_000,
+        output_tokens: 1_000_000,
+        ..Default::default()
+    };
+    assert_eq!(usage::cost_usd("claude-opus-4-1-20250805", &usage), 90.0);
+    assert_eq!(usage::cost_usd("claude-opus-4-5", &usage), 30.0);
+    assert_eq!(usage::cost_usd("claude-sonnet-4-5-20250929", &usage), 18.0);
+    assert_eq!(usage::cost_usd("gpt-5-codex", &usage), 11.25);
+    assert_eq!(usage::cost_usd("some-local-model", &usage), 0.0);
+}
+
+#[test]
+fn test_format_usage() {
+    assert_eq!(usage::format_tokens(950), "950");
+    assert_eq!(usage::format_tokens(12_345), "12.3k");
+    assert_eq!(usage::format_tokens(4_100_000), "4.1M");
+    let usage = Usage {
+        output_tokens: 12_345,
+        cost_usd: 0.4213,
+        ..Default::default()
+    };
+    assert_eq!(usage::format_usage(&usage), "12.3k tok $0.42");
+    assert_eq!(
+        usage::format_usage(&Usage {
+            cost_usd: 0.0,
+            ..usage
+        }),
+        "12.3k tok"
+    );
+}